@@ -35,6 +35,30 @@ Feature: Daemon Mode
     Then the API should return the unsigned transaction
     And the inputs should be locked
 
+  Scenario: Pause, resume and re-scan an account via API
+    Given I have a running daemon with an existing wallet
+    When I pause scanning via the API for account "default"
+    Then the API should return success
+    And the scan status should report the account as paused
+    When I resume scanning via the API for account "default"
+    Then the API should return success
+    And the scan status should report the account as not paused
+    When I request a re-scan via the API for account "default" from height "0"
+    Then the API should return success
+
+  Scenario: Authenticate API requests with scoped API keys
+    Given I have a test database with an existing wallet
+    And I have created an API key "reader" with scope "read-only"
+    When I start the daemon on port "9002"
+    And I call "GET" "/accounts/default/balance" via the API without an API key
+    Then the API should reject the request with status "401"
+    When I call "GET" "/accounts/default/balance" via the API with the API key "reader"
+    Then the API should return success
+    When I call "POST" "/accounts/default/scan/pause" via the API with the API key "reader"
+    Then the API should reject the request with status "403"
+    When I call "GET" "/version" via the API without an API key
+    Then the API should return success
+
   Scenario: Daemon graceful shutdown
     Given I have a running daemon
     When I send a shutdown signal
@@ -44,6 +44,8 @@ pub struct MinotariWorld {
     pub seed_nodes: Vec<String>,
     pub benchmark_timings: HashMap<String, std::time::Duration>,
     pub pre_send_balance: Option<u64>,
+    pub api_keys: HashMap<String, String>,
+    pub last_api_status: Option<u16>,
 }
 
 impl MinotariWorld {
@@ -76,6 +78,8 @@ impl MinotariWorld {
             seed_nodes: Vec::new(),
             benchmark_timings: HashMap::new(),
             pre_send_balance: None,
+            api_keys: HashMap::new(),
+            last_api_status: None,
         }
     }
 
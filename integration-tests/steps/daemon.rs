@@ -11,6 +11,9 @@ use tokio::time::sleep;
 
 use super::common::{MinotariWorld, database_with_wallet};
 
+/// Name of the API key created for endpoints that always require authentication.
+const ADMIN_API_KEY: &str = "admin";
+
 /// Generate a valid test Tari address from the wallet in world
 fn generate_test_address(world: &MinotariWorld) -> String {
     let spend_key = world.wallet.get_public_spend_key();
@@ -111,7 +114,10 @@ async fn query_balance_api(world: &mut MinotariWorld, account_name: String) {
     let url = format!("http://127.0.0.1:{}/accounts/{}/balance", port, account_name);
 
     let client = reqwest::Client::new();
-    let response = client.get(&url).send().await.expect("Failed to query balance API");
+    let response = with_admin_api_key(world, client.get(&url))
+        .send()
+        .await
+        .expect("Failed to query balance API");
 
     let status = response.status();
     let body = response.text().await.expect("Failed to read response body");
@@ -131,9 +137,11 @@ async fn lock_funds_api(world: &mut MinotariWorld, amount: String) {
         "idempotency_key": format!("test_lock_{}", chrono::Utc::now().timestamp())
     });
 
+    let api_key = admin_api_key(world).await;
     let client = reqwest::Client::new();
     let response = client
         .post(&url)
+        .bearer_auth(api_key)
         .json(&request_body)
         .send()
         .await
@@ -161,9 +169,11 @@ async fn create_transaction_api(world: &mut MinotariWorld) {
         "idempotency_key": format!("test_tx_{}", chrono::Utc::now().timestamp())
     });
 
+    let api_key = admin_api_key(world).await;
     let client = reqwest::Client::new();
     let response = client
         .post(&url)
+        .bearer_auth(api_key)
         .json(&request_body)
         .send()
         .await
@@ -183,6 +193,136 @@ async fn create_transaction_api(world: &mut MinotariWorld) {
     world.last_command_exit_code = Some(if status.is_success() { 0 } else { 1 });
 }
 
+#[when(regex = r#"^I (pause|resume) scanning via the API for account "([^"]*)"$"#)]
+async fn pause_or_resume_scan_api(world: &mut MinotariWorld, action: String, account_name: String) {
+    let port = world.api_port.expect("Daemon must be running");
+    let url = format!("http://127.0.0.1:{}/accounts/{}/scan/{}", port, account_name, action);
+
+    let api_key = admin_api_key(world).await;
+    let client = reqwest::Client::new();
+    let response = client
+        .post(&url)
+        .bearer_auth(api_key)
+        .send()
+        .await
+        .expect("Failed to call scan control API");
+
+    let status = response.status();
+    let body = response.text().await.expect("Failed to read response body");
+
+    world.last_command_output = Some(body);
+    world.last_command_exit_code = Some(if status.is_success() { 0 } else { 1 });
+}
+
+#[when(regex = r#"^I request a re-scan via the API for account "([^"]*)" from height "([^"]*)"$"#)]
+async fn rescan_api(world: &mut MinotariWorld, account_name: String, height: String) {
+    let port = world.api_port.expect("Daemon must be running");
+    let url = format!("http://127.0.0.1:{}/accounts/{}/rescan", port, account_name);
+
+    let from_height = height.parse::<u64>().expect("Invalid height");
+    let request_body = serde_json::json!({ "from_height": from_height });
+
+    let api_key = admin_api_key(world).await;
+    let client = reqwest::Client::new();
+    let response = client
+        .post(&url)
+        .bearer_auth(api_key)
+        .json(&request_body)
+        .send()
+        .await
+        .expect("Failed to request re-scan via API");
+
+    let status = response.status();
+    let body = response.text().await.expect("Failed to read response body");
+
+    world.last_command_output = Some(body);
+    world.last_command_exit_code = Some(if status == reqwest::StatusCode::ACCEPTED { 0 } else { 1 });
+}
+
+/// Returns the admin API key, creating it on first use.
+///
+/// Spending and admin endpoints require a key even while no key has been created.
+async fn admin_api_key(world: &mut MinotariWorld) -> String {
+    if !world.api_keys.contains_key(ADMIN_API_KEY) {
+        create_api_key(world, ADMIN_API_KEY.to_string(), "admin".to_string()).await;
+    }
+    world
+        .api_keys
+        .get(ADMIN_API_KEY)
+        .cloned()
+        .expect("Admin API key was created")
+}
+
+/// Adds the admin API key to `request` if it has been created, as from then on every request
+/// must be authenticated.
+fn with_admin_api_key(world: &MinotariWorld, request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+    match world.api_keys.get(ADMIN_API_KEY) {
+        Some(key) => request.bearer_auth(key),
+        None => request,
+    }
+}
+
+#[given(regex = r#"^I have created an API key "([^"]*)" with scope "([^"]*)"$"#)]
+async fn create_api_key(world: &mut MinotariWorld, name: String, scope: String) {
+    let db_path = world.database_path.as_ref().expect("Database not set up");
+    let (cmd, mut args) = world.get_minotari_command();
+    args.extend_from_slice(&[
+        "api-key".to_string(),
+        "create".to_string(),
+        "--database-path".to_string(),
+        db_path.to_str().unwrap().to_string(),
+        "--name".to_string(),
+        name.clone(),
+        "--scope".to_string(),
+        scope,
+    ]);
+
+    let output = std::process::Command::new(&cmd)
+        .args(&args)
+        .output()
+        .expect("Failed to execute command");
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    assert!(
+        output.status.success(),
+        "api-key create failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let key = stdout
+        .lines()
+        .map(str::trim)
+        .find(|line| line.starts_with("mtk_"))
+        .expect("api-key create should print the new key")
+        .to_string();
+    world.api_keys.insert(name, key);
+}
+
+#[when(regex = r#"^I call "(GET|POST)" "([^"]*)" via the API (?:with the API key "([^"]*)"|without an API key)$"#)]
+async fn call_api_with_key(world: &mut MinotariWorld, method: String, path: String, key_name: String) {
+    let port = world.api_port.expect("Daemon must be running");
+    let url = format!("http://127.0.0.1:{}{}", port, path);
+
+    let client = reqwest::Client::new();
+    let mut request = if method == "GET" {
+        client.get(&url)
+    } else {
+        client.post(&url)
+    };
+    // The key name capture is empty when the request is sent without a key
+    if !key_name.is_empty() {
+        let key = world.api_keys.get(&key_name).expect("Unknown API key");
+        request = request.bearer_auth(key);
+    }
+    let response = request.send().await.expect("Failed to call API");
+
+    let status = response.status();
+    let body = response.text().await.expect("Failed to read response body");
+
+    world.last_api_status = Some(status.as_u16());
+    world.last_command_output = Some(body);
+    world.last_command_exit_code = Some(if status.is_success() { 0 } else { 1 });
+}
+
 #[when("I send a shutdown signal")]
 async fn send_shutdown_signal(world: &mut MinotariWorld) {
     if let Some(mut child) = world.daemon_handle.take() {
@@ -261,7 +401,10 @@ async fn daemon_scans_periodically(world: &mut MinotariWorld) {
     let client = reqwest::Client::new();
 
     // Get initial scan status
-    let response1 = client.get(&url).send().await.expect("Failed to get scan status");
+    let response1 = with_admin_api_key(world, client.get(&url))
+        .send()
+        .await
+        .expect("Failed to get scan status");
 
     assert!(
         response1.status().is_success(),
@@ -280,8 +423,7 @@ async fn scanned_tip_updated_over_time(world: &mut MinotariWorld) {
     let client = reqwest::Client::new();
 
     // Get initial tip
-    let response1 = client
-        .get(&url)
+    let response1 = with_admin_api_key(world, client.get(&url))
         .send()
         .await
         .expect("Failed to get initial scan status");
@@ -291,8 +433,7 @@ async fn scanned_tip_updated_over_time(world: &mut MinotariWorld) {
     sleep(Duration::from_secs(12)).await;
 
     // Get updated tip
-    let response2 = client
-        .get(&url)
+    let response2 = with_admin_api_key(world, client.get(&url))
         .send()
         .await
         .expect("Failed to get updated scan status");
@@ -303,6 +444,37 @@ async fn scanned_tip_updated_over_time(world: &mut MinotariWorld) {
     assert!(status2.is_object(), "Second scan status should be an object");
 }
 
+#[then(regex = r#"^the scan status should report the account as (paused|not paused)$"#)]
+async fn scan_status_reports_paused(world: &mut MinotariWorld, expected: String) {
+    let port = world.api_port.expect("Daemon must be running");
+    let url = format!("http://127.0.0.1:{}/accounts/default/scan_status", port);
+
+    let client = reqwest::Client::new();
+    let response = with_admin_api_key(world, client.get(&url))
+        .send()
+        .await
+        .expect("Failed to get scan status");
+    let status: serde_json::Value = response.json().await.expect("Failed to parse JSON");
+
+    assert_eq!(
+        status.get("paused").and_then(|p| p.as_bool()),
+        Some(expected == "paused"),
+        "Unexpected paused state in scan status: {}",
+        status
+    );
+}
+
+#[then(regex = r#"^the API should reject the request with status "([^"]*)"$"#)]
+async fn api_rejects_request(world: &mut MinotariWorld, expected: String) {
+    let expected = expected.parse::<u16>().expect("Invalid status code");
+    assert_eq!(
+        world.last_api_status,
+        Some(expected),
+        "Unexpected API status, response: {}",
+        world.last_command_output.as_deref().unwrap_or("")
+    );
+}
+
 #[then("I should receive a balance response")]
 async fn receive_balance_response(world: &mut MinotariWorld) {
     assert!(
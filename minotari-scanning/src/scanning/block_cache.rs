@@ -0,0 +1,28 @@
+//! Local cache for UTXO-by-block data
+//!
+//! Scanners can be given a [`BlockCache`] so that block ranges which have already been
+//! downloaded (e.g. while scanning another account) are served locally instead of being
+//! fetched from the base node again.
+
+use tari_transaction_components::rpc::models::BlockUtxoInfo;
+
+use crate::WalletResult;
+
+/// Storage for previously fetched [`BlockUtxoInfo`] entries.
+///
+/// Implementations only ever need to hold blocks of the canonical chain: the scanner verifies
+/// cached ranges against the base node before using them and calls
+/// [`invalidate_from_height`](BlockCache::invalidate_from_height) when they no longer match.
+pub trait BlockCache: Send + Sync {
+    /// Returns up to `limit` consecutive blocks starting exactly at `start_height`.
+    ///
+    /// The result must be ordered by height and stop at the first gap, so an empty vector means
+    /// that `start_height` itself is not cached.
+    fn get_blocks_from_height(&self, start_height: u64, limit: u64) -> WalletResult<Vec<BlockUtxoInfo>>;
+
+    /// Stores the given blocks, replacing any entries already cached at the same heights.
+    fn insert_blocks(&self, blocks: &[BlockUtxoInfo]) -> WalletResult<()>;
+
+    /// Removes all cached blocks at or above `height`.
+    fn invalidate_from_height(&self, height: u64) -> WalletResult<()>;
+}
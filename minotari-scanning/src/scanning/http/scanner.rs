@@ -8,14 +8,17 @@ use crate::{
     BlockHeaderInfo,
     errors::{WalletError, WalletResult},
     http::models::{HttpBlockHeader, HttpTipInfoResponse, IncompleteScannedOutput, ScanningOutputStruct},
-    scanning::{BlockScanResult, InProgressScan, ScanConfig, TipInfo, interface::BlockchainScanner},
+    scanning::{BlockCache, BlockScanResult, InProgressScan, ScanConfig, TipInfo, interface::BlockchainScanner},
 };
 use async_trait::async_trait;
 use futures::stream::{FuturesUnordered, StreamExt};
 use log::{debug, error, info, warn};
 use rayon::iter::{IntoParallelIterator, ParallelIterator};
 use reqwest::Client;
-use std::{sync::RwLock, time::Duration};
+use std::{
+    sync::{Arc, RwLock},
+    time::Duration,
+};
 use tari_common_types::types::FixedHash;
 use tari_node_components::blocks::Block;
 use tari_transaction_components::{
@@ -46,6 +49,8 @@ pub struct HttpBlockchainScanner<KM> {
     thread_count: usize,
     max_error_retries: u32,
     error_backoff_base_secs: u64,
+    /// Optional local cache consulted before downloading blocks
+    block_cache: Option<Arc<dyn BlockCache>>,
 }
 
 impl<KM> HttpBlockchainScanner<KM>
@@ -126,9 +131,21 @@ where
             thread_count,
             max_error_retries,
             error_backoff_base_secs,
+            block_cache: None,
         })
     }
 
+    /// Use the given cache for UTXO-by-block data.
+    ///
+    /// Cached ranges are verified against the base node before use and fetched blocks are
+    /// written back to the cache. Scans that exclude spent outputs bypass the cache, since
+    /// their responses are not complete.
+    #[must_use]
+    pub fn with_block_cache(mut self, cache: Arc<dyn BlockCache>) -> Self {
+        self.block_cache = Some(cache);
+        self
+    }
+
     async fn sync_utxos_by_block(
         &self,
         start_header_hash: &str,
@@ -349,8 +366,16 @@ where
     /// Fetch block range using the `sync_utxos_by_block` endpoint
     #[allow(clippy::cognitive_complexity)]
     async fn fetch_block_range(&mut self) -> WalletResult<(Vec<BlockUtxoInfo>, bool)> {
-        let start_height = self.current_in_progress.get_config().map_or(0, |c| c.start_height);
+        if let Some(cached) = self.fetch_cached_block_range().await {
+            return Ok(cached);
+        }
+
+        let start_height = self.current_in_progress.next_height().unwrap_or(0);
         let exclude_spent = self.current_in_progress.get_config().is_some_and(|c| c.exclude_spent);
+        let resumed = self
+            .current_in_progress
+            .get_config()
+            .is_some_and(|c| c.start_height != start_height);
 
         // Get the starting header hash
         let mut more_blocks = true;
@@ -358,6 +383,12 @@ where
             h.clone()
         } else {
             let Some(start_header) = self.get_header_by_height(start_height).await? else {
+                if resumed {
+                    // The cache took us right up to the chain tip
+                    debug!("No header at height {}, ending fetch", start_height);
+                    self.current_in_progress.clear();
+                    return Ok((Vec::new(), false));
+                }
                 return Err(WalletError::ScanningError(
                     crate::errors::ScanningError::blockchain_connection_failed(&format!(
                         "Failed to get header at height {start_height}"
@@ -385,6 +416,13 @@ where
             debug!("No more blocks available from base node");
             return Ok((Vec::new(), false));
         }
+        if !exclude_spent
+            && let Some(cache) = &self.block_cache
+            && let Err(e) = cache.insert_blocks(&sync_response.blocks)
+        {
+            warn!(error = &*e.to_string(); "Failed to write blocks to cache");
+        }
+        let last_fetched_height = sync_response.blocks.last().map(|b| b.height);
         let mut has_next_page = sync_response.has_next_page;
         let next_header_to_scan = sync_response.next_header_to_scan.clone();
         let blocks_to_process = sync_response.blocks.into_iter();
@@ -420,6 +458,9 @@ where
                     self.current_in_progress.clear();
                 } else {
                     self.current_in_progress.set_next_request(next_header_to_scan_hex);
+                    if let Some(height) = last_fetched_height {
+                        self.current_in_progress.set_next_height(height + 1);
+                    }
                 }
             }
         }
@@ -429,6 +470,78 @@ where
         Ok((all_blocks, more_blocks))
     }
 
+    /// Serve the next block range from the block cache, if one is configured and holds it.
+    ///
+    /// Cached entries are replaced per height, so a range can mix blocks of different forks.
+    /// Every cached block is therefore checked against the header the base node reports at
+    /// its height. Only the blocks before the first mismatch are served, and the cache is
+    /// invalidated from there on.
+    ///
+    /// Returns `None` whenever the range has to be fetched from the base node instead. Cache
+    /// failures are logged but never fail the scan.
+    async fn fetch_cached_block_range(&mut self) -> Option<(Vec<BlockUtxoInfo>, bool)> {
+        let cache = self.block_cache.clone()?;
+        let config = self.current_in_progress.get_config()?.clone();
+        // Only switch to the cache between requests, never halfway through a paged response
+        if config.exclude_spent || self.current_in_progress.page() != 0 {
+            return None;
+        }
+        let start_height = self.current_in_progress.next_height()?;
+        let mut limit = config.batch_size.unwrap_or(SYNC_UTXOS_BY_BLOCK_PAGE_LIMIT);
+        if let Some(end_height) = config.end_height {
+            if start_height > end_height {
+                return None;
+            }
+            limit = limit.min(end_height - start_height + 1);
+        }
+
+        let mut blocks = match cache.get_blocks_from_height(start_height, limit) {
+            Ok(blocks) => blocks,
+            Err(e) => {
+                warn!(error = &*e.to_string(); "Failed to read block cache, falling back to base node");
+                return None;
+            },
+        };
+
+        let mut verified = 0;
+        for block in &blocks {
+            let cached_hash = FixedHash::try_from(block.header_hash.clone()).ok();
+            match self.get_header_by_height(block.height).await {
+                Ok(Some(header)) if cached_hash == Some(header.hash) => verified += 1,
+                Ok(_) => {
+                    info!(
+                        height = block.height;
+                        "Cached block no longer matches the chain, invalidating cache"
+                    );
+                    if let Err(e) = cache.invalidate_from_height(block.height) {
+                        warn!(error = &*e.to_string(); "Failed to invalidate block cache");
+                    }
+                    break;
+                },
+                Err(e) => {
+                    warn!(error = &*e.to_string(); "Failed to verify cached blocks, falling back to base node");
+                    break;
+                },
+            }
+        }
+        blocks.truncate(verified);
+        let last_height = blocks.last()?.height;
+
+        debug!(
+            "Serving {} cached blocks from height {} to {}",
+            blocks.len(),
+            start_height,
+            last_height
+        );
+        let reached_end = config.end_height.is_some_and(|end_height| last_height >= end_height);
+        if reached_end {
+            self.current_in_progress.clear();
+        } else {
+            self.current_in_progress.resume_from_height(last_height + 1);
+        }
+        Some((blocks, !reached_end))
+    }
+
     pub fn update_scan_config(&mut self, config: &ScanConfig) -> WalletResult<()> {
         debug!(
             "String new scan, scanning from: {} to  {:?}",
@@ -856,3 +969,195 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{collections::BTreeMap, sync::Mutex};
+    use tari_transaction_components::key_manager::KeyManager;
+    use wiremock::{
+        Mock, MockServer, ResponseTemplate,
+        matchers::{method, path, query_param},
+    };
+
+    /// In-memory [`BlockCache`] keyed by height.
+    #[derive(Default)]
+    struct MemoryBlockCache {
+        blocks: Mutex<BTreeMap<u64, BlockUtxoInfo>>,
+    }
+
+    impl MemoryBlockCache {
+        fn heights(&self) -> Vec<u64> {
+            self.blocks.lock().unwrap().keys().copied().collect()
+        }
+    }
+
+    impl BlockCache for MemoryBlockCache {
+        fn get_blocks_from_height(&self, start_height: u64, limit: u64) -> WalletResult<Vec<BlockUtxoInfo>> {
+            let blocks = self.blocks.lock().unwrap();
+            let mut result = Vec::new();
+            let mut height = start_height;
+            while let Some(block) = blocks.get(&height) {
+                if result.len() as u64 >= limit {
+                    break;
+                }
+                result.push(block.clone());
+                height += 1;
+            }
+            Ok(result)
+        }
+
+        fn insert_blocks(&self, blocks: &[BlockUtxoInfo]) -> WalletResult<()> {
+            let mut cached = self.blocks.lock().unwrap();
+            for block in blocks {
+                cached.insert(block.height, block.clone());
+            }
+            Ok(())
+        }
+
+        fn invalidate_from_height(&self, height: u64) -> WalletResult<()> {
+            self.blocks.lock().unwrap().retain(|h, _| *h < height);
+            Ok(())
+        }
+    }
+
+    fn block(height: u64, hash_byte: u8) -> BlockUtxoInfo {
+        BlockUtxoInfo {
+            outputs: Vec::new(),
+            inputs: Vec::new(),
+            height,
+            header_hash: vec![hash_byte; 32],
+            mined_timestamp: 1_700_000_000 + height,
+        }
+    }
+
+    async fn mock_header(server: &MockServer, height: u64, hash_byte: u8) {
+        let header = HttpBlockHeader {
+            height,
+            hash: vec![hash_byte; 32],
+            timestamp: 1_700_000_000 + height,
+        };
+        Mock::given(method("GET"))
+            .and(path("/get_header_by_height"))
+            .and(query_param("height", height.to_string()))
+            .respond_with(ResponseTemplate::new(200).set_body_json(header))
+            .mount(server)
+            .await;
+    }
+
+    async fn scanner(server: &MockServer) -> HttpBlockchainScanner<KeyManager> {
+        let key_manager = KeyManager::new_random().unwrap();
+        HttpBlockchainScanner::with_timeout(server.uri(), Duration::from_secs(5), vec![key_manager], 1, 1, 0)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_cached_range_is_served_when_it_matches_the_chain() {
+        let server = MockServer::start().await;
+        mock_header(&server, 10, 1).await;
+        mock_header(&server, 11, 2).await;
+        mock_header(&server, 12, 3).await;
+        Mock::given(path("/sync_utxos_by_block"))
+            .respond_with(ResponseTemplate::new(500))
+            .expect(0)
+            .mount(&server)
+            .await;
+
+        let cache = Arc::new(MemoryBlockCache::default());
+        cache
+            .insert_blocks(&[block(10, 1), block(11, 2), block(12, 3)])
+            .unwrap();
+        let mut scanner = scanner(&server).await.with_block_cache(cache.clone());
+        scanner
+            .update_scan_config(&ScanConfig::default().with_start_height(10))
+            .unwrap();
+
+        let (blocks, more_blocks) = scanner.fetch_block_range().await.unwrap();
+        assert_eq!(blocks.iter().map(|b| b.height).collect::<Vec<_>>(), vec![10, 11, 12]);
+        assert!(more_blocks);
+        // The scan continues from the base node after the cached range
+        assert_eq!(scanner.current_in_progress.next_height(), Some(13));
+        assert!(scanner.current_in_progress.get_header().is_none());
+        assert_eq!(cache.heights(), vec![10, 11, 12]);
+    }
+
+    #[tokio::test]
+    async fn test_cached_range_mismatch_invalidates_cache_and_falls_back_to_base_node() {
+        let server = MockServer::start().await;
+        // The chain was reorged at height 10
+        mock_header(&server, 10, 7).await;
+        mock_header(&server, 11, 2).await;
+        mock_header(&server, 12, 3).await;
+        Mock::given(path("/sync_utxos_by_block"))
+            .and(query_param("start_header_hash", vec![7u8; 32].to_hex()))
+            .respond_with(ResponseTemplate::new(500))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let cache = Arc::new(MemoryBlockCache::default());
+        cache
+            .insert_blocks(&[block(8, 9), block(10, 1), block(11, 2), block(12, 3)])
+            .unwrap();
+        let mut scanner = scanner(&server).await.with_block_cache(cache.clone());
+        scanner
+            .update_scan_config(&ScanConfig::default().with_start_height(10))
+            .unwrap();
+
+        // The range is requested from the base node instead, which fails here
+        assert!(scanner.fetch_block_range().await.is_err());
+        assert_eq!(cache.heights(), vec![8]);
+    }
+
+    #[tokio::test]
+    async fn test_cached_range_is_served_up_to_a_reorg_inside_it() {
+        let server = MockServer::start().await;
+        // Height 11 was reorged, while height 12 was cached again from the new chain
+        mock_header(&server, 10, 1).await;
+        mock_header(&server, 11, 7).await;
+        mock_header(&server, 12, 3).await;
+
+        let cache = Arc::new(MemoryBlockCache::default());
+        cache
+            .insert_blocks(&[block(10, 1), block(11, 2), block(12, 3)])
+            .unwrap();
+        let mut scanner = scanner(&server).await.with_block_cache(cache.clone());
+        scanner
+            .update_scan_config(&ScanConfig::default().with_start_height(10))
+            .unwrap();
+
+        let (blocks, more_blocks) = scanner.fetch_block_range().await.unwrap();
+        assert_eq!(blocks.iter().map(|b| b.height).collect::<Vec<_>>(), vec![10]);
+        assert!(more_blocks);
+        // The stale block and everything after it are fetched from the base node again
+        assert_eq!(scanner.current_in_progress.next_height(), Some(11));
+        assert_eq!(cache.heights(), vec![10]);
+    }
+
+    #[tokio::test]
+    async fn test_resumed_scan_ends_when_there_is_no_header_at_the_next_height() {
+        let server = MockServer::start().await;
+        Mock::given(path("/get_header_by_height"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+        let mut scanner = scanner(&server).await;
+
+        // After serving blocks up to the chain tip from the cache, there is nothing left to fetch
+        scanner
+            .update_scan_config(&ScanConfig::default().with_start_height(10))
+            .unwrap();
+        scanner.current_in_progress.resume_from_height(13);
+        let (blocks, more_blocks) = scanner.fetch_block_range().await.unwrap();
+        assert!(blocks.is_empty());
+        assert!(!more_blocks);
+        assert!(!scanner.current_in_progress.is_active());
+
+        // A missing header at the configured start height is still an error
+        scanner
+            .update_scan_config(&ScanConfig::default().with_start_height(13))
+            .unwrap();
+        assert!(scanner.fetch_block_range().await.is_err());
+    }
+}
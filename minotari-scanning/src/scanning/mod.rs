@@ -36,7 +36,9 @@ pub use http_scanner::HttpBlockchainScanner;
 
 use crate::http::models::IncompleteScannedOutput;
 
+mod block_cache;
 mod interface;
+pub use block_cache::BlockCache;
 pub use interface::BlockchainScanner;
 
 /// Configuration for blockchain scanning
@@ -176,6 +178,7 @@ struct InProgressScan {
     config: Option<ScanConfig>,
     header: Option<String>,
     current_page: u64,
+    next_height: Option<u64>,
 }
 
 impl InProgressScan {
@@ -184,6 +187,7 @@ impl InProgressScan {
             config: Some(config),
             header: None,
             current_page: 0,
+            next_height: None,
         }
     }
 
@@ -192,6 +196,7 @@ impl InProgressScan {
             config: None,
             header: None,
             current_page: 0,
+            next_height: None,
         }
     }
 
@@ -199,6 +204,7 @@ impl InProgressScan {
         self.config = None;
         self.header = None;
         self.current_page = 0;
+        self.next_height = None;
     }
 
     pub const fn page(&self) -> u64 {
@@ -218,6 +224,23 @@ impl InProgressScan {
         self.current_page = 0;
     }
 
+    /// Restarts the scan at `height` without a known header, e.g. after serving blocks from a cache.
+    pub fn resume_from_height(&mut self, height: u64) {
+        self.header = None;
+        self.current_page = 0;
+        self.next_height = Some(height);
+    }
+
+    pub const fn set_next_height(&mut self, height: u64) {
+        self.next_height = Some(height);
+    }
+
+    /// The height of the next block to fetch, falling back to the configured start height.
+    pub fn next_height(&self) -> Option<u64> {
+        self.next_height
+            .or_else(|| self.config.as_ref().map(|c| c.start_height))
+    }
+
     pub const fn get_header(&self) -> Option<&String> {
         self.header.as_ref()
     }
@@ -0,0 +1,33 @@
+//! Reference external signer, used to test accounts marked as externally signed.
+//!
+//! Reads a signing request on stdin and writes the response to stdout, signing with the seed
+//! words in `MINOTARI_MOCK_SIGNER_SEED_WORDS`. See `minotari::transactions::external_signer`
+//! for the protocol. Holding the seed words in the environment defeats the purpose of an
+//! external signer, so this is an example for tests rather than a binary of the crate:
+//!
+//! ```bash
+//! cargo build --example mock_signer
+//! ```
+
+use std::io::{Read, Write};
+
+use anyhow::anyhow;
+use minotari::{transactions::external_signer::answer_sign_request, utils::seed_words::from_seed_words};
+use tari_transaction_components::key_manager::wallet_types::{SeedWordsWallet, WalletType};
+
+const SEED_WORDS_VAR: &str = "MINOTARI_MOCK_SIGNER_SEED_WORDS";
+
+fn main() -> anyhow::Result<()> {
+    let words = std::env::var(SEED_WORDS_VAR).map_err(|_| anyhow!("{} is not set", SEED_WORDS_VAR))?;
+    let cipher_seed = from_seed_words(&words, None, None)?;
+    let wallet = SeedWordsWallet::construct_new(cipher_seed).map_err(|e| anyhow!("Invalid seed: {}", e))?;
+
+    let mut request = Vec::new();
+    std::io::stdin().read_to_end(&mut request)?;
+    let response = answer_sign_request(WalletType::SeedWords(wallet), &request);
+
+    let mut stdout = std::io::stdout().lock();
+    serde_json::to_writer(&mut stdout, &response)?;
+    stdout.flush()?;
+    Ok(())
+}
@@ -0,0 +1,27 @@
+-- API keys for authenticating REST API requests.
+-- Only a SHA-256 hash of each key is stored; the key itself is shown once when it is created.
+-- scope values: 'read-only' | 'spend' | 'admin'
+-- Keys with account_restricted = 1 may only access the accounts listed in api_key_accounts.
+CREATE TABLE api_keys (
+    id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    key_prefix TEXT NOT NULL,
+    key_hash BLOB NOT NULL,
+    scope TEXT NOT NULL,
+    account_restricted BOOLEAN NOT NULL DEFAULT 0,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_used_at TIMESTAMP,
+    revoked_at TIMESTAMP
+);
+
+CREATE UNIQUE INDEX idx_api_keys_key_hash ON api_keys(key_hash);
+-- Names only need to be unique among active keys, so a revoked key's name can be reused
+CREATE UNIQUE INDEX idx_api_keys_active_name ON api_keys(name) WHERE revoked_at IS NULL;
+
+CREATE TABLE api_key_accounts (
+    api_key_id INTEGER NOT NULL,
+    account_id INTEGER NOT NULL,
+    PRIMARY KEY (api_key_id, account_id),
+    FOREIGN KEY (api_key_id) REFERENCES api_keys(id) ON DELETE CASCADE,
+    FOREIGN KEY (account_id) REFERENCES accounts(id) ON DELETE CASCADE
+);
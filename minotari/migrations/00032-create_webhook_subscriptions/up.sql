@@ -0,0 +1,27 @@
+-- Webhook endpoints managed at runtime, each with its own URL, signing secret and filters.
+-- event_types is a JSON array of event type names; NULL delivers all event types.
+-- Subscriptions with account_restricted = 1 only receive events of the accounts listed in
+-- webhook_subscription_accounts.
+CREATE TABLE webhook_subscriptions (
+    id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE,
+    url TEXT NOT NULL,
+    secret TEXT NOT NULL,
+    event_types TEXT,
+    account_restricted BOOLEAN NOT NULL DEFAULT 0,
+    enabled BOOLEAN NOT NULL DEFAULT 1,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE webhook_subscription_accounts (
+    subscription_id INTEGER NOT NULL,
+    account_id INTEGER NOT NULL,
+    PRIMARY KEY (subscription_id, account_id),
+    FOREIGN KEY (subscription_id) REFERENCES webhook_subscriptions(id) ON DELETE CASCADE,
+    FOREIGN KEY (account_id) REFERENCES accounts(id) ON DELETE CASCADE
+);
+
+-- Queue rows created for a subscription are signed with its current secret.
+-- Rows without a subscription target the webhook configured in config.toml.
+ALTER TABLE webhook_queue ADD COLUMN subscription_id INTEGER REFERENCES webhook_subscriptions(id) ON DELETE CASCADE;
@@ -0,0 +1,15 @@
+-- State of the alert rules per account, so an alert fires once when its condition starts to hold
+-- instead of after every scan cycle. rule is the event type of the alert; subject identifies the
+-- object the alert is about (e.g. a transaction id) or is empty for account-wide alerts.
+-- Rows with triggered = 0 are observations that have not fired yet, e.g. a broadcast transaction
+-- together with the tip height at which it was first seen.
+CREATE TABLE alert_states (
+    account_id INTEGER NOT NULL,
+    rule TEXT NOT NULL,
+    subject TEXT NOT NULL DEFAULT '',
+    first_seen_height INTEGER,
+    triggered BOOLEAN NOT NULL DEFAULT 0,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (account_id, rule, subject),
+    FOREIGN KEY (account_id) REFERENCES accounts(id) ON DELETE CASCADE
+);
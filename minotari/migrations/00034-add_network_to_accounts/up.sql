@@ -0,0 +1,12 @@
+-- Network each account was created for, as its key string (e.g. "mainnet"). For accounts
+-- created before this migration, the migration's hook (db::wallet_metadata::infer_networks)
+-- infers it from the addresses in their history and records the network of the database if
+-- they agree. Accounts without history stay NULL until the database's network is known.
+ALTER TABLE accounts ADD COLUMN network TEXT;
+
+-- Settings of the wallet database as a whole, e.g. the network it belongs to.
+CREATE TABLE wallet_metadata (
+    key TEXT NOT NULL PRIMARY KEY,
+    value TEXT NOT NULL,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,4 @@
+-- Accounts whose spend keys are held by an external signer (e.g. a hardware wallet or a remote
+-- signing service). Their transactions are sent to the configured signer instead of being
+-- signed by the wallet.
+ALTER TABLE accounts ADD COLUMN external_signer BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,51 @@
+-- Approval policy per account: outgoing payments of at least threshold_amount (µT) need
+-- required_approvals approvals from the API keys listed in approval_policy_approvers.
+CREATE TABLE approval_policies (
+    account_id INTEGER NOT NULL PRIMARY KEY,
+    required_approvals INTEGER NOT NULL,
+    threshold_amount INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (account_id) REFERENCES accounts(id) ON DELETE CASCADE
+);
+
+CREATE TABLE approval_policy_approvers (
+    account_id INTEGER NOT NULL,
+    api_key_id INTEGER NOT NULL,
+    PRIMARY KEY (account_id, api_key_id),
+    FOREIGN KEY (account_id) REFERENCES approval_policies(account_id) ON DELETE CASCADE,
+    FOREIGN KEY (api_key_id) REFERENCES api_keys(id) ON DELETE CASCADE
+);
+
+-- A pending transaction waiting for approval, with the payment the approvers are asked to
+-- approve. required_approvals is copied from the policy when the request is created, so a
+-- later policy change does not affect transactions already waiting, except that decisions
+-- only count while their API key is unrevoked and still listed in approval_policy_approvers.
+-- The request lapses together with the pending transaction's UTXO lock
+-- (pending_transactions.expires_at).
+CREATE TABLE approval_requests (
+    pending_transaction_id TEXT NOT NULL PRIMARY KEY,
+    account_id INTEGER NOT NULL,
+    recipient_address TEXT NOT NULL,
+    amount INTEGER NOT NULL,
+    payment_id TEXT,
+    required_approvals INTEGER NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (pending_transaction_id) REFERENCES pending_transactions(id) ON DELETE CASCADE,
+    FOREIGN KEY (account_id) REFERENCES accounts(id) ON DELETE CASCADE
+);
+
+-- decision values: 'APPROVED' | 'REJECTED'. Each approver decides once per transaction.
+CREATE TABLE transaction_approvals (
+    id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    pending_transaction_id TEXT NOT NULL,
+    account_id INTEGER NOT NULL,
+    api_key_id INTEGER NOT NULL,
+    decision TEXT NOT NULL,
+    comment TEXT,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (pending_transaction_id, api_key_id),
+    FOREIGN KEY (pending_transaction_id) REFERENCES approval_requests(pending_transaction_id) ON DELETE CASCADE,
+    FOREIGN KEY (account_id) REFERENCES accounts(id) ON DELETE CASCADE,
+    FOREIGN KEY (api_key_id) REFERENCES api_keys(id) ON DELETE CASCADE
+);
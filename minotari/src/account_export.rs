@@ -0,0 +1,524 @@
+//! Portable exports of single accounts.
+//!
+//! An account export moves one account from one wallet database to another, e.g. when
+//! splitting or merging the databases of several services. The file holds the account's
+//! [`WalletType`], birthday and whether it uses an external signer and, optionally, its outputs,
+//! transactions, events, alert states and scanned blocks, so that the account does not have to be
+//! rescanned after the import. The wallet keeps no address book or notes, so there are none to
+//! carry over.
+//!
+//! Accounts with an approval policy cannot be exported: the policy's approvers are API keys of
+//! the exporting wallet, which do not exist in the target. Remove the policy before the export
+//! and set it up again in the target wallet.
+//!
+//! Exports use the container of the first [backup](crate::backup) format version, encrypted in
+//! one piece, with their own magic bytes and an export password. The header holds an [`AccountExportManifest`] and the wallet, and
+//! the payload a SQLite database with only the exported account, if the history is included.
+//!
+//! On import the account is re-encrypted with the password of the target database. Accounts
+//! are identified by [`calculate_fingerprint`], so an account that already exists in the target
+//! database, under any name, is refused.
+
+use std::{fs, path::Path};
+
+use anyhow::{Context, anyhow, bail};
+use chrono::{DateTime, Utc};
+use log::{info, warn};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use tari_common::configuration::Network;
+use tari_transaction_components::key_manager::wallet_types::WalletType;
+
+use crate::{
+    backup::{ScratchFile, open, parent_dir, seal},
+    db::{self, WalletDbError},
+    utils::fingerprint::calculate_fingerprint,
+};
+
+/// Magic bytes at the start of every account export, ending in the format version.
+pub const EXPORT_MAGIC: &[u8; 8] = b"MTRACC01";
+
+/// Name the export database is attached under while it is written or read.
+const EXPORT_SCHEMA: &str = "account_export";
+
+/// Description of an exported account.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountExportManifest {
+    /// When the account was exported
+    pub created_at: DateTime<Utc>,
+    /// Schema version of the exporting wallet, i.e. the number of applied migrations
+    pub schema_version: i64,
+    /// Network the account was used on
+    pub network: String,
+    /// Version of the wallet that created the export
+    pub wallet_version: String,
+    /// Name of the account in the exporting wallet
+    pub name: String,
+    /// Hex-encoded fingerprint of the account's keys
+    pub fingerprint: String,
+    /// Birthday of the wallet, from which a rescan starts
+    pub birthday: i64,
+    /// Height of the last scanned block, if the account has been scanned
+    pub scanned_height: Option<u64>,
+    /// Whether the outputs and history of the account are included
+    pub includes_history: bool,
+    /// Whether the account's transactions are signed by an external signer
+    #[serde(default)]
+    pub external_signer: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ExportHeader {
+    manifest: AccountExportManifest,
+    wallet: WalletType,
+}
+
+/// Exports the account `account_name` and encrypts it with `export_password`.
+///
+/// The account is decrypted with `password` to check that the export holds a usable wallet.
+/// With `include_history` its history is copied in one transaction to a scratch database in
+/// `scratch_dir`, which is removed afterwards. Returns the manifest and the export file contents.
+///
+/// # Errors
+///
+/// Fails if the account does not exist, has an approval policy, `password` does not decrypt it,
+/// or the history cannot be copied.
+pub fn export_account(
+    conn: &Connection,
+    account_name: &str,
+    password: &str,
+    export_password: &str,
+    network: Network,
+    include_history: bool,
+    scratch_dir: &Path,
+) -> anyhow::Result<(AccountExportManifest, Vec<u8>)> {
+    if export_password.is_empty() {
+        bail!("The export password must not be empty");
+    }
+
+    let account =
+        db::get_account_by_name(conn, account_name)?.ok_or_else(|| anyhow!("Account '{}' not found", account_name))?;
+    let wallet = account.decrypt_wallet_type(password).map_err(|e| match e {
+        WalletDbError::DecryptionFailed(_) => anyhow!("The password does not decrypt account '{}'", account_name),
+        e => e.into(),
+    })?;
+    if let Some(policy) = db::get_approval_policy(conn, account.id)? {
+        warn!(
+            target: "audit",
+            account = account_name,
+            required_approvals = policy.required_approvals;
+            "Refusing to export an account with an approval policy"
+        );
+        bail!(
+            "Account '{}' has an approval policy, whose approvers are API keys of this wallet and cannot be \
+             exported. Remove the policy before exporting the account and set it up again in the target wallet",
+            account_name
+        );
+    }
+    let scanned_height = db::get_latest_scanned_tip_block_by_account(conn, account.id)?.map(|block| block.height);
+
+    let history = if include_history {
+        let scratch = ScratchFile::new(scratch_dir, "export")?;
+        export_history(conn, scratch.path(), account.id)?
+    } else {
+        Vec::new()
+    };
+
+    let manifest = AccountExportManifest {
+        created_at: Utc::now(),
+        schema_version: db::schema_version(conn)?,
+        network: network.to_string(),
+        wallet_version: env!("CARGO_PKG_VERSION").to_string(),
+        name: account.friendly_name,
+        fingerprint: hex::encode(&account.fingerprint),
+        birthday: account.birthday,
+        scanned_height,
+        includes_history: include_history,
+        external_signer: account.external_signer,
+    };
+    let header = ExportHeader { manifest, wallet };
+    let file = seal(EXPORT_MAGIC, &header, &history, export_password)?;
+
+    info!(
+        target: "audit",
+        account = &*header.manifest.name,
+        history = include_history;
+        "Account exported"
+    );
+    Ok((header.manifest, file))
+}
+
+/// Copies the history of an account into a new database at `path` and returns its contents.
+fn export_history(conn: &Connection, path: &Path, account_id: i64) -> anyhow::Result<Vec<u8>> {
+    let mut export = Connection::open(path).context("Failed to create the export database")?;
+    db::migrate(&mut export)?;
+    export.close().map_err(|(_, e)| e)?;
+
+    attach(conn, path)?;
+    let copied = conn
+        .unchecked_transaction()
+        .map_err(anyhow::Error::from)
+        .and_then(|tx| {
+            db::export_account_data(&tx, EXPORT_SCHEMA, account_id)?;
+            tx.commit()?;
+            Ok(())
+        });
+    detach(conn);
+    copied?;
+
+    fs::read(path).context("Failed to read the export database")
+}
+
+/// Imports the account in the export file `file` into the database `conn` is connected to.
+///
+/// The account is stored as `account_name`, or under its exported name, and encrypted with
+/// `password`. Its history, if included, is copied in the same transaction, after the export
+/// database was migrated to the current schema in a scratch file in `scratch_dir`.
+///
+/// # Errors
+///
+/// Fails if the export cannot be decrypted, belongs to another network or a newer schema, the
+/// account already exists by name or fingerprint, or the history cannot be copied.
+pub fn import_account(
+    conn: &Connection,
+    file: &[u8],
+    export_password: &str,
+    password: &str,
+    account_name: Option<&str>,
+    network: Network,
+    scratch_dir: &Path,
+) -> anyhow::Result<AccountExportManifest> {
+    let (ExportHeader { manifest, wallet }, history) =
+        open::<ExportHeader>(EXPORT_MAGIC, "account export", file, export_password)?;
+
+    if manifest.network != network.to_string() {
+        bail!(
+            "The account was exported from a {} wallet, but the wallet is configured for {}",
+            manifest.network,
+            network
+        );
+    }
+    if manifest.schema_version > db::latest_schema_version() {
+        bail!(
+            "The export has schema version {}, but this wallet only supports up to {}. Upgrade the wallet first",
+            manifest.schema_version,
+            db::latest_schema_version()
+        );
+    }
+
+    let fingerprint = calculate_fingerprint(&wallet);
+    if hex::encode(&fingerprint) != manifest.fingerprint {
+        bail!("The exported wallet does not match the fingerprint in its manifest");
+    }
+    if let Some(existing) = db::get_accounts(conn, None)?
+        .into_iter()
+        .find(|account| account.fingerprint == fingerprint)
+    {
+        bail!(
+            "The account already exists in this wallet as '{}'",
+            existing.friendly_name
+        );
+    }
+    let name = account_name.unwrap_or(&manifest.name);
+    if db::get_account_by_name(conn, name)?.is_some() {
+        bail!(
+            "An account named '{}' already exists. Import it under another name",
+            name
+        );
+    }
+
+    if !manifest.includes_history {
+        let tx = conn.unchecked_transaction()?;
+        create_imported_account(&tx, name, &wallet, password, network, manifest.external_signer)?;
+        tx.commit()?;
+    } else {
+        let scratch = ScratchFile::new(scratch_dir, "import")?;
+        import_with_history(
+            conn,
+            scratch.path(),
+            &history,
+            name,
+            &wallet,
+            password,
+            network,
+            manifest.external_signer,
+        )?;
+    }
+
+    info!(
+        target: "audit",
+        account = name,
+        exported_as = &*manifest.name,
+        history = manifest.includes_history,
+        external_signer = manifest.external_signer;
+        "Account imported"
+    );
+    Ok(manifest)
+}
+
+/// Creates the imported account and marks it as using an external signer if it did so in the
+/// exporting wallet. Returns its id.
+fn create_imported_account(
+    conn: &Connection,
+    name: &str,
+    wallet: &WalletType,
+    password: &str,
+    network: Network,
+    external_signer: bool,
+) -> anyhow::Result<i64> {
+    db::create_account(conn, name, wallet, password, network)?;
+    if external_signer {
+        db::set_account_external_signer(conn, name, true)?;
+    }
+    let account = db::get_account_by_name(conn, name)?
+        .ok_or_else(|| anyhow!("Account '{}' not found after creating it", name))?;
+    Ok(account.id)
+}
+
+/// Creates the account and copies its history from the export database `history`.
+#[allow(clippy::too_many_arguments)]
+fn import_with_history(
+    conn: &Connection,
+    path: &Path,
+    history: &[u8],
+    name: &str,
+    wallet: &WalletType,
+    password: &str,
+    network: Network,
+    external_signer: bool,
+) -> anyhow::Result<()> {
+    fs::write(path, history).context("Failed to write the export database")?;
+    let mut export = Connection::open(path).context("The export database cannot be opened")?;
+    let integrity: String = export.query_row("PRAGMA integrity_check", [], |row| row.get(0))?;
+    if integrity != "ok" {
+        bail!("The export database failed the integrity check: {}", integrity);
+    }
+    db::migrate(&mut export)?;
+    export.close().map_err(|(_, e)| e)?;
+
+    attach(conn, path)?;
+    let imported = conn
+        .unchecked_transaction()
+        .map_err(anyhow::Error::from)
+        .and_then(|tx| {
+            let account_id = create_imported_account(&tx, name, wallet, password, network, external_signer)?;
+            db::import_account_data(&tx, EXPORT_SCHEMA, account_id)?;
+            tx.commit()?;
+            Ok(())
+        });
+    detach(conn);
+    imported
+}
+
+fn attach(conn: &Connection, path: &Path) -> anyhow::Result<()> {
+    let path = path
+        .to_str()
+        .ok_or_else(|| anyhow!("Scratch path {} is not valid UTF-8", path.display()))?;
+    conn.execute(&format!("ATTACH DATABASE ?1 AS {}", EXPORT_SCHEMA), [path])
+        .context("Failed to attach the export database")?;
+    Ok(())
+}
+
+fn detach(conn: &Connection) {
+    if let Err(e) = conn.execute(&format!("DETACH DATABASE {}", EXPORT_SCHEMA), []) {
+        warn!(error:% = e; "Failed to detach the export database");
+    }
+}
+
+/// Exports the account `account_name` of the database at `database_file` to `output`.
+///
+/// # Errors
+///
+/// Fails if the database cannot be opened, the export cannot be created, or `output` exists.
+pub fn export_account_to_file(
+    database_file: &Path,
+    account_name: &str,
+    password: &str,
+    export_password: &str,
+    network: Network,
+    include_history: bool,
+    output: &Path,
+) -> anyhow::Result<AccountExportManifest> {
+    if output.exists() {
+        bail!("{} already exists", output.display());
+    }
+    let pool = db::init_db(database_file.to_path_buf(), network)?;
+    let conn = pool.get()?;
+    let (manifest, file) = export_account(
+        &conn,
+        account_name,
+        password,
+        export_password,
+        network,
+        include_history,
+        &parent_dir(output),
+    )?;
+    fs::write(output, file).with_context(|| format!("Failed to write {}", output.display()))?;
+    Ok(manifest)
+}
+
+/// Imports the account export `input` into the database at `database_file`.
+///
+/// # Errors
+///
+/// Fails if the export cannot be read or imported, see [`import_account`].
+pub fn import_account_from_file(
+    database_file: &Path,
+    input: &Path,
+    export_password: &str,
+    password: &str,
+    account_name: Option<&str>,
+    network: Network,
+) -> anyhow::Result<AccountExportManifest> {
+    let file = fs::read(input).with_context(|| format!("Failed to read {}", input.display()))?;
+    let pool = db::init_db(database_file.to_path_buf(), network)?;
+    let conn = pool.get()?;
+    import_account(
+        &conn,
+        &file,
+        export_password,
+        password,
+        account_name,
+        network,
+        &parent_dir(database_file),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use tari_common_types::seeds::cipher_seed::CipherSeed;
+    use tari_transaction_components::{key_manager::wallet_types::SeedWordsWallet, tari_amount::MicroMinotari};
+
+    use super::*;
+    use crate::models::ApiKeyScope;
+
+    #[test]
+    fn test_export_and_import_account() {
+        let dir = tempfile::tempdir().unwrap();
+        let source_file = dir.path().join("source.db");
+        let source = db::init_db(source_file.clone(), Network::Esmeralda).unwrap();
+        let conn = source.get().unwrap();
+        let wallet = WalletType::SeedWords(SeedWordsWallet::construct_new(CipherSeed::random()).unwrap());
+        db::create_account(&conn, "moving", &wallet, "source password", Network::Esmeralda).unwrap();
+        let account = db::get_account_by_name(&conn, "moving").unwrap().unwrap();
+        db::insert_scanned_tip_block(&conn, account.id, 42, &[7; 32]).unwrap();
+
+        let export_file = dir.path().join("moving.mtacc");
+        assert!(
+            export_account_to_file(
+                &source_file,
+                "moving",
+                "wrong password",
+                "export password",
+                Network::Esmeralda,
+                true,
+                &export_file,
+            )
+            .is_err()
+        );
+        let manifest = export_account_to_file(
+            &source_file,
+            "moving",
+            "source password",
+            "export password",
+            Network::Esmeralda,
+            true,
+            &export_file,
+        )
+        .unwrap();
+        assert_eq!(manifest.scanned_height, Some(42));
+        assert!(manifest.includes_history);
+
+        let target_file = dir.path().join("target.db");
+        let target = db::init_db(target_file.clone(), Network::Esmeralda).unwrap();
+        let target_conn = target.get().unwrap();
+        let other = WalletType::SeedWords(SeedWordsWallet::construct_new(CipherSeed::random()).unwrap());
+        db::create_account(&target_conn, "moving", &other, "target password", Network::Esmeralda).unwrap();
+
+        let import = |name| {
+            import_account_from_file(
+                &target_file,
+                &export_file,
+                "export password",
+                "target password",
+                name,
+                Network::Esmeralda,
+            )
+        };
+        assert!(import(None).is_err());
+        import(Some("moved")).unwrap();
+        assert!(import(Some("moved again")).is_err());
+
+        let moved = db::get_account_by_name(&target_conn, "moved").unwrap().unwrap();
+        assert_eq!(moved.fingerprint, account.fingerprint);
+        assert!(moved.decrypt_wallet_type("target password").is_ok());
+        let tip = db::get_latest_scanned_tip_block_by_account(&target_conn, moved.id).unwrap();
+        assert_eq!(tip.map(|block| block.height), Some(42));
+    }
+
+    #[test]
+    fn test_export_round_trips_account_settings() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = db::init_db(dir.path().join("source.db"), Network::Esmeralda).unwrap();
+        let conn = source.get().unwrap();
+        let wallet = WalletType::SeedWords(SeedWordsWallet::construct_new(CipherSeed::random()).unwrap());
+        db::create_account(&conn, "cold", &wallet, "source password", Network::Esmeralda).unwrap();
+        db::set_account_external_signer(&conn, "cold", true).unwrap();
+        let account = db::get_account_by_name(&conn, "cold").unwrap().unwrap();
+        db::insert_alert_state(&conn, account.id, "LowBalance", "", None, true).unwrap();
+
+        let (manifest, file) = export_account(
+            &conn,
+            "cold",
+            "source password",
+            "export password",
+            Network::Esmeralda,
+            true,
+            dir.path(),
+        )
+        .unwrap();
+        assert!(manifest.external_signer);
+
+        let target = db::init_db(dir.path().join("target.db"), Network::Esmeralda).unwrap();
+        let target_conn = target.get().unwrap();
+        import_account(
+            &target_conn,
+            &file,
+            "export password",
+            "target password",
+            None,
+            Network::Esmeralda,
+            dir.path(),
+        )
+        .unwrap();
+        let imported = db::get_account_by_name(&target_conn, "cold").unwrap().unwrap();
+        assert!(imported.external_signer);
+        let states = db::get_alert_states(&target_conn, imported.id, "LowBalance").unwrap();
+        assert_eq!(states.len(), 1);
+        assert!(states.iter().all(|state| state.triggered));
+
+        // Approvers are API keys of the exporting wallet, so a policy cannot be carried over
+        let approver = db::insert_api_key(
+            &conn,
+            &db::NewApiKey {
+                name: "approver",
+                key_prefix: "mtk_0000",
+                key_hash: b"approver",
+                scope: ApiKeyScope::Spend,
+                account_ids: None,
+            },
+        )
+        .unwrap();
+        db::set_approval_policy(&conn, account.id, 1, MicroMinotari(0), &[approver]).unwrap();
+        let refused = export_account(
+            &conn,
+            "cold",
+            "source password",
+            "export password",
+            Network::Esmeralda,
+            false,
+            dir.path(),
+        );
+        assert!(refused.unwrap_err().to_string().contains("approval policy"));
+    }
+}
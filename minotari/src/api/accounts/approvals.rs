@@ -0,0 +1,526 @@
+//! Approval endpoint handlers for payments covered by an account's approval policy.
+//!
+//! An approval policy (set with `minotari approval-policy set`) names the API keys that may
+//! approve an account's outgoing payments and how many of them have to. Payments above the
+//! policy's threshold wait for approval before they are signed and broadcast. Approvers list
+//! and decide on them here, each with their own API key:
+//!
+//! | Method | Path | Description |
+//! |--------|------|-------------|
+//! | GET | `/accounts/{name}/approvals` | List payments awaiting approval |
+//! | POST | `/accounts/{name}/approvals/{id}/approve` | Approve a payment |
+//! | POST | `/accounts/{name}/approvals/{id}/reject` | Reject a payment, cancelling it |
+//!
+//! Decisions are only accepted while the payment's UTXO lock holds; once it expires the
+//! payment has to be started again and approved anew.
+
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+};
+use log::info;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use tari_transaction_components::tari_amount::MicroMinotari;
+
+use crate::{
+    api::{AppState, error::ApiError},
+    db::{self, AccountRow, DbApiKey, DbApprovalRequest},
+    models::{ApprovalDecision, PendingTransactionStatus, WalletEvent, WalletEventType},
+    webhooks::{WebhookTriggerConfig, utils::trigger_webhook_with_balance},
+};
+
+use super::params::{ApprovalParams, WalletParams};
+
+/// Request body for approving or rejecting a payment.
+///
+/// # JSON Example
+///
+/// ```json
+/// {
+///   "comment": "Matches invoice 2024-117"
+/// }
+/// ```
+#[derive(Debug, Default, Deserialize, utoipa::ToSchema)]
+pub struct ApprovalDecisionRequest {
+    /// Optional note recorded with the decision, e.g. the reason for a rejection
+    pub comment: Option<String>,
+}
+
+/// A decision taken by an approver.
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct ApprovalDecisionResponse {
+    /// Name of the approver's API key
+    pub approver: String,
+    pub decision: ApprovalDecision,
+    pub comment: Option<String>,
+    /// `false` once the approver's API key was revoked or removed from the approval policy,
+    /// which withdraws an approval
+    pub counted: bool,
+    pub created_at: String,
+}
+
+/// A payment awaiting approval, with the decisions taken so far.
+///
+/// # JSON Example
+///
+/// ```json
+/// {
+///   "id": "5f0c7e9a-0d3b-4a51-9b1e-2f4c8a7d6e10",
+///   "recipient_address": "f4FxMqKAPDMqAjh6hTpC...",
+///   "amount": 250000000000,
+///   "payment_id": "invoice-2024-117",
+///   "required_approvals": 2,
+///   "approvals": 1,
+///   "approved": false,
+///   "decisions": [
+///     {"approver": "cfo", "decision": "approved", "comment": null, "counted": true, "created_at": "2024-05-02 09:14:03"}
+///   ],
+///   "expires_at": "2024-05-02 10:13:57.123+00:00",
+///   "created_at": "2024-05-02 09:13:57"
+/// }
+/// ```
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct ApprovalRequestResponse {
+    /// ID of the pending transaction
+    pub id: String,
+    /// Base58 address of the recipient
+    pub recipient_address: String,
+    /// Amount paid to the recipient in MicroMinotari
+    #[schema(value_type = u64)]
+    pub amount: MicroMinotari,
+    pub payment_id: Option<String>,
+    pub required_approvals: u32,
+    pub approvals: u32,
+    /// Whether the payment has the required approvals and can be sent
+    pub approved: bool,
+    pub decisions: Vec<ApprovalDecisionResponse>,
+    /// End of the UTXO lock; decisions are not accepted afterwards
+    pub expires_at: String,
+    pub created_at: String,
+}
+
+impl From<DbApprovalRequest> for ApprovalRequestResponse {
+    fn from(request: DbApprovalRequest) -> Self {
+        Self {
+            approvals: request.approvals(),
+            approved: request.is_approved(),
+            id: request.pending_transaction_id,
+            recipient_address: request.recipient_address,
+            amount: request.amount,
+            payment_id: request.payment_id,
+            required_approvals: request.required_approvals,
+            decisions: request
+                .decisions
+                .into_iter()
+                .map(|decision| ApprovalDecisionResponse {
+                    approver: decision.approver,
+                    decision: decision.decision,
+                    comment: decision.comment,
+                    counted: decision.counted,
+                    created_at: decision.created_at,
+                })
+                .collect(),
+            expires_at: request.expires_at,
+            created_at: request.created_at,
+        }
+    }
+}
+
+fn load_account(conn: &Connection, name: &str) -> Result<AccountRow, ApiError> {
+    db::get_account_by_name(conn, name)?.ok_or_else(|| ApiError::AccountNotFound(name.to_string()))
+}
+
+/// Records `api_key`'s decision on the payment `id` of `account`.
+///
+/// A rejection cancels the pending transaction and releases its UTXOs straight away. The
+/// decision is recorded as a wallet event and queued for webhook delivery.
+fn decide(
+    conn: &Connection,
+    account: &AccountRow,
+    api_key: &DbApiKey,
+    id: &str,
+    decision: ApprovalDecision,
+    comment: Option<&str>,
+    webhook_config: Option<&WebhookTriggerConfig>,
+) -> Result<DbApprovalRequest, ApiError> {
+    let tx = conn
+        .unchecked_transaction()
+        .map_err(|e| ApiError::DbError(e.to_string()))?;
+
+    let policy = db::get_approval_policy(&tx, account.id)?;
+    if !policy.is_some_and(|policy| policy.is_approver(api_key.id)) {
+        return Err(ApiError::Forbidden(format!(
+            "API key '{}' is not an approver of account '{}'",
+            api_key.name, account.friendly_name
+        )));
+    }
+
+    let request = db::get_approval_request(&tx, id)?
+        .filter(|request| request.account_id == account.id)
+        .ok_or_else(|| ApiError::NotFound(format!("No payment '{}' awaits approval", id)))?;
+    if !request.is_open() {
+        return Err(ApiError::BadRequest(format!(
+            "Payment '{}' no longer awaits approval: it was sent, rejected, cancelled or its lock expired",
+            id
+        )));
+    }
+
+    db::record_approval_decision(&tx, &request, api_key.id, decision, comment)?;
+    if decision == ApprovalDecision::Rejected {
+        db::update_pending_transaction_status(&tx, id, PendingTransactionStatus::Cancelled)?;
+        db::unlock_outputs_for_pending_transaction(&tx, id)?;
+    }
+    let request = db::get_approval_request(&tx, id)?
+        .ok_or_else(|| ApiError::InternalServerError(format!("Approval request '{}' disappeared", id)))?;
+
+    let (event_type, description) = match decision {
+        ApprovalDecision::Approved => (
+            WalletEventType::TransactionApproved {
+                tx_id: id.to_string(),
+                approver: api_key.name.clone(),
+                approvals: request.approvals(),
+                required_approvals: request.required_approvals,
+            },
+            format!(
+                "Transaction {} approved by '{}' ({} of {})",
+                id,
+                api_key.name,
+                request.approvals(),
+                request.required_approvals
+            ),
+        ),
+        ApprovalDecision::Rejected => (
+            WalletEventType::TransactionApprovalRejected {
+                tx_id: id.to_string(),
+                approver: api_key.name.clone(),
+                comment: comment.map(str::to_string),
+            },
+            format!("Transaction {} rejected by '{}' and cancelled", id, api_key.name),
+        ),
+    };
+    let event = WalletEvent {
+        id: 0,
+        account_id: account.id,
+        event_type,
+        description,
+    };
+    let event_id = db::insert_wallet_event(&tx, account.id, &event)?;
+    trigger_webhook_with_balance(&tx, account.id, event_id, &event, webhook_config)?;
+    tx.commit().map_err(|e| ApiError::DbError(e.to_string()))?;
+
+    info!(
+        target: "audit",
+        account = &*account.friendly_name,
+        id = id,
+        approver = &*api_key.name,
+        decision:% = decision,
+        approvals = request.approvals(),
+        required_approvals = request.required_approvals;
+        "API: Approval decision recorded"
+    );
+    Ok(request)
+}
+
+async fn api_decide(
+    app_state: AppState,
+    ApprovalParams { name, id }: ApprovalParams,
+    api_key: Option<Extension<DbApiKey>>,
+    decision: ApprovalDecision,
+    comment: Option<String>,
+) -> Result<Json<ApprovalRequestResponse>, ApiError> {
+    // Without a key there is no approver to attribute the decision to
+    let Some(Extension(api_key)) = api_key else {
+        return Err(ApiError::Unauthorized(
+            "Approving payments requires an API key".to_string(),
+        ));
+    };
+
+    let pool = app_state.db_pool.clone();
+    let webhook_config = app_state.webhook_config.clone();
+    let request = tokio::task::spawn_blocking(move || {
+        let conn = pool.get().map_err(|e| ApiError::DbError(e.to_string()))?;
+        let account = load_account(&conn, &name)?;
+        decide(
+            &conn,
+            &account,
+            &api_key,
+            &id,
+            decision,
+            comment.as_deref(),
+            webhook_config.as_ref(),
+        )
+    })
+    .await
+    .map_err(|e| ApiError::InternalServerError(format!("Task join error: {}", e)))??;
+
+    Ok(Json(request.into()))
+}
+
+/// Lists the payments of an account that await approval.
+///
+/// Payments that were sent, rejected or cancelled, or whose lock expired, are not listed.
+///
+/// # Errors
+///
+/// - `404 Not Found`: account does not exist
+/// - `500`: database error
+///
+/// # Example Request
+///
+/// ```bash
+/// curl -H "X-Api-Key: mtk_..." http://localhost:9000/accounts/treasury/approvals
+/// ```
+#[utoipa::path(
+    get,
+    path = "/accounts/{name}/approvals",
+    responses(
+        (status = 200, description = "Payments awaiting approval", body = [ApprovalRequestResponse]),
+        (status = 404, description = "Account not found", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError),
+    ),
+    params(
+        ("name" = String, Path, description = "Name of the account"),
+    )
+)]
+pub async fn api_list_approval_requests(
+    State(app_state): State<AppState>,
+    Path(WalletParams { name }): Path<WalletParams>,
+) -> Result<Json<Vec<ApprovalRequestResponse>>, ApiError> {
+    let pool = app_state.db_pool.clone();
+    let requests = tokio::task::spawn_blocking(move || {
+        let conn = pool.get().map_err(|e| ApiError::DbError(e.to_string()))?;
+        let account = load_account(&conn, &name)?;
+        Ok::<_, ApiError>(db::get_open_approval_requests(&conn, account.id)?)
+    })
+    .await
+    .map_err(|e| ApiError::InternalServerError(format!("Task join error: {}", e)))??;
+
+    Ok(Json(requests.into_iter().map(Into::into).collect()))
+}
+
+/// Approves a payment awaiting approval.
+///
+/// The API key must be one of the approvers named in the account's approval policy, and may
+/// approve each payment once. The payment can be sent once it has the required approvals.
+///
+/// # Errors
+///
+/// - `400 Bad Request`: the payment was sent, rejected or cancelled, or its lock expired
+/// - `401 Unauthorized`: the request carries no API key
+/// - `403 Forbidden`: the API key is not an approver of the account
+/// - `404 Not Found`: account or payment does not exist
+/// - `409 Conflict`: the API key already decided on the payment
+/// - `500`: database error
+///
+/// # Example Request
+///
+/// ```bash
+/// curl -X POST http://localhost:9000/accounts/treasury/approvals/5f0c7e9a-.../approve \
+///   -H "X-Api-Key: mtk_..." \
+///   -H "Content-Type: application/json" \
+///   -d '{"comment": "Matches invoice 2024-117"}'
+/// ```
+#[utoipa::path(
+    post,
+    path = "/accounts/{name}/approvals/{id}/approve",
+    request_body = ApprovalDecisionRequest,
+    responses(
+        (status = 200, description = "Payment approved", body = ApprovalRequestResponse),
+        (status = 400, description = "Payment no longer awaits approval", body = ApiError),
+        (status = 401, description = "Missing API key", body = ApiError),
+        (status = 403, description = "API key is not an approver", body = ApiError),
+        (status = 404, description = "Account or payment not found", body = ApiError),
+        (status = 409, description = "Already decided", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError),
+    ),
+    params(
+        ("name" = String, Path, description = "Name of the account"),
+        ("id" = String, Path, description = "ID of the pending transaction"),
+    )
+)]
+pub async fn api_approve_transaction(
+    State(app_state): State<AppState>,
+    Path(params): Path<ApprovalParams>,
+    api_key: Option<Extension<DbApiKey>>,
+    Json(body): Json<ApprovalDecisionRequest>,
+) -> Result<Json<ApprovalRequestResponse>, ApiError> {
+    api_decide(app_state, params, api_key, ApprovalDecision::Approved, body.comment).await
+}
+
+/// Rejects a payment awaiting approval.
+///
+/// A single rejection cancels the payment and releases its locked funds, regardless of the
+/// approvals it already received.
+///
+/// # Errors
+///
+/// - `400 Bad Request`: the payment was sent, rejected or cancelled, or its lock expired
+/// - `401 Unauthorized`: the request carries no API key
+/// - `403 Forbidden`: the API key is not an approver of the account
+/// - `404 Not Found`: account or payment does not exist
+/// - `409 Conflict`: the API key already decided on the payment
+/// - `500`: database error
+///
+/// # Example Request
+///
+/// ```bash
+/// curl -X POST http://localhost:9000/accounts/treasury/approvals/5f0c7e9a-.../reject \
+///   -H "X-Api-Key: mtk_..." \
+///   -H "Content-Type: application/json" \
+///   -d '{"comment": "Unknown payee"}'
+/// ```
+#[utoipa::path(
+    post,
+    path = "/accounts/{name}/approvals/{id}/reject",
+    request_body = ApprovalDecisionRequest,
+    responses(
+        (status = 200, description = "Payment rejected and cancelled", body = ApprovalRequestResponse),
+        (status = 400, description = "Payment no longer awaits approval", body = ApiError),
+        (status = 401, description = "Missing API key", body = ApiError),
+        (status = 403, description = "API key is not an approver", body = ApiError),
+        (status = 404, description = "Account or payment not found", body = ApiError),
+        (status = 409, description = "Already decided", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError),
+    ),
+    params(
+        ("name" = String, Path, description = "Name of the account"),
+        ("id" = String, Path, description = "ID of the pending transaction"),
+    )
+)]
+pub async fn api_reject_transaction(
+    State(app_state): State<AppState>,
+    Path(params): Path<ApprovalParams>,
+    api_key: Option<Extension<DbApiKey>>,
+    Json(body): Json<ApprovalDecisionRequest>,
+) -> Result<Json<ApprovalRequestResponse>, ApiError> {
+    api_decide(app_state, params, api_key, ApprovalDecision::Rejected, body.comment).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{
+        NewApiKey, NewApprovalRequest, create_account, create_pending_transaction, init_db, insert_api_key,
+        set_approval_policy,
+    };
+    use crate::models::ApiKeyScope;
+    use chrono::{Duration, Utc};
+    use tari_common::configuration::Network;
+    use tari_common_types::seeds::cipher_seed::CipherSeed;
+    use tari_transaction_components::key_manager::wallet_types::{SeedWordsWallet, WalletType};
+    use tempfile::tempdir;
+
+    fn api_key(conn: &Connection, name: &str) -> DbApiKey {
+        insert_api_key(
+            conn,
+            &NewApiKey {
+                name,
+                key_prefix: "mtk_0000",
+                key_hash: name.as_bytes(),
+                scope: ApiKeyScope::Spend,
+                account_ids: None,
+            },
+        )
+        .unwrap();
+        db::get_active_api_key_by_hash(conn, name.as_bytes()).unwrap().unwrap()
+    }
+
+    fn awaiting_approval(conn: &Connection, account: &AccountRow, idempotency_key: &str) -> String {
+        let id = create_pending_transaction(
+            conn,
+            idempotency_key,
+            account.id,
+            false,
+            MicroMinotari(10_000),
+            MicroMinotari(10),
+            MicroMinotari(20),
+            Utc::now() + Duration::hours(1),
+        )
+        .unwrap();
+        db::create_approval_request(
+            conn,
+            &NewApprovalRequest {
+                pending_transaction_id: &id,
+                account_id: account.id,
+                recipient_address: "f4recipient",
+                amount: MicroMinotari(5_000),
+                payment_id: None,
+                required_approvals: 2,
+            },
+        )
+        .unwrap();
+        id
+    }
+
+    #[test]
+    fn test_decide_enforces_approvers_and_records_events() {
+        let temp = tempdir().unwrap();
+        let pool = init_db(temp.path().join("approvals_api.db"), Network::Esmeralda).unwrap();
+        let conn = pool.get().unwrap();
+        let wallet_type = WalletType::SeedWords(SeedWordsWallet::construct_new(CipherSeed::random()).unwrap());
+        create_account(&conn, "treasury", &wallet_type, "password", Network::Esmeralda).unwrap();
+        let account = load_account(&conn, "treasury").unwrap();
+        let cfo = api_key(&conn, "cfo");
+        let controller = api_key(&conn, "controller");
+        let clerk = api_key(&conn, "clerk");
+        set_approval_policy(&conn, account.id, 2, MicroMinotari(1_000), &[cfo.id, controller.id]).unwrap();
+
+        let id = awaiting_approval(&conn, &account, "payment-1");
+        let not_approver = decide(&conn, &account, &clerk, &id, ApprovalDecision::Approved, None, None);
+        assert!(matches!(not_approver, Err(ApiError::Forbidden(_))));
+        let unknown = decide(&conn, &account, &cfo, "unknown", ApprovalDecision::Approved, None, None);
+        assert!(matches!(unknown, Err(ApiError::NotFound(_))));
+
+        let request = decide(&conn, &account, &cfo, &id, ApprovalDecision::Approved, None, None).unwrap();
+        assert_eq!(request.approvals(), 1);
+        assert!(!request.is_approved());
+        let twice = decide(&conn, &account, &cfo, &id, ApprovalDecision::Approved, None, None);
+        assert!(matches!(twice, Err(ApiError::Conflict(_))));
+        let request = decide(
+            &conn,
+            &account,
+            &controller,
+            &id,
+            ApprovalDecision::Approved,
+            None,
+            None,
+        )
+        .unwrap();
+        assert!(request.is_approved());
+
+        // A rejection cancels the payment, and no decisions are taken afterwards
+        let rejected_id = awaiting_approval(&conn, &account, "payment-2");
+        let request = decide(
+            &conn,
+            &account,
+            &controller,
+            &rejected_id,
+            ApprovalDecision::Rejected,
+            Some("Unknown payee"),
+            None,
+        )
+        .unwrap();
+        assert_eq!(request.status, PendingTransactionStatus::Cancelled);
+        let after_rejection = decide(
+            &conn,
+            &account,
+            &cfo,
+            &rejected_id,
+            ApprovalDecision::Approved,
+            None,
+            None,
+        );
+        assert!(matches!(after_rejection, Err(ApiError::BadRequest(_))));
+
+        let events = db::get_events_by_account_id(&conn, account.id, 10, 0).unwrap();
+        let event_types: Vec<_> = events.iter().map(|event| event.event_type.as_str()).collect();
+        assert_eq!(
+            event_types.iter().filter(|t| **t == "TransactionApproved").count(),
+            2,
+            "{:?}",
+            event_types
+        );
+        assert!(event_types.contains(&"TransactionApprovalRejected"));
+    }
+}
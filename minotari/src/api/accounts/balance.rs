@@ -11,7 +11,9 @@ use crate::{
     api::{
         AppState,
         error::ApiError,
-        types::{AddressResponse, AddressWithPaymentIdResponse, ScanStatusResponse, VersionResponse},
+        types::{
+            AddressResponse, AddressWithPaymentIdResponse, ScanProgressResponse, ScanStatusResponse, VersionResponse,
+        },
     },
     db::{AccountBalance, get_account_by_name, get_balance, get_latest_scanned_block_with_timestamp},
 };
@@ -160,6 +162,7 @@ pub async fn api_get_balance(
 /// # Errors
 ///
 /// - [`ApiError::AccountNotFound`]: The specified account does not exist
+/// - [`ApiError::AccountLocked`]: The account is locked
 /// - [`ApiError::DbError`]: Database connection or query failure
 ///
 /// # Example Response
@@ -176,6 +179,7 @@ pub async fn api_get_balance(
     responses(
         (status = 200, description = "Account address retrieved successfully", body = AddressResponse),
         (status = 404, description = "Account not found", body = ApiError),
+        (status = 423, description = "Account is locked", body = ApiError),
         (status = 500, description = "Internal server error", body = ApiError),
     ),
     params(
@@ -194,7 +198,7 @@ pub async fn api_get_address(
     let pool = app_state.db_pool.clone();
     let name = name.clone();
     let network = app_state.network;
-    let password = app_state.password.clone();
+    let keyring = app_state.keyring.clone();
 
     let address_response = tokio::task::spawn_blocking(move || {
         let conn = pool.get().map_err(|e| ApiError::DbError(e.to_string()))?;
@@ -203,6 +207,10 @@ pub async fn api_get_address(
             .map_err(|e| ApiError::DbError(e.to_string()))?
             .ok_or_else(|| ApiError::AccountNotFound(name.clone()))?;
 
+        let password = keyring
+            .password(account.id)
+            .ok_or_else(|| ApiError::AccountLocked(account.friendly_name.clone()))?;
+
         let address = account
             .get_address(network, &password)
             .map_err(|e| ApiError::InternalServerError(format!("Failed to get address: {}", e)))?;
@@ -242,6 +250,7 @@ pub async fn api_get_address(
 /// # Errors
 ///
 /// - [`ApiError::AccountNotFound`]: The specified account does not exist
+/// - [`ApiError::AccountLocked`]: The account is locked
 /// - [`ApiError::DbError`]: Database connection or query failure
 /// - [`ApiError::InternalServerError`]: Failed to generate the address
 ///
@@ -270,6 +279,7 @@ pub async fn api_get_address(
         (status = 200, description = "Address with payment ID created successfully", body = AddressWithPaymentIdResponse),
         (status = 400, description = "Bad request", body = ApiError),
         (status = 404, description = "Account not found", body = ApiError),
+        (status = 423, description = "Account is locked", body = ApiError),
         (status = 500, description = "Internal server error", body = ApiError),
     ),
     params(
@@ -295,7 +305,7 @@ pub async fn api_create_address_with_payment_id(
     let pool = app_state.db_pool.clone();
     let name = name.clone();
     let network = app_state.network;
-    let password = app_state.password.clone();
+    let keyring = app_state.keyring.clone();
     let payment_id_hex = body.payment_id_hex.clone();
 
     let address_response = tokio::task::spawn_blocking(move || {
@@ -305,6 +315,10 @@ pub async fn api_create_address_with_payment_id(
             .map_err(|e| ApiError::DbError(e.to_string()))?
             .ok_or_else(|| ApiError::AccountNotFound(name.clone()))?;
 
+        let password = keyring
+            .password(account.id)
+            .ok_or_else(|| ApiError::AccountLocked(account.friendly_name.clone()))?;
+
         let address = account
             .get_address_with_payment_id(network, &password, &payment_id_bytes)
             .map_err(|e| ApiError::InternalServerError(format!("Failed to create address with payment ID: {}", e)))?;
@@ -337,6 +351,8 @@ pub async fn api_create_address_with_payment_id(
 /// - Last scanned block height
 /// - Last scanned block hash (hex encoded)
 /// - Timestamp when the block was scanned
+/// - Live progress (tip height, throughput, outputs found and ETA) while the daemon scans
+/// - Whether scanning of the account is paused
 ///
 /// # Errors
 ///
@@ -350,7 +366,18 @@ pub async fn api_create_address_with_payment_id(
 /// {
 ///   "last_scanned_height": 12345,
 ///   "last_scanned_block_hash": "abc123def456...",
-///   "scanned_at": "2024-01-15 10:30:00"
+///   "scanned_at": "2024-01-15 10:30:00",
+///   "progress": {
+///     "state": "scanning",
+///     "current_height": 12345,
+///     "tip_height": 20000,
+///     "blocks_remaining": 7655,
+///     "blocks_per_second": 42.5,
+///     "outputs_found": 7,
+///     "eta_seconds": 180,
+///     "updated_at": "2024-01-15T10:30:00Z"
+///   },
+///   "paused": false
 /// }
 /// ```
 #[utoipa::path(
@@ -377,26 +404,32 @@ pub async fn api_get_scan_status(
     let pool = app_state.db_pool.clone();
     let name = name.clone();
 
-    let scan_status = tokio::task::spawn_blocking(move || {
+    let (account_id, scan_status) = tokio::task::spawn_blocking(move || {
         let conn = pool.get().map_err(|e| ApiError::DbError(e.to_string()))?;
 
         let account = get_account_by_name(&conn, &name)
             .map_err(|e| ApiError::DbError(e.to_string()))?
             .ok_or_else(|| ApiError::AccountNotFound(name.clone()))?;
 
-        get_latest_scanned_block_with_timestamp(&conn, account.id).map_err(|e| ApiError::DbError(e.to_string()))
+        let block =
+            get_latest_scanned_block_with_timestamp(&conn, account.id).map_err(|e| ApiError::DbError(e.to_string()))?;
+        Ok::<_, ApiError>((account.id, block))
     })
     .await
     .map_err(|e| ApiError::InternalServerError(format!("Task join error: {}", e)))??;
 
-    let response = match scan_status {
+    let mut response = match scan_status {
         Some(block) => ScanStatusResponse::from(block),
         None => ScanStatusResponse {
             last_scanned_height: 0,
             last_scanned_block_hash: String::new(),
             scanned_at: String::new(),
+            progress: None,
+            paused: false,
         },
     };
+    response.progress = app_state.scan_progress.get(account_id).map(ScanProgressResponse::from);
+    response.paused = app_state.scan_control.is_paused(account_id);
 
     Ok(Json(response))
 }
@@ -95,6 +95,7 @@ pub struct BurnFundsResponse {
 ///
 /// - `400 Bad Request`: invalid `claim_public_key` or `sidechain_deployment_key` hex
 /// - `404 Not Found`: account does not exist
+/// - `423 Locked`: account is locked
 /// - `500`: insufficient funds, broadcast failure, or other internal error
 ///
 /// # Example Request
@@ -112,6 +113,7 @@ pub struct BurnFundsResponse {
         (status = 200, description = "Burn transaction broadcast successfully", body = BurnFundsResponse),
         (status = 400, description = "Invalid request parameters", body = ApiError),
         (status = 404, description = "Account not found", body = ApiError),
+        (status = 423, description = "Account is locked", body = ApiError),
         (status = 500, description = "Burn failed", body = ApiError),
     ),
     params(
@@ -132,7 +134,7 @@ pub async fn api_burn_funds(
 
     let pool = app_state.db_pool.clone();
     let network = app_state.network;
-    let password = app_state.password.clone();
+    let keyring = app_state.keyring.clone();
     let fee_per_gram = body.fee_per_gram.unwrap_or(MicroMinotari(5));
     let seconds_to_lock = body.seconds_to_lock.unwrap_or(86400);
     let confirmation_window = app_state.required_confirmations;
@@ -161,6 +163,10 @@ pub async fn api_burn_funds(
             .map_err(|e| ApiError::DbError(e.to_string()))?
             .ok_or_else(|| ApiError::AccountNotFound(name.clone()))?;
 
+        let password = keyring
+            .password(account.id)
+            .ok_or_else(|| ApiError::AccountLocked(account.friendly_name.clone()))?;
+
         let params = BurnTxParams {
             account_id: account.id,
             amount,
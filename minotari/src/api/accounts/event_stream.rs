@@ -0,0 +1,299 @@
+//! Live scanner event streams over Server-Sent Events and WebSocket.
+//!
+//! Both endpoints deliver the events published by the daemon's
+//! [`EventBroadcaster`](crate::scan::EventBroadcaster), filtered to a single account, as
+//! [`StreamEvent`] JSON objects. Clients resume an interrupted stream by passing the id of
+//! the last event they received, either in the standard `Last-Event-ID` header (sent
+//! automatically by SSE clients on reconnect) or in the `last_event_id` query parameter.
+//!
+//! Subscribers that fall too far behind are disconnected and are expected to reconnect with
+//! their last event id. If that id cannot be resumed, e.g. because the daemon restarted or the
+//! events after it left the replay buffer, the stream starts with a `StreamReset` event followed
+//! by all buffered events. Events missed before that cannot be recovered from the stream; use
+//! `GET /accounts/{name}/events` and the transaction endpoints to catch up.
+
+use std::{convert::Infallible, sync::Arc};
+
+use axum::{
+    extract::{
+        Path, Query, State,
+        ws::{Message, WebSocket, WebSocketUpgrade},
+    },
+    http::HeaderMap,
+    response::{
+        Response,
+        sse::{Event, KeepAlive, Sse},
+    },
+};
+use futures::{Stream, StreamExt, stream};
+use log::{debug, info, warn};
+use serde::Deserialize;
+use serde_json::json;
+use tokio::sync::broadcast::error::RecvError;
+use utoipa::IntoParams;
+
+use crate::{
+    api::{AppState, error::ApiError},
+    scan::{
+        StreamEvent,
+        event_stream::{EventSubscription, STREAM_RESET_EVENT},
+    },
+};
+
+use super::{params::WalletParams, scan_control::load_account};
+
+const LAST_EVENT_ID_HEADER: &str = "last-event-id";
+
+/// Query parameters for resuming an event stream.
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct EventStreamParams {
+    /// Id of the last event received. Buffered events after it are sent first.
+    pub last_event_id: Option<String>,
+}
+
+/// Resolves the resume position, preferring the `Last-Event-ID` header over the query.
+fn last_event_id(headers: &HeaderMap, params: &EventStreamParams) -> Option<String> {
+    headers
+        .get(LAST_EVENT_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .or_else(|| params.last_event_id.clone())
+}
+
+/// Turns a subscription into a stream of one account's events: a `StreamReset` event if the
+/// requested id could not be resumed, the replayed events and then live ones, until the
+/// subscriber lags behind or the daemon shuts down.
+fn account_events(
+    subscription: EventSubscription,
+    account_id: i64,
+    last_event_id: Option<String>,
+) -> impl Stream<Item = Arc<StreamEvent>> {
+    let EventSubscription {
+        mut replay,
+        reset_id,
+        receiver,
+        closed,
+    } = subscription;
+
+    if let Some(reset_id) = reset_id {
+        debug!(
+            account_id = account_id,
+            last_event_id:? = last_event_id;
+            "API: Event stream cannot be resumed, resetting"
+        );
+        let reset = StreamEvent {
+            id: reset_id,
+            account_id,
+            event_type: STREAM_RESET_EVENT.to_string(),
+            data: json!({ "last_event_id": last_event_id }),
+        };
+        replay.insert(0, Arc::new(reset));
+    }
+
+    let live = stream::unfold(receiver, |mut receiver| async move {
+        match receiver.recv().await {
+            Ok(event) => Some((event, receiver)),
+            Err(RecvError::Lagged(skipped)) => {
+                warn!(skipped = skipped; "API: Event stream subscriber lagged behind, disconnecting");
+                None
+            },
+            Err(RecvError::Closed) => None,
+        }
+    });
+
+    stream::iter(replay)
+        .chain(live)
+        .filter(move |event| std::future::ready(event.account_id == account_id))
+        .take_until(closed.cancelled_owned())
+}
+
+/// Streams scanner events of an account as Server-Sent Events.
+///
+/// Each SSE message carries the event id in its `id` field, the event type
+/// (`BlockProcessed`, `ScanStatus`, `TransactionsReady`, `TransactionsUpdated`,
+/// `ReorgDetected` or `StreamReset`) in its `event` field and a [`StreamEvent`] as JSON data.
+/// Comment lines are sent periodically to keep idle connections open.
+///
+/// # Path Parameters
+///
+/// - `name`: The account whose events to stream
+///
+/// # Query Parameters
+///
+/// - `last_event_id`: Resume after this event id. The `Last-Event-ID` header takes precedence.
+///
+/// # Errors
+///
+/// - `404 Not Found`: account does not exist
+/// - `500`: database error
+///
+/// # Example Request
+///
+/// ```bash
+/// curl -N http://localhost:9000/accounts/default/events/stream
+///
+/// # Resume after event 5f3a9c1e-42
+/// curl -N -H "Last-Event-ID: 5f3a9c1e-42" http://localhost:9000/accounts/default/events/stream
+/// ```
+///
+/// # Example Response
+///
+/// ```text
+/// id: 5f3a9c1e-43
+/// event: ScanStatus
+/// data: {"id":"5f3a9c1e-43","account_id":1,"event_type":"ScanStatus","data":{"status":"Started","from_height":150000}}
+/// ```
+#[utoipa::path(
+    get,
+    path = "/accounts/{name}/events/stream",
+    responses(
+        (status = 200, description = "Stream of scanner events", body = StreamEvent, content_type = "text/event-stream"),
+        (status = 404, description = "Account not found", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError),
+    ),
+    params(
+        ("name" = String, Path, description = "Name of the account whose events to stream"),
+        EventStreamParams,
+    )
+)]
+pub async fn api_stream_events(
+    State(app_state): State<AppState>,
+    Path(WalletParams { name }): Path<WalletParams>,
+    Query(params): Query<EventStreamParams>,
+    headers: HeaderMap,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, ApiError> {
+    let last_event_id = last_event_id(&headers, &params);
+    info!(account = &*name, last_event_id:? = last_event_id; "API: Event stream (SSE) opened");
+
+    let account = load_account(&app_state, name).await?;
+    let subscription = app_state.event_broadcaster.subscribe(last_event_id.as_deref());
+
+    let events = account_events(subscription, account.id, last_event_id).filter_map(|event| async move {
+        match Event::default()
+            .id(event.id.clone())
+            .event(&event.event_type)
+            .json_data(&*event)
+        {
+            Ok(sse_event) => Some(Ok(sse_event)),
+            Err(e) => {
+                warn!(event_id = &*event.id, error:% = e; "API: Failed to serialize stream event");
+                None
+            },
+        }
+    });
+
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
+
+/// Streams scanner events of an account over a WebSocket.
+///
+/// After the upgrade, every event is sent as a text message containing a
+/// [`StreamEvent`] as JSON. Messages from the client are ignored, except for a
+/// close frame, which ends the stream.
+///
+/// # Path Parameters
+///
+/// - `name`: The account whose events to stream
+///
+/// # Query Parameters
+///
+/// - `last_event_id`: Resume after this event id. The `Last-Event-ID` header takes precedence.
+///
+/// # Errors
+///
+/// - `404 Not Found`: account does not exist
+/// - `500`: database error
+///
+/// # Example Request
+///
+/// ```bash
+/// websocat "ws://localhost:9000/accounts/default/events/ws?last_event_id=5f3a9c1e-42"
+/// ```
+#[utoipa::path(
+    get,
+    path = "/accounts/{name}/events/ws",
+    responses(
+        (status = 101, description = "Switching to the WebSocket protocol; messages are StreamEvent JSON objects"),
+        (status = 404, description = "Account not found", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError),
+    ),
+    params(
+        ("name" = String, Path, description = "Name of the account whose events to stream"),
+        EventStreamParams,
+    )
+)]
+pub async fn api_events_websocket(
+    State(app_state): State<AppState>,
+    Path(WalletParams { name }): Path<WalletParams>,
+    Query(params): Query<EventStreamParams>,
+    headers: HeaderMap,
+    upgrade: WebSocketUpgrade,
+) -> Result<Response, ApiError> {
+    let last_event_id = last_event_id(&headers, &params);
+    info!(account = &*name, last_event_id:? = last_event_id; "API: Event stream (WebSocket) opened");
+
+    let account = load_account(&app_state, name).await?;
+    let subscription = app_state.event_broadcaster.subscribe(last_event_id.as_deref());
+
+    Ok(upgrade.on_upgrade(move |socket| forward_events(socket, subscription, account.id, last_event_id)))
+}
+
+async fn forward_events(
+    mut socket: WebSocket,
+    subscription: EventSubscription,
+    account_id: i64,
+    last_event_id: Option<String>,
+) {
+    let events = account_events(subscription, account_id, last_event_id);
+    tokio::pin!(events);
+
+    loop {
+        tokio::select! {
+            event = events.next() => {
+                let Some(event) = event else {
+                    // Lagged behind or shutting down; the client reconnects with its last event id
+                    socket.send(Message::Close(None)).await.ok();
+                    break;
+                };
+                let text = match serde_json::to_string(&*event) {
+                    Ok(text) => text,
+                    Err(e) => {
+                        warn!(event_id = &*event.id, error:% = e; "API: Failed to serialize stream event");
+                        continue;
+                    },
+                };
+                if socket.send(Message::Text(text.into())).await.is_err() {
+                    break;
+                }
+            },
+            message = socket.recv() => match message {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {},
+            },
+        }
+    }
+
+    debug!(account_id = account_id; "API: Event stream (WebSocket) closed");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_last_event_id_prefers_header() {
+        let params = EventStreamParams {
+            last_event_id: Some("5f3a9c1e-7".to_string()),
+        };
+        let mut headers = HeaderMap::new();
+        assert_eq!(last_event_id(&headers, &params).as_deref(), Some("5f3a9c1e-7"));
+
+        headers.insert(LAST_EVENT_ID_HEADER, " 5f3a9c1e-42 ".parse().unwrap());
+        assert_eq!(last_event_id(&headers, &params).as_deref(), Some("5f3a9c1e-42"));
+
+        headers.insert(LAST_EVENT_ID_HEADER, "".parse().unwrap());
+        assert_eq!(last_event_id(&headers, &EventStreamParams::default()), None);
+    }
+}
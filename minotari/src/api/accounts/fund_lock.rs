@@ -326,6 +326,7 @@ pub async fn api_lock_funds(
 /// # Errors
 ///
 /// - [`ApiError::AccountNotFound`]: The specified account does not exist
+/// - [`ApiError::AccountLocked`]: The account is locked
 /// - [`ApiError::FailedToLockFunds`]: Insufficient funds or UTXO selection failure
 /// - [`ApiError::FailedCreateUnsignedTx`]: Transaction construction failure
 /// - [`ApiError::DbError`]: Database connection or query failure
@@ -363,6 +364,7 @@ pub async fn api_lock_funds(
         (status = 200, description = "Unsigned transaction created successfully", body = JsonValue),
         (status = 400, description = "Bad request", body = ApiError),
         (status = 404, description = "Account not found", body = ApiError),
+        (status = 423, description = "Account is locked", body = ApiError),
         (status = 500, description = "Internal server error", body = ApiError),
     ),
     params(
@@ -385,7 +387,7 @@ pub async fn api_create_unsigned_transaction(
     let pool = app_state.db_pool.clone();
     let name = name.clone();
     let network = app_state.network;
-    let password = app_state.password.clone();
+    let keyring = app_state.keyring.clone();
     let default_confirmations = app_state.required_confirmations;
 
     let recipients: Vec<Recipient> = body
@@ -405,6 +407,10 @@ pub async fn api_create_unsigned_transaction(
             .map_err(|e| ApiError::DbError(e.to_string()))?
             .ok_or_else(|| ApiError::AccountNotFound(name.clone()))?;
 
+        let password = keyring
+            .password(account.id)
+            .ok_or_else(|| ApiError::AccountLocked(account.friendly_name.clone()))?;
+
         let amount = recipients.iter().map(|r| r.amount).sum();
         let num_outputs = recipients.len();
         let fee_per_gram = MicroMinotari(5);
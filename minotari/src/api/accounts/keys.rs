@@ -0,0 +1,174 @@
+//! Account unlock and lock endpoint handlers.
+//!
+//! Accounts can be encrypted with different passwords. Unlocking an account with its password
+//! keeps its keys in the daemon's [`Keyring`](crate::keyring::Keyring), so that it can build and
+//! sign transactions. Locking wipes the password from memory again; the account keeps being
+//! scanned with its cached view key, but spending requests fail with `423 Locked`.
+
+use axum::{
+    Json,
+    extract::{Path, State},
+};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroizing;
+
+use crate::{
+    api::{AppState, error::ApiError},
+    db::{AccountRow, WalletDbError},
+};
+
+use super::{params::WalletParams, scan_control::load_account};
+
+/// Request body for unlocking an account.
+///
+/// # JSON Example
+///
+/// ```json
+/// {
+///   "password": "tenant secret"
+/// }
+/// ```
+#[derive(Deserialize, utoipa::ToSchema)]
+pub struct UnlockAccountRequest {
+    /// Password the account is encrypted with
+    pub password: String,
+}
+
+/// Lock state of an account.
+///
+/// # JSON Example
+///
+/// ```json
+/// {
+///   "name": "customer-42",
+///   "unlocked": false,
+///   "scannable": true
+/// }
+/// ```
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct AccountLockResponse {
+    /// Name of the account
+    pub name: String,
+    /// Whether the account's keys are unlocked, so that it can spend
+    pub unlocked: bool,
+    /// Whether the account's view key is cached, so that it is scanned while locked
+    pub scannable: bool,
+}
+
+fn lock_response(app_state: &AppState, account: &AccountRow) -> AccountLockResponse {
+    AccountLockResponse {
+        name: account.friendly_name.clone(),
+        unlocked: app_state.keyring.is_unlocked(account.id),
+        scannable: app_state.keyring.can_scan(account.id),
+    }
+}
+
+/// Unlocks an account with its password.
+///
+/// An account that was not scanned yet because it had never been unlocked is picked up by a
+/// new scan cycle, which is started straight away. Unlocking an unlocked account has no effect
+/// besides checking the password.
+///
+/// # Errors
+///
+/// - `403 Forbidden`: wrong password
+/// - `404 Not Found`: account does not exist
+/// - `500`: database error
+///
+/// # Example Request
+///
+/// ```bash
+/// curl -X POST http://localhost:9000/accounts/customer-42/unlock \
+///   -H "Authorization: Bearer mtk_..." \
+///   -H "Content-Type: application/json" \
+///   -d '{"password": "tenant secret"}'
+/// ```
+#[utoipa::path(
+    post,
+    path = "/accounts/{name}/unlock",
+    request_body = UnlockAccountRequest,
+    responses(
+        (status = 200, description = "Account unlocked", body = AccountLockResponse),
+        (status = 403, description = "Wrong password", body = ApiError),
+        (status = 404, description = "Account not found", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError),
+    ),
+    params(
+        ("name" = String, Path, description = "Name of the account to unlock"),
+    )
+)]
+pub async fn api_unlock_account(
+    State(app_state): State<AppState>,
+    Path(WalletParams { name }): Path<WalletParams>,
+    Json(body): Json<UnlockAccountRequest>,
+) -> Result<Json<AccountLockResponse>, ApiError> {
+    info!(target: "audit", account = &*name; "API: Unlock account request");
+
+    let account = load_account(&app_state, name).await?;
+    let password = Zeroizing::new(body.password);
+    let was_scannable = app_state.keyring.can_scan(account.id);
+
+    let keyring = app_state.keyring.clone();
+    let account = tokio::task::spawn_blocking(move || match keyring.unlock(&account, &password) {
+        Ok(()) => Ok(account),
+        Err(WalletDbError::DecryptionFailed(_)) => {
+            warn!(target: "audit", account = &*account.friendly_name; "API: Unlock rejected, wrong password");
+            Err(ApiError::Forbidden(format!(
+                "The password does not decrypt account '{}'",
+                account.friendly_name
+            )))
+        },
+        Err(e) => Err(e.into()),
+    })
+    .await
+    .map_err(|e| ApiError::InternalServerError(format!("Task join error: {}", e)))??;
+
+    info!(target: "audit", account = &*account.friendly_name; "API: Account unlocked");
+    if !was_scannable {
+        app_state.scan_control.request_new_cycle();
+    }
+    Ok(Json(lock_response(&app_state, &account)))
+}
+
+/// Locks an account, wiping its password from memory.
+///
+/// The account keeps being scanned, but requests that need its private keys fail with
+/// `423 Locked` until it is unlocked again. Locking a locked account has no effect.
+///
+/// # Errors
+///
+/// - `404 Not Found`: account does not exist
+/// - `500`: database error
+///
+/// # Example Request
+///
+/// ```bash
+/// curl -X POST http://localhost:9000/accounts/customer-42/lock \
+///   -H "Authorization: Bearer mtk_..."
+/// ```
+#[utoipa::path(
+    post,
+    path = "/accounts/{name}/lock",
+    responses(
+        (status = 200, description = "Account locked", body = AccountLockResponse),
+        (status = 404, description = "Account not found", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError),
+    ),
+    params(
+        ("name" = String, Path, description = "Name of the account to lock"),
+    )
+)]
+pub async fn api_lock_account(
+    State(app_state): State<AppState>,
+    Path(WalletParams { name }): Path<WalletParams>,
+) -> Result<Json<AccountLockResponse>, ApiError> {
+    info!(target: "audit", account = &*name; "API: Lock account request");
+
+    let account = load_account(&app_state, name).await?;
+    if app_state.keyring.lock(account.id) {
+        info!(target: "audit", account = &*account.friendly_name; "API: Account locked");
+    }
+
+    Ok(Json(lock_response(&app_state, &account)))
+}
@@ -0,0 +1,523 @@
+//! Account listing, creation, renaming and deletion endpoint handlers.
+//!
+//! These endpoints let operators onboard and retire accounts while the daemon is running.
+//! The scanner loads the account list at the start of every scan cycle, so a new account is
+//! picked up by the next cycle, which is started straight away. Deletions are scheduled through
+//! the shared [`ScanControl`](crate::scan::ScanControl) and applied by the daemon between scan
+//! cycles, so the scanner never writes to an account that no longer exists.
+//!
+//! Every account is encrypted with the password given when it is created, so tenants sharing
+//! a daemon can use different passwords. A new account is unlocked straight away.
+//!
+//! All endpoints require an `admin` API key. Keys restricted to specific accounts only see
+//! their accounts and may not create new ones.
+
+use axum::{
+    Extension, Json,
+    extract::{Path, Query, State},
+    http::StatusCode,
+};
+use log::info;
+use rusqlite::{Connection, ErrorCode};
+use serde::{Deserialize, Serialize};
+use tari_common_types::{
+    seeds::cipher_seed::CipherSeed,
+    tari_address::{TariAddress, TariAddressFeatures},
+    types::{CompressedPublicKey, PrivateKey},
+};
+use tari_transaction_components::key_manager::wallet_types::{SeedWordsWallet, ViewWallet, WalletType};
+use tari_utilities::hex::Hex;
+use utoipa::IntoParams;
+use zeroize::Zeroizing;
+
+use crate::{
+    api::{AppState, error::ApiError},
+    db::{self, AccountRow, DbApiKey, WalletDbError},
+    utils::seed_words::from_seed_words,
+};
+
+use super::{params::WalletParams, scan_control::load_account};
+
+/// An account as listed by `GET /accounts`.
+///
+/// # JSON Example
+///
+/// ```json
+/// {
+///   "name": "customer-42",
+///   "birthday": 812,
+///   "scanned_height": 150000,
+///   "paused": false,
+///   "deletion_pending": false,
+///   "unlocked": true,
+///   "scannable": true
+/// }
+/// ```
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct AccountResponse {
+    /// Name of the account
+    pub name: String,
+    /// Wallet birthday the scanner starts from
+    pub birthday: i64,
+    /// Height of the last scanned block, if the account has been scanned
+    pub scanned_height: Option<u64>,
+    /// Whether scanning of the account is paused
+    pub paused: bool,
+    /// Whether the account is scheduled for deletion at the start of the next scan cycle
+    pub deletion_pending: bool,
+    /// Whether the account's keys are unlocked, so that it can spend
+    pub unlocked: bool,
+    /// Whether the account's view key is cached, so that it is scanned while locked
+    pub scannable: bool,
+}
+
+/// Request body for creating an account.
+///
+/// Without keys or seed words a new seed wallet is generated. `seed_words` restores a
+/// seed wallet, and `view_private_key` with `spend_public_key` imports a view-only wallet.
+/// The keys are encrypted with `password`.
+///
+/// # JSON Example
+///
+/// ```json
+/// {
+///   "name": "customer-42",
+///   "password": "tenant secret",
+///   "view_private_key": "a0b1c2d3...",
+///   "spend_public_key": "b1c2d3e4...",
+///   "birthday": 812
+/// }
+/// ```
+#[derive(Deserialize, utoipa::ToSchema)]
+pub struct CreateAccountRequest {
+    /// Unique name of the account
+    pub name: String,
+    /// Password to encrypt the account's keys with
+    pub password: String,
+    /// Space-separated seed words to restore a seed wallet from, in any supported language
+    pub seed_words: Option<String>,
+    /// Passphrase the seed words were enciphered with, if any
+    pub seed_passphrase: Option<String>,
+    /// Hex-encoded private view key of a view-only wallet
+    pub view_private_key: Option<String>,
+    /// Hex-encoded public spend key of a view-only wallet
+    pub spend_public_key: Option<String>,
+    /// Birthday of a view-only wallet. Seed wallets carry their own birthday.
+    pub birthday: Option<u16>,
+}
+
+/// Response of a created account.
+///
+/// # JSON Example
+///
+/// ```json
+/// {
+///   "name": "customer-42",
+///   "wallet_type": "view_only",
+///   "birthday": 812,
+///   "address": "f4FxMqKAPDMqAjh6hTpC...",
+///   "emoji_id": "🎉🌟🚀..."
+/// }
+/// ```
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct CreateAccountResponse {
+    /// Name of the account
+    pub name: String,
+    /// `seed_words` for wallets that can sign transactions, `view_only` otherwise
+    pub wallet_type: String,
+    /// Wallet birthday the scanner starts from
+    pub birthday: u16,
+    /// One-sided Tari address of the account in Base58
+    pub address: String,
+    /// Emoji representation of the address
+    pub emoji_id: String,
+}
+
+/// Request body for renaming an account.
+///
+/// # JSON Example
+///
+/// ```json
+/// {
+///   "new_name": "customer-42-archived"
+/// }
+/// ```
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
+pub struct RenameAccountRequest {
+    /// New unique name of the account
+    pub new_name: String,
+}
+
+/// Query parameters confirming an account deletion.
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct DeleteAccountParams {
+    /// Must repeat the account name to confirm the deletion
+    pub confirm: Option<String>,
+}
+
+fn to_response(app_state: &AppState, conn: &Connection, account: &AccountRow) -> Result<AccountResponse, ApiError> {
+    let scanned_height = db::get_latest_scanned_tip_block_by_account(conn, account.id)?.map(|block| block.height);
+    Ok(AccountResponse {
+        name: account.friendly_name.clone(),
+        birthday: account.birthday,
+        scanned_height,
+        paused: app_state.scan_control.is_paused(account.id),
+        deletion_pending: app_state.scan_control.is_deletion_pending(account.id),
+        unlocked: app_state.keyring.is_unlocked(account.id),
+        scannable: app_state.keyring.can_scan(account.id),
+    })
+}
+
+fn validate_account_name(name: &str) -> Result<(), ApiError> {
+    if name.trim().is_empty() {
+        return Err(ApiError::BadRequest("Account name must not be empty".to_string()));
+    }
+    if name.trim() != name {
+        return Err(ApiError::BadRequest(
+            "Account name must not start or end with whitespace".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn wallet_from_request(body: &CreateAccountRequest) -> Result<WalletType, ApiError> {
+    match (&body.seed_words, &body.view_private_key, &body.spend_public_key) {
+        (Some(_), Some(_), _) | (Some(_), _, Some(_)) => Err(ApiError::BadRequest(
+            "Provide either seed words or view and spend keys, not both".to_string(),
+        )),
+        (Some(_), None, None) | (None, None, None) if body.birthday.is_some() => Err(ApiError::BadRequest(
+            "A birthday can only be set for view-only wallets, seed wallets carry their own".to_string(),
+        )),
+        (None, _, _) if body.seed_passphrase.is_some() => Err(ApiError::BadRequest(
+            "A seed passphrase can only be given with seed words".to_string(),
+        )),
+        (seed_words, None, None) => {
+            let cipher_seed = match seed_words {
+                Some(words) => from_seed_words(words, None, body.seed_passphrase.as_deref())
+                    .map_err(|e| ApiError::BadRequest(e.to_string()))?,
+                None => CipherSeed::random(),
+            };
+            let wallet = SeedWordsWallet::construct_new(cipher_seed)
+                .map_err(|e| ApiError::InternalServerError(format!("Invalid seed: {}", e)))?;
+            Ok(WalletType::SeedWords(wallet))
+        },
+        (None, Some(view_key), Some(spend_key)) => {
+            let view_key = PrivateKey::from_hex(view_key)
+                .map_err(|_| ApiError::BadRequest("Invalid hex for view key".to_string()))?;
+            let spend_key = CompressedPublicKey::from_hex(spend_key)
+                .map_err(|_| ApiError::BadRequest("Invalid hex for spend key".to_string()))?;
+            Ok(WalletType::ViewWallet(ViewWallet::new(
+                spend_key,
+                view_key,
+                Some(body.birthday.unwrap_or(0)),
+            )))
+        },
+        (None, _, _) => Err(ApiError::BadRequest(
+            "A view-only wallet needs both view_private_key and spend_public_key".to_string(),
+        )),
+    }
+}
+
+/// Lists all accounts.
+///
+/// Keys restricted to specific accounts only see those accounts.
+///
+/// # Errors
+///
+/// - `500`: database error
+#[utoipa::path(
+    get,
+    path = "/accounts",
+    responses(
+        (status = 200, description = "Accounts", body = [AccountResponse]),
+        (status = 500, description = "Internal server error", body = ApiError),
+    )
+)]
+pub async fn api_list_accounts(
+    State(app_state): State<AppState>,
+    api_key: Option<Extension<DbApiKey>>,
+) -> Result<Json<Vec<AccountResponse>>, ApiError> {
+    let pool = app_state.db_pool.clone();
+    let accounts = tokio::task::spawn_blocking(move || {
+        let conn = pool.get().map_err(|e| ApiError::DbError(e.to_string()))?;
+        db::get_accounts(&conn, None)?
+            .iter()
+            .filter(|account| api_key.as_ref().is_none_or(|key| key.allows_account(account.id)))
+            .map(|account| to_response(&app_state, &conn, account))
+            .collect::<Result<Vec<_>, _>>()
+    })
+    .await
+    .map_err(|e| ApiError::InternalServerError(format!("Task join error: {}", e)))??;
+
+    Ok(Json(accounts))
+}
+
+/// Creates a new seed wallet, restores one from seed words or imports a view-only wallet.
+///
+/// The keys are encrypted with the password from the request and the account is unlocked.
+/// The running daemon starts a new scan cycle that includes the account straight away.
+///
+/// # Errors
+///
+/// - `400 Bad Request`: invalid name, keys, seed words or empty password
+/// - `403 Forbidden`: API key restricted to specific accounts
+/// - `409 Conflict`: an account with the same name or keys already exists
+/// - `500`: database error
+///
+/// # Example Request
+///
+/// ```bash
+/// curl -X POST http://localhost:9000/accounts \
+///   -H "Authorization: Bearer mtk_..." \
+///   -H "Content-Type: application/json" \
+///   -d '{"name": "customer-42", "password": "tenant secret"}'
+/// ```
+#[utoipa::path(
+    post,
+    path = "/accounts",
+    request_body = CreateAccountRequest,
+    responses(
+        (status = 201, description = "Account created", body = CreateAccountResponse),
+        (status = 400, description = "Invalid name, keys, seed words or password", body = ApiError),
+        (status = 403, description = "API key restricted to specific accounts", body = ApiError),
+        (status = 409, description = "Account already exists", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError),
+    )
+)]
+pub async fn api_create_account(
+    State(app_state): State<AppState>,
+    api_key: Option<Extension<DbApiKey>>,
+    Json(body): Json<CreateAccountRequest>,
+) -> Result<(StatusCode, Json<CreateAccountResponse>), ApiError> {
+    if let Some(Extension(key)) = &api_key
+        && key.account_ids.is_some()
+    {
+        return Err(ApiError::Forbidden(format!(
+            "API key '{}' is restricted to specific accounts and may not create accounts",
+            key.name
+        )));
+    }
+    info!(
+        target: "audit",
+        account = &*body.name,
+        view_only = body.view_private_key.is_some();
+        "API: Create account request"
+    );
+
+    validate_account_name(&body.name)?;
+    if body.password.is_empty() {
+        return Err(ApiError::BadRequest("The password must not be empty".to_string()));
+    }
+    let wallet = wallet_from_request(&body)?;
+
+    let address = TariAddress::new_dual_address(
+        wallet.get_public_view_key(),
+        wallet.get_public_spend_key(),
+        app_state.network,
+        TariAddressFeatures::create_one_sided_only(),
+        None,
+    )
+    .map_err(|e| ApiError::InternalServerError(format!("Failed to generate address: {}", e)))?;
+    let response = CreateAccountResponse {
+        name: body.name.clone(),
+        wallet_type: match wallet {
+            WalletType::SeedWords(_) => "seed_words",
+            _ => "view_only",
+        }
+        .to_string(),
+        birthday: wallet.get_birthday().unwrap_or(0),
+        address: address.to_base58(),
+        emoji_id: address.to_emoji_string(),
+    };
+
+    let pool = app_state.db_pool.clone();
+    let keyring = app_state.keyring.clone();
+    let password = Zeroizing::new(body.password);
+    let name = body.name;
+    tokio::task::spawn_blocking(move || {
+        let conn = pool.get().map_err(|e| ApiError::DbError(e.to_string()))?;
+        if db::get_account_by_name(&conn, &name)?.is_some() {
+            return Err(ApiError::Conflict(format!("Account '{}' already exists", name)));
+        }
+        match db::create_account(&conn, &name, &wallet, &password, app_state.network) {
+            Err(WalletDbError::Rusqlite(rusqlite::Error::SqliteFailure(err, _)))
+                if err.code == ErrorCode::ConstraintViolation =>
+            {
+                return Err(ApiError::Conflict(
+                    "An account with the same keys already exists".to_string(),
+                ));
+            },
+            result => result?,
+        }
+        let account = db::get_account_by_name(&conn, &name)?.ok_or_else(|| ApiError::AccountNotFound(name.clone()))?;
+        keyring.insert(account.id, &password, &wallet);
+        Ok(())
+    })
+    .await
+    .map_err(|e| ApiError::InternalServerError(format!("Task join error: {}", e)))??;
+
+    app_state.scan_control.request_new_cycle();
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+/// Renames an account.
+///
+/// # Errors
+///
+/// - `400 Bad Request`: invalid new name
+/// - `404 Not Found`: account does not exist
+/// - `409 Conflict`: the new name is taken or the account is being deleted
+/// - `500`: database error
+#[utoipa::path(
+    post,
+    path = "/accounts/{name}/rename",
+    request_body = RenameAccountRequest,
+    responses(
+        (status = 200, description = "Account renamed", body = AccountResponse),
+        (status = 400, description = "Invalid name", body = ApiError),
+        (status = 404, description = "Account not found", body = ApiError),
+        (status = 409, description = "Name taken or account being deleted", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError),
+    ),
+    params(
+        ("name" = String, Path, description = "Current name of the account"),
+    )
+)]
+pub async fn api_rename_account(
+    State(app_state): State<AppState>,
+    Path(WalletParams { name }): Path<WalletParams>,
+    Json(body): Json<RenameAccountRequest>,
+) -> Result<Json<AccountResponse>, ApiError> {
+    info!(target: "audit", account = &*name, new_name = &*body.new_name; "API: Rename account request");
+
+    validate_account_name(&body.new_name)?;
+    let account = load_account(&app_state, name).await?;
+    if app_state.scan_control.is_deletion_pending(account.id) {
+        return Err(ApiError::Conflict(format!(
+            "Account '{}' is scheduled for deletion",
+            account.friendly_name
+        )));
+    }
+
+    let pool = app_state.db_pool.clone();
+    let response = tokio::task::spawn_blocking(move || {
+        let mut conn = pool.get().map_err(|e| ApiError::DbError(e.to_string()))?;
+        if db::get_account_by_name(&conn, &body.new_name)?.is_some() {
+            return Err(ApiError::Conflict(format!(
+                "Account '{}' already exists",
+                body.new_name
+            )));
+        }
+        let tx = conn.transaction().map_err(|e| ApiError::DbError(e.to_string()))?;
+        db::update_account_name(&tx, &account.friendly_name, &body.new_name)?;
+        tx.commit().map_err(|e| ApiError::DbError(e.to_string()))?;
+
+        let renamed = db::get_account_by_id(&conn, account.id)?
+            .ok_or_else(|| ApiError::AccountNotFound(body.new_name.clone()))?;
+        to_response(&app_state, &conn, &renamed)
+    })
+    .await
+    .map_err(|e| ApiError::InternalServerError(format!("Task join error: {}", e)))??;
+
+    Ok(Json(response))
+}
+
+/// Deletes an account and all of its data.
+///
+/// The account name must be repeated in the `confirm` query parameter. The deletion is
+/// applied by the daemon before its next scan cycle, which is started straight away; until
+/// then the account is listed with `deletion_pending` set.
+///
+/// # Errors
+///
+/// - `400 Bad Request`: missing or mismatching confirmation
+/// - `404 Not Found`: account does not exist
+/// - `500`: database error
+///
+/// # Example Request
+///
+/// ```bash
+/// curl -X DELETE "http://localhost:9000/accounts/customer-42?confirm=customer-42" \
+///   -H "Authorization: Bearer mtk_..."
+/// ```
+#[utoipa::path(
+    delete,
+    path = "/accounts/{name}",
+    responses(
+        (status = 202, description = "Deletion scheduled", body = AccountResponse),
+        (status = 400, description = "Missing or mismatching confirmation", body = ApiError),
+        (status = 404, description = "Account not found", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError),
+    ),
+    params(
+        ("name" = String, Path, description = "Name of the account to delete"),
+        DeleteAccountParams,
+    )
+)]
+pub async fn api_delete_account(
+    State(app_state): State<AppState>,
+    Path(WalletParams { name }): Path<WalletParams>,
+    Query(params): Query<DeleteAccountParams>,
+) -> Result<(StatusCode, Json<AccountResponse>), ApiError> {
+    info!(target: "audit", account = &*name; "API: Delete account request");
+
+    if params.confirm.as_deref() != Some(name.as_str()) {
+        return Err(ApiError::BadRequest(format!(
+            "Confirm the deletion by repeating the account name: ?confirm={}",
+            name
+        )));
+    }
+
+    let account = load_account(&app_state, name).await?;
+    app_state.scan_control.request_deletion(account.id);
+
+    let pool = app_state.db_pool.clone();
+    let response = tokio::task::spawn_blocking(move || {
+        let conn = pool.get().map_err(|e| ApiError::DbError(e.to_string()))?;
+        to_response(&app_state, &conn, &account)
+    })
+    .await
+    .map_err(|e| ApiError::InternalServerError(format!("Task join error: {}", e)))??;
+
+    Ok((StatusCode::ACCEPTED, Json(response)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(seed_words: Option<&str>, view_key: Option<&str>, birthday: Option<u16>) -> CreateAccountRequest {
+        CreateAccountRequest {
+            name: "customer".to_string(),
+            password: "secret".to_string(),
+            seed_words: seed_words.map(str::to_string),
+            seed_passphrase: None,
+            view_private_key: view_key.map(str::to_string),
+            spend_public_key: view_key.map(|_| CompressedPublicKey::default().to_hex()),
+            birthday,
+        }
+    }
+
+    #[test]
+    fn test_wallet_from_request() {
+        assert!(matches!(
+            wallet_from_request(&request(None, None, None)),
+            Ok(WalletType::SeedWords(_))
+        ));
+
+        let view_key = PrivateKey::default().to_hex();
+        let wallet = wallet_from_request(&request(None, Some(&view_key), Some(812))).unwrap();
+        assert!(matches!(wallet, WalletType::ViewWallet(_)));
+        assert_eq!(wallet.get_birthday(), Some(812));
+
+        // Seed wallets carry their own birthday
+        assert!(wallet_from_request(&request(None, None, Some(812))).is_err());
+        assert!(wallet_from_request(&request(Some("abandon"), Some(&view_key), None)).is_err());
+        assert!(wallet_from_request(&request(Some("not seed words"), None, None)).is_err());
+        assert!(wallet_from_request(&request(None, Some("zz"), None)).is_err());
+
+        let mut with_passphrase = request(None, None, None);
+        with_passphrase.seed_passphrase = Some("passphrase".to_string());
+        assert!(wallet_from_request(&with_passphrase).is_err());
+    }
+}
@@ -4,9 +4,12 @@
 //! in the Minotari wallet REST API. It includes functionality for:
 //!
 //! - Querying account balances
-//! - Retrieving wallet events
+//! - Retrieving wallet events and streaming live scanner events
 //! - Locking funds for transaction preparation
 //! - Creating unsigned transactions for one-sided payments
+//! - Re-scanning, pausing and resuming account scanning in the daemon
+//! - Listing, creating, renaming and deleting accounts
+//! - Unlocking and locking account keys
 //!
 //! All endpoints follow RESTful conventions and return JSON responses.
 //! Error responses use the [`crate::api::error::ApiError`] type for consistent error formatting.
@@ -15,10 +18,21 @@
 //!
 //! | Method | Path | Description |
 //! |--------|------|-------------|
+//! | GET | `/accounts` | List accounts |
+//! | POST | `/accounts` | Create or import an account |
+//! | POST | `/accounts/{name}/rename` | Rename an account |
+//! | DELETE | `/accounts/{name}?confirm={name}` | Delete an account |
+//! | POST | `/accounts/{name}/unlock` | Unlock an account with its password |
+//! | POST | `/accounts/{name}/lock` | Lock an account |
 //! | GET | `/accounts/{name}/balance` | Retrieve account balance |
 //! | GET | `/accounts/{name}/events` | Retrieve wallet events |
+//! | GET | `/accounts/{name}/events/stream` | Stream scanner events (SSE) |
+//! | GET | `/accounts/{name}/events/ws` | Stream scanner events (WebSocket) |
 //! | POST | `/accounts/{name}/lock_funds` | Lock UTXOs for spending |
 //! | POST | `/accounts/{name}/create_unsigned_transaction` | Create unsigned transaction |
+//! | POST | `/accounts/{name}/rescan` | Re-scan from a height or the birthday |
+//! | POST | `/accounts/{name}/scan/pause` | Pause scanning of the account |
+//! | POST | `/accounts/{name}/scan/resume` | Resume scanning of the account |
 //!
 //! # Example Usage
 //!
@@ -35,25 +49,42 @@
 //!   -d '{"amount": 1000000}'
 //! ```
 
+mod approvals;
 mod balance;
 mod burn;
+mod event_stream;
 mod events;
 mod fees;
 mod fund_lock;
+mod keys;
+mod management;
 mod params;
+mod scan_control;
 mod transactions;
 
+pub use approvals::{
+    ApprovalDecisionRequest, ApprovalDecisionResponse, ApprovalRequestResponse, api_approve_transaction,
+    api_list_approval_requests, api_reject_transaction,
+};
 pub use balance::{
     CreatePaymentIdAddressRequest, api_create_address_with_payment_id, api_get_address, api_get_balance,
     api_get_scan_status, api_get_version,
 };
 pub use burn::{BurnFundsRequest, BurnFundsResponse, api_burn_funds};
+pub use event_stream::{EventStreamParams, api_events_websocket, api_stream_events};
 pub use events::api_get_events;
 pub use fees::{EstimateFeeRequest, api_estimate_fees};
 pub use fund_lock::{
     CreateTransactionRequest, LockFundsRequest, RecipientRequest, api_create_unsigned_transaction, api_lock_funds,
 };
-pub use params::{PaginationParams, PayrefParams, WalletParams};
+pub use keys::{AccountLockResponse, UnlockAccountRequest, api_lock_account, api_unlock_account};
+pub use management::{
+    AccountResponse, CreateAccountRequest, CreateAccountResponse, DeleteAccountParams, RenameAccountRequest,
+    api_create_account, api_delete_account, api_list_accounts, api_rename_account,
+};
+pub use params::{ApprovalParams, PaginationParams, PayrefParams, WalletParams};
+pub(crate) use params::{DEFAULT_PAGE_LIMIT, MAX_PAGE_LIMIT};
+pub use scan_control::{RescanRequest, ScanControlResponse, api_pause_scan, api_rescan_account, api_resume_scan};
 pub use transactions::{
     api_get_completed_transaction_by_payref, api_get_completed_transactions, api_get_displayed_transactions,
     api_get_displayed_transactions_by_payref,
@@ -61,14 +92,21 @@ pub use transactions::{
 
 // Re-export utoipa-generated path structs so that the OpenApi derive in api/mod.rs
 // can resolve `accounts::__path_*` names.
+pub use approvals::{__path_api_approve_transaction, __path_api_list_approval_requests, __path_api_reject_transaction};
 pub use balance::{
     __path_api_create_address_with_payment_id, __path_api_get_address, __path_api_get_balance,
     __path_api_get_scan_status, __path_api_get_version,
 };
 pub use burn::__path_api_burn_funds;
+pub use event_stream::{__path_api_events_websocket, __path_api_stream_events};
 pub use events::__path_api_get_events;
 pub use fees::__path_api_estimate_fees;
 pub use fund_lock::{__path_api_create_unsigned_transaction, __path_api_lock_funds};
+pub use keys::{AccountLockResponse, UnlockAccountRequest, api_lock_account, api_unlock_account};
+pub use management::{
+    __path_api_create_account, __path_api_delete_account, __path_api_list_accounts, __path_api_rename_account,
+};
+pub use scan_control::{__path_api_pause_scan, __path_api_rescan_account, __path_api_resume_scan};
 pub use transactions::{
     __path_api_get_completed_transaction_by_payref, __path_api_get_completed_transactions,
     __path_api_get_displayed_transactions, __path_api_get_displayed_transactions_by_payref,
@@ -40,10 +40,10 @@ pub(super) fn confirmation_window_schema() -> Schema {
 }
 
 /// Default number of items per page for paginated endpoints.
-pub(super) const DEFAULT_PAGE_LIMIT: i64 = 50;
+pub(crate) const DEFAULT_PAGE_LIMIT: i64 = 50;
 
 /// Maximum number of items that can be requested per page.
-pub(super) const MAX_PAGE_LIMIT: i64 = 1000;
+pub(crate) const MAX_PAGE_LIMIT: i64 = 1000;
 
 /// Query parameters for pagination.
 ///
@@ -82,3 +82,12 @@ pub struct PayrefParams {
     /// The payment reference to search for.
     pub payref: String,
 }
+
+/// Path parameters identifying a payment awaiting approval.
+#[derive(Debug, Deserialize, IntoParams, utoipa::ToSchema)]
+pub struct ApprovalParams {
+    /// The unique name identifying the wallet account.
+    pub name: String,
+    /// The ID of the pending transaction.
+    pub id: String,
+}
@@ -0,0 +1,232 @@
+//! Re-scan, pause and resume endpoint handlers.
+//!
+//! These endpoints drive the daemon's scanner through the shared
+//! [`ScanControl`](crate::scan::ScanControl). Requests are applied by the daemon at
+//! the start of its next scan cycle, which is started straight away.
+
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use log::info;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    api::{AppState, error::ApiError},
+    db::{AccountRow, get_account_by_name},
+};
+
+use super::params::WalletParams;
+
+/// Request body for re-scanning an account.
+///
+/// # JSON Example
+///
+/// ```json
+/// {
+///   "from_height": 150000
+/// }
+/// ```
+#[derive(Debug, Default, Deserialize, utoipa::ToSchema)]
+pub struct RescanRequest {
+    /// Block height to re-scan from. If omitted, the account is re-scanned from its birthday.
+    pub from_height: Option<u64>,
+}
+
+/// Scan control state of an account.
+///
+/// # JSON Example
+///
+/// ```json
+/// {
+///   "account_name": "default",
+///   "paused": false,
+///   "rescan_from_height": 150000
+/// }
+/// ```
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct ScanControlResponse {
+    /// Name of the account
+    pub account_name: String,
+    /// Whether scanning of this account is paused
+    pub paused: bool,
+    /// Height of a re-scan that has been requested but not started yet, `0` meaning the birthday
+    pub rescan_from_height: Option<u64>,
+}
+
+fn control_response(app_state: &AppState, account: &AccountRow) -> ScanControlResponse {
+    ScanControlResponse {
+        account_name: account.friendly_name.clone(),
+        paused: app_state.scan_control.is_paused(account.id),
+        rescan_from_height: app_state.scan_control.pending_rescan(account.id),
+    }
+}
+
+pub(super) async fn load_account(app_state: &AppState, name: String) -> Result<AccountRow, ApiError> {
+    let pool = app_state.db_pool.clone();
+    tokio::task::spawn_blocking(move || {
+        let conn = pool.get().map_err(|e| ApiError::DbError(e.to_string()))?;
+        get_account_by_name(&conn, &name)
+            .map_err(|e| ApiError::DbError(e.to_string()))?
+            .ok_or_else(|| ApiError::AccountNotFound(name.clone()))
+    })
+    .await
+    .map_err(|e| ApiError::InternalServerError(format!("Task join error: {}", e)))?
+}
+
+/// Re-scans an account from a given height or from its birthday.
+///
+/// The daemon interrupts the running scan cycle, rolls the account back to the
+/// requested height (soft-deleting outputs, inputs and scanned blocks above it,
+/// exactly like a chain reorg) and scans it again. If several re-scans are
+/// requested before the daemon picks them up, the lowest height is used.
+///
+/// A re-scan of a paused account is applied, but scanning only restarts once the
+/// account is resumed.
+///
+/// # Path Parameters
+///
+/// - `name`: The account to re-scan
+///
+/// # Request Body
+///
+/// See [`RescanRequest`]. An empty object re-scans from the birthday.
+///
+/// # Response
+///
+/// Returns `202 Accepted` with the account's [`ScanControlResponse`].
+///
+/// # Errors
+///
+/// - `404 Not Found`: account does not exist
+/// - `500`: database error
+///
+/// # Example Request
+///
+/// ```bash
+/// curl -X POST http://localhost:9000/accounts/default/rescan \
+///   -H "Content-Type: application/json" \
+///   -d '{"from_height": 150000}'
+/// ```
+#[utoipa::path(
+    post,
+    path = "/accounts/{name}/rescan",
+    request_body = RescanRequest,
+    responses(
+        (status = 202, description = "Re-scan scheduled", body = ScanControlResponse),
+        (status = 404, description = "Account not found", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError),
+    ),
+    params(
+        ("name" = String, Path, description = "Name of the account to re-scan"),
+    )
+)]
+pub async fn api_rescan_account(
+    State(app_state): State<AppState>,
+    Path(WalletParams { name }): Path<WalletParams>,
+    Json(body): Json<RescanRequest>,
+) -> Result<(StatusCode, Json<ScanControlResponse>), ApiError> {
+    info!(
+        target: "audit",
+        account = &*name,
+        from_height:? = body.from_height;
+        "API: Re-scan request"
+    );
+
+    let account = load_account(&app_state, name).await?;
+    // Rolling back to height 0 removes all scanned blocks, so the scanner restarts from the birthday
+    app_state
+        .scan_control
+        .request_rescan(account.id, body.from_height.unwrap_or(0));
+
+    Ok((StatusCode::ACCEPTED, Json(control_response(&app_state, &account))))
+}
+
+/// Pauses scanning of an account.
+///
+/// The running scan cycle is interrupted and subsequent cycles skip the account
+/// until it is resumed. Other accounts keep scanning. The paused state is kept in
+/// memory and is reset when the daemon restarts.
+///
+/// # Path Parameters
+///
+/// - `name`: The account to pause
+///
+/// # Errors
+///
+/// - `404 Not Found`: account does not exist
+/// - `500`: database error
+///
+/// # Example Request
+///
+/// ```bash
+/// curl -X POST http://localhost:9000/accounts/default/scan/pause
+/// ```
+#[utoipa::path(
+    post,
+    path = "/accounts/{name}/scan/pause",
+    responses(
+        (status = 200, description = "Scanning paused", body = ScanControlResponse),
+        (status = 404, description = "Account not found", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError),
+    ),
+    params(
+        ("name" = String, Path, description = "Name of the account to pause"),
+    )
+)]
+pub async fn api_pause_scan(
+    State(app_state): State<AppState>,
+    Path(WalletParams { name }): Path<WalletParams>,
+) -> Result<Json<ScanControlResponse>, ApiError> {
+    info!(target: "audit", account = &*name; "API: Pause scan request");
+
+    let account = load_account(&app_state, name).await?;
+    app_state.scan_control.pause(account.id);
+
+    Ok(Json(control_response(&app_state, &account)))
+}
+
+/// Resumes scanning of a paused account.
+///
+/// The daemon starts a new scan cycle straight away, which picks the account up
+/// from its last scanned block (or from a re-scan height requested while paused).
+/// Resuming an account that is not paused has no effect.
+///
+/// # Path Parameters
+///
+/// - `name`: The account to resume
+///
+/// # Errors
+///
+/// - `404 Not Found`: account does not exist
+/// - `500`: database error
+///
+/// # Example Request
+///
+/// ```bash
+/// curl -X POST http://localhost:9000/accounts/default/scan/resume
+/// ```
+#[utoipa::path(
+    post,
+    path = "/accounts/{name}/scan/resume",
+    responses(
+        (status = 200, description = "Scanning resumed", body = ScanControlResponse),
+        (status = 404, description = "Account not found", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError),
+    ),
+    params(
+        ("name" = String, Path, description = "Name of the account to resume"),
+    )
+)]
+pub async fn api_resume_scan(
+    State(app_state): State<AppState>,
+    Path(WalletParams { name }): Path<WalletParams>,
+) -> Result<Json<ScanControlResponse>, ApiError> {
+    info!(target: "audit", account = &*name; "API: Resume scan request");
+
+    let account = load_account(&app_state, name).await?;
+    app_state.scan_control.resume(account.id);
+
+    Ok(Json(control_response(&app_state, &account)))
+}
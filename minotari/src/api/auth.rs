@@ -0,0 +1,207 @@
+//! API key authentication for the REST API.
+//!
+//! Every endpoint except `/version`, the `/health` probes and the OpenAPI documentation
+//! requires a scope (see [`ApiKeyScope`]). Clients present a key either as a bearer token
+//! or in the `X-Api-Key` header:
+//!
+//! ```bash
+//! curl -H "Authorization: Bearer mtk_..." http://localhost:9000/accounts/default/balance
+//! curl -H "X-Api-Key: mtk_..." http://localhost:9000/accounts/default/balance
+//! ```
+//!
+//! Keys are created with `minotari api-key create`. Only a SHA-256 hash of each key is
+//! stored, so a lost key cannot be recovered and has to be revoked and replaced.
+//!
+//! # Unauthenticated Access
+//!
+//! To keep existing setups working, read-only requests without a key are allowed as long as
+//! no API key has ever been created and `require_api_key` is disabled. Endpoints that spend
+//! funds, decide on approvals or administer the wallet always require a key. As soon as the
+//! first key is created every request must be authenticated, even after all keys have been
+//! revoked.
+//!
+//! # Account Restrictions
+//!
+//! A key can be restricted to a set of accounts. Restricted keys are rejected with
+//! `403 Forbidden` on any endpoint whose `{name}` path parameter refers to another account,
+//! and on endpoints that are not tied to an account, such as webhook subscription management.
+//!
+//! The authenticated key is added to the request extensions as a [`DbApiKey`], so handlers
+//! can apply further checks.
+
+use axum::{
+    extract::{RawPathParams, Request, State},
+    http::{HeaderMap, header::AUTHORIZATION},
+    middleware::Next,
+    response::Response,
+};
+use log::{debug, info};
+use rand::{RngCore, rngs::OsRng};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    api::{AppState, error::ApiError},
+    db::{self, DbApiKey, get_account_by_name},
+    models::ApiKeyScope,
+};
+
+/// Prefix of every generated API key, making keys easy to spot in configs and logs.
+pub const API_KEY_PREFIX: &str = "mtk_";
+
+/// Number of leading key characters stored in clear text to identify a key.
+const DISPLAY_PREFIX_LEN: usize = 12;
+
+const API_KEY_HEADER: &str = "x-api-key";
+
+/// Generates a new random API key, returning the key and its display prefix.
+pub fn generate_api_key() -> (String, String) {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    let key = format!("{API_KEY_PREFIX}{}", hex::encode(bytes));
+    let display_prefix = key.chars().take(DISPLAY_PREFIX_LEN).collect();
+    (key, display_prefix)
+}
+
+/// Hashes an API key for storage and lookup.
+pub fn hash_api_key(key: &str) -> Vec<u8> {
+    Sha256::digest(key.as_bytes()).to_vec()
+}
+
+fn extract_api_key(headers: &HeaderMap) -> Option<&str> {
+    let bearer = headers
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    bearer
+        .or_else(|| headers.get(API_KEY_HEADER).and_then(|value| value.to_str().ok()))
+        .map(str::trim)
+        .filter(|key| !key.is_empty())
+}
+
+/// State of the [`authorize`] middleware: the scope required by the routes it guards.
+#[derive(Clone)]
+pub struct ScopeGuard {
+    app_state: AppState,
+    required_scope: ApiKeyScope,
+}
+
+impl ScopeGuard {
+    pub fn new(app_state: AppState, required_scope: ApiKeyScope) -> Self {
+        Self {
+            app_state,
+            required_scope,
+        }
+    }
+}
+
+/// Middleware checking the request's API key against the required scope and, for
+/// account-restricted keys, against the account named in the request path.
+///
+/// # Errors
+///
+/// - `401 Unauthorized`: the key is missing (while authentication is enforced), unknown or revoked
+/// - `403 Forbidden`: the key's scope is too low or it may not access the requested account
+pub async fn authorize(
+    State(guard): State<ScopeGuard>,
+    path_params: RawPathParams,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let presented_key = extract_api_key(request.headers()).map(hash_api_key);
+    let account_name = path_params
+        .iter()
+        .find(|(key, _)| *key == "name")
+        .map(|(_, value)| value.to_string());
+    let required_scope = guard.required_scope;
+    let require_api_key = guard.app_state.require_api_key;
+    let pool = guard.app_state.db_pool.clone();
+    let path = request.uri().path().to_string();
+
+    let api_key = tokio::task::spawn_blocking(move || {
+        let conn = pool.get().map_err(|e| ApiError::DbError(e.to_string()))?;
+
+        let Some(key_hash) = presented_key else {
+            if !require_api_key && required_scope == ApiKeyScope::ReadOnly && !db::has_api_keys(&conn)? {
+                debug!(path = &*path; "API: No API keys configured, allowing unauthenticated request");
+                return Ok(None);
+            }
+            return Err(ApiError::Unauthorized("Missing API key".to_string()));
+        };
+
+        let api_key = db::get_active_api_key_by_hash(&conn, &key_hash)?
+            .ok_or_else(|| ApiError::Unauthorized("Invalid or revoked API key".to_string()))?;
+
+        if !api_key.scope.allows(required_scope) {
+            return Err(ApiError::Forbidden(format!(
+                "API key '{}' has scope '{}', but '{}' is required",
+                api_key.name, api_key.scope, required_scope
+            )));
+        }
+
+        if api_key.account_ids.is_some()
+            && let Some(account_name) = &account_name
+        {
+            let allowed =
+                get_account_by_name(&conn, account_name)?.is_some_and(|account| api_key.allows_account(account.id));
+            if !allowed {
+                return Err(ApiError::Forbidden(format!(
+                    "API key '{}' may not access account '{}'",
+                    api_key.name, account_name
+                )));
+            }
+        }
+
+        db::touch_api_key(&conn, &api_key)?;
+        info!(
+            target: "audit",
+            api_key = &*api_key.name,
+            scope:% = api_key.scope,
+            path = &*path;
+            "API: Request authenticated"
+        );
+        Ok(Some(api_key))
+    })
+    .await
+    .map_err(|e| ApiError::InternalServerError(format!("Task join error: {}", e)))??;
+
+    if let Some(api_key) = api_key {
+        request.extensions_mut().insert(api_key);
+    }
+    Ok(next.run(request).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generated_keys_are_unique_and_prefixed() {
+        let (key, display_prefix) = generate_api_key();
+        let (other, _) = generate_api_key();
+        assert!(key.starts_with(API_KEY_PREFIX));
+        assert_eq!(key.len(), API_KEY_PREFIX.len() + 64);
+        assert!(key.starts_with(&display_prefix));
+        assert_eq!(display_prefix.len(), DISPLAY_PREFIX_LEN);
+        assert_ne!(key, other);
+        assert_ne!(hash_api_key(&key), hash_api_key(&other));
+    }
+
+    #[test]
+    fn test_extract_api_key_from_headers() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(extract_api_key(&headers), None);
+
+        headers.insert(API_KEY_HEADER, "mtk_header".parse().unwrap());
+        assert_eq!(extract_api_key(&headers), Some("mtk_header"));
+
+        headers.insert(AUTHORIZATION, "Bearer mtk_bearer".parse().unwrap());
+        assert_eq!(
+            extract_api_key(&headers),
+            Some("mtk_bearer"),
+            "bearer token takes precedence"
+        );
+
+        headers.insert(AUTHORIZATION, "Basic dXNlcjpwYXNz".parse().unwrap());
+        assert_eq!(extract_api_key(&headers), Some("mtk_header"));
+    }
+}
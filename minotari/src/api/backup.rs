@@ -0,0 +1,167 @@
+//! Encrypted wallet database backup endpoint.
+//!
+//! `POST /wallet/backup` takes a consistent snapshot of the running wallet's database and returns
+//! it encrypted with the password in the request body, in the same format as the `backup`
+//! command. The file can be restored with the `restore` command while the daemon is stopped.
+//!
+//! The snapshot is kept in a scratch file next to the database and encrypted chunk by chunk while
+//! the response is sent, so the database is never held in memory. If encryption fails midway,
+//! the response is aborted; the partial file fails to decrypt.
+//!
+//! The endpoint requires an `admin` API key that is not restricted to specific accounts, as the
+//! backup contains every account.
+
+use std::{
+    io::{self, Write},
+    path::Path,
+};
+
+use axum::{
+    Extension, Json,
+    body::Body,
+    extract::State,
+    http::header,
+    response::{IntoResponse, Response},
+};
+use chrono::Utc;
+use futures::stream;
+use log::{error, info};
+use serde::Deserialize;
+use tokio::sync::mpsc;
+use zeroize::Zeroizing;
+
+use crate::{
+    api::{AppState, error::ApiError},
+    backup,
+    db::DbApiKey,
+};
+
+/// Number of encrypted chunks buffered for a client that reads the backup slowly.
+const BODY_CHANNEL_CAPACITY: usize = 16;
+
+/// Request body for creating a backup.
+///
+/// # JSON Example
+///
+/// ```json
+/// {
+///   "password": "backup secret"
+/// }
+/// ```
+#[derive(Deserialize, utoipa::ToSchema)]
+pub struct BackupRequest {
+    /// Password to encrypt the backup with
+    pub password: String,
+}
+
+/// Creates an encrypted backup of the wallet database.
+///
+/// The snapshot is taken with SQLite's online backup API, so the daemon keeps scanning and
+/// serving requests while it is taken. The response is the backup file, which holds a manifest
+/// of the schema version, network and accounts that `restore` verifies.
+///
+/// # Errors
+///
+/// - `400 Bad Request`: empty password
+/// - `403 Forbidden`: API key restricted to specific accounts
+/// - `500`: the snapshot could not be taken or encrypted
+///
+/// # Example Request
+///
+/// ```bash
+/// curl -X POST http://localhost:9000/wallet/backup \
+///   -H "Authorization: Bearer mtk_..." \
+///   -H "Content-Type: application/json" \
+///   -d '{"password": "backup secret"}' \
+///   --output wallet.mtbak
+/// ```
+#[utoipa::path(
+    post,
+    path = "/wallet/backup",
+    request_body = BackupRequest,
+    responses(
+        (status = 200, description = "Encrypted backup file", body = Vec<u8>, content_type = "application/octet-stream"),
+        (status = 400, description = "Empty password", body = ApiError),
+        (status = 403, description = "API key restricted to specific accounts", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError),
+    )
+)]
+pub async fn api_backup(
+    State(app_state): State<AppState>,
+    api_key: Option<Extension<DbApiKey>>,
+    Json(body): Json<BackupRequest>,
+) -> Result<Response, ApiError> {
+    info!(target: "audit"; "API: Backup request");
+
+    if let Some(Extension(key)) = api_key
+        && key.account_ids.is_some()
+    {
+        return Err(ApiError::Forbidden(format!(
+            "API key '{}' is restricted to specific accounts and may not back up the wallet",
+            key.name
+        )));
+    }
+
+    let password = Zeroizing::new(body.password);
+    if password.is_empty() {
+        return Err(ApiError::BadRequest(
+            "The backup password must not be empty".to_string(),
+        ));
+    }
+
+    let pool = app_state.db_pool.clone();
+    let network = app_state.network;
+    let snapshot = tokio::task::spawn_blocking(move || {
+        let conn = pool.get().map_err(|e| ApiError::DbError(e.to_string()))?;
+        let scratch_dir = conn
+            .path()
+            .filter(|path| !path.is_empty())
+            .and_then(|path| Path::new(path).parent().map(Path::to_path_buf))
+            .unwrap_or_else(std::env::temp_dir);
+        backup::take_snapshot(&conn, network, &scratch_dir)
+            .map_err(|e| ApiError::InternalServerError(format!("Failed to create backup: {}", e)))
+    })
+    .await
+    .map_err(|e| ApiError::InternalServerError(format!("Task join error: {}", e)))??;
+
+    let (sender, receiver) = mpsc::channel(BODY_CHANNEL_CAPACITY);
+    tokio::task::spawn_blocking(move || {
+        if let Err(e) = snapshot.encrypt(&password, ChannelWriter(sender.clone())) {
+            error!(error:% = e; "API: Failed to stream the backup");
+            // Aborts the response instead of ending it as if the backup were complete
+            let _unused = sender.blocking_send(Err(io::Error::other(e.to_string())));
+        }
+    });
+    let body = Body::from_stream(stream::unfold(receiver, |mut receiver| async move {
+        receiver.recv().await.map(|chunk| (chunk, receiver))
+    }));
+
+    let disposition = format!(
+        "attachment; filename=\"minotari-backup-{}.mtbak\"",
+        Utc::now().format("%Y%m%dT%H%M%SZ")
+    );
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/octet-stream".to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        body,
+    )
+        .into_response())
+}
+
+/// Passes the encrypted backup to the response body as it is written.
+struct ChannelWriter(mpsc::Sender<Result<Vec<u8>, io::Error>>);
+
+impl Write for ChannelWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0
+            .blocking_send(Ok(buf.to_vec()))
+            .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "The client closed the connection"))?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
@@ -21,6 +21,10 @@
 //! | [`ApiError::InternalServerError`] | 500 Internal Server Error |
 //! | [`ApiError::DbError`] | 500 Internal Server Error |
 //! | [`ApiError::AccountNotFound`] | 404 Not Found |
+//! | [`ApiError::Unauthorized`] | 401 Unauthorized |
+//! | [`ApiError::Forbidden`] | 403 Forbidden |
+//! | [`ApiError::Conflict`] | 409 Conflict |
+//! | [`ApiError::AccountLocked`] | 423 Locked |
 //! | [`ApiError::FailedToLockFunds`] | 500 Internal Server Error |
 //! | [`ApiError::FailedCreateUnsignedTx`] | 500 Internal Server Error |
 //!
@@ -141,6 +145,48 @@ pub enum ApiError {
     #[error("{0}")]
     BadRequest(String),
 
+    /// The request carries no valid API key.
+    ///
+    /// Returns HTTP 401 Unauthorized.
+    ///
+    /// # Example
+    ///
+    /// ```json
+    /// {
+    ///   "error": "Invalid or revoked API key"
+    /// }
+    /// ```
+    #[error("Unauthorized: {0}")]
+    Unauthorized(String),
+
+    /// The API key is valid but may not perform the request.
+    ///
+    /// Returned when the key's scope is too low or the key is restricted to
+    /// other accounts. Returns HTTP 403 Forbidden.
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
+
+    /// The request conflicts with an existing resource.
+    ///
+    /// Returns HTTP 409 Conflict.
+    ///
+    /// # Example
+    ///
+    /// ```json
+    /// {
+    ///   "error": "A webhook subscription named 'accounting' already exists"
+    /// }
+    /// ```
+    #[error("{0}")]
+    Conflict(String),
+
+    /// The account's keys are not unlocked in the daemon.
+    ///
+    /// The contained string is the account name. Unlock the account with
+    /// `POST /accounts/{name}/unlock` first. Returns HTTP 423 Locked.
+    #[error("Account is locked: {0}")]
+    AccountLocked(String),
+
     /// Failed to lock funds for a transaction.
     ///
     /// This typically occurs when there are insufficient available funds
@@ -183,11 +229,15 @@ pub enum ApiError {
 
 /// Converts database errors into API errors.
 ///
-/// All database errors are wrapped as [`ApiError::DbError`] with the
-/// original error message preserved for debugging purposes.
+/// Duplicate entries become [`ApiError::Conflict`]. All other database errors are
+/// wrapped as [`ApiError::DbError`] with the original error message preserved for
+/// debugging purposes.
 impl From<WalletDbError> for ApiError {
     fn from(err: WalletDbError) -> Self {
-        ApiError::DbError(err.to_string())
+        match err {
+            WalletDbError::DuplicateEntry(msg) => ApiError::Conflict(msg),
+            err => ApiError::DbError(err.to_string()),
+        }
     }
 }
 
@@ -232,6 +282,10 @@ impl From<serde_json::Error> for ApiError {
 /// | `AccountNotFound` | 404 |
 /// | `NotFound` | 404 |
 /// | `BadRequest` | 400 |
+/// | `Unauthorized` | 401 |
+/// | `Forbidden` | 403 |
+/// | `Conflict` | 409 |
+/// | `AccountLocked` | 423 |
 /// | `FailedToLockFunds` | 500 |
 /// | `FailedCreateUnsignedTx` | 500 |
 impl IntoResponse for ApiError {
@@ -257,6 +311,25 @@ impl IntoResponse for ApiError {
                 warn!(message = msg.as_str(); "API: Bad Request");
                 (StatusCode::BAD_REQUEST, msg.clone())
             },
+            ApiError::Unauthorized(msg) => {
+                warn!(target: "audit", message = msg.as_str(); "API: Unauthorized");
+                (StatusCode::UNAUTHORIZED, msg.clone())
+            },
+            ApiError::Forbidden(msg) => {
+                warn!(target: "audit", message = msg.as_str(); "API: Forbidden");
+                (StatusCode::FORBIDDEN, msg.clone())
+            },
+            ApiError::Conflict(msg) => {
+                warn!(message = msg.as_str(); "API: Conflict");
+                (StatusCode::CONFLICT, msg.clone())
+            },
+            ApiError::AccountLocked(name) => {
+                warn!(account = name.as_str(); "API: Account Locked");
+                (
+                    StatusCode::LOCKED,
+                    format!("Account '{}' is locked, unlock it with its password first", name),
+                )
+            },
             ApiError::FailedToLockFunds(e) => {
                 error!(target: "audit", error = e.as_str(); "API: Failed to lock funds");
                 (StatusCode::INTERNAL_SERVER_ERROR, e.clone())
@@ -0,0 +1,184 @@
+//! Liveness and readiness probes.
+//!
+//! Both endpoints are unauthenticated, like `/version`, so that orchestrators such as
+//! Kubernetes can call them without an API key. They answer `200 OK` when healthy and
+//! `503 Service Unavailable` otherwise, with the individual checks in the body.
+//!
+//! - `GET /health/live` passes while the scanner loop, transaction unlocker, burn proof
+//!   worker and webhook worker are running and not stalled.
+//! - `GET /health/ready` additionally requires a reachable database, a successful last base
+//!   node request and every account that is not paused to be within `max_scan_lag_blocks`
+//!   of the chain tip. Accounts that are not scanned because they were never unlocked since
+//!   start-up are left out of the lag check.
+//!
+//! Accounts are identified by id only, as the probes do not require authentication.
+
+use axum::{Json, extract::State, http::StatusCode};
+use log::warn;
+use rusqlite::Connection;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::{
+    api::AppState,
+    db,
+    tasks::health::{BaseNodeHealth, TaskHealth},
+};
+
+/// Response of `GET /health/live`.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LivenessResponse {
+    /// Whether every background task is running and not stalled
+    pub live: bool,
+    /// State of each background task
+    pub tasks: Vec<TaskHealth>,
+}
+
+/// Scan lag of an account as reported by `GET /health/ready`.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AccountScanHealth {
+    pub account_id: i64,
+    /// Height of the last scanned block, if any
+    pub scanned_height: Option<u64>,
+    /// Number of blocks between the scanned height and the chain tip, if both are known
+    pub lag_blocks: Option<u64>,
+    /// Paused accounts are not required to keep up with the chain tip
+    pub paused: bool,
+    /// Whether the account's view key is cached, so that it is scanned. Accounts that were not
+    /// unlocked since start-up are not scanned and not required to keep up with the chain tip.
+    pub scannable: bool,
+    /// Whether the account is paused, not scannable or within the configured lag
+    pub ready: bool,
+}
+
+/// Response of `GET /health/ready`.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ReadinessResponse {
+    /// Whether every check passed
+    pub ready: bool,
+    /// State of each background task
+    pub tasks: Vec<TaskHealth>,
+    /// Whether a database connection could be opened and queried
+    pub database: bool,
+    /// Result of the last base node request, absent before the first scan cycle
+    pub base_node: Option<BaseNodeHealth>,
+    /// Maximum number of blocks an account may be behind the chain tip
+    pub max_scan_lag_blocks: u64,
+    /// Scan lag of each account
+    pub accounts: Vec<AccountScanHealth>,
+}
+
+fn status_code(healthy: bool) -> StatusCode {
+    if healthy {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    }
+}
+
+/// Reports whether the daemon's background tasks are alive.
+///
+/// A task that exited (e.g. after an error) or has not made progress within the configured
+/// stall timeout fails the probe, so the orchestrator can restart the daemon.
+#[utoipa::path(
+    get,
+    path = "/health/live",
+    responses(
+        (status = 200, description = "All background tasks are running", body = LivenessResponse),
+        (status = 503, description = "A background task stopped or stalled", body = LivenessResponse),
+    )
+)]
+pub async fn api_health_live(State(app_state): State<AppState>) -> (StatusCode, Json<LivenessResponse>) {
+    let tasks = app_state.health.tasks();
+    let live = tasks.iter().all(TaskHealth::is_healthy);
+    (status_code(live), Json(LivenessResponse { live, tasks }))
+}
+
+/// Reports whether the daemon is ready to serve requests with up-to-date wallet data.
+#[utoipa::path(
+    get,
+    path = "/health/ready",
+    responses(
+        (status = 200, description = "The daemon is ready", body = ReadinessResponse),
+        (status = 503, description = "A check failed", body = ReadinessResponse),
+    )
+)]
+pub async fn api_health_ready(State(app_state): State<AppState>) -> (StatusCode, Json<ReadinessResponse>) {
+    let tasks = app_state.health.tasks();
+    let base_node = app_state.health.base_node();
+    let max_scan_lag_blocks = app_state.health.max_scan_lag_blocks();
+    let tip_height = base_node.as_ref().and_then(|node| node.tip_height);
+    let scan_control = app_state.scan_control.clone();
+    let keyring = app_state.keyring.clone();
+
+    let pool = app_state.db_pool.clone();
+    let accounts = tokio::task::spawn_blocking(move || -> db::WalletDbResult<Vec<AccountScanHealth>> {
+        let conn = pool.get()?;
+        conn.query_row("SELECT 1", [], |_| Ok(()))?;
+        account_scan_health(
+            &conn,
+            tip_height,
+            max_scan_lag_blocks,
+            |account_id| scan_control.is_paused(account_id),
+            |account_id| keyring.can_scan(account_id),
+        )
+    })
+    .await;
+
+    let (database, accounts) = match accounts {
+        Ok(Ok(accounts)) => (true, accounts),
+        Ok(Err(e)) => {
+            warn!(error:% = e; "Health: Database check failed");
+            (false, Vec::new())
+        },
+        Err(e) => {
+            warn!(error:% = e; "Health: Database check task failed");
+            (false, Vec::new())
+        },
+    };
+
+    let ready = tasks.iter().all(TaskHealth::is_healthy)
+        && database
+        && base_node.as_ref().is_some_and(|node| node.reachable)
+        && accounts.iter().all(|account| account.ready);
+
+    (
+        status_code(ready),
+        Json(ReadinessResponse {
+            ready,
+            tasks,
+            database,
+            base_node,
+            max_scan_lag_blocks,
+            accounts,
+        }),
+    )
+}
+
+fn account_scan_health(
+    conn: &Connection,
+    tip_height: Option<u64>,
+    max_scan_lag_blocks: u64,
+    is_paused: impl Fn(i64) -> bool,
+    is_scannable: impl Fn(i64) -> bool,
+) -> db::WalletDbResult<Vec<AccountScanHealth>> {
+    db::get_accounts(conn, None)?
+        .into_iter()
+        .map(|account| {
+            let scanned_height = db::get_latest_scanned_tip_block_by_account(conn, account.id)?.map(|b| b.height);
+            let lag_blocks = tip_height
+                .zip(scanned_height)
+                .map(|(tip, scanned)| tip.saturating_sub(scanned));
+            let paused = is_paused(account.id);
+            let scannable = is_scannable(account.id);
+            Ok(AccountScanHealth {
+                account_id: account.id,
+                scanned_height,
+                lag_blocks,
+                paused,
+                scannable,
+                ready: paused || !scannable || lag_blocks.is_some_and(|lag| lag <= max_scan_lag_blocks),
+            })
+        })
+        .collect()
+}
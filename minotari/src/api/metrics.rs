@@ -0,0 +1,94 @@
+//! Prometheus metrics endpoint and API request timing.
+//!
+//! `GET /metrics` serves the daemon's [`WalletMetrics`] in the Prometheus text format. It
+//! requires a `read_only` API key that is not restricted to specific accounts, as the metrics
+//! cover every account. Scrapers can pass the key as a bearer token:
+//!
+//! ```yaml
+//! scrape_configs:
+//!   - job_name: minotari
+//!     authorization:
+//!       credentials: mtk_...
+//!     static_configs:
+//!       - targets: ["localhost:9000"]
+//! ```
+
+use std::time::Instant;
+
+use axum::{
+    Extension,
+    extract::{MatchedPath, Request, State},
+    http::header,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::{
+    api::{AppState, error::ApiError},
+    db::DbApiKey,
+    metrics::WalletMetrics,
+};
+
+/// Returns the wallet metrics in the Prometheus text exposition format.
+///
+/// Balances, scanned heights, transaction counts and the webhook queue depth are read
+/// from the database on every scrape.
+///
+/// # Errors
+///
+/// - [`ApiError::Forbidden`] if the API key is restricted to specific accounts
+/// - [`ApiError::DbError`] if the database gauges cannot be refreshed
+#[utoipa::path(
+    get,
+    path = "/metrics",
+    responses(
+        (status = 200, description = "Metrics in the Prometheus text format", body = String, content_type = "text/plain"),
+        (status = 403, description = "API key restricted to specific accounts", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError),
+    )
+)]
+pub async fn api_metrics(
+    State(app_state): State<AppState>,
+    api_key: Option<Extension<DbApiKey>>,
+) -> Result<impl IntoResponse, ApiError> {
+    if let Some(Extension(key)) = api_key
+        && key.account_ids.is_some()
+    {
+        return Err(ApiError::Forbidden(format!(
+            "API key '{}' is restricted to specific accounts and may not read wallet metrics",
+            key.name
+        )));
+    }
+
+    let pool = app_state.db_pool.clone();
+    let metrics = app_state.metrics.clone();
+    let body = tokio::task::spawn_blocking(move || {
+        let conn = pool.get().map_err(|e| ApiError::DbError(e.to_string()))?;
+        metrics.refresh_from_db(&conn)?;
+        metrics
+            .encode()
+            .map_err(|e| ApiError::InternalServerError(format!("Failed to encode metrics: {}", e)))
+    })
+    .await
+    .map_err(|e| ApiError::InternalServerError(format!("Task join error: {}", e)))??;
+
+    Ok(([(header::CONTENT_TYPE, WalletMetrics::content_type())], body))
+}
+
+/// Middleware recording the duration of every API request.
+///
+/// Requests are labelled with their route template (e.g. `/accounts/{name}/balance`) so
+/// that account names do not create a time series each.
+pub async fn record_request_duration(State(metrics): State<WalletMetrics>, request: Request, next: Next) -> Response {
+    let method = request.method().to_string();
+    let path = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| "unmatched".to_string());
+
+    let start = Instant::now();
+    let response = next.run(request).await;
+    metrics.observe_api_request(&method, &path, response.status().as_u16(), start.elapsed());
+    response
+}
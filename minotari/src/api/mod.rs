@@ -9,17 +9,38 @@
 //! The API exposes the following endpoints:
 //!
 //! - `GET /version` - Retrieve wallet version information
+//! - `GET /health/live` - Liveness of the daemon's background tasks (see [`health`])
+//! - `GET /health/ready` - Readiness including database, base node and scan lag checks
+//! - `GET|POST /accounts` - List accounts or create a new one
+//! - `POST /accounts/{name}/rename` - Rename an account
+//! - `POST /accounts/{name}/unlock` - Unlock an account's keys with its password
+//! - `POST /accounts/{name}/lock` - Lock an account, wiping its keys from memory
+//! - `DELETE /accounts/{name}?confirm={name}` - Delete an account and all of its data
 //! - `GET /accounts/{name}/balance` - Retrieve account balance
 //! - `GET /accounts/{name}/address` - Retrieve account Tari address
 //! - `POST /accounts/{name}/address_with_payment_id` - Create address with embedded payment ID
 //! - `GET /accounts/{name}/scan_status` - Retrieve last scanned block height and timestamp
+//! - `POST /accounts/{name}/rescan` - Re-scan an account from a height or its birthday
+//! - `POST /accounts/{name}/scan/pause` - Pause scanning of an account
+//! - `POST /accounts/{name}/scan/resume` - Resume scanning of an account
 //! - `GET /accounts/{name}/events` - Retrieve all wallet events for an account
+//! - `GET /accounts/{name}/events/stream` - Stream live scanner events as Server-Sent Events
+//! - `GET /accounts/{name}/events/ws` - Stream live scanner events over a WebSocket
 //! - `GET /accounts/{name}/completed_transactions` - Retrieve all completed transactions for an account
 //! - `GET /accounts/{name}/completed_transactions/by_payref/{payref}` - Retrieve completed transaction by payment reference
 //! - `GET /accounts/{name}/displayed_transactions` - Retrieve all displayed transactions for an account
 //! - `GET /accounts/{name}/displayed_transactions/by_payref/{payref}` - Retrieve displayed transactions by payment reference
 //! - `POST /accounts/{name}/lock_funds` - Lock UTXOs for transaction creation
 //! - `POST /accounts/{name}/create_unsigned_transaction` - Create an unsigned one-sided transaction
+//! - `GET /accounts/{name}/approvals` - List payments awaiting approval
+//! - `POST /accounts/{name}/approvals/{id}/approve|reject` - Approve or reject a payment awaiting approval
+//! - `GET|POST /webhooks/subscriptions` - List or create webhook subscriptions
+//! - `GET|PATCH /webhooks/subscriptions/{subscription}` - Retrieve or update a webhook subscription
+//! - `POST /webhooks/subscriptions/{subscription}/disable` - Disable a webhook subscription
+//! - `POST /webhooks/subscriptions/{subscription}/rotate_secret` - Rotate a subscription's signing secret
+//! - `GET /metrics` - Prometheus metrics (see [`metrics`])
+//! - `POST /wallet/change_password` - Re-encrypt accounts with a new password (see [`password`])
+//! - `POST /wallet/backup` - Download an encrypted backup of the wallet database (see [`backup`])
 //! - `GET /swagger-ui` - Interactive Swagger UI documentation
 //! - `GET /openapi.json` - OpenAPI specification in JSON format
 //!
@@ -36,13 +57,14 @@
 //! use minotari::init_db;
 //! use tari_common::configuration::Network;
 //! use std::path::PathBuf;
+//! use minotari::keyring::Keyring;
 //!
 //! # async fn example() -> anyhow::Result<()> {
-//! let db_pool = init_db(PathBuf::from("wallet.db"))?;
 //! let network = Network::Esmeralda;
-//! let password = "secure_password".to_string();
+//! let db_pool = init_db(PathBuf::from("wallet.db"), network)?;
+//! let keyring = Keyring::new();
 //!
-//! let router = create_router(db_pool, network, password);
+//! let router = create_router(db_pool, network, keyring);
 //!
 //! // Serve with axum
 //! let listener = tokio::net::TcpListener::bind("127.0.0.1:3000").await?;
@@ -53,40 +75,72 @@
 //!
 //! # Security Considerations
 //!
-//! - The password stored in [`AppState`] is used to decrypt wallet keys for transaction operations
+//! - Requests are authenticated with scoped API keys (see [`auth`]); keys are managed
+//!   with `minotari api-key` and stored hashed in the database
+//! - The passwords of unlocked accounts are held in the [`Keyring`] of the [`AppState`] and used to
+//!   decrypt wallet keys for transaction operations; locked accounts cannot spend
 //! - Fund locking prevents double-spending by temporarily reserving UTXOs
 //! - Idempotency keys can be used to prevent duplicate operations
 //! - All API errors are properly typed and do not leak sensitive information
 
-use axum::{Router, extract::FromRef, routing::get, routing::post};
+use axum::{Router, extract::FromRef, middleware, routing::delete, routing::get, routing::post};
 use log::info;
 use tari_common::configuration::Network;
 use utoipa::OpenApi;
 use utoipa_swagger_ui::SwaggerUi;
 
-use crate::db::SqlitePool;
+use crate::{
+    db::SqlitePool,
+    keyring::Keyring,
+    metrics::WalletMetrics,
+    models::ApiKeyScope,
+    scan::{EventBroadcaster, ScanControl, ScanProgressStore},
+    tasks::health::HealthMonitor,
+    webhooks::WebhookTriggerConfig,
+};
 
 pub mod accounts;
+pub mod auth;
+pub mod backup;
 mod error;
+pub mod health;
+pub mod metrics;
+pub mod password;
+pub mod server;
 pub mod types;
+pub mod webhooks;
 
 /// Application state shared across all API handlers.
 ///
 /// This state is cloned for each request and provides access to the database,
-/// network configuration, and wallet password for decrypting keys.
+/// network configuration, and keys of the unlocked accounts.
 ///
 /// # Fields
 ///
 /// * `db_pool` - SQLite connection pool for database operations
 /// * `network` - Tari network configuration (Esmeralda, Nextnet, Mainnet, etc.)
-/// * `password` - Password for decrypting wallet keys (stored in memory)
+/// * `keyring` - Passwords and view keys of unlocked accounts (wiped from memory when locked)
+/// * `scan_progress` - Live scan progress reported by the daemon's scanner
+/// * `scan_control` - Handle for pausing, resuming and re-scanning accounts in the daemon
+/// * `event_broadcaster` - Live scanner events for the streaming endpoints
+/// * `webhook_config` - Webhook from the config file, included when events are re-sent
+/// * `metrics` - Metrics served by `/metrics`
+/// * `health` - Task and base node state reported by the health endpoints
+/// * `require_api_key` - Reject unauthenticated requests even if no API key has been created yet
 #[derive(Clone)]
 pub struct AppState {
     pub db_pool: SqlitePool,
     pub network: Network,
-    pub password: String,
+    pub keyring: Keyring,
     pub required_confirmations: u64,
     pub base_node_url: String,
+    pub scan_progress: ScanProgressStore,
+    pub scan_control: ScanControl,
+    pub event_broadcaster: EventBroadcaster,
+    pub webhook_config: Option<WebhookTriggerConfig>,
+    pub metrics: WalletMetrics,
+    pub health: HealthMonitor,
+    pub require_api_key: bool,
 }
 
 impl FromRef<AppState> for SqlitePool {
@@ -107,24 +161,44 @@ impl FromRef<AppState> for SqlitePool {
 ///
 /// ## Paths (Endpoints)
 /// - `/version` - Get wallet version information
+/// - `/health/live`, `/health/ready` - Liveness and readiness probes
+/// - `/accounts` - List or create accounts
+/// - `/accounts/{name}` - Delete an account
+/// - `/accounts/{name}/rename` - Rename an account
 /// - `/accounts/{name}/balance` - Get account balance
 /// - `/accounts/{name}/address` - Get account address
 /// - `/accounts/{name}/address_with_payment_id` - Create address with payment ID
 /// - `/accounts/{name}/scan_status` - Get last scanned block info
+/// - `/accounts/{name}/rescan` - Re-scan an account
+/// - `/accounts/{name}/scan/pause` - Pause account scanning
+/// - `/accounts/{name}/scan/resume` - Resume account scanning
 /// - `/accounts/{name}/events` - Get wallet events
+/// - `/accounts/{name}/events/stream` - Stream scanner events (SSE)
+/// - `/accounts/{name}/events/ws` - Stream scanner events (WebSocket)
 /// - `/accounts/{name}/completed_transactions` - Get completed transactions
 /// - `/accounts/{name}/completed_transactions/by_payref/{payref}` - Get completed transaction by payment reference
 /// - `/accounts/{name}/displayed_transactions` - Get displayed transactions
 /// - `/accounts/{name}/displayed_transactions/by_payref/{payref}` - Get displayed transactions by payment reference
 /// - `/accounts/{name}/lock_funds` - Lock funds for transaction
 /// - `/accounts/{name}/create_unsigned_transaction` - Create unsigned transaction
+/// - `/accounts/{name}/approvals[/{id}/approve|/{id}/reject]` - List, approve or reject payments awaiting approval
+/// - `/webhooks/subscriptions[/{subscription}[/disable|/rotate_secret]]` - Manage webhook subscriptions
+/// - `/webhooks/deliveries[/{delivery}][/replay]` - Inspect and replay webhook deliveries
+/// - `/webhooks/events/{event_id}/resend` - Re-send a stored wallet event
+/// - `/metrics` - Prometheus metrics
+/// - `/accounts/{name}/unlock`, `/accounts/{name}/lock` - Unlock or lock an account's keys
+/// - `/wallet/change_password` - Change the wallet password
+/// - `/wallet/backup` - Download an encrypted database backup
 ///
 /// ## Schemas
 /// - `VersionResponse` - Wallet version information
+/// - `LivenessResponse`, `ReadinessResponse` - Results of the health probes
+/// - `AccountResponse`, `CreateAccountResponse` - Listed and newly created accounts
 /// - `AccountBalance` - Balance information with available/pending amounts
 /// - `AddressResponse` - Account address in Base58 with emoji ID
 /// - `AddressWithPaymentIdResponse` - Address with embedded payment ID
 /// - `DbWalletEvent` - Wallet event record with type, description and data
+/// - `StreamEvent` - Live scanner event delivered by the streaming endpoints
 /// - `CompletedTransactionResponse` - Completed transaction details
 /// - `ApiError` - Standardized error responses
 /// - `WalletParams` - Account name path parameter
@@ -136,15 +210,36 @@ impl FromRef<AppState> for SqlitePool {
 /// - `FeeEstimateResponse` - Fee estimation result
 /// - `FeePriorityResponse` - Fee priority enumeration
 /// - `EstimateFeeRequest` - Request body for fee estimation
+/// - `ApprovalRequestResponse`, `ApprovalDecisionResponse` - Payments awaiting approval and their decisions
+/// - `ApprovalDecisionRequest` - Request body for approving or rejecting a payment
+/// - `WebhookSubscriptionResponse` - Webhook subscription with its filters
+/// - `WebhookDeliveryResponse` - Webhook delivery with its status and last error
+/// - `UnlockAccountRequest`, `AccountLockResponse` - Account unlocking and locking
+/// - `ChangePasswordRequest`, `ChangePasswordResponse` - Wallet password rotation
+/// - `BackupRequest` - Request body for database backups
+/// - `WebhookPayload` - Versioned body posted to webhook receivers (not returned by any endpoint)
 #[derive(OpenApi)]
 #[openapi(
     paths(
         accounts::api_get_version,
+        health::api_health_live,
+        health::api_health_ready,
+        accounts::api_list_accounts,
+        accounts::api_create_account,
+        accounts::api_rename_account,
+        accounts::api_delete_account,
+        accounts::api_unlock_account,
+        accounts::api_lock_account,
         accounts::api_get_balance,
         accounts::api_get_address,
         accounts::api_create_address_with_payment_id,
         accounts::api_get_scan_status,
+        accounts::api_rescan_account,
+        accounts::api_pause_scan,
+        accounts::api_resume_scan,
         accounts::api_get_events,
+        accounts::api_stream_events,
+        accounts::api_events_websocket,
         accounts::api_get_completed_transactions,
         accounts::api_get_completed_transaction_by_payref,
         accounts::api_get_displayed_transactions,
@@ -153,13 +248,33 @@ impl FromRef<AppState> for SqlitePool {
         accounts::api_create_unsigned_transaction,
         accounts::api_estimate_fees,
         accounts::api_burn_funds,
+        accounts::api_list_approval_requests,
+        accounts::api_approve_transaction,
+        accounts::api_reject_transaction,
+        webhooks::api_list_webhook_subscriptions,
+        webhooks::api_create_webhook_subscription,
+        webhooks::api_get_webhook_subscription,
+        webhooks::api_update_webhook_subscription,
+        webhooks::api_disable_webhook_subscription,
+        webhooks::api_rotate_webhook_subscription_secret,
+        webhooks::api_list_webhook_deliveries,
+        webhooks::api_get_webhook_delivery,
+        webhooks::api_replay_webhook_delivery,
+        webhooks::api_replay_failed_webhook_deliveries,
+        webhooks::api_resend_webhook_event,
+        metrics::api_metrics,
+        password::api_change_password,
+        backup::api_backup,
     ),
     components(
         schemas(
             crate::db::AccountBalance,
             crate::db::DbWalletEvent,
+            crate::scan::StreamEvent,
             error::ApiError,
             accounts::WalletParams,
+            accounts::UnlockAccountRequest,
+            accounts::AccountLockResponse,
             accounts::LockFundsRequest,
             accounts::CreateTransactionRequest,
             accounts::CreatePaymentIdAddressRequest,
@@ -169,6 +284,10 @@ impl FromRef<AppState> for SqlitePool {
             crate::api::types::TariAddressBase58,
             crate::api::types::CompletedTransactionResponse,
             crate::api::types::ScanStatusResponse,
+            crate::api::types::ScanProgressResponse,
+            crate::scan::ScanState,
+            accounts::RescanRequest,
+            accounts::ScanControlResponse,
             crate::api::types::AddressResponse,
             crate::api::types::AddressWithPaymentIdResponse,
             crate::api::types::VersionResponse,
@@ -176,6 +295,21 @@ impl FromRef<AppState> for SqlitePool {
             crate::api::types::FeePriorityResponse,
             accounts::BurnFundsRequest,
             accounts::BurnFundsResponse,
+            accounts::ApprovalRequestResponse,
+            accounts::ApprovalDecisionResponse,
+            accounts::ApprovalDecisionRequest,
+            crate::models::ApprovalDecision,
+            accounts::AccountResponse,
+            accounts::CreateAccountRequest,
+            accounts::CreateAccountResponse,
+            accounts::RenameAccountRequest,
+            webhooks::CreateWebhookSubscriptionRequest,
+            webhooks::UpdateWebhookSubscriptionRequest,
+            webhooks::WebhookSubscriptionResponse,
+            webhooks::WebhookDeliveryResponse,
+            webhooks::ReplayFailedDeliveriesRequest,
+            webhooks::ResendEventRequest,
+            webhooks::WebhookDeliveriesReplayedResponse,
             crate::transactions::DisplayedTransaction,
             crate::transactions::TransactionDirection,
             crate::transactions::TransactionSource,
@@ -187,6 +321,17 @@ impl FromRef<AppState> for SqlitePool {
             crate::transactions::TransactionInput,
             crate::transactions::TransactionOutput,
             crate::models::OutputStatus,
+            crate::models::WebhookPayload,
+            crate::models::WebhookBalanceSnapshot,
+            crate::models::WebhookTransaction,
+            health::LivenessResponse,
+            health::ReadinessResponse,
+            health::AccountScanHealth,
+            crate::tasks::health::TaskHealth,
+            crate::tasks::health::BaseNodeHealth,
+            password::ChangePasswordRequest,
+            password::ChangePasswordResponse,
+            backup::BackupRequest,
         )
     ),
     tags(
@@ -198,16 +343,24 @@ pub struct ApiDoc;
 /// Creates and configures the API router with all endpoints and middleware.
 ///
 /// This function sets up the complete Axum router with:
-/// - All API endpoints for account operations
+/// - All API endpoints for account operations, guarded by API key scopes
 /// - Swagger UI at `/swagger-ui` for interactive API documentation
 /// - OpenAPI specification at `/openapi.json`
-/// - Shared application state containing database pool, network, and password
+/// - Shared application state containing database pool, network, and keyring
+/// - Request duration metrics for every route, served with the other metrics at `/metrics`
 ///
 /// # Parameters
 ///
 /// * `db_pool` - SQLite connection pool for database access
 /// * `network` - Tari network configuration (Esmeralda, Nextnet, Mainnet, etc.)
-/// * `password` - Password for decrypting wallet keys (kept in memory for API operations)
+/// * `keyring` - Keys of the unlocked accounts, shared with the daemon's scanner
+/// * `scan_progress` - Progress store fed by the daemon's scanner, reported by `/scan_status`
+/// * `scan_control` - Scan control shared with the daemon's scan loop
+/// * `event_broadcaster` - Scanner events fanned out to `/events/stream` and `/events/ws` clients
+/// * `webhook_config` - Webhook from the config file, used by `/webhooks/events/{event_id}/resend`
+/// * `metrics` - Metrics served by `/metrics`, also recording the duration of every request
+/// * `health` - Task and base node state reported by `/health/live` and `/health/ready`
+/// * `require_api_key` - Require an API key even while none has been created (see [`auth`])
 ///
 /// # Returns
 ///
@@ -222,8 +375,8 @@ pub struct ApiDoc;
 /// use std::path::PathBuf;
 ///
 /// # async fn example() -> anyhow::Result<()> {
-/// let db_pool = init_db(PathBuf::from("wallet.db"))?;
-/// let router = create_router(db_pool, Network::Esmeralda, "password".to_string(), 3);
+/// let db_pool = init_db(PathBuf::from("wallet.db"), Network::Esmeralda)?;
+/// let router = create_router(db_pool, Network::Esmeralda, Keyring::new(), 3);
 ///
 /// let listener = tokio::net::TcpListener::bind("0.0.0.0:3000").await?;
 /// axum::serve(listener, router).await?;
@@ -233,9 +386,16 @@ pub struct ApiDoc;
 pub fn create_router(
     db_pool: SqlitePool,
     network: Network,
-    password: String,
+    keyring: Keyring,
     required_confirmations: u64,
     base_node_url: String,
+    scan_progress: ScanProgressStore,
+    scan_control: ScanControl,
+    event_broadcaster: EventBroadcaster,
+    webhook_config: Option<WebhookTriggerConfig>,
+    metrics: WalletMetrics,
+    health: HealthMonitor,
+    require_api_key: bool,
 ) -> Router {
     info!(
         network:% = network;
@@ -245,14 +405,23 @@ pub fn create_router(
     let app_state = AppState {
         db_pool,
         network,
-        password,
+        keyring,
         required_confirmations,
         base_node_url,
+        scan_progress,
+        scan_control,
+        event_broadcaster,
+        webhook_config,
+        metrics: metrics.clone(),
+        health,
+        require_api_key,
     };
 
-    Router::new()
-        .merge(SwaggerUi::new("/swagger-ui").url("/openapi.json", ApiDoc::openapi()))
-        .route("/version", get(accounts::api_get_version))
+    // Every route except `/version`, the health probes and the documentation requires an API key with a minimum scope
+    let scope_layer =
+        |scope| middleware::from_fn_with_state(auth::ScopeGuard::new(app_state.clone(), scope), auth::authorize);
+
+    let read_only = Router::new()
         .route("/accounts/{name}/balance", get(accounts::api_get_balance))
         .route("/accounts/{name}/address", get(accounts::api_get_address))
         .route(
@@ -261,6 +430,8 @@ pub fn create_router(
         )
         .route("/accounts/{name}/scan_status", get(accounts::api_get_scan_status))
         .route("/accounts/{name}/events", get(accounts::api_get_events))
+        .route("/accounts/{name}/events/stream", get(accounts::api_stream_events))
+        .route("/accounts/{name}/events/ws", get(accounts::api_events_websocket))
         .route(
             "/accounts/{name}/completed_transactions",
             get(accounts::api_get_completed_transactions),
@@ -277,12 +448,88 @@ pub fn create_router(
             "/accounts/{name}/displayed_transactions/by_payref/{payref}",
             get(accounts::api_get_displayed_transactions_by_payref),
         )
+        .route("/accounts/{name}/estimate_fees", post(accounts::api_estimate_fees))
+        .route("/accounts/{name}/approvals", get(accounts::api_list_approval_requests))
+        .route("/metrics", get(metrics::api_metrics))
+        .route_layer(scope_layer(ApiKeyScope::ReadOnly));
+
+    let spend = Router::new()
         .route("/accounts/{name}/lock_funds", post(accounts::api_lock_funds))
         .route(
             "/accounts/{name}/create_unsigned_transaction",
             post(accounts::api_create_unsigned_transaction),
         )
-        .route("/accounts/{name}/estimate_fees", post(accounts::api_estimate_fees))
         .route("/accounts/{name}/burn", post(accounts::api_burn_funds))
+        .route(
+            "/accounts/{name}/approvals/{id}/approve",
+            post(accounts::api_approve_transaction),
+        )
+        .route(
+            "/accounts/{name}/approvals/{id}/reject",
+            post(accounts::api_reject_transaction),
+        )
+        .route_layer(scope_layer(ApiKeyScope::Spend));
+
+    let admin = Router::new()
+        .route(
+            "/accounts",
+            get(accounts::api_list_accounts).post(accounts::api_create_account),
+        )
+        .route("/accounts/{name}", delete(accounts::api_delete_account))
+        .route("/accounts/{name}/rename", post(accounts::api_rename_account))
+        .route("/accounts/{name}/unlock", post(accounts::api_unlock_account))
+        .route("/accounts/{name}/lock", post(accounts::api_lock_account))
+        .route("/accounts/{name}/rescan", post(accounts::api_rescan_account))
+        .route("/accounts/{name}/scan/pause", post(accounts::api_pause_scan))
+        .route("/accounts/{name}/scan/resume", post(accounts::api_resume_scan))
+        .route(
+            "/webhooks/subscriptions",
+            get(webhooks::api_list_webhook_subscriptions).post(webhooks::api_create_webhook_subscription),
+        )
+        .route(
+            "/webhooks/subscriptions/{subscription}",
+            get(webhooks::api_get_webhook_subscription).patch(webhooks::api_update_webhook_subscription),
+        )
+        .route(
+            "/webhooks/subscriptions/{subscription}/disable",
+            post(webhooks::api_disable_webhook_subscription),
+        )
+        .route(
+            "/webhooks/subscriptions/{subscription}/rotate_secret",
+            post(webhooks::api_rotate_webhook_subscription_secret),
+        )
+        .route("/webhooks/deliveries", get(webhooks::api_list_webhook_deliveries))
+        .route(
+            "/webhooks/deliveries/replay",
+            post(webhooks::api_replay_failed_webhook_deliveries),
+        )
+        .route(
+            "/webhooks/deliveries/{delivery}",
+            get(webhooks::api_get_webhook_delivery),
+        )
+        .route(
+            "/webhooks/deliveries/{delivery}/replay",
+            post(webhooks::api_replay_webhook_delivery),
+        )
+        .route(
+            "/webhooks/events/{event_id}/resend",
+            post(webhooks::api_resend_webhook_event),
+        )
+        .route("/wallet/change_password", post(password::api_change_password))
+        .route("/wallet/backup", post(backup::api_backup))
+        .route_layer(scope_layer(ApiKeyScope::Admin));
+
+    Router::new()
+        .merge(SwaggerUi::new("/swagger-ui").url("/openapi.json", ApiDoc::openapi()))
+        .route("/version", get(accounts::api_get_version))
+        .route("/health/live", get(health::api_health_live))
+        .route("/health/ready", get(health::api_health_ready))
+        .merge(read_only)
+        .merge(spend)
+        .merge(admin)
+        .layer(middleware::from_fn_with_state(
+            metrics,
+            metrics::record_request_duration,
+        ))
         .with_state(app_state)
 }
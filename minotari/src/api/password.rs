@@ -0,0 +1,176 @@
+//! Wallet password rotation endpoint.
+//!
+//! `POST /wallet/change_password` re-encrypts accounts with a new password in a single database
+//! transaction. With an `account` only that account is re-encrypted, otherwise every account
+//! encrypted with the current password, so that tenants sharing a daemon rotate their password
+//! independently. Unlocked accounts stay unlocked with the new password, so the daemon keeps
+//! working without a restart. A dry run only checks which accounts the current password decrypts.
+//!
+//! The endpoint requires an `admin` API key. Keys restricted to specific accounts must name one
+//! of their accounts.
+
+use axum::{Extension, Json, extract::State};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroizing;
+
+use crate::{
+    api::{AppState, error::ApiError},
+    db::{self, DbApiKey, WalletDbError},
+};
+
+/// Request body for changing the wallet password.
+///
+/// # JSON Example
+///
+/// ```json
+/// {
+///   "account": "customer-42",
+///   "current_password": "old secret",
+///   "new_password": "new secret",
+///   "dry_run": false
+/// }
+/// ```
+#[derive(Deserialize, utoipa::ToSchema)]
+pub struct ChangePasswordRequest {
+    /// Account to re-encrypt. If omitted, every account encrypted with the current password.
+    pub account: Option<String>,
+    /// Password the accounts are currently encrypted with
+    pub current_password: String,
+    /// Password to re-encrypt the accounts with
+    pub new_password: String,
+    /// Only check which accounts the current password decrypts
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// Result of a password change.
+///
+/// # JSON Example
+///
+/// ```json
+/// {
+///   "dry_run": false,
+///   "accounts": ["customer-42"]
+/// }
+/// ```
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct ChangePasswordResponse {
+    /// Whether the request was a dry run that changed nothing
+    pub dry_run: bool,
+    /// Accounts that would be (dry run) or were re-encrypted
+    pub accounts: Vec<String>,
+}
+
+/// Changes the password of an account, or of every account encrypted with the current password.
+///
+/// The accounts are decrypted with the current password before anything is written, and
+/// re-encrypted in one transaction, so a failure leaves the wallet unchanged.
+///
+/// # Errors
+///
+/// - `400 Bad Request`: empty new password or new password equal to the current one
+/// - `403 Forbidden`: the current password decrypts no account or not the given one, or an API
+///   key restricted to other accounts
+/// - `404 Not Found`: the given account does not exist
+/// - `500`: database error
+///
+/// # Example Request
+///
+/// ```bash
+/// curl -X POST http://localhost:9000/wallet/change_password \
+///   -H "Authorization: Bearer mtk_..." \
+///   -H "Content-Type: application/json" \
+///   -d '{"account": "customer-42", "current_password": "old secret", "new_password": "new secret"}'
+/// ```
+#[utoipa::path(
+    post,
+    path = "/wallet/change_password",
+    request_body = ChangePasswordRequest,
+    responses(
+        (status = 200, description = "Password changed or dry run passed", body = ChangePasswordResponse),
+        (status = 400, description = "Invalid new password", body = ApiError),
+        (status = 403, description = "Wrong current password or restricted API key", body = ApiError),
+        (status = 404, description = "Account not found", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError),
+    )
+)]
+pub async fn api_change_password(
+    State(app_state): State<AppState>,
+    api_key: Option<Extension<DbApiKey>>,
+    Json(body): Json<ChangePasswordRequest>,
+) -> Result<Json<ChangePasswordResponse>, ApiError> {
+    info!(target: "audit", account:? = body.account, dry_run = body.dry_run; "API: Change password request");
+
+    let current_password = Zeroizing::new(body.current_password);
+    let new_password = Zeroizing::new(body.new_password);
+    if new_password.is_empty() {
+        return Err(ApiError::BadRequest("The new password must not be empty".to_string()));
+    }
+    if new_password == current_password {
+        return Err(ApiError::BadRequest(
+            "The new password must differ from the current one".to_string(),
+        ));
+    }
+
+    let pool = app_state.db_pool.clone();
+    let dry_run = body.dry_run;
+    let account_name = body.account;
+    let password = new_password.clone();
+    let accounts = tokio::task::spawn_blocking(move || {
+        let mut conn = pool.get().map_err(|e| ApiError::DbError(e.to_string()))?;
+        if let Some(Extension(key)) = &api_key
+            && key.account_ids.is_some()
+        {
+            let allowed = match &account_name {
+                Some(name) => {
+                    db::get_account_by_name(&conn, name)?
+                        .ok_or_else(|| ApiError::AccountNotFound(name.clone()))?
+                        .id
+                },
+                None => {
+                    return Err(ApiError::Forbidden(format!(
+                        "API key '{}' is restricted to specific accounts and must name the account",
+                        key.name
+                    )));
+                },
+            };
+            if !key.allows_account(allowed) {
+                return Err(ApiError::Forbidden(format!(
+                    "API key '{}' may not access this account",
+                    key.name
+                )));
+            }
+        }
+
+        let tx = conn.transaction().map_err(|e| ApiError::DbError(e.to_string()))?;
+        let accounts = if dry_run {
+            db::find_accounts_decrypting(&tx, account_name.as_deref(), &current_password)
+        } else {
+            db::change_accounts_password(&tx, account_name.as_deref(), &current_password, &password)
+        }
+        .map_err(|e| match e {
+            WalletDbError::DecryptionFailed(message) => {
+                warn!(target: "audit", "API: Change password rejected, wrong current password");
+                ApiError::Forbidden(message)
+            },
+            WalletDbError::InvalidInput(_) => ApiError::AccountNotFound(account_name.clone().unwrap_or_default()),
+            e => e.into(),
+        })?;
+        tx.commit().map_err(|e| ApiError::DbError(e.to_string()))?;
+        Ok(accounts)
+    })
+    .await
+    .map_err(|e| ApiError::InternalServerError(format!("Task join error: {}", e)))??;
+
+    if !dry_run {
+        for account in &accounts {
+            app_state.keyring.update_password(account.id, &new_password);
+        }
+        info!(target: "audit", accounts = accounts.len(); "API: Account password changed");
+    }
+    Ok(Json(ChangePasswordResponse {
+        dry_run,
+        accounts: accounts.into_iter().map(|account| account.friendly_name).collect(),
+    }))
+}
@@ -0,0 +1,269 @@
+//! Listeners for the daemon's REST API.
+//!
+//! The router built by [`create_router`](super::create_router) can be served on any number of
+//! TCP addresses and, on Unix, on a Unix domain socket at the same time (see [`ApiServerConfig`]).
+//! TCP listeners are served over TLS when a certificate and key are configured; adding a client
+//! CA enables mutual TLS, rejecting clients without a certificate signed by that CA during the
+//! handshake.
+//!
+//! All listeners are bound before [`serve`] returns, so an unavailable address or an unreadable
+//! certificate fails daemon startup instead of a background task.
+//!
+//! # Example
+//!
+//! ```toml
+//! [wallet.api]
+//! bind_addresses = ["127.0.0.1", "10.0.0.5:9443"]
+//! unix_socket = "data/minotari.sock"
+//!
+//! [wallet.api.tls]
+//! cert_path = "certs/server.pem"
+//! key_path = "certs/server.key"
+//! client_ca_path = "certs/clients-ca.pem"
+//! ```
+
+use std::{
+    net::{IpAddr, SocketAddr},
+    path::Path,
+    sync::Arc,
+    time::Duration,
+};
+
+use anyhow::{Context, anyhow};
+use axum::Router;
+use axum_server::tls_rustls::RustlsConfig;
+use log::{error, info, warn};
+use rustls::{RootCertStore, ServerConfig, crypto::CryptoProvider, server::WebPkiClientVerifier};
+use rustls_pki_types::{CertificateDer, PrivateKeyDer, pem::PemObject};
+use tokio::{net::TcpListener, sync::broadcast, task::JoinHandle};
+
+use crate::config::defaults::{ApiServerConfig, ApiTlsConfig};
+
+/// Time given to open TLS connections to finish after shutdown was requested.
+const TLS_SHUTDOWN_GRACE_PERIOD: Duration = Duration::from_secs(10);
+
+/// Binds every configured listener and serves `router` on them until `shutdown_tx` fires.
+///
+/// Returns a handle that completes once all listeners have shut down.
+///
+/// # Errors
+///
+/// Fails if no listener is configured, an address is invalid or cannot be bound, or the
+/// TLS certificate, key or client CA cannot be loaded.
+pub async fn serve(
+    router: Router,
+    config: &ApiServerConfig,
+    default_port: u16,
+    shutdown_tx: &broadcast::Sender<()>,
+) -> Result<JoinHandle<()>, anyhow::Error> {
+    let addresses = resolve_bind_addresses(&config.bind_addresses, default_port)?;
+    if addresses.is_empty() && config.unix_socket.is_none() {
+        return Err(anyhow!("The API server has no bind address or Unix socket configured"));
+    }
+
+    let tls_config = config.tls.as_ref().map(load_tls_config).transpose()?;
+    let mtls = config.tls.as_ref().is_some_and(|tls| tls.client_ca_path.is_some());
+    let mut handles = Vec::new();
+
+    for addr in addresses {
+        let listener = TcpListener::bind(addr)
+            .await
+            .with_context(|| format!("Failed to bind API server to {}", addr))?;
+        let mut shutdown_rx = shutdown_tx.subscribe();
+
+        let handle = match &tls_config {
+            Some(tls_config) => {
+                info!(address:% = addr, mtls = mtls; "API server listening (HTTPS)");
+                let server_handle = axum_server::Handle::new();
+                let shutdown_handle = server_handle.clone();
+                tokio::spawn(async move {
+                    shutdown_rx.recv().await.ok();
+                    shutdown_handle.graceful_shutdown(Some(TLS_SHUTDOWN_GRACE_PERIOD));
+                });
+                let server = axum_server::from_tcp_rustls(listener.into_std()?, tls_config.clone())
+                    .handle(server_handle)
+                    .serve(router.clone().into_make_service());
+                tokio::spawn(async move {
+                    if let Err(e) = server.await {
+                        error!(address:% = addr, error:% = e; "API server failed");
+                    }
+                })
+            },
+            None => {
+                if !addr.ip().is_loopback() {
+                    warn!(
+                        address:% = addr;
+                        "API server is reachable from the network over plain HTTP. Configure TLS for production hosts."
+                    );
+                }
+                info!(address:% = addr; "API server listening");
+                let router = router.clone();
+                tokio::spawn(async move {
+                    let result = axum::serve(listener, router)
+                        .with_graceful_shutdown(async move {
+                            shutdown_rx.recv().await.ok();
+                        })
+                        .await;
+                    if let Err(e) = result {
+                        error!(address:% = addr, error:% = e; "API server failed");
+                    }
+                })
+            },
+        };
+        handles.push(handle);
+    }
+
+    if let Some(path) = &config.unix_socket {
+        handles.push(serve_unix_socket(router, path, shutdown_tx.subscribe())?);
+    }
+
+    Ok(tokio::spawn(async move {
+        for handle in handles {
+            if let Err(e) = handle.await {
+                error!(error:% = e; "API server task panicked");
+            }
+        }
+    }))
+}
+
+#[cfg(unix)]
+fn serve_unix_socket(
+    router: Router,
+    path: &Path,
+    mut shutdown_rx: broadcast::Receiver<()>,
+) -> Result<JoinHandle<()>, anyhow::Error> {
+    use std::os::unix::fs::FileTypeExt;
+
+    // A socket left behind by an unclean shutdown would make the bind fail
+    if let Ok(metadata) = std::fs::symlink_metadata(path) {
+        if !metadata.file_type().is_socket() {
+            return Err(anyhow!(
+                "Refusing to replace {} with the API socket: not a socket",
+                path.display()
+            ));
+        }
+        std::fs::remove_file(path).with_context(|| format!("Failed to remove stale socket {}", path.display()))?;
+    }
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let listener = tokio::net::UnixListener::bind(path)
+        .with_context(|| format!("Failed to bind API server to {}", path.display()))?;
+    info!(path:% = path.display(); "API server listening on Unix socket");
+
+    let path = path.to_path_buf();
+    Ok(tokio::spawn(async move {
+        let result = axum::serve(listener, router)
+            .with_graceful_shutdown(async move {
+                shutdown_rx.recv().await.ok();
+            })
+            .await;
+        if let Err(e) = result {
+            error!(path:% = path.display(), error:% = e; "API server failed");
+        }
+        std::fs::remove_file(&path).ok();
+    }))
+}
+
+#[cfg(not(unix))]
+fn serve_unix_socket(
+    _router: Router,
+    path: &Path,
+    _shutdown_rx: broadcast::Receiver<()>,
+) -> Result<JoinHandle<()>, anyhow::Error> {
+    Err(anyhow!(
+        "Cannot listen on {}: Unix sockets are not supported on this platform",
+        path.display()
+    ))
+}
+
+/// Resolves configured bind addresses, adding `default_port` to entries without a port.
+///
+/// Accepts `127.0.0.1`, `::1`, `[::1]`, `0.0.0.0:9000` and `[::]:9000`.
+pub fn resolve_bind_addresses(addresses: &[String], default_port: u16) -> Result<Vec<SocketAddr>, anyhow::Error> {
+    addresses
+        .iter()
+        .map(|address| {
+            let address = address.trim();
+            if let Ok(addr) = address.parse::<SocketAddr>() {
+                return Ok(addr);
+            }
+            let ip = address
+                .trim_start_matches('[')
+                .trim_end_matches(']')
+                .parse::<IpAddr>()
+                .map_err(|_| anyhow!("Invalid API bind address '{}'", address))?;
+            Ok(SocketAddr::new(ip, default_port))
+        })
+        .collect()
+}
+
+/// Builds the rustls server configuration from PEM files, enabling client certificate
+/// verification when a client CA is configured.
+fn load_tls_config(tls: &ApiTlsConfig) -> Result<RustlsConfig, anyhow::Error> {
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+
+    let certs = CertificateDer::pem_file_iter(&tls.cert_path)
+        .and_then(|certs| certs.collect::<Result<Vec<_>, _>>())
+        .with_context(|| format!("Failed to read TLS certificate {}", tls.cert_path.display()))?;
+    if certs.is_empty() {
+        return Err(anyhow!("No certificate found in {}", tls.cert_path.display()));
+    }
+    let key = PrivateKeyDer::from_pem_file(&tls.key_path)
+        .with_context(|| format!("Failed to read TLS private key {}", tls.key_path.display()))?;
+
+    let builder = ServerConfig::builder_with_provider(provider.clone()).with_safe_default_protocol_versions()?;
+    let builder = match &tls.client_ca_path {
+        Some(ca_path) => builder.with_client_cert_verifier(client_verifier(ca_path, provider)?),
+        None => builder.with_no_client_auth(),
+    };
+    let mut server_config = builder
+        .with_single_cert(certs, key)
+        .context("Invalid TLS certificate or private key")?;
+    server_config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+
+    Ok(RustlsConfig::from_config(Arc::new(server_config)))
+}
+
+fn client_verifier(
+    ca_path: &Path,
+    provider: Arc<CryptoProvider>,
+) -> Result<Arc<dyn rustls::server::danger::ClientCertVerifier>, anyhow::Error> {
+    let mut roots = RootCertStore::empty();
+    let certs = CertificateDer::pem_file_iter(ca_path)
+        .and_then(|certs| certs.collect::<Result<Vec<_>, _>>())
+        .with_context(|| format!("Failed to read client CA {}", ca_path.display()))?;
+    for cert in certs {
+        roots.add(cert)?;
+    }
+    if roots.is_empty() {
+        return Err(anyhow!("No CA certificate found in {}", ca_path.display()));
+    }
+    WebPkiClientVerifier::builder_with_provider(Arc::new(roots), provider)
+        .build()
+        .context("Invalid client CA")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_bind_addresses() {
+        let addresses = ["127.0.0.1", "0.0.0.0:8443", "::1", "[::1]", "[::]:9443"].map(String::from);
+        let resolved = resolve_bind_addresses(&addresses, 9000).unwrap();
+        assert_eq!(
+            resolved,
+            vec![
+                "127.0.0.1:9000".parse::<SocketAddr>().unwrap(),
+                "0.0.0.0:8443".parse().unwrap(),
+                "[::1]:9000".parse().unwrap(),
+                "[::1]:9000".parse().unwrap(),
+                "[::]:9443".parse().unwrap(),
+            ]
+        );
+
+        assert!(resolve_bind_addresses(&["localhost".to_string()], 9000).is_err());
+    }
+}
@@ -21,6 +21,8 @@ use tari_common_types::transaction::TxId;
 use tari_transaction_components::{tari_amount::MicroMinotari, transaction_components::WalletOutput};
 use utoipa::openapi::{Object, Schema, Type};
 
+use crate::scan::{ScanProgressSnapshot, ScanState};
+
 /// A wrapper type for [`TariAddress`] with Base58 serialization.
 ///
 /// This type provides JSON serialization and deserialization of Tari addresses
@@ -212,7 +214,8 @@ impl From<crate::db::CompletedTransaction> for CompletedTransactionResponse {
 /// API response type for the scan status endpoint.
 ///
 /// Contains information about the last scanned block including height,
-/// block hash, and the timestamp when it was scanned.
+/// block hash, and the timestamp when it was scanned. When the daemon is
+/// actively scanning the account, live progress is included as well.
 ///
 /// # JSON Example
 ///
@@ -220,7 +223,18 @@ impl From<crate::db::CompletedTransaction> for CompletedTransactionResponse {
 /// {
 ///   "last_scanned_height": 12345,
 ///   "last_scanned_block_hash": "abc123def456...",
-///   "scanned_at": "2024-01-15 10:30:00"
+///   "scanned_at": "2024-01-15 10:30:00",
+///   "progress": {
+///     "state": "scanning",
+///     "current_height": 12345,
+///     "tip_height": 20000,
+///     "blocks_remaining": 7655,
+///     "blocks_per_second": 42.5,
+///     "outputs_found": 7,
+///     "eta_seconds": 180,
+///     "updated_at": "2024-01-15T10:30:00Z"
+///   },
+///   "paused": false
 /// }
 /// ```
 #[derive(Debug, serde::Serialize, utoipa::ToSchema)]
@@ -231,6 +245,10 @@ pub struct ScanStatusResponse {
     pub last_scanned_block_hash: String,
     /// Timestamp when this block was scanned
     pub scanned_at: String,
+    /// Live scan progress, if a scan for this account has reported progress in this process
+    pub progress: Option<ScanProgressResponse>,
+    /// Whether scanning of this account has been paused through the API
+    pub paused: bool,
 }
 
 impl From<crate::db::LatestScannedBlock> for ScanStatusResponse {
@@ -239,6 +257,46 @@ impl From<crate::db::LatestScannedBlock> for ScanStatusResponse {
             last_scanned_height: block.height,
             last_scanned_block_hash: hex::encode(&block.hash),
             scanned_at: block.scanned_at,
+            progress: None,
+            paused: false,
+        }
+    }
+}
+
+/// Live progress of a running account scan.
+#[derive(Debug, serde::Serialize, utoipa::ToSchema)]
+pub struct ScanProgressResponse {
+    /// Current lifecycle state of the scan
+    pub state: ScanState,
+    /// The height the scan has reached
+    pub current_height: u64,
+    /// The chain tip height reported by the base node, if known
+    pub tip_height: Option<u64>,
+    /// Number of blocks remaining until the tip, if the tip is known
+    pub blocks_remaining: Option<u64>,
+    /// Scan throughput over a sliding window, in blocks per second
+    pub blocks_per_second: f64,
+    /// Number of wallet outputs found during the current scan session
+    pub outputs_found: u64,
+    /// Estimated number of seconds until the scan reaches the tip
+    pub eta_seconds: Option<u64>,
+    /// Timestamp of the last progress update
+    pub updated_at: String,
+}
+
+impl From<ScanProgressSnapshot> for ScanProgressResponse {
+    fn from(snapshot: ScanProgressSnapshot) -> Self {
+        Self {
+            state: snapshot.state,
+            current_height: snapshot.current_height,
+            tip_height: snapshot.tip_height,
+            blocks_remaining: snapshot
+                .tip_height
+                .map(|tip| tip.saturating_sub(snapshot.current_height)),
+            blocks_per_second: snapshot.blocks_per_second,
+            outputs_found: snapshot.outputs_found,
+            eta_seconds: snapshot.eta.map(|eta| eta.as_secs()),
+            updated_at: snapshot.updated_at.to_rfc3339(),
         }
     }
 }
@@ -0,0 +1,967 @@
+//! Webhook subscription management and delivery log endpoint handlers.
+//!
+//! Webhook subscriptions let several consumers (e.g. accounting, support and fraud
+//! teams) each receive their own feed of wallet events, with a separate URL, signing
+//! secret, event type filter and set of accounts. They are delivered alongside the
+//! webhook configured in `config.toml`.
+//!
+//! Every delivery is recorded in the webhook queue with its status, attempt count and last
+//! error until it is removed by the daemon's periodic clean-up. Deliveries that ran out of
+//! retries can be inspected and replayed, and any stored wallet event can be re-sent.
+//!
+//! All endpoints require an `admin` API key that is not restricted to specific accounts.
+//! Signing secrets are only returned when a subscription is created or its secret is rotated.
+//!
+//! # Endpoint Overview
+//!
+//! | Method | Path | Description |
+//! |--------|------|-------------|
+//! | GET | `/webhooks/subscriptions` | List subscriptions |
+//! | POST | `/webhooks/subscriptions` | Create a subscription |
+//! | GET | `/webhooks/subscriptions/{subscription}` | Retrieve a subscription |
+//! | PATCH | `/webhooks/subscriptions/{subscription}` | Update URL, filters or enabled state |
+//! | POST | `/webhooks/subscriptions/{subscription}/disable` | Stop deliveries to a subscription |
+//! | POST | `/webhooks/subscriptions/{subscription}/rotate_secret` | Replace the signing secret |
+//! | GET | `/webhooks/deliveries` | List deliveries by status, event type and date |
+//! | GET | `/webhooks/deliveries/{delivery}` | Retrieve a delivery with its last error |
+//! | POST | `/webhooks/deliveries/{delivery}/replay` | Send a delivery again |
+//! | POST | `/webhooks/deliveries/replay` | Send all failed deliveries since a time again |
+//! | POST | `/webhooks/events/{event_id}/resend` | Re-send a stored wallet event |
+
+use std::collections::HashMap;
+
+use axum::{
+    Extension, Json,
+    extract::{Path, Query, State},
+    http::StatusCode,
+};
+use chrono::{DateTime, Utc};
+use log::info;
+use rusqlite::Connection;
+use serde::{Deserialize, Deserializer, Serialize};
+use utoipa::IntoParams;
+
+use crate::{
+    api::{
+        AppState,
+        accounts::{DEFAULT_PAGE_LIMIT, MAX_PAGE_LIMIT},
+        error::ApiError,
+    },
+    db::{
+        self, DbApiKey, DbWebhookSubscription, NewWebhookSubscription, WalletDbError, WebhookDeliveryFilter,
+        WebhookSubscriptionUpdate,
+    },
+    webhooks::{
+        models::WebhookQueueItem,
+        utils::{generate_webhook_secret, resend_event, validate_event_types, validate_webhook_url},
+    },
+};
+
+/// Path parameter identifying a webhook subscription.
+#[derive(Debug, Deserialize)]
+pub struct SubscriptionParams {
+    /// Name of the subscription
+    pub subscription: String,
+}
+
+/// Distinguishes a missing field (`None`) from an explicit `null` (`Some(None)`).
+fn double_option<'de, T, D>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+/// Request body for creating a webhook subscription.
+///
+/// # JSON Example
+///
+/// ```json
+/// {
+///   "name": "accounting",
+///   "url": "https://accounting.example.com/minotari",
+///   "event_types": ["TransactionConfirmed", "OutputConfirmed"],
+///   "accounts": ["default"]
+/// }
+/// ```
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
+pub struct CreateWebhookSubscriptionRequest {
+    /// Unique name of the subscription
+    pub name: String,
+    /// HTTP(S) endpoint the events are posted to
+    pub url: String,
+    /// Signing secret. A random secret is generated if omitted.
+    pub secret: Option<String>,
+    /// Event types to deliver. All event types are delivered if omitted.
+    pub event_types: Option<Vec<String>>,
+    /// Accounts whose events are delivered. All accounts if omitted.
+    pub accounts: Option<Vec<String>>,
+}
+
+/// Request body for updating a webhook subscription.
+///
+/// Omitted fields are left unchanged. Setting `event_types` or `accounts` to `null`
+/// removes the filter, delivering all event types or all accounts.
+///
+/// # JSON Example
+///
+/// ```json
+/// {
+///   "event_types": null,
+///   "enabled": true
+/// }
+/// ```
+#[derive(Debug, Default, Deserialize, utoipa::ToSchema)]
+pub struct UpdateWebhookSubscriptionRequest {
+    /// New HTTP(S) endpoint
+    pub url: Option<String>,
+    /// New event type filter, `null` for all event types
+    #[serde(default, deserialize_with = "double_option")]
+    #[schema(value_type = Option<Vec<String>>, nullable)]
+    pub event_types: Option<Option<Vec<String>>>,
+    /// New account filter, `null` for all accounts
+    #[serde(default, deserialize_with = "double_option")]
+    #[schema(value_type = Option<Vec<String>>, nullable)]
+    pub accounts: Option<Option<Vec<String>>>,
+    /// Enables or disables deliveries
+    pub enabled: Option<bool>,
+}
+
+/// A webhook subscription.
+///
+/// # JSON Example
+///
+/// ```json
+/// {
+///   "name": "accounting",
+///   "url": "https://accounting.example.com/minotari",
+///   "event_types": ["TransactionConfirmed"],
+///   "accounts": ["default"],
+///   "enabled": true,
+///   "created_at": "2025-06-01 12:00:00",
+///   "updated_at": "2025-06-01 12:00:00",
+///   "secret": "whsec_4f9c..."
+/// }
+/// ```
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct WebhookSubscriptionResponse {
+    /// Unique name of the subscription
+    pub name: String,
+    /// HTTP(S) endpoint the events are posted to
+    pub url: String,
+    /// Event types delivered, `null` for all event types
+    pub event_types: Option<Vec<String>>,
+    /// Accounts whose events are delivered, `null` for all accounts
+    pub accounts: Option<Vec<String>>,
+    /// Whether events are currently delivered
+    pub enabled: bool,
+    pub created_at: String,
+    pub updated_at: String,
+    /// Signing secret, only included when the subscription is created or its secret rotated
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+}
+
+/// Rejects API keys restricted to specific accounts, as subscriptions are shared by all accounts.
+fn ensure_unrestricted(api_key: Option<Extension<DbApiKey>>) -> Result<(), ApiError> {
+    match api_key {
+        Some(Extension(key)) if key.account_ids.is_some() => Err(ApiError::Forbidden(format!(
+            "API key '{}' is restricted to specific accounts and may not manage webhook subscriptions",
+            key.name
+        ))),
+        _ => Ok(()),
+    }
+}
+
+fn resolve_account_ids(conn: &Connection, accounts: &[String]) -> Result<Vec<i64>, ApiError> {
+    accounts
+        .iter()
+        .map(|name| {
+            db::get_account_by_name(conn, name)?
+                .map(|account| account.id)
+                .ok_or_else(|| ApiError::AccountNotFound(name.clone()))
+        })
+        .collect()
+}
+
+fn to_response(
+    conn: &Connection,
+    subscription: DbWebhookSubscription,
+) -> Result<WebhookSubscriptionResponse, ApiError> {
+    let accounts = match &subscription.account_ids {
+        None => None,
+        Some(ids) => Some(
+            db::get_accounts(conn, None)?
+                .into_iter()
+                .filter(|account| ids.contains(&account.id))
+                .map(|account| account.friendly_name)
+                .collect(),
+        ),
+    };
+    Ok(WebhookSubscriptionResponse {
+        name: subscription.name,
+        url: subscription.url,
+        event_types: subscription.event_types,
+        accounts,
+        enabled: subscription.enabled,
+        created_at: subscription.created_at,
+        updated_at: subscription.updated_at,
+        secret: None,
+    })
+}
+
+fn load_subscription(conn: &Connection, name: &str) -> Result<DbWebhookSubscription, ApiError> {
+    db::get_webhook_subscription_by_name(conn, name)?
+        .ok_or_else(|| ApiError::NotFound(format!("Webhook subscription '{}' not found", name)))
+}
+
+async fn run_blocking<T, F>(app_state: &AppState, f: F) -> Result<T, ApiError>
+where
+    T: Send + 'static,
+    F: FnOnce(&Connection) -> Result<T, ApiError> + Send + 'static,
+{
+    let pool = app_state.db_pool.clone();
+    tokio::task::spawn_blocking(move || {
+        let conn = pool.get().map_err(|e| ApiError::DbError(e.to_string()))?;
+        f(&conn)
+    })
+    .await
+    .map_err(|e| ApiError::InternalServerError(format!("Task join error: {}", e)))?
+}
+
+/// Lists all webhook subscriptions, including disabled ones.
+///
+/// # Errors
+///
+/// - `403 Forbidden`: the API key is restricted to specific accounts
+/// - `500`: database error
+///
+/// # Example Request
+///
+/// ```bash
+/// curl -H "X-Api-Key: mtk_..." http://localhost:9000/webhooks/subscriptions
+/// ```
+#[utoipa::path(
+    get,
+    path = "/webhooks/subscriptions",
+    responses(
+        (status = 200, description = "Webhook subscriptions", body = [WebhookSubscriptionResponse]),
+        (status = 403, description = "API key restricted to specific accounts", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError),
+    )
+)]
+pub async fn api_list_webhook_subscriptions(
+    State(app_state): State<AppState>,
+    api_key: Option<Extension<DbApiKey>>,
+) -> Result<Json<Vec<WebhookSubscriptionResponse>>, ApiError> {
+    ensure_unrestricted(api_key)?;
+
+    let subscriptions = run_blocking(&app_state, |conn| {
+        db::get_webhook_subscriptions(conn)?
+            .into_iter()
+            .map(|subscription| to_response(conn, subscription))
+            .collect()
+    })
+    .await?;
+    Ok(Json(subscriptions))
+}
+
+/// Creates a webhook subscription.
+///
+/// The response contains the signing secret, which is not returned again. Payloads are
+/// signed exactly like those of the webhook configured in `config.toml` (see the
+/// `X-Minotari-Signature` header).
+///
+/// # Errors
+///
+/// - `400 Bad Request`: invalid URL, local event sink or unknown event type
+/// - `403 Forbidden`: the API key is restricted to specific accounts
+/// - `404 Not Found`: one of the accounts does not exist
+/// - `409 Conflict`: a subscription with this name already exists
+/// - `500`: database error
+///
+/// # Example Request
+///
+/// ```bash
+/// curl -X POST http://localhost:9000/webhooks/subscriptions \
+///   -H "X-Api-Key: mtk_..." \
+///   -H "Content-Type: application/json" \
+///   -d '{"name": "fraud", "url": "https://fraud.example.com/hook", "event_types": ["OutputDetected"]}'
+/// ```
+#[utoipa::path(
+    post,
+    path = "/webhooks/subscriptions",
+    request_body = CreateWebhookSubscriptionRequest,
+    responses(
+        (status = 201, description = "Subscription created", body = WebhookSubscriptionResponse),
+        (status = 400, description = "Invalid URL or event type", body = ApiError),
+        (status = 403, description = "API key restricted to specific accounts", body = ApiError),
+        (status = 404, description = "Account not found", body = ApiError),
+        (status = 409, description = "Subscription already exists", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError),
+    )
+)]
+pub async fn api_create_webhook_subscription(
+    State(app_state): State<AppState>,
+    api_key: Option<Extension<DbApiKey>>,
+    Json(body): Json<CreateWebhookSubscriptionRequest>,
+) -> Result<(StatusCode, Json<WebhookSubscriptionResponse>), ApiError> {
+    ensure_unrestricted(api_key)?;
+    info!(target: "audit", name = &*body.name, url = &*body.url; "API: Create webhook subscription request");
+
+    if body.name.trim().is_empty() {
+        return Err(ApiError::BadRequest("Subscription name must not be empty".to_string()));
+    }
+    validate_webhook_url(&body.url, false).map_err(ApiError::BadRequest)?;
+    if let Some(event_types) = &body.event_types {
+        validate_event_types(event_types).map_err(ApiError::BadRequest)?;
+    }
+
+    let response = run_blocking(&app_state, move |conn| {
+        let account_ids = body
+            .accounts
+            .as_deref()
+            .map(|accounts| resolve_account_ids(conn, accounts))
+            .transpose()?;
+        let secret = body.secret.unwrap_or_else(generate_webhook_secret);
+        db::insert_webhook_subscription(
+            conn,
+            &NewWebhookSubscription {
+                name: &body.name,
+                url: &body.url,
+                secret: &secret,
+                event_types: body.event_types.as_deref(),
+                account_ids: account_ids.as_deref(),
+            },
+        )?;
+
+        let mut response = to_response(conn, load_subscription(conn, &body.name)?)?;
+        response.secret = Some(secret);
+        Ok(response)
+    })
+    .await?;
+
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+/// Retrieves a webhook subscription.
+///
+/// # Errors
+///
+/// - `403 Forbidden`: the API key is restricted to specific accounts
+/// - `404 Not Found`: subscription does not exist
+/// - `500`: database error
+///
+/// # Example Request
+///
+/// ```bash
+/// curl -H "X-Api-Key: mtk_..." http://localhost:9000/webhooks/subscriptions/accounting
+/// ```
+#[utoipa::path(
+    get,
+    path = "/webhooks/subscriptions/{subscription}",
+    responses(
+        (status = 200, description = "Webhook subscription", body = WebhookSubscriptionResponse),
+        (status = 403, description = "API key restricted to specific accounts", body = ApiError),
+        (status = 404, description = "Subscription not found", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError),
+    ),
+    params(
+        ("subscription" = String, Path, description = "Name of the subscription"),
+    )
+)]
+pub async fn api_get_webhook_subscription(
+    State(app_state): State<AppState>,
+    api_key: Option<Extension<DbApiKey>>,
+    Path(SubscriptionParams { subscription }): Path<SubscriptionParams>,
+) -> Result<Json<WebhookSubscriptionResponse>, ApiError> {
+    ensure_unrestricted(api_key)?;
+
+    let response = run_blocking(&app_state, move |conn| {
+        to_response(conn, load_subscription(conn, &subscription)?)
+    })
+    .await?;
+    Ok(Json(response))
+}
+
+/// Updates the URL, event type filter, account filter or enabled state of a subscription.
+///
+/// # Errors
+///
+/// - `400 Bad Request`: invalid URL, local event sink or unknown event type
+/// - `403 Forbidden`: the API key is restricted to specific accounts
+/// - `404 Not Found`: subscription or one of the accounts does not exist
+/// - `500`: database error
+///
+/// # Example Request
+///
+/// ```bash
+/// curl -X PATCH http://localhost:9000/webhooks/subscriptions/accounting \
+///   -H "X-Api-Key: mtk_..." \
+///   -H "Content-Type: application/json" \
+///   -d '{"url": "https://accounting.example.com/v2/minotari", "accounts": null}'
+/// ```
+#[utoipa::path(
+    patch,
+    path = "/webhooks/subscriptions/{subscription}",
+    request_body = UpdateWebhookSubscriptionRequest,
+    responses(
+        (status = 200, description = "Subscription updated", body = WebhookSubscriptionResponse),
+        (status = 400, description = "Invalid URL or event type", body = ApiError),
+        (status = 403, description = "API key restricted to specific accounts", body = ApiError),
+        (status = 404, description = "Subscription or account not found", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError),
+    ),
+    params(
+        ("subscription" = String, Path, description = "Name of the subscription to update"),
+    )
+)]
+pub async fn api_update_webhook_subscription(
+    State(app_state): State<AppState>,
+    api_key: Option<Extension<DbApiKey>>,
+    Path(SubscriptionParams { subscription }): Path<SubscriptionParams>,
+    Json(body): Json<UpdateWebhookSubscriptionRequest>,
+) -> Result<Json<WebhookSubscriptionResponse>, ApiError> {
+    ensure_unrestricted(api_key)?;
+    info!(target: "audit", name = &*subscription; "API: Update webhook subscription request");
+
+    if let Some(url) = &body.url {
+        validate_webhook_url(url, false).map_err(ApiError::BadRequest)?;
+    }
+    if let Some(Some(event_types)) = &body.event_types {
+        validate_event_types(event_types).map_err(ApiError::BadRequest)?;
+    }
+
+    let response = run_blocking(&app_state, move |conn| {
+        let account_ids = match &body.accounts {
+            Some(Some(accounts)) => Some(Some(resolve_account_ids(conn, accounts)?)),
+            Some(None) => Some(None),
+            None => None,
+        };
+        let update = WebhookSubscriptionUpdate {
+            url: body.url.as_deref(),
+            event_types: body.event_types.as_ref().map(Option::as_deref),
+            account_ids: account_ids.as_ref().map(Option::as_deref),
+            enabled: body.enabled,
+        };
+        if !db::update_webhook_subscription(conn, &subscription, &update)? {
+            return Err(ApiError::NotFound(format!(
+                "Webhook subscription '{}' not found",
+                subscription
+            )));
+        }
+        to_response(conn, load_subscription(conn, &subscription)?)
+    })
+    .await?;
+    Ok(Json(response))
+}
+
+/// Disables a webhook subscription.
+///
+/// No new events are queued for a disabled subscription. Deliveries that are already
+/// queued are held back and sent if the subscription is enabled again.
+///
+/// # Errors
+///
+/// - `403 Forbidden`: the API key is restricted to specific accounts
+/// - `404 Not Found`: subscription does not exist
+/// - `500`: database error
+///
+/// # Example Request
+///
+/// ```bash
+/// curl -X POST -H "X-Api-Key: mtk_..." http://localhost:9000/webhooks/subscriptions/support/disable
+/// ```
+#[utoipa::path(
+    post,
+    path = "/webhooks/subscriptions/{subscription}/disable",
+    responses(
+        (status = 200, description = "Subscription disabled", body = WebhookSubscriptionResponse),
+        (status = 403, description = "API key restricted to specific accounts", body = ApiError),
+        (status = 404, description = "Subscription not found", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError),
+    ),
+    params(
+        ("subscription" = String, Path, description = "Name of the subscription to disable"),
+    )
+)]
+pub async fn api_disable_webhook_subscription(
+    State(app_state): State<AppState>,
+    api_key: Option<Extension<DbApiKey>>,
+    Path(SubscriptionParams { subscription }): Path<SubscriptionParams>,
+) -> Result<Json<WebhookSubscriptionResponse>, ApiError> {
+    api_update_webhook_subscription(
+        State(app_state),
+        api_key,
+        Path(SubscriptionParams { subscription }),
+        Json(UpdateWebhookSubscriptionRequest {
+            enabled: Some(false),
+            ..Default::default()
+        }),
+    )
+    .await
+}
+
+/// Replaces the signing secret of a webhook subscription with a new random secret.
+///
+/// The new secret takes effect immediately, including for deliveries that are already
+/// queued or being retried. It is returned in the response and cannot be retrieved later.
+///
+/// # Errors
+///
+/// - `403 Forbidden`: the API key is restricted to specific accounts
+/// - `404 Not Found`: subscription does not exist
+/// - `500`: database error
+///
+/// # Example Request
+///
+/// ```bash
+/// curl -X POST -H "X-Api-Key: mtk_..." http://localhost:9000/webhooks/subscriptions/support/rotate_secret
+/// ```
+#[utoipa::path(
+    post,
+    path = "/webhooks/subscriptions/{subscription}/rotate_secret",
+    responses(
+        (status = 200, description = "Secret rotated", body = WebhookSubscriptionResponse),
+        (status = 403, description = "API key restricted to specific accounts", body = ApiError),
+        (status = 404, description = "Subscription not found", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError),
+    ),
+    params(
+        ("subscription" = String, Path, description = "Name of the subscription"),
+    )
+)]
+pub async fn api_rotate_webhook_subscription_secret(
+    State(app_state): State<AppState>,
+    api_key: Option<Extension<DbApiKey>>,
+    Path(SubscriptionParams { subscription }): Path<SubscriptionParams>,
+) -> Result<Json<WebhookSubscriptionResponse>, ApiError> {
+    ensure_unrestricted(api_key)?;
+    info!(target: "audit", name = &*subscription; "API: Rotate webhook subscription secret request");
+
+    let response = run_blocking(&app_state, move |conn| {
+        let secret = generate_webhook_secret();
+        if !db::rotate_webhook_subscription_secret(conn, &subscription, &secret)? {
+            return Err(ApiError::NotFound(format!(
+                "Webhook subscription '{}' not found",
+                subscription
+            )));
+        }
+        let mut response = to_response(conn, load_subscription(conn, &subscription)?)?;
+        response.secret = Some(secret);
+        Ok(response)
+    })
+    .await?;
+    Ok(Json(response))
+}
+
+/// Path parameter identifying a webhook delivery.
+#[derive(Debug, Deserialize)]
+pub struct DeliveryParams {
+    /// Id of the delivery
+    pub delivery: i64,
+}
+
+/// Path parameter identifying a wallet event.
+#[derive(Debug, Deserialize)]
+pub struct EventIdParams {
+    /// Id of the wallet event
+    pub event_id: i64,
+}
+
+/// Query parameters for listing webhook deliveries.
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct WebhookDeliveryParams {
+    /// Only deliveries with this status (`pending`, `success`, `failed` or `permanent_failure`)
+    pub status: Option<String>,
+    /// Only deliveries of this event type
+    pub event_type: Option<String>,
+    /// Only deliveries queued at or after this RFC 3339 timestamp
+    #[param(value_type = Option<String>)]
+    pub since: Option<DateTime<Utc>>,
+    /// Only deliveries queued before this RFC 3339 timestamp
+    #[param(value_type = Option<String>)]
+    pub until: Option<DateTime<Utc>>,
+    /// Maximum number of deliveries to return (default: 50, max: 1000)
+    pub limit: Option<i64>,
+    /// Number of deliveries to skip for pagination (default: 0)
+    pub offset: Option<i64>,
+}
+
+/// Request body for replaying all failed deliveries since a point in time.
+///
+/// # JSON Example
+///
+/// ```json
+/// {
+///   "since": "2025-06-01T00:00:00Z"
+/// }
+/// ```
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
+pub struct ReplayFailedDeliveriesRequest {
+    /// Replay failed deliveries queued at or after this RFC 3339 timestamp
+    #[schema(value_type = String)]
+    pub since: DateTime<Utc>,
+}
+
+/// Request body for re-sending a stored wallet event.
+///
+/// # JSON Example
+///
+/// ```json
+/// {
+///   "subscription": "accounting"
+/// }
+/// ```
+#[derive(Debug, Default, Deserialize, utoipa::ToSchema)]
+pub struct ResendEventRequest {
+    /// Only send the event to this subscription. If omitted, the event is sent to every
+    /// receiver whose filters match it.
+    pub subscription: Option<String>,
+}
+
+/// Number of deliveries queued by a replay or resend request.
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct WebhookDeliveriesReplayedResponse {
+    /// Number of deliveries queued for sending
+    pub queued: usize,
+}
+
+/// A queued or past webhook delivery.
+///
+/// # JSON Example
+///
+/// ```json
+/// {
+///   "id": 17,
+///   "event_id": 42,
+///   "event_type": "TransactionConfirmed",
+///   "target_url": "https://accounting.example.com/minotari",
+///   "subscription": "accounting",
+///   "status": "permanent_failure",
+///   "attempt_count": 5,
+///   "last_error": "HTTP 500: Internal Server Error",
+///   "next_retry_at": "2025-06-01T13:30:00",
+///   "created_at": "2025-06-01T12:00:00",
+///   "payload": {"event_id": 42, "event_type": "TransactionConfirmed", "...": "..."}
+/// }
+/// ```
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct WebhookDeliveryResponse {
+    pub id: i64,
+    /// Wallet event the delivery was created for
+    pub event_id: Option<i64>,
+    pub event_type: String,
+    /// Where the delivery is sent: the subscription's current URL, or the `config.toml` webhook
+    /// URL at the time the event was queued
+    pub target_url: String,
+    /// Subscription the delivery belongs to, `null` for the webhook from `config.toml`
+    pub subscription: Option<String>,
+    /// `pending`, `success`, `failed` or `permanent_failure`
+    pub status: String,
+    /// Number of delivery attempts made so far
+    pub attempt_count: i32,
+    /// Error of the last failed attempt
+    pub last_error: Option<String>,
+    pub next_retry_at: String,
+    pub created_at: String,
+    /// The JSON body that is posted to the receiver
+    #[schema(value_type = Object)]
+    pub payload: serde_json::Value,
+}
+
+fn to_delivery_responses(
+    conn: &Connection,
+    items: Vec<WebhookQueueItem>,
+) -> Result<Vec<WebhookDeliveryResponse>, ApiError> {
+    let subscription_names: HashMap<i64, String> = db::get_webhook_subscriptions(conn)?
+        .into_iter()
+        .map(|subscription| (subscription.id, subscription.name))
+        .collect();
+
+    Ok(items
+        .into_iter()
+        .map(|item| WebhookDeliveryResponse {
+            id: item.id,
+            event_id: item.event_id,
+            event_type: item.event_type,
+            target_url: item.target_url,
+            subscription: item.subscription_id.and_then(|id| subscription_names.get(&id).cloned()),
+            status: item.status.to_string(),
+            attempt_count: item.attempt_count,
+            last_error: item.last_error,
+            next_retry_at: item.next_retry_at.to_string(),
+            created_at: item.created_at.to_string(),
+            payload: serde_json::from_str(&item.payload).unwrap_or(serde_json::Value::String(item.payload)),
+        })
+        .collect())
+}
+
+fn load_delivery(conn: &Connection, id: i64) -> Result<WebhookDeliveryResponse, ApiError> {
+    let item = db::get_webhook_delivery(conn, id)?
+        .ok_or_else(|| ApiError::NotFound(format!("Webhook delivery {} not found", id)))?;
+    to_delivery_responses(conn, vec![item])?
+        .pop()
+        .ok_or_else(|| ApiError::InternalServerError("Failed to build delivery response".to_string()))
+}
+
+/// Lists webhook deliveries, newest first.
+///
+/// Deliveries are kept until they are removed by the daemon's periodic clean-up, so
+/// this includes successful deliveries as well as failures that are no longer retried.
+///
+/// # Query Parameters
+///
+/// - `status`: `pending`, `success`, `failed` or `permanent_failure`
+/// - `event_type`: Only deliveries of this event type
+/// - `since` / `until`: RFC 3339 bounds on the time the delivery was queued
+/// - `limit`: Maximum number of deliveries to return (default: 50, max: 1000)
+/// - `offset`: Number of deliveries to skip for pagination (default: 0)
+///
+/// # Errors
+///
+/// - `400 Bad Request`: unknown status
+/// - `403 Forbidden`: the API key is restricted to specific accounts
+/// - `500`: database error
+///
+/// # Example Request
+///
+/// ```bash
+/// curl -H "X-Api-Key: mtk_..." \
+///   "http://localhost:9000/webhooks/deliveries?status=permanent_failure&since=2025-06-01T00:00:00Z"
+/// ```
+#[utoipa::path(
+    get,
+    path = "/webhooks/deliveries",
+    responses(
+        (status = 200, description = "Webhook deliveries", body = [WebhookDeliveryResponse]),
+        (status = 400, description = "Unknown status", body = ApiError),
+        (status = 403, description = "API key restricted to specific accounts", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError),
+    ),
+    params(WebhookDeliveryParams)
+)]
+pub async fn api_list_webhook_deliveries(
+    State(app_state): State<AppState>,
+    api_key: Option<Extension<DbApiKey>>,
+    Query(params): Query<WebhookDeliveryParams>,
+) -> Result<Json<Vec<WebhookDeliveryResponse>>, ApiError> {
+    ensure_unrestricted(api_key)?;
+
+    let filter = WebhookDeliveryFilter {
+        status: params
+            .status
+            .as_deref()
+            .map(str::parse)
+            .transpose()
+            .map_err(ApiError::BadRequest)?,
+        event_type: params.event_type,
+        since: params.since.map(|since| since.naive_utc()),
+        until: params.until.map(|until| until.naive_utc()),
+    };
+    let limit = params.limit.unwrap_or(DEFAULT_PAGE_LIMIT).min(MAX_PAGE_LIMIT);
+    let offset = params.offset.unwrap_or(0).max(0);
+
+    let deliveries = run_blocking(&app_state, move |conn| {
+        to_delivery_responses(conn, db::get_webhook_deliveries(conn, &filter, limit, offset)?)
+    })
+    .await?;
+    Ok(Json(deliveries))
+}
+
+/// Retrieves a webhook delivery, including its payload, attempt count and last error.
+///
+/// # Errors
+///
+/// - `403 Forbidden`: the API key is restricted to specific accounts
+/// - `404 Not Found`: delivery does not exist
+/// - `500`: database error
+///
+/// # Example Request
+///
+/// ```bash
+/// curl -H "X-Api-Key: mtk_..." http://localhost:9000/webhooks/deliveries/17
+/// ```
+#[utoipa::path(
+    get,
+    path = "/webhooks/deliveries/{delivery}",
+    responses(
+        (status = 200, description = "Webhook delivery", body = WebhookDeliveryResponse),
+        (status = 403, description = "API key restricted to specific accounts", body = ApiError),
+        (status = 404, description = "Delivery not found", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError),
+    ),
+    params(
+        ("delivery" = i64, Path, description = "Id of the delivery"),
+    )
+)]
+pub async fn api_get_webhook_delivery(
+    State(app_state): State<AppState>,
+    api_key: Option<Extension<DbApiKey>>,
+    Path(DeliveryParams { delivery }): Path<DeliveryParams>,
+) -> Result<Json<WebhookDeliveryResponse>, ApiError> {
+    ensure_unrestricted(api_key)?;
+
+    let response = run_blocking(&app_state, move |conn| load_delivery(conn, delivery)).await?;
+    Ok(Json(response))
+}
+
+/// Queues a webhook delivery to be sent again, whatever its current status.
+///
+/// The attempt count is reset, so the delivery gets the full number of retries. The
+/// original payload is sent, signed with the current secret of its subscription.
+///
+/// # Errors
+///
+/// - `403 Forbidden`: the API key is restricted to specific accounts
+/// - `404 Not Found`: delivery does not exist
+/// - `500`: database error
+///
+/// # Example Request
+///
+/// ```bash
+/// curl -X POST -H "X-Api-Key: mtk_..." http://localhost:9000/webhooks/deliveries/17/replay
+/// ```
+#[utoipa::path(
+    post,
+    path = "/webhooks/deliveries/{delivery}/replay",
+    responses(
+        (status = 200, description = "Delivery queued", body = WebhookDeliveryResponse),
+        (status = 403, description = "API key restricted to specific accounts", body = ApiError),
+        (status = 404, description = "Delivery not found", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError),
+    ),
+    params(
+        ("delivery" = i64, Path, description = "Id of the delivery to replay"),
+    )
+)]
+pub async fn api_replay_webhook_delivery(
+    State(app_state): State<AppState>,
+    api_key: Option<Extension<DbApiKey>>,
+    Path(DeliveryParams { delivery }): Path<DeliveryParams>,
+) -> Result<Json<WebhookDeliveryResponse>, ApiError> {
+    ensure_unrestricted(api_key)?;
+    info!(target: "audit", delivery = delivery; "API: Replay webhook delivery request");
+
+    let response = run_blocking(&app_state, move |conn| {
+        if !db::requeue_webhook(conn, delivery)? {
+            return Err(ApiError::NotFound(format!("Webhook delivery {} not found", delivery)));
+        }
+        load_delivery(conn, delivery)
+    })
+    .await?;
+    Ok(Json(response))
+}
+
+/// Queues all failed deliveries since a point in time to be sent again.
+///
+/// Both deliveries still being retried (`failed`) and those that ran out of retries
+/// (`permanent_failure`) are replayed, with their attempt count reset.
+///
+/// # Errors
+///
+/// - `403 Forbidden`: the API key is restricted to specific accounts
+/// - `500`: database error
+///
+/// # Example Request
+///
+/// ```bash
+/// curl -X POST http://localhost:9000/webhooks/deliveries/replay \
+///   -H "X-Api-Key: mtk_..." \
+///   -H "Content-Type: application/json" \
+///   -d '{"since": "2025-06-01T00:00:00Z"}'
+/// ```
+#[utoipa::path(
+    post,
+    path = "/webhooks/deliveries/replay",
+    request_body = ReplayFailedDeliveriesRequest,
+    responses(
+        (status = 200, description = "Failed deliveries queued", body = WebhookDeliveriesReplayedResponse),
+        (status = 403, description = "API key restricted to specific accounts", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError),
+    )
+)]
+pub async fn api_replay_failed_webhook_deliveries(
+    State(app_state): State<AppState>,
+    api_key: Option<Extension<DbApiKey>>,
+    Json(body): Json<ReplayFailedDeliveriesRequest>,
+) -> Result<Json<WebhookDeliveriesReplayedResponse>, ApiError> {
+    ensure_unrestricted(api_key)?;
+    info!(target: "audit", since:% = body.since; "API: Replay failed webhook deliveries request");
+
+    let queued = run_blocking(&app_state, move |conn| {
+        Ok(db::requeue_failed_webhooks(conn, body.since.naive_utc())?)
+    })
+    .await?;
+    Ok(Json(WebhookDeliveriesReplayedResponse { queued }))
+}
+
+/// Re-sends a stored wallet event to webhook receivers.
+///
+/// New deliveries are queued for every receiver whose filters match the event, or only
+/// for the given subscription. The payload carries the account's current balance rather
+/// than the balance at the time of the event.
+///
+/// # Errors
+///
+/// - `400 Bad Request`: the subscription is scoped to accounts other than the event's
+/// - `403 Forbidden`: the API key is restricted to specific accounts
+/// - `404 Not Found`: event or subscription does not exist
+/// - `500`: database error
+///
+/// # Example Request
+///
+/// ```bash
+/// curl -X POST http://localhost:9000/webhooks/events/42/resend \
+///   -H "X-Api-Key: mtk_..." \
+///   -H "Content-Type: application/json" \
+///   -d '{"subscription": "accounting"}'
+/// ```
+#[utoipa::path(
+    post,
+    path = "/webhooks/events/{event_id}/resend",
+    request_body = ResendEventRequest,
+    responses(
+        (status = 200, description = "Event queued for delivery", body = WebhookDeliveriesReplayedResponse),
+        (status = 400, description = "Subscription does not receive events of the event's account", body = ApiError),
+        (status = 403, description = "API key restricted to specific accounts", body = ApiError),
+        (status = 404, description = "Event or subscription not found", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError),
+    ),
+    params(
+        ("event_id" = i64, Path, description = "Id of the wallet event to re-send"),
+    )
+)]
+pub async fn api_resend_webhook_event(
+    State(app_state): State<AppState>,
+    api_key: Option<Extension<DbApiKey>>,
+    Path(EventIdParams { event_id }): Path<EventIdParams>,
+    Json(body): Json<ResendEventRequest>,
+) -> Result<Json<WebhookDeliveriesReplayedResponse>, ApiError> {
+    ensure_unrestricted(api_key)?;
+    info!(
+        target: "audit",
+        event_id = event_id,
+        subscription:? = body.subscription;
+        "API: Resend webhook event request"
+    );
+
+    let config = app_state.webhook_config.clone();
+    let queued = run_blocking(&app_state, move |conn| {
+        let subscription = body
+            .subscription
+            .as_deref()
+            .map(|name| load_subscription(conn, name))
+            .transpose()?;
+        resend_event(conn, event_id, config.as_ref(), subscription.as_ref())
+            .map_err(|e| match e {
+                WalletDbError::InvalidInput(msg) => ApiError::BadRequest(msg),
+                e => e.into(),
+            })?
+            .ok_or_else(|| ApiError::NotFound(format!("Wallet event {} not found", event_id)))
+    })
+    .await?;
+    Ok(Json(WebhookDeliveriesReplayedResponse { queued }))
+}
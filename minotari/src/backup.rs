@@ -0,0 +1,806 @@
+//! Encrypted backups of the wallet database.
+//!
+//! A backup is a consistent snapshot of the whole SQLite database, taken with SQLite's online
+//! backup API so that it can be created while the daemon keeps writing in WAL mode. The
+//! snapshot is encrypted with a key derived from a backup password (Argon2id and
+//! XChaCha20-Poly1305, see [`crate::utils::crypto`]) together with a [`BackupManifest`]
+//! describing its contents.
+//!
+//! # File Format
+//!
+//! | Bytes | Content |
+//! |-------|---------|
+//! | 8 | [`BACKUP_MAGIC`] |
+//! | 1 | Length `n` of the salt |
+//! | `n` | Argon2 salt |
+//! | 19 | Nonce prefix |
+//! | rest | Ciphertext chunks |
+//!
+//! The plaintext holds the length of the manifest JSON as a little-endian `u32`, the manifest
+//! JSON and the SQLite database file. It is encrypted in chunks of [`STREAM_CHUNK_SIZE`] bytes,
+//! each followed by its 16-byte tag, with XChaCha20-Poly1305 in the STREAM construction (see
+//! [`StreamEncryptor`]), so that backups are written and restored without holding the database
+//! in memory. The last chunk is shorter than the others, possibly empty, so a truncated backup
+//! fails to decrypt.
+//!
+//! Backups of the first format version, [`BACKUP_MAGIC_V1`], encrypted the plaintext in one
+//! piece after a 24-byte nonce. They can still be restored.
+//!
+//! # Restoring
+//!
+//! [`restore_backup`] decrypts the backup into a private scratch file next to the target
+//! database and checks it before replacing anything: SQLite's integrity check
+//! must pass, and the schema version, accounts, fingerprints and scanned heights must match the
+//! manifest. The daemon must be stopped while a backup is restored.
+
+use std::{
+    fs,
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use anyhow::{Context, anyhow, bail};
+use chrono::{DateTime, Utc};
+use log::{info, warn};
+use rusqlite::{Connection, backup::Backup};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use tari_common::configuration::Network;
+use zeroize::Zeroizing;
+
+use crate::{
+    db,
+    utils::crypto::{
+        FullEncryptedData, STREAM_CHUNK_SIZE, STREAM_NONCE_PREFIX_LENGTH, STREAM_TAG_LENGTH, StreamDecryptor,
+        StreamEncryptor, decrypt_data, encrypt_data,
+    },
+};
+
+/// Magic bytes at the start of every backup file, ending in the format version.
+pub const BACKUP_MAGIC: &[u8; 8] = b"MTRBAK02";
+
+/// Magic bytes of backups of the first format version, which are encrypted in one piece.
+pub const BACKUP_MAGIC_V1: &[u8; 8] = b"MTRBAK01";
+
+const NONCE_LENGTH: usize = 24;
+
+/// Size of a full ciphertext chunk of a backup.
+const CIPHERTEXT_CHUNK_SIZE: u64 = (STREAM_CHUNK_SIZE + STREAM_TAG_LENGTH) as u64;
+
+/// Description of a backup, stored encrypted alongside the database snapshot.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupManifest {
+    /// When the snapshot was taken
+    pub created_at: DateTime<Utc>,
+    /// Schema version of the snapshot, i.e. the number of applied migrations
+    pub schema_version: i64,
+    /// Network the wallet was used on
+    pub network: String,
+    /// Version of the wallet that created the backup
+    pub wallet_version: String,
+    /// Accounts contained in the snapshot
+    pub accounts: Vec<BackupAccount>,
+}
+
+/// An account contained in a backup.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupAccount {
+    /// Name of the account
+    pub name: String,
+    /// Hex-encoded fingerprint of the account's keys
+    pub fingerprint: String,
+    /// Height of the last scanned block, if the account has been scanned
+    pub scanned_height: Option<u64>,
+}
+
+/// Takes a consistent snapshot of the database `conn` is connected to and writes it to `output`,
+/// encrypted with `password`.
+///
+/// The snapshot is written to a private scratch file in `scratch_dir` while it is taken, and
+/// encrypted from there in chunks. The scratch file is removed afterwards. Returns the manifest.
+///
+/// # Errors
+///
+/// Fails if the snapshot cannot be taken or read, or encryption or writing to `output` fails.
+pub fn create_backup<W: Write>(
+    conn: &Connection,
+    network: Network,
+    password: &str,
+    scratch_dir: &Path,
+    output: W,
+) -> anyhow::Result<BackupManifest> {
+    if password.is_empty() {
+        bail!("The backup password must not be empty");
+    }
+    take_snapshot(conn, network, scratch_dir)?.encrypt(password, output)
+}
+
+/// A consistent snapshot of the wallet database, kept unencrypted in a private scratch file
+/// until it is encrypted with [`Snapshot::encrypt`].
+pub struct Snapshot {
+    manifest: BackupManifest,
+    file: ScratchFile,
+}
+
+/// Takes a consistent snapshot of the database `conn` is connected to, into a private scratch
+/// file in `scratch_dir`.
+///
+/// # Errors
+///
+/// Fails if the scratch file cannot be created or the snapshot cannot be taken.
+pub fn take_snapshot(conn: &Connection, network: Network, scratch_dir: &Path) -> anyhow::Result<Snapshot> {
+    let file = ScratchFile::new(scratch_dir, "snapshot")?;
+    let manifest = snapshot(conn, file.path(), network)?;
+    Ok(Snapshot { manifest, file })
+}
+
+impl Snapshot {
+    /// Encrypts the snapshot with `password` into `output`, one chunk at a time, and removes the
+    /// scratch file. Returns the manifest.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the password is empty, the snapshot cannot be read, or encryption or writing to
+    /// `output` fails.
+    pub fn encrypt<W: Write>(self, password: &str, output: W) -> anyhow::Result<BackupManifest> {
+        if password.is_empty() {
+            bail!("The backup password must not be empty");
+        }
+
+        let mut database = fs::File::open(self.file.path()).context("Failed to read the database snapshot")?;
+        let mut writer = SealWriter::new(BACKUP_MAGIC, &self.manifest, password, output)?;
+        io::copy(&mut database, &mut writer).context("Failed to write the backup")?;
+        writer.finish().context("Failed to write the backup")?;
+
+        info!(
+            target: "audit",
+            accounts = self.manifest.accounts.len(),
+            schema_version = self.manifest.schema_version;
+            "Wallet database backup created"
+        );
+        Ok(self.manifest)
+    }
+}
+
+/// Writes an encrypted backup of the database at `database_file` to `output`.
+///
+/// # Errors
+///
+/// Fails if the database cannot be opened, the backup cannot be created, or `output` exists.
+/// A partly written `output` is removed.
+pub fn backup_to_file(
+    database_file: &Path,
+    network: Network,
+    password: &str,
+    output: &Path,
+) -> anyhow::Result<BackupManifest> {
+    if output.exists() {
+        bail!("{} already exists", output.display());
+    }
+    let pool = db::init_db(database_file.to_path_buf(), network)?;
+    let conn = pool.get()?;
+    let file = fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(output)
+        .with_context(|| format!("Failed to create {}", output.display()))?;
+
+    let written = create_backup(&conn, network, password, &parent_dir(output), BufWriter::new(file));
+    if written.is_err() {
+        let _unused = fs::remove_file(output);
+    }
+    written
+}
+
+/// Decrypts a backup file held in memory and returns its manifest and database snapshot.
+///
+/// [`restore_backup`] decrypts backup files without reading them into memory.
+///
+/// # Errors
+///
+/// Fails if the file is not a backup, the password is wrong or the file was modified.
+pub fn decrypt_backup(file: &[u8], password: &str) -> anyhow::Result<(BackupManifest, Vec<u8>)> {
+    let (manifest, mut database) = open_backup(file, password)?;
+    let mut snapshot = Vec::new();
+    database
+        .read_to_end(&mut snapshot)
+        .context("Wrong backup password or corrupted backup file")?;
+    Ok((manifest, snapshot))
+}
+
+/// Reads the manifest of a backup of either format version from `input` and returns it together
+/// with a reader of the database snapshot, which is decrypted as it is read.
+fn open_backup<'a, R: Read + 'a>(mut input: R, password: &str) -> anyhow::Result<(BackupManifest, Box<dyn Read + 'a>)> {
+    let mut magic = [0u8; 8];
+    input
+        .read_exact(&mut magic)
+        .map_err(|_| anyhow!("Not a wallet backup file or unsupported backup version"))?;
+    // Puts the magic bytes back in front of the rest of the file
+    let mut input = io::Cursor::new(magic).chain(input);
+
+    if &magic == BACKUP_MAGIC_V1 {
+        let mut file = Vec::new();
+        input.read_to_end(&mut file).context("Failed to read the backup")?;
+        let (manifest, database) = open(BACKUP_MAGIC_V1, "backup", &file, password)?;
+        return Ok((manifest, Box::new(io::Cursor::new(database))));
+    }
+    let (manifest, reader) = OpenReader::new(BACKUP_MAGIC, "backup", input, password)?;
+    Ok((manifest, Box::new(reader)))
+}
+
+/// Writes the chunked container of backup files (see the [module documentation](self)),
+/// encrypting the plaintext as it is written. [`SealWriter::finish`] ends the stream.
+struct SealWriter<W: Write> {
+    output: W,
+    encryptor: StreamEncryptor,
+    chunk: Zeroizing<Vec<u8>>,
+}
+
+impl<W: Write> SealWriter<W> {
+    /// Writes the container's prefix to `output`, followed by `header` as JSON.
+    fn new<H: Serialize>(magic: &[u8; 8], header: &H, password: &str, mut output: W) -> anyhow::Result<Self> {
+        let encryptor = StreamEncryptor::new(password)?;
+        let salt_length = u8::try_from(encryptor.salt().len()).context("Salt too long")?;
+        output.write_all(magic)?;
+        output.write_all(&[salt_length])?;
+        output.write_all(encryptor.salt())?;
+        output.write_all(encryptor.nonce_prefix())?;
+
+        let mut writer = Self {
+            output,
+            encryptor,
+            chunk: Zeroizing::new(Vec::with_capacity(STREAM_CHUNK_SIZE)),
+        };
+        let header_json = Zeroizing::new(serde_json::to_vec(header)?);
+        let header_length = u32::try_from(header_json.len()).context("Header too large")?;
+        writer.write_all(&header_length.to_le_bytes())?;
+        writer.write_all(&header_json)?;
+        Ok(writer)
+    }
+
+    /// Encrypts the remaining plaintext as the last chunk and returns the output.
+    fn finish(mut self) -> anyhow::Result<W> {
+        let last = self.encryptor.encrypt_chunk(&self.chunk, true)?;
+        self.output.write_all(&last)?;
+        self.output.flush()?;
+        Ok(self.output)
+    }
+}
+
+impl<W: Write> Write for SealWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let (taken, _) = buf.split_at(buf.len().min(STREAM_CHUNK_SIZE - self.chunk.len()));
+        self.chunk.extend_from_slice(taken);
+        if self.chunk.len() == STREAM_CHUNK_SIZE {
+            let ciphertext = self
+                .encryptor
+                .encrypt_chunk(&self.chunk, false)
+                .map_err(io::Error::other)?;
+            self.output.write_all(&ciphertext)?;
+            self.chunk.clear();
+        }
+        Ok(taken.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        // A partial chunk is only encrypted by `finish`, as the last one
+        self.output.flush()
+    }
+}
+
+/// Reads the chunked container written by [`SealWriter`], decrypting the plaintext as it is read.
+struct OpenReader<R: Read> {
+    input: R,
+    decryptor: StreamDecryptor,
+    ciphertext: Vec<u8>,
+    chunk: Zeroizing<Vec<u8>>,
+    position: usize,
+    finished: bool,
+}
+
+impl<R: Read> OpenReader<R> {
+    /// Reads the container's prefix and header from `input`. `kind` names the expected file in
+    /// error messages.
+    fn new<H: DeserializeOwned>(
+        magic: &[u8; 8],
+        kind: &str,
+        mut input: R,
+        password: &str,
+    ) -> anyhow::Result<(H, Self)> {
+        let truncated = |_: io::Error| anyhow!("Truncated {} file", kind);
+        let mut file_magic = [0u8; 8];
+        input.read_exact(&mut file_magic).map_err(truncated)?;
+        if &file_magic != magic {
+            bail!("Not a wallet {} file or unsupported {} version", kind, kind);
+        }
+        let mut salt_length = [0u8; 1];
+        input.read_exact(&mut salt_length).map_err(truncated)?;
+        let mut salt = vec![0u8; usize::from(u8::from_le_bytes(salt_length))];
+        input.read_exact(&mut salt).map_err(truncated)?;
+        let mut nonce_prefix = [0u8; STREAM_NONCE_PREFIX_LENGTH];
+        input.read_exact(&mut nonce_prefix).map_err(truncated)?;
+
+        let mut reader = Self {
+            input,
+            decryptor: StreamDecryptor::new(password, &salt, &nonce_prefix)?,
+            ciphertext: Vec::with_capacity(STREAM_CHUNK_SIZE + STREAM_TAG_LENGTH),
+            chunk: Zeroizing::new(Vec::new()),
+            position: 0,
+            finished: false,
+        };
+        let mut length = [0u8; 4];
+        reader
+            .read_exact(&mut length)
+            .map_err(|_| anyhow!("Wrong {} password or corrupted {} file", kind, kind))?;
+        let mut header_json = Zeroizing::new(vec![0u8; usize::try_from(u32::from_le_bytes(length))?]);
+        reader
+            .read_exact(&mut header_json)
+            .map_err(|_| anyhow!("The {} is missing its header", kind))?;
+        let header = serde_json::from_slice(&header_json).with_context(|| format!("Invalid {} header", kind))?;
+        Ok((header, reader))
+    }
+
+    /// Reads and decrypts the next chunk. A chunk shorter than a full one is the last.
+    fn read_chunk(&mut self) -> io::Result<()> {
+        self.ciphertext.clear();
+        let read = self
+            .input
+            .by_ref()
+            .take(CIPHERTEXT_CHUNK_SIZE)
+            .read_to_end(&mut self.ciphertext)?;
+        let last = read < STREAM_CHUNK_SIZE + STREAM_TAG_LENGTH;
+        let chunk = self
+            .decryptor
+            .decrypt_chunk(&self.ciphertext, last)
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "The file is corrupted or truncated"))?;
+        self.chunk = Zeroizing::new(chunk);
+        self.position = 0;
+        self.finished = last;
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for OpenReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.position == self.chunk.len() {
+            if self.finished {
+                return Ok(0);
+            }
+            self.read_chunk()?;
+        }
+        let (_, remaining) = self.chunk.split_at(self.position);
+        let count = remaining.len().min(buf.len());
+        let (source, _) = remaining.split_at(count);
+        let (target, _) = buf.split_at_mut(count);
+        target.copy_from_slice(source);
+        self.position += count;
+        Ok(count)
+    }
+}
+
+/// Encrypts `header` as JSON together with `payload` into a file starting with `magic`.
+///
+/// This is the container of the first version of backup files, which account exports use with
+/// their own magic bytes. The whole file is encrypted in one piece.
+pub(crate) fn seal<H: Serialize>(
+    magic: &[u8; 8],
+    header: &H,
+    payload: &[u8],
+    password: &str,
+) -> anyhow::Result<Vec<u8>> {
+    let header_json = Zeroizing::new(serde_json::to_vec(header)?);
+    let header_length = u32::try_from(header_json.len()).context("Header too large")?;
+    let mut plaintext = Zeroizing::new(Vec::with_capacity(4 + header_json.len() + payload.len()));
+    plaintext.extend_from_slice(&header_length.to_le_bytes());
+    plaintext.extend_from_slice(&header_json);
+    plaintext.extend_from_slice(payload);
+
+    let encrypted = encrypt_data(&plaintext, password)?;
+    let salt_length = u8::try_from(encrypted.salt_bytes.len()).context("Salt too long")?;
+    let mut file = Vec::with_capacity(magic.len() + 1 + encrypted.salt_bytes.len() + NONCE_LENGTH);
+    file.extend_from_slice(magic);
+    file.push(salt_length);
+    file.extend_from_slice(&encrypted.salt_bytes);
+    file.extend_from_slice(&encrypted.nonce);
+    file.extend_from_slice(&encrypted.ciphertext);
+    Ok(file)
+}
+
+/// Decrypts a file written by [`seal`] and returns its header and payload.
+///
+/// `kind` names the expected file in error messages.
+pub(crate) fn open<H: DeserializeOwned>(
+    magic: &[u8; 8],
+    kind: &str,
+    file: &[u8],
+    password: &str,
+) -> anyhow::Result<(H, Vec<u8>)> {
+    let rest = file
+        .strip_prefix(magic.as_slice())
+        .ok_or_else(|| anyhow!("Not a wallet {} file or unsupported {} version", kind, kind))?;
+    let (&salt_length, rest) = rest.split_first().ok_or_else(|| anyhow!("Truncated {} file", kind))?;
+    let (salt, rest) = rest
+        .split_at_checked(usize::from(salt_length))
+        .ok_or_else(|| anyhow!("Truncated {} file", kind))?;
+    let (nonce, ciphertext) = rest
+        .split_at_checked(NONCE_LENGTH)
+        .ok_or_else(|| anyhow!("Truncated {} file", kind))?;
+
+    let plaintext = Zeroizing::new(
+        decrypt_data(
+            &FullEncryptedData {
+                ciphertext,
+                nonce,
+                salt_bytes: salt,
+            },
+            password,
+        )
+        .map_err(|_| anyhow!("Wrong {} password or corrupted {} file", kind, kind))?,
+    );
+
+    let (length, rest) = plaintext
+        .split_first_chunk::<4>()
+        .ok_or_else(|| anyhow!("The {} is missing its header", kind))?;
+    let (header_json, payload) = rest
+        .split_at_checked(usize::try_from(u32::from_le_bytes(*length))?)
+        .ok_or_else(|| anyhow!("The {} is missing its header", kind))?;
+    let header = serde_json::from_slice(header_json).with_context(|| format!("Invalid {} header", kind))?;
+    Ok((header, payload.to_vec()))
+}
+
+/// Restores the backup file `input` to `database_file`.
+///
+/// The snapshot is verified against its manifest before anything is replaced. An existing
+/// database is only replaced with `force`, and is kept as `<database_file>.pre-restore`.
+///
+/// # Errors
+///
+/// Fails if the backup cannot be decrypted or verified, belongs to another network, has a
+/// newer schema than this wallet supports, or the database exists and `force` is not set.
+pub fn restore_backup(
+    input: &Path,
+    password: &str,
+    database_file: &Path,
+    network: Network,
+    force: bool,
+) -> anyhow::Result<BackupManifest> {
+    let file = fs::File::open(input).with_context(|| format!("Failed to read {}", input.display()))?;
+    let (manifest, mut database) = open_backup(BufReader::new(file), password)?;
+
+    if manifest.network != network.to_string() {
+        bail!(
+            "The backup is of a {} wallet, but the wallet is configured for {}",
+            manifest.network,
+            network
+        );
+    }
+    if manifest.schema_version > db::latest_schema_version() {
+        bail!(
+            "The backup has schema version {}, but this wallet only supports up to {}. Upgrade the wallet first",
+            manifest.schema_version,
+            db::latest_schema_version()
+        );
+    }
+    if database_file.exists() && !force {
+        bail!("{} already exists. Use --force to replace it", database_file.display());
+    }
+
+    let target_dir = parent_dir(database_file);
+    fs::create_dir_all(&target_dir).with_context(|| format!("Failed to create {}", target_dir.display()))?;
+    let restore = ScratchFile::new(&target_dir, "restore")?;
+    let mut restored = fs::OpenOptions::new()
+        .write(true)
+        .open(restore.path())
+        .context("Failed to write the restored database")?;
+    io::copy(&mut database, &mut restored).context("Failed to decrypt the backup")?;
+    restored.sync_all().context("Failed to write the restored database")?;
+    drop(restored);
+    verify_snapshot(restore.path(), &manifest)?;
+
+    if database_file.exists() {
+        let previous = append_extension(database_file, "pre-restore");
+        remove_database_files(&previous);
+        fs::rename(database_file, &previous)
+            .with_context(|| format!("Failed to move {} aside", database_file.display()))?;
+        for suffix in ["-wal", "-shm"] {
+            let stale = append_suffix(database_file, suffix);
+            if stale.exists() {
+                fs::rename(&stale, append_suffix(&previous, suffix))
+                    .with_context(|| format!("Failed to move {} aside", stale.display()))?;
+            }
+        }
+        warn!(previous:? = previous; "Existing database kept next to the restored one");
+    }
+    fs::rename(restore.path(), database_file)
+        .with_context(|| format!("Failed to move the restored database to {}", database_file.display()))?;
+
+    info!(
+        target: "audit",
+        accounts = manifest.accounts.len(),
+        schema_version = manifest.schema_version;
+        "Wallet database restored from backup"
+    );
+    Ok(manifest)
+}
+
+/// Copies the database into `path` with the online backup API and describes the copy.
+fn snapshot(conn: &Connection, path: &Path, network: Network) -> anyhow::Result<BackupManifest> {
+    let mut target = Connection::open(path).context("Failed to create the database snapshot")?;
+    {
+        let backup = Backup::new(conn, &mut target)?;
+        // Copying every page in one step reads the source in a single transaction, so the
+        // snapshot is consistent even while other connections keep writing.
+        backup
+            .run_to_completion(-1, Duration::from_millis(100), None)
+            .context("Failed to take the database snapshot")?;
+    }
+    // Make the snapshot a self-contained file without a WAL
+    target.execute_batch("PRAGMA journal_mode = DELETE;")?;
+
+    let manifest = BackupManifest {
+        created_at: Utc::now(),
+        schema_version: db::schema_version(&target)?,
+        network: network.to_string(),
+        wallet_version: env!("CARGO_PKG_VERSION").to_string(),
+        accounts: backup_accounts(&target)?,
+    };
+    target.close().map_err(|(_, e)| e)?;
+    Ok(manifest)
+}
+
+/// Lists the accounts of a database, which may have an older schema than this wallet.
+fn backup_accounts(conn: &Connection) -> anyhow::Result<Vec<BackupAccount>> {
+    let mut stmt = conn.prepare("SELECT id, friendly_name, fingerprint FROM accounts ORDER BY friendly_name")?;
+    let accounts = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, Vec<u8>>(2)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    accounts
+        .into_iter()
+        .map(|(id, name, fingerprint)| {
+            let scanned_height = db::get_latest_scanned_tip_block_by_account(conn, id)?.map(|b| b.height);
+            Ok(BackupAccount {
+                name,
+                fingerprint: hex::encode(fingerprint),
+                scanned_height,
+            })
+        })
+        .collect()
+}
+
+/// Checks a restored database file against the manifest of its backup.
+fn verify_snapshot(path: &Path, manifest: &BackupManifest) -> anyhow::Result<()> {
+    let conn = Connection::open(path).context("The restored database cannot be opened")?;
+    let integrity: String = conn.query_row("PRAGMA integrity_check", [], |row| row.get(0))?;
+    if integrity != "ok" {
+        bail!("The restored database failed the integrity check: {}", integrity);
+    }
+
+    let schema_version = db::schema_version(&conn)?;
+    if schema_version != manifest.schema_version {
+        bail!(
+            "The restored database has schema version {}, but the manifest records {}",
+            schema_version,
+            manifest.schema_version
+        );
+    }
+    let accounts = backup_accounts(&conn)?;
+    if accounts != manifest.accounts {
+        bail!("The accounts in the restored database do not match the backup manifest");
+    }
+    conn.close().map_err(|(_, e)| e)?;
+    Ok(())
+}
+
+pub(crate) fn parent_dir(path: &Path) -> PathBuf {
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    }
+}
+
+/// A scratch database file holding unencrypted wallet data, e.g. a snapshot being backed up.
+///
+/// The file is created empty, readable only by the current user, in a fresh directory of
+/// `dir` that only the current user can access. Directory and file are removed when dropped.
+pub(crate) struct ScratchFile {
+    // Removes the directory and everything in it when dropped
+    _dir: tempfile::TempDir,
+    path: PathBuf,
+}
+
+impl ScratchFile {
+    pub(crate) fn new(dir: &Path, purpose: &str) -> anyhow::Result<Self> {
+        let mut builder = tempfile::Builder::new();
+        builder.prefix(".minotari-scratch-");
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            builder.permissions(fs::Permissions::from_mode(0o700));
+        }
+        let scratch_dir = builder
+            .tempdir_in(dir)
+            .with_context(|| format!("Failed to create a scratch directory in {}", dir.display()))?;
+
+        let path = scratch_dir.path().join(format!("{}.db", purpose));
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        options
+            .open(&path)
+            .with_context(|| format!("Failed to create {}", path.display()))?;
+
+        Ok(Self {
+            _dir: scratch_dir,
+            path,
+        })
+    }
+
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+fn append_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+fn append_extension(path: &Path, extension: &str) -> PathBuf {
+    append_suffix(path, &format!(".{}", extension))
+}
+
+/// Removes a database file together with its journal files, ignoring missing ones.
+pub(crate) fn remove_database_files(path: &Path) {
+    for suffix in ["", "-journal", "-wal", "-shm"] {
+        let _unused = fs::remove_file(append_suffix(path, suffix));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tari_common_types::seeds::cipher_seed::CipherSeed;
+    use tari_transaction_components::key_manager::wallet_types::{SeedWordsWallet, WalletType};
+
+    use super::*;
+
+    #[test]
+    fn test_scratch_file_is_private_and_removed() {
+        let dir = tempfile::tempdir().unwrap();
+        let scratch = ScratchFile::new(dir.path(), "snapshot").unwrap();
+        let path = scratch.path().to_path_buf();
+        assert!(path.exists());
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+            let scratch_dir = path.parent().unwrap();
+            assert_eq!(fs::metadata(scratch_dir).unwrap().permissions().mode() & 0o777, 0o700);
+        }
+
+        drop(scratch);
+        assert!(!path.exists());
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_backup_and_restore() {
+        let dir = tempfile::tempdir().unwrap();
+        let database_file = dir.path().join("wallet.db");
+        let pool = db::init_db(database_file.clone(), Network::Esmeralda).unwrap();
+        let conn = pool.get().unwrap();
+        let wallet = WalletType::SeedWords(SeedWordsWallet::construct_new(CipherSeed::random()).unwrap());
+        db::create_account(&conn, "default", &wallet, "wallet password", Network::Esmeralda).unwrap();
+
+        let backup_file = dir.path().join("wallet.mtbak");
+        let manifest = backup_to_file(&database_file, Network::Esmeralda, "backup password", &backup_file).unwrap();
+        assert_eq!(manifest.schema_version, db::latest_schema_version());
+        assert_eq!(manifest.accounts.len(), 1);
+
+        let file = fs::read(&backup_file).unwrap();
+        assert!(decrypt_backup(&file, "wrong password").is_err());
+        let (decrypted, _) = decrypt_backup(&file, "backup password").unwrap();
+        assert_eq!(decrypted, manifest);
+
+        let restored_file = dir.path().join("restored").join("wallet.db");
+        assert!(restore_backup(&backup_file, "backup password", &restored_file, Network::MainNet, false).is_err());
+        restore_backup(
+            &backup_file,
+            "backup password",
+            &restored_file,
+            Network::Esmeralda,
+            false,
+        )
+        .unwrap();
+        assert!(
+            restore_backup(
+                &backup_file,
+                "backup password",
+                &restored_file,
+                Network::Esmeralda,
+                false
+            )
+            .is_err()
+        );
+        restore_backup(
+            &backup_file,
+            "backup password",
+            &restored_file,
+            Network::Esmeralda,
+            true,
+        )
+        .unwrap();
+        assert!(append_extension(&restored_file, "pre-restore").exists());
+
+        let restored = Connection::open(&restored_file).unwrap();
+        let accounts = db::get_accounts(&restored, None).unwrap();
+        assert_eq!(accounts.len(), 1);
+        assert!(
+            accounts
+                .iter()
+                .all(|account| account.decrypt_wallet_type("wallet password").is_ok())
+        );
+
+        // A modified backup is rejected
+        let mut tampered = file;
+        if let Some(byte) = tampered.last_mut() {
+            *byte ^= 1;
+        }
+        assert!(decrypt_backup(&tampered, "backup password").is_err());
+    }
+
+    #[test]
+    fn test_backup_truncated_at_a_chunk_boundary_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let pool = db::init_db(dir.path().join("wallet.db"), Network::Esmeralda).unwrap();
+        let conn = pool.get().unwrap();
+        let mut file = Vec::new();
+        create_backup(&conn, Network::Esmeralda, "backup password", dir.path(), &mut file).unwrap();
+        assert!(decrypt_backup(&file, "backup password").is_ok());
+
+        let salt_length = usize::from(*file.get(BACKUP_MAGIC.len()).unwrap());
+        let first_chunk_end =
+            BACKUP_MAGIC.len() + 1 + salt_length + STREAM_NONCE_PREFIX_LENGTH + STREAM_CHUNK_SIZE + STREAM_TAG_LENGTH;
+        assert!(file.len() > first_chunk_end, "The snapshot should span several chunks");
+        file.truncate(first_chunk_end);
+        assert!(decrypt_backup(&file, "backup password").is_err());
+    }
+
+    #[test]
+    fn test_restore_backup_of_first_format_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let pool = db::init_db(dir.path().join("wallet.db"), Network::Esmeralda).unwrap();
+        let conn = pool.get().unwrap();
+        let wallet = WalletType::SeedWords(SeedWordsWallet::construct_new(CipherSeed::random()).unwrap());
+        db::create_account(&conn, "default", &wallet, "wallet password", Network::Esmeralda).unwrap();
+
+        let snapshot = take_snapshot(&conn, Network::Esmeralda, dir.path()).unwrap();
+        let database = fs::read(snapshot.file.path()).unwrap();
+        let file = seal(BACKUP_MAGIC_V1, &snapshot.manifest, &database, "backup password").unwrap();
+        let backup_file = dir.path().join("wallet-v1.mtbak");
+        fs::write(&backup_file, file).unwrap();
+
+        let restored_file = dir.path().join("restored").join("wallet.db");
+        let manifest = restore_backup(
+            &backup_file,
+            "backup password",
+            &restored_file,
+            Network::Esmeralda,
+            false,
+        )
+        .unwrap();
+        assert_eq!(manifest, snapshot.manifest);
+        let restored = Connection::open(&restored_file).unwrap();
+        assert_eq!(db::get_accounts(&restored, None).unwrap().len(), 1);
+    }
+}
@@ -1,7 +1,18 @@
+use chrono::{DateTime, Utc};
 use clap::{Args, Parser, Subcommand};
 use std::path::PathBuf;
 use tari_common::configuration::Network;
 use tari_transaction_components::tari_amount::MicroMinotari;
+use zeroize::Zeroizing;
+
+use crate::{
+    db::RECENT_BLOCKS_TO_KEEP,
+    models::ApiKeyScope,
+    utils::{
+        password::{self, PasswordSource},
+        seed_words::SeedLanguage,
+    },
+};
 
 /// Command-line interface definition for the Tari wallet.
 ///
@@ -17,7 +28,11 @@ use tari_transaction_components::tari_amount::MicroMinotari;
 /// - [`Commands::Daemon`] - Run continuous scanning daemon
 /// - [`Commands::Balance`] - Display wallet balance
 /// - [`Commands::CreateUnsignedTransaction`] - Build an unsigned transaction
+/// - [`Commands::Send`] - Sign and broadcast a payment
 /// - [`Commands::LockFunds`] - Lock UTXOs for a pending transaction
+/// - [`Commands::ApiKey`] - Manage REST API keys
+/// - [`Commands::Webhook`] - Manage webhook subscriptions
+/// - [`Commands::ApprovalPolicy`] - Manage M-of-N approval policies for outgoing payments
 #[derive(Parser)]
 #[command(name = "tari", about = "Tari wallet CLI", version, long_about = None)]
 pub struct Cli {
@@ -34,11 +49,239 @@ pub struct Cli {
     pub command: Commands,
 }
 
+/// Source of the password used to encrypt/decrypt the wallet.
+///
+/// At most one source can be given. Without one, the password is read from the
+/// `MINOTARI_PASSWORD` environment variable or prompted for interactively.
 #[derive(Args, Debug)]
+#[group(multiple = false)]
 pub struct SecurityArgs {
-    /// Password to encrypt/decrypt the wallet file.
-    #[arg(short, long, help = "Wallet password")]
-    pub password: String,
+    /// Password to encrypt/decrypt the wallet file. Visible in the shell history and process list.
+    #[arg(short, long, help = "Wallet password (prefer --password-file or the prompt)")]
+    pub password: Option<String>,
+
+    /// Name of an environment variable holding the password.
+    #[arg(
+        long,
+        value_name = "VAR",
+        help = "Read the wallet password from an environment variable"
+    )]
+    pub password_env: Option<String>,
+
+    /// Inherited file descriptor the password is read from, e.g. `3` with `3< secret`.
+    #[arg(long, value_name = "FD", help = "Read the wallet password from a file descriptor")]
+    pub password_fd: Option<u32>,
+
+    /// File holding the password. It must not be accessible by group or others.
+    #[arg(long, value_name = "PATH", help = "Read the wallet password from a file (mode 600)")]
+    pub password_file: Option<PathBuf>,
+}
+
+impl SecurityArgs {
+    pub fn source(&self) -> PasswordSource {
+        if let Some(password) = &self.password {
+            PasswordSource::Argument(password.clone())
+        } else if let Some(name) = &self.password_env {
+            PasswordSource::EnvVar(name.clone())
+        } else if let Some(fd) = self.password_fd {
+            PasswordSource::FileDescriptor(fd)
+        } else if let Some(path) = &self.password_file {
+            PasswordSource::File(path.clone())
+        } else {
+            PasswordSource::Default
+        }
+    }
+
+    /// Reads the password of an existing wallet.
+    pub fn read_password(&self) -> anyhow::Result<Zeroizing<String>> {
+        password::read_password(&self.source(), "Wallet password", false)
+    }
+
+    /// Reads the password a new wallet is encrypted with, asking twice when prompting.
+    pub fn read_new_password(&self) -> anyhow::Result<Zeroizing<String>> {
+        password::read_password(&self.source(), "Wallet password", true)
+    }
+
+    /// Reads the password a backup is encrypted with, asking twice when prompting for a new one.
+    pub fn read_backup_password(&self, confirm: bool) -> anyhow::Result<Zeroizing<String>> {
+        password::read_password(&self.source(), "Backup password", confirm)
+    }
+}
+
+/// Accounts the daemon unlocks at start-up.
+///
+/// By default every account the start-up password decrypts is unlocked. Accounts encrypted
+/// with other passwords are unlocked through the API (`POST /accounts/{name}/unlock`).
+#[derive(Args, Debug)]
+pub struct UnlockArgs {
+    /// Only unlock these accounts at start-up. Can be repeated.
+    #[arg(
+        long = "unlock-account",
+        value_name = "NAME",
+        help = "Only unlock this account at start-up"
+    )]
+    pub unlock_accounts: Vec<String>,
+
+    /// Start without unlocking any account and without reading a password.
+    #[arg(
+        long,
+        conflicts_with_all = ["SecurityArgs", "unlock_accounts"],
+        help = "Start with every account locked"
+    )]
+    pub start_locked: bool,
+}
+
+/// Source of the new password for `change-password`.
+///
+/// At most one source can be given. Without one, the new password is prompted for twice.
+#[derive(Args, Debug)]
+#[group(multiple = false)]
+pub struct NewPasswordArgs {
+    /// New wallet password. Visible in the shell history and process list.
+    #[arg(long, help = "New wallet password (prefer --new-password-file or the prompt)")]
+    pub new_password: Option<String>,
+
+    /// Name of an environment variable holding the new password.
+    #[arg(
+        long,
+        value_name = "VAR",
+        help = "Read the new password from an environment variable"
+    )]
+    pub new_password_env: Option<String>,
+
+    /// Inherited file descriptor the new password is read from.
+    #[arg(long, value_name = "FD", help = "Read the new password from a file descriptor")]
+    pub new_password_fd: Option<u32>,
+
+    /// File holding the new password. It must not be accessible by group or others.
+    #[arg(long, value_name = "PATH", help = "Read the new password from a file (mode 600)")]
+    pub new_password_file: Option<PathBuf>,
+}
+
+impl NewPasswordArgs {
+    pub fn source(&self) -> PasswordSource {
+        if let Some(password) = &self.new_password {
+            PasswordSource::Argument(password.clone())
+        } else if let Some(name) = &self.new_password_env {
+            PasswordSource::EnvVar(name.clone())
+        } else if let Some(fd) = self.new_password_fd {
+            PasswordSource::FileDescriptor(fd)
+        } else if let Some(path) = &self.new_password_file {
+            PasswordSource::File(path.clone())
+        } else {
+            PasswordSource::Prompt
+        }
+    }
+
+    /// Reads the new password, asking twice when prompting.
+    pub fn read_password(&self) -> anyhow::Result<Zeroizing<String>> {
+        password::read_password(&self.source(), "New wallet password", true)
+    }
+}
+
+/// Source of the password an account export is encrypted with.
+///
+/// At most one source can be given. Without one, the export password is prompted for.
+#[derive(Args, Debug)]
+#[group(multiple = false)]
+pub struct ExportPasswordArgs {
+    /// Export password. Visible in the shell history and process list.
+    #[arg(long, help = "Export password (prefer --export-password-file or the prompt)")]
+    pub export_password: Option<String>,
+
+    /// Name of an environment variable holding the export password.
+    #[arg(
+        long,
+        value_name = "VAR",
+        help = "Read the export password from an environment variable"
+    )]
+    pub export_password_env: Option<String>,
+
+    /// Inherited file descriptor the export password is read from.
+    #[arg(long, value_name = "FD", help = "Read the export password from a file descriptor")]
+    pub export_password_fd: Option<u32>,
+
+    /// File holding the export password. It must not be accessible by group or others.
+    #[arg(long, value_name = "PATH", help = "Read the export password from a file (mode 600)")]
+    pub export_password_file: Option<PathBuf>,
+}
+
+impl ExportPasswordArgs {
+    pub fn source(&self) -> PasswordSource {
+        if let Some(password) = &self.export_password {
+            PasswordSource::Argument(password.clone())
+        } else if let Some(name) = &self.export_password_env {
+            PasswordSource::EnvVar(name.clone())
+        } else if let Some(fd) = self.export_password_fd {
+            PasswordSource::FileDescriptor(fd)
+        } else if let Some(path) = &self.export_password_file {
+            PasswordSource::File(path.clone())
+        } else {
+            PasswordSource::Prompt
+        }
+    }
+
+    /// Reads the export password, asking twice when prompting for a new one.
+    pub fn read_password(&self, confirm: bool) -> anyhow::Result<Zeroizing<String>> {
+        password::read_password(&self.source(), "Export password", confirm)
+    }
+}
+
+/// Source of the passphrase seed words are enciphered with.
+///
+/// At most one source can be given. Without one, the seed words use no passphrase. The
+/// passphrase only protects the seed words; the keys of the wallet are the same without it.
+#[derive(Args, Debug)]
+#[group(multiple = false)]
+pub struct SeedPassphraseArgs {
+    /// Seed passphrase. Visible in the shell history and process list.
+    #[arg(long, help = "Seed words passphrase (prefer --seed-passphrase-file or the prompt)")]
+    pub seed_passphrase: Option<String>,
+
+    /// Name of an environment variable holding the seed passphrase.
+    #[arg(
+        long,
+        value_name = "VAR",
+        help = "Read the seed passphrase from an environment variable"
+    )]
+    pub seed_passphrase_env: Option<String>,
+
+    /// Inherited file descriptor the seed passphrase is read from.
+    #[arg(long, value_name = "FD", help = "Read the seed passphrase from a file descriptor")]
+    pub seed_passphrase_fd: Option<u32>,
+
+    /// File holding the seed passphrase. It must not be accessible by group or others.
+    #[arg(long, value_name = "PATH", help = "Read the seed passphrase from a file (mode 600)")]
+    pub seed_passphrase_file: Option<PathBuf>,
+
+    /// Prompt for the seed passphrase.
+    #[arg(long, help = "Prompt for the seed passphrase")]
+    pub seed_passphrase_prompt: bool,
+}
+
+impl SeedPassphraseArgs {
+    pub fn source(&self) -> Option<PasswordSource> {
+        if let Some(passphrase) = &self.seed_passphrase {
+            Some(PasswordSource::Argument(passphrase.clone()))
+        } else if let Some(name) = &self.seed_passphrase_env {
+            Some(PasswordSource::EnvVar(name.clone()))
+        } else if let Some(fd) = self.seed_passphrase_fd {
+            Some(PasswordSource::FileDescriptor(fd))
+        } else if let Some(path) = &self.seed_passphrase_file {
+            Some(PasswordSource::File(path.clone()))
+        } else if self.seed_passphrase_prompt {
+            Some(PasswordSource::Prompt)
+        } else {
+            None
+        }
+    }
+
+    /// Reads the seed passphrase if a source was given, asking twice when prompting for a new one.
+    pub fn read_passphrase(&self, confirm: bool) -> anyhow::Result<Option<Zeroizing<String>>> {
+        self.source()
+            .map(|source| password::read_password(&source, "Seed passphrase", confirm))
+            .transpose()
+    }
 }
 
 #[derive(Args, Debug)]
@@ -57,6 +300,10 @@ pub struct NodeArgs {
     /// Number of blocks to fetch per API request for efficiency.
     #[arg(long, help = "Batch size for scanning")]
     pub batch_size: Option<u64>,
+
+    /// SQLite file used to cache downloaded blocks, making re-scans of known heights near-instant.
+    #[arg(long, help = "Path to the on-disk block cache")]
+    pub block_cache_path: Option<PathBuf>,
 }
 
 #[derive(Args, Debug)]
@@ -66,6 +313,29 @@ pub struct AccountArgs {
     pub account_name: Option<String>,
 }
 
+#[derive(Args, Debug)]
+pub struct ApiListenArgs {
+    /// Address for the REST API to listen on, as `ip` or `ip:port`. Replaces the configured addresses.
+    #[arg(long = "api-bind-address", help = "API listen address (repeatable)")]
+    pub bind_addresses: Vec<String>,
+
+    /// Unix domain socket for the REST API to listen on.
+    #[arg(long, help = "Path of a Unix socket for the API server")]
+    pub api_unix_socket: Option<PathBuf>,
+
+    /// PEM certificate chain enabling TLS on the REST API. Requires `--api-tls-key`.
+    #[arg(long, requires = "api_tls_key", help = "TLS certificate file (PEM)")]
+    pub api_tls_cert: Option<PathBuf>,
+
+    /// PEM private key for the REST API's TLS certificate. Requires `--api-tls-cert`.
+    #[arg(long, requires = "api_tls_cert", help = "TLS private key file (PEM)")]
+    pub api_tls_key: Option<PathBuf>,
+
+    /// PEM CA certificates; clients must present a certificate signed by one of them (mTLS).
+    #[arg(long, help = "CA file for verifying client certificates (PEM)")]
+    pub api_tls_client_ca: Option<PathBuf>,
+}
+
 #[derive(Args, Debug)]
 pub struct TransactionArgs {
     /// Unique key to prevent duplicate transactions.
@@ -87,6 +357,14 @@ pub struct TransactionArgs {
 ///
 /// - [`Commands::CreateAddress`] - Generate a brand new wallet
 /// - [`Commands::ImportViewKey`] - Import an existing wallet using keys
+/// - [`Commands::ChangePassword`] - Re-encrypt accounts with a new password
+/// - [`Commands::Backup`] - Write an encrypted backup of the wallet database
+/// - [`Commands::Restore`] - Restore the wallet database from a backup
+/// - [`Commands::ExportAccount`] - Export a single account to an encrypted file
+/// - [`Commands::ImportAccount`] - Import an exported account into the database
+/// - [`Commands::DbMaintenance`] - Check, prune and vacuum the wallet database
+/// - [`Commands::ConfirmNetwork`] - Record the network of a database it could not be inferred for
+/// - [`Commands::Verify`] - Reconcile balances against outputs and displayed transactions
 ///
 /// # Scanning Commands
 ///
@@ -108,7 +386,7 @@ pub enum Commands {
     ///
     /// Generates a new wallet with:
     /// - Random cipher seed
-    /// - Mnemonic seed words (English)
+    /// - Mnemonic seed words, in any supported language and optionally with a passphrase
     /// - View key (private) and spend key (public)
     /// - Tari address for receiving funds
     ///
@@ -122,13 +400,21 @@ pub enum Commands {
     /// - `address`: Base58-encoded Tari address
     /// - `view_key` / `encrypted_view_key`: Private view key
     /// - `spend_key` / `encrypted_spend_key`: Public spend key
-    /// - `seed_words` / `encrypted_seed_words`: Mnemonic recovery phrase
+    /// - `seed_words` / `encrypted_seed_words`: Mnemonic recovery phrase, in `--seed-language`
+    ///   and enciphered with the seed passphrase if one is given
     /// - `birthday`: Block height when wallet was created
     /// - `nonce`: (encrypted only) Encryption nonce
     CreateAddress {
         #[arg(short, long, help = "Password to encrypt the wallet file")]
         password: Option<String>,
 
+        #[command(flatten)]
+        seed_passphrase: SeedPassphraseArgs,
+
+        /// Language of the generated seed words.
+        #[arg(long, value_enum, default_value_t, help = "Language of the seed words")]
+        seed_language: SeedLanguage,
+
         /// Path to write the wallet credentials JSON file.
         #[arg(short, long, help = "Path to the output file", default_value = "data/output.json")]
         output_file: PathBuf,
@@ -147,6 +433,10 @@ pub enum Commands {
     /// 3. Records detected outputs in the SQLite database
     /// 4. Updates the scanned tip height
     ///
+    /// When run in a terminal, a live progress line showing the current height,
+    /// tip height, throughput, outputs found and estimated time to tip is
+    /// rendered on stderr.
+    ///
     /// # Performance Tuning
     ///
     /// - `max_blocks_to_scan`: Limits scan duration (default: 50)
@@ -175,6 +465,8 @@ pub enum Commands {
     /// - Handling blockchain reorganizations
     /// - Debugging missing transactions
     ///
+    /// Like `scan`, a live progress line is rendered on stderr when run in a terminal.
+    ///
     /// # Warning
     ///
     /// This operation modifies the database by removing outputs detected
@@ -213,6 +505,13 @@ pub enum Commands {
     ///
     /// API documentation is available at `/swagger-ui/` when the daemon is running.
     ///
+    /// # Unlocking
+    ///
+    /// Accounts can be encrypted with different passwords. The daemon unlocks the accounts the
+    /// start-up password decrypts (or only those given with `--unlock-account`); the others stay
+    /// locked until unlocked through the API. Locked accounts cannot spend, and are only scanned
+    /// once they have been unlocked since start-up.
+    ///
     /// # Shutdown
     ///
     /// Press Ctrl+C to initiate graceful shutdown. The daemon will:
@@ -223,6 +522,8 @@ pub enum Commands {
         #[command(flatten)]
         security: SecurityArgs,
         #[command(flatten)]
+        unlock: UnlockArgs,
+        #[command(flatten)]
         node: NodeArgs,
         #[command(flatten)]
         db: DatabaseArgs,
@@ -233,6 +534,11 @@ pub enum Commands {
         /// TCP port for the REST API server.
         #[arg(long, help = "Port for the API server")]
         api_port: Option<u16>,
+        /// Reject unauthenticated API requests even if no API key has been created yet.
+        #[arg(long, help = "Require an API key for every API request")]
+        require_api_key: bool,
+        #[command(flatten)]
+        api_listen: ApiListenArgs,
     },
 
     /// Display the wallet balance.
@@ -259,6 +565,7 @@ pub enum Commands {
     /// - Restoring a wallet from backed-up keys
     /// - Creating a watch-only wallet (view key only)
     /// - Importing a wallet generated by another application
+    /// - Importing a hardware or remotely held wallet, signed with `--external-signer`
     ///
     /// # Key Format
     ///
@@ -286,12 +593,18 @@ pub enum Commands {
         /// Block height when the wallet was created (for scan optimization).
         #[arg(short, long, help = "The wallet birthday (block height)", default_value = "0")]
         birthday: u16,
+
+        /// Sign the account's transactions with the external signer configured under
+        /// `[wallet.external_signer]`, which holds the spend key.
+        #[arg(long, help = "Sign transactions with the configured external signer")]
+        external_signer: bool,
     },
 
     /// Create a new wallet or restore from seed words.
     ///
     /// This initializes the database with a full signing wallet (SeedWordsWallet).
-    /// - If `seed_words` are provided, it restores the wallet.
+    /// - If `seed_words` are provided, it restores the wallet. Their language is detected,
+    ///   and seed words protected by a passphrase need the seed passphrase options.
     /// - If omitted, it generates a generic random wallet.
     Create {
         #[command(flatten)]
@@ -300,16 +613,29 @@ pub enum Commands {
         db: DatabaseArgs,
         #[command(flatten)]
         account: AccountArgs,
+        #[command(flatten)]
+        seed_passphrase: SeedPassphraseArgs,
 
         /// Optional space-separated seed words to restore from.
         #[arg(short, long, help = "Restore from specific seed words (space separated)")]
         seed_words: Option<String>,
+
+        /// Language of the seed words, for words that exist in several word lists.
+        #[arg(
+            long,
+            value_enum,
+            requires = "seed_words",
+            help = "Language of the seed words (detected by default)"
+        )]
+        seed_language: Option<SeedLanguage>,
     },
 
     /// Reveal the seed words for a specific wallet.
     ///
-    /// Requires the wallet password to decrypt the seed.
-    /// Will fail if the wallet is a View-Only wallet or Ledger wallet.
+    /// Requires the wallet password to decrypt the seed. The words are written in
+    /// `--seed-language` and, with the seed passphrase options, enciphered with a passphrase
+    /// that is then needed to restore them. Will fail if the wallet is a View-Only wallet or
+    /// Ledger wallet.
     ShowSeedWords {
         #[command(flatten)]
         security: SecurityArgs,
@@ -317,6 +643,12 @@ pub enum Commands {
         db: DatabaseArgs,
         #[command(flatten)]
         account: AccountArgs,
+        #[command(flatten)]
+        seed_passphrase: SeedPassphraseArgs,
+
+        /// Language of the seed words.
+        #[arg(long, value_enum, default_value_t, help = "Language of the seed words")]
+        seed_language: SeedLanguage,
     },
 
     /// Reveal the cryptographic keys for a specific wallet.
@@ -356,7 +688,7 @@ pub enum Commands {
     /// tari create-unsigned-transaction \
     ///     --account-name main \
     ///     --recipient "f2ABC...123::1000000" \
-    ///     --password secret
+    ///     --password-file ~/.minotari/password
     /// ```
     CreateUnsignedTransaction {
         #[command(flatten)]
@@ -389,6 +721,49 @@ pub enum Commands {
         seconds_to_lock: u64,
     },
 
+    /// Send a one-sided payment and broadcast it.
+    ///
+    /// The transaction is signed by the wallet, or by the external signer configured under
+    /// `[wallet.external_signer]` if the account was imported with `--external-signer`. The
+    /// locked UTXOs are released if the signer refuses to sign.
+    ///
+    /// Payments covered by the account's approval policy (see [`Commands::ApprovalPolicy`])
+    /// wait for approval; run the command again with the printed `--idempotency-key` once
+    /// they are approved.
+    ///
+    /// # Example
+    ///
+    /// ```bash
+    /// tari send \
+    ///     --account-name cold-storage \
+    ///     --recipient "f2ABC...123::1000000" \
+    ///     --password-file ~/.minotari/password
+    /// ```
+    Send {
+        #[command(flatten)]
+        security: SecurityArgs,
+        #[command(flatten)]
+        node: NodeArgs,
+        #[command(flatten)]
+        db: DatabaseArgs,
+        #[command(flatten)]
+        tx: TransactionArgs,
+
+        /// Name of the account to spend from.
+        #[arg(short, long, help = "Name of the account to send from")]
+        account_name: String,
+        /// Recipient in `address::amount[::payment_id]` format.
+        #[arg(
+            short,
+            long,
+            help = "Recipient address, amount and optional payment id (e.g., address::amount or address::amount::payment_id)"
+        )]
+        recipient: String,
+        /// Duration in seconds to lock input UTXOs until the transaction is signed.
+        #[arg(long, help = "Optional seconds to lock UTXOs", default_value_t = 3600)]
+        seconds_to_lock: u64,
+    },
+
     /// Lock funds (reserve UTXOs) for a pending transaction.
     ///
     /// Reserves a set of UTXOs totaling at least the specified amount plus
@@ -631,6 +1006,220 @@ pub enum Commands {
         seconds_to_lock: u64,
     },
 
+    /// Change the password accounts are encrypted with.
+    ///
+    /// Decrypts the accounts with the current password and re-encrypts them with the new one
+    /// in a single database transaction. With `--account-name` only that account is changed,
+    /// otherwise every account the current password decrypts; accounts encrypted with other
+    /// passwords are left untouched. Use `--dry-run` to only list the accounts that would change.
+    ///
+    /// Accounts unlocked in a running daemon keep the old password until it is restarted, so
+    /// stop it first or change the password through its API (`POST /wallet/change_password`).
+    ///
+    /// # Example
+    ///
+    /// ```bash
+    /// tari change-password \
+    ///     --password-file ~/.minotari/password \
+    ///     --new-password-file ~/.minotari/new-password
+    /// ```
+    ChangePassword {
+        #[command(flatten)]
+        security: SecurityArgs,
+        #[command(flatten)]
+        new_password: NewPasswordArgs,
+        #[command(flatten)]
+        db: DatabaseArgs,
+        #[command(flatten)]
+        account: AccountArgs,
+        #[arg(long, help = "Only list the accounts the current password decrypts")]
+        dry_run: bool,
+    },
+
+    /// Write an encrypted backup of the wallet database.
+    ///
+    /// Takes a consistent snapshot with SQLite's online backup API, so it can run while the
+    /// daemon is running, and encrypts it together with a manifest of its schema version,
+    /// network, accounts and scanned heights. The password options set the backup password,
+    /// which is independent of the passwords the accounts are encrypted with.
+    ///
+    /// # Example
+    ///
+    /// ```bash
+    /// tari backup --password-file ~/.minotari/backup-password --output wallet-2024-06-01.mtbak
+    /// ```
+    Backup {
+        #[command(flatten)]
+        security: SecurityArgs,
+        #[command(flatten)]
+        db: DatabaseArgs,
+        /// File to write the backup to. It must not exist yet.
+        #[arg(long, value_name = "PATH", help = "File to write the backup to")]
+        output: PathBuf,
+    },
+
+    /// Restore the wallet database from an encrypted backup.
+    ///
+    /// The snapshot is checked against its manifest and SQLite's integrity check before the
+    /// database is replaced. The backup must be of the configured network. Stop the daemon
+    /// before restoring; an existing database is only replaced with `--force` and kept as
+    /// `<database>.pre-restore`.
+    Restore {
+        #[command(flatten)]
+        security: SecurityArgs,
+        #[command(flatten)]
+        db: DatabaseArgs,
+        /// Backup file to restore.
+        #[arg(long, value_name = "PATH", help = "Backup file to restore")]
+        input: PathBuf,
+        /// Replace an existing database.
+        #[arg(long, help = "Replace an existing database")]
+        force: bool,
+    },
+
+    /// Export a single account to an encrypted file.
+    ///
+    /// The file holds the account's keys and birthday and, with `--include-history`, its
+    /// outputs, transactions, events and scanned blocks, so that it does not have to be
+    /// rescanned after it is imported into another database. The password options decrypt the
+    /// account; the export password options set the password of the file.
+    ///
+    /// # Example
+    ///
+    /// ```bash
+    /// tari export-account --account-name customer-42 --include-history \
+    ///     --password-file ~/.minotari/password \
+    ///     --export-password-file ~/.minotari/export-password \
+    ///     --output customer-42.mtacc
+    /// ```
+    ExportAccount {
+        #[command(flatten)]
+        security: SecurityArgs,
+        #[command(flatten)]
+        export_password: ExportPasswordArgs,
+        #[command(flatten)]
+        db: DatabaseArgs,
+        #[command(flatten)]
+        account: AccountArgs,
+        /// File to write the export to. It must not exist yet.
+        #[arg(long, value_name = "PATH", help = "File to write the export to")]
+        output: PathBuf,
+        /// Include the outputs and history of the account.
+        #[arg(long, help = "Include the outputs and history of the account")]
+        include_history: bool,
+    },
+
+    /// Import an account exported with `export-account`.
+    ///
+    /// The account is stored under its exported name, or `--account-name`, and encrypted with
+    /// the wallet password. It is refused if the database already holds an account with the
+    /// same keys, or if it was exported from another network.
+    ImportAccount {
+        #[command(flatten)]
+        security: SecurityArgs,
+        #[command(flatten)]
+        export_password: ExportPasswordArgs,
+        #[command(flatten)]
+        db: DatabaseArgs,
+        #[command(flatten)]
+        account: AccountArgs,
+        /// Export file to import.
+        #[arg(long, value_name = "PATH", help = "Export file to import")]
+        input: PathBuf,
+    },
+
+    /// Check the wallet database, delete rows it no longer needs and vacuum it.
+    ///
+    /// Runs SQLite's integrity and foreign key checks, then deletes old wallet events and
+    /// delivered webhooks, outputs and inputs invalidated by reorgs below the reorg horizon and
+    /// excess scanned blocks, and vacuums the file. Nothing is deleted if a check fails. Stop
+    /// the daemon first, as vacuuming waits for its transactions.
+    ///
+    /// # Example
+    ///
+    /// ```bash
+    /// tari db-maintenance --event-retention-days 30
+    /// ```
+    DbMaintenance {
+        #[command(flatten)]
+        db: DatabaseArgs,
+        /// Only run the integrity and foreign key checks.
+        #[arg(long, help = "Only run the integrity and foreign key checks")]
+        check_only: bool,
+        /// Delete wallet events older than this many days.
+        #[arg(
+            long,
+            value_name = "DAYS",
+            default_value_t = 90,
+            help = "Delete wallet events older than this"
+        )]
+        event_retention_days: u32,
+        /// Delete delivered webhooks older than this many days.
+        #[arg(
+            long,
+            value_name = "DAYS",
+            default_value_t = 30,
+            help = "Delete delivered webhooks older than this"
+        )]
+        webhook_retention_days: u32,
+        /// Keep outputs and inputs invalidated by reorgs within this many blocks of the scanned tip.
+        #[arg(
+            long,
+            value_name = "BLOCKS",
+            default_value_t = RECENT_BLOCKS_TO_KEEP,
+            help = "Keep reorged outputs within this many blocks of the tip"
+        )]
+        reorg_horizon: u64,
+        /// Skip vacuuming the database file.
+        #[arg(long, help = "Skip vacuuming the database file")]
+        no_vacuum: bool,
+    },
+
+    /// Record the network of a database whose network could not be inferred.
+    ///
+    /// The network of a database from before networks were recorded is inferred from the
+    /// addresses in its accounts' history. If none of its accounts has any history, the wallet
+    /// refuses to open it until its network is confirmed with this command. The confirmed
+    /// network must be the one the wallet is configured for.
+    ///
+    /// # Example
+    ///
+    /// ```bash
+    /// tari --network mainnet confirm-network --confirm-network mainnet
+    /// ```
+    ConfirmNetwork {
+        #[command(flatten)]
+        db: DatabaseArgs,
+        /// Network to record for the database; must match the configured network.
+        #[arg(long, value_name = "NETWORK", help = "Network to record for the database")]
+        confirm_network: Network,
+    },
+
+    /// Reconcile account balances against the outputs and inputs they were derived from.
+    ///
+    /// Recomputes each balance from the unspent outputs and compares it to the balance
+    /// changes and the displayed transactions, and checks that spent outputs have exactly one
+    /// input, that locked outputs belong to live pending transactions and that reversed balance
+    /// changes have their reversal. With `--repair`, the displayed transactions of accounts that
+    /// disagree with the ledger are regenerated, which needs the wallet password.
+    ///
+    /// # Example
+    ///
+    /// ```bash
+    /// tari verify --account-name default --repair --password-file ~/.minotari/password
+    /// ```
+    Verify {
+        #[command(flatten)]
+        security: SecurityArgs,
+        #[command(flatten)]
+        db: DatabaseArgs,
+        #[command(flatten)]
+        account: AccountArgs,
+        /// Regenerate displayed transactions that disagree with the ledger.
+        #[arg(long, help = "Regenerate displayed transactions that disagree with the ledger")]
+        repair: bool,
+    },
+
     /// Delete a wallet account and all associated data.
     ///
     /// This permanently removes the account, transaction history, and keys from the database.
@@ -660,7 +1249,7 @@ pub enum Commands {
     ///     --account-name main \
     ///     --amount 1000000 \
     ///     --claim-public-key <hex> \
-    ///     --password secret
+    ///     --password-file ~/.minotari/password
     /// ```
     BurnFunds {
         #[command(flatten)]
@@ -696,6 +1285,276 @@ pub enum Commands {
         #[arg(long, default_value_t = 86400)]
         seconds_to_lock: u64,
     },
+
+    /// Manage the API keys that authenticate requests to the daemon's REST API.
+    ///
+    /// Keys are stored hashed in the wallet database; the key itself is only shown
+    /// once, when it is created. Each key has a scope (`read-only`, `spend` or `admin`)
+    /// and can optionally be restricted to specific accounts.
+    ///
+    /// # Example
+    ///
+    /// ```bash
+    /// tari api-key create --name pos-terminal --scope read-only --account shop
+    /// tari api-key list
+    /// tari api-key revoke --name pos-terminal
+    /// ```
+    ApiKey {
+        #[command(subcommand)]
+        command: ApiKeyCommand,
+    },
+
+    /// Manage webhook subscriptions.
+    ///
+    /// Each subscription receives wallet events at its own URL, signed with its own
+    /// secret, optionally filtered by event type and account. Subscriptions are
+    /// delivered by the daemon in addition to the webhook configured in `config.toml`.
+    ///
+    /// # Example
+    ///
+    /// ```bash
+    /// tari webhook create --name accounting --url https://accounting.example.com/hook \
+    ///     --event-type TransactionConfirmed --account shop
+    /// tari webhook list
+    /// tari webhook rotate-secret --name accounting
+    /// tari webhook disable --name accounting
+    /// ```
+    Webhook {
+        #[command(subcommand)]
+        command: WebhookCommand,
+    },
+
+    /// Manage the approval policies that require M-of-N approvers for outgoing payments.
+    ///
+    /// Approvers are API keys. Payments of at least the threshold wait in `send` until the
+    /// required number of approvers has approved them through the REST API
+    /// (`POST /accounts/{name}/approvals/{id}/approve`); a single rejection cancels them.
+    ///
+    /// # Example
+    ///
+    /// ```bash
+    /// tari approval-policy set --account treasury --required 2 \
+    ///     --approver cfo --approver controller --approver ceo --threshold 100000000000
+    /// tari approval-policy show
+    /// tari approval-policy remove --account treasury
+    /// ```
+    ApprovalPolicy {
+        #[command(subcommand)]
+        command: ApprovalPolicyCommand,
+    },
+}
+
+/// Subcommands of [`Commands::ApiKey`].
+#[derive(Subcommand)]
+pub enum ApiKeyCommand {
+    /// Create a new API key and print it.
+    Create {
+        #[command(flatten)]
+        db: DatabaseArgs,
+
+        /// Unique name identifying the key.
+        #[arg(short, long, help = "Name of the API key")]
+        name: String,
+        /// Permission level of the key.
+        #[arg(short, long, help = "Scope of the API key (read-only, spend, admin)")]
+        scope: ApiKeyScope,
+        /// Restrict the key to this account. Can be repeated; all accounts if omitted.
+        #[arg(short, long = "account", help = "Account the key is restricted to (repeatable)")]
+        accounts: Vec<String>,
+    },
+    /// List all API keys, including revoked ones.
+    List {
+        #[command(flatten)]
+        db: DatabaseArgs,
+    },
+    /// Revoke an API key. Requests using it are rejected immediately.
+    Revoke {
+        #[command(flatten)]
+        db: DatabaseArgs,
+
+        /// Name of the key to revoke.
+        #[arg(short, long, help = "Name of the API key")]
+        name: String,
+    },
+}
+
+/// Subcommands of [`Commands::ApprovalPolicy`].
+#[derive(Subcommand)]
+pub enum ApprovalPolicyCommand {
+    /// Set the approval policy of an account, replacing its previous policy.
+    Set {
+        #[command(flatten)]
+        db: DatabaseArgs,
+
+        /// Name of the account the policy applies to.
+        #[arg(short, long, help = "Name of the account")]
+        account: String,
+        /// Number of distinct approvers that have to approve a payment.
+        #[arg(short, long, help = "Number of approvals required (M)")]
+        required: u32,
+        /// Name of an API key that may approve payments. Repeat for each approver.
+        #[arg(
+            long = "approver",
+            required = true,
+            help = "API key name of an approver (repeatable)"
+        )]
+        approvers: Vec<String>,
+        /// Payments of at least this amount in microTari need approval.
+        #[arg(short, long, help = "Smallest amount in microTari that needs approval", default_value_t = MicroMinotari(0))]
+        threshold: MicroMinotari,
+    },
+    /// Show the approval policies of all accounts, or of one account.
+    Show {
+        #[command(flatten)]
+        db: DatabaseArgs,
+
+        /// Only show the policy of this account.
+        #[arg(short, long, help = "Name of the account")]
+        account: Option<String>,
+    },
+    /// Remove the approval policy of an account. Payments already awaiting approval keep waiting.
+    Remove {
+        #[command(flatten)]
+        db: DatabaseArgs,
+
+        /// Name of the account.
+        #[arg(short, long, help = "Name of the account")]
+        account: String,
+    },
+}
+
+/// Subcommands of [`Commands::Webhook`].
+#[derive(Subcommand)]
+pub enum WebhookCommand {
+    /// Create a webhook subscription and print its signing secret.
+    Create {
+        #[command(flatten)]
+        db: DatabaseArgs,
+
+        /// Unique name identifying the subscription.
+        #[arg(short, long, help = "Name of the subscription")]
+        name: String,
+        /// HTTP(S) endpoint the events are posted to, or a `file:`, `unix:` or `exec:` event sink.
+        #[arg(
+            short,
+            long,
+            help = "Webhook URL or event sink (file:///path, unix:///path, exec:///path)"
+        )]
+        url: String,
+        /// Signing secret. A random secret is generated if omitted.
+        #[arg(long, help = "Signing secret (generated if omitted)")]
+        secret: Option<String>,
+        /// Only deliver this event type. Can be repeated; all event types if omitted.
+        #[arg(short, long = "event-type", help = "Event type to deliver (repeatable)")]
+        event_types: Vec<String>,
+        /// Only deliver events of this account. Can be repeated; all accounts if omitted.
+        #[arg(short, long = "account", help = "Account whose events are delivered (repeatable)")]
+        accounts: Vec<String>,
+    },
+    /// List all webhook subscriptions.
+    List {
+        #[command(flatten)]
+        db: DatabaseArgs,
+    },
+    /// Change the URL, event types or accounts of a subscription.
+    Update {
+        #[command(flatten)]
+        db: DatabaseArgs,
+
+        /// Name of the subscription to update.
+        #[arg(short, long, help = "Name of the subscription")]
+        name: String,
+        /// New webhook URL.
+        #[arg(short, long, help = "New webhook URL")]
+        url: Option<String>,
+        /// Replace the event type filter. Can be repeated.
+        #[arg(short, long = "event-type", help = "Event type to deliver (repeatable)")]
+        event_types: Vec<String>,
+        /// Remove the event type filter, delivering all event types.
+        #[arg(long, conflicts_with = "event_types")]
+        all_events: bool,
+        /// Replace the account filter. Can be repeated.
+        #[arg(short, long = "account", help = "Account whose events are delivered (repeatable)")]
+        accounts: Vec<String>,
+        /// Remove the account filter, delivering events of all accounts.
+        #[arg(long, conflicts_with = "accounts")]
+        all_accounts: bool,
+    },
+    /// Resume deliveries to a disabled subscription, including events queued meanwhile.
+    Enable {
+        #[command(flatten)]
+        db: DatabaseArgs,
+
+        /// Name of the subscription to enable.
+        #[arg(short, long, help = "Name of the subscription")]
+        name: String,
+    },
+    /// Stop deliveries to a subscription.
+    Disable {
+        #[command(flatten)]
+        db: DatabaseArgs,
+
+        /// Name of the subscription to disable.
+        #[arg(short, long, help = "Name of the subscription")]
+        name: String,
+    },
+    /// Replace the signing secret of a subscription and print the new one.
+    RotateSecret {
+        #[command(flatten)]
+        db: DatabaseArgs,
+
+        /// Name of the subscription.
+        #[arg(short, long, help = "Name of the subscription")]
+        name: String,
+    },
+    /// List webhook deliveries with their status, attempt count and last error, newest first.
+    Deliveries {
+        #[command(flatten)]
+        db: DatabaseArgs,
+
+        /// Only list deliveries with this status.
+        #[arg(long, help = "pending, success, failed or permanent_failure")]
+        status: Option<String>,
+        /// Only list deliveries of this event type.
+        #[arg(short, long, help = "Event type of the deliveries")]
+        event_type: Option<String>,
+        /// Only list deliveries queued at or after this time.
+        #[arg(long, help = "RFC 3339 timestamp, e.g. 2025-06-01T00:00:00Z")]
+        since: Option<DateTime<Utc>>,
+        /// Maximum number of deliveries to list.
+        #[arg(long, default_value_t = 50)]
+        limit: i64,
+    },
+    /// Send a single delivery, or all failed deliveries since a time, again.
+    Replay {
+        #[command(flatten)]
+        db: DatabaseArgs,
+
+        /// Id of the delivery to send again.
+        #[arg(
+            long,
+            help = "Id of the delivery",
+            conflicts_with = "failed_since",
+            required_unless_present = "failed_since"
+        )]
+        id: Option<i64>,
+        /// Send all failed deliveries queued at or after this time again.
+        #[arg(long, help = "RFC 3339 timestamp, e.g. 2025-06-01T00:00:00Z")]
+        failed_since: Option<DateTime<Utc>>,
+    },
+    /// Queue a stored wallet event for delivery again.
+    ResendEvent {
+        #[command(flatten)]
+        db: DatabaseArgs,
+
+        /// Id of the wallet event in the events table.
+        #[arg(long, help = "Id of the wallet event")]
+        event_id: i64,
+        /// Only send the event to this subscription, regardless of its event type filter. The
+        /// subscription must receive events of the event's account.
+        #[arg(short, long, help = "Name of the subscription")]
+        subscription: Option<String>,
+    },
 }
 
 #[derive(Args, Debug)]
@@ -709,6 +1568,12 @@ pub struct BurnArgs {
 pub struct DaemonArgs {
     pub scan_interval_secs: Option<u64>,
     pub api_port: Option<u16>,
+    pub require_api_key: bool,
+    pub api_bind_addresses: Vec<String>,
+    pub api_unix_socket: Option<PathBuf>,
+    pub api_tls_cert: Option<PathBuf>,
+    pub api_tls_key: Option<PathBuf>,
+    pub api_tls_client_ca: Option<PathBuf>,
 }
 
 pub trait ApplyArgs {
@@ -0,0 +1,85 @@
+//! CLI handlers for the `export-account` and `import-account` commands.
+//!
+//! See [`crate::account_export`] for what an export holds and how imports are checked.
+
+use std::path::Path;
+
+use tari_common::configuration::Network;
+
+use crate::account_export::{self, AccountExportManifest};
+
+/// Exports the account `account_name` to `output` and prints its manifest.
+pub fn handle_export_account(
+    database_file: &Path,
+    account_name: &str,
+    password: &str,
+    export_password: &str,
+    network: Network,
+    include_history: bool,
+    output: &Path,
+) -> Result<(), anyhow::Error> {
+    let manifest = account_export::export_account_to_file(
+        database_file,
+        account_name,
+        password,
+        export_password,
+        network,
+        include_history,
+        output,
+    )?;
+    println!("Account exported to {}", output.display());
+    print_manifest(&manifest);
+    Ok(())
+}
+
+/// Imports the account exported to `input` and prints its manifest.
+pub fn handle_import_account(
+    database_file: &Path,
+    input: &Path,
+    export_password: &str,
+    password: &str,
+    account_name: Option<&str>,
+    network: Network,
+) -> Result<(), anyhow::Error> {
+    let manifest = account_export::import_account_from_file(
+        database_file,
+        input,
+        export_password,
+        password,
+        account_name,
+        network,
+    )?;
+    println!(
+        "Imported account '{}' from {}",
+        account_name.unwrap_or(&manifest.name),
+        input.display()
+    );
+    print_manifest(&manifest);
+    if !manifest.includes_history {
+        println!("The export holds no history. Scan to rebuild it from the birthday.");
+    }
+    Ok(())
+}
+
+fn print_manifest(manifest: &AccountExportManifest) {
+    println!("  Exported:       {}", manifest.created_at.to_rfc3339());
+    println!("  Exported as:    {}", manifest.name);
+    println!("  Network:        {}", manifest.network);
+    println!("  Fingerprint:    {}", manifest.fingerprint);
+    println!("  Birthday:       {}", manifest.birthday);
+    let height = manifest
+        .scanned_height
+        .map_or_else(|| "not scanned".to_string(), |height| height.to_string());
+    println!("  Scanned height: {}", height);
+    println!(
+        "  History:        {}",
+        if manifest.includes_history {
+            "included"
+        } else {
+            "not included"
+        }
+    );
+    if manifest.external_signer {
+        println!("  Signing:        external signer");
+    }
+}
@@ -0,0 +1,113 @@
+//! CLI handlers for the `api-key` commands.
+//!
+//! API keys authenticate requests to the daemon's REST API (see [`crate::api::auth`]).
+//! Only a hash of each key is stored, so the key is printed once on creation.
+
+use std::path::PathBuf;
+
+use anyhow::anyhow;
+use log::info;
+use tari_common::configuration::Network;
+
+use crate::{
+    api::auth::{generate_api_key, hash_api_key},
+    db::{self, NewApiKey, init_db},
+    models::ApiKeyScope,
+};
+
+/// Creates an API key, optionally restricted to the given accounts, and prints it.
+pub fn handle_create_api_key(
+    database_file: PathBuf,
+    network: Network,
+    name: String,
+    scope: ApiKeyScope,
+    accounts: Vec<String>,
+) -> Result<(), anyhow::Error> {
+    let pool = init_db(database_file, network)?;
+    let conn = pool.get()?;
+
+    let account_ids = accounts
+        .iter()
+        .map(|account_name| {
+            db::get_account_by_name(&conn, account_name)?
+                .map(|account| account.id)
+                .ok_or_else(|| anyhow!("Account '{}' not found", account_name))
+        })
+        .collect::<Result<Vec<_>, anyhow::Error>>()?;
+
+    let (key, key_prefix) = generate_api_key();
+    let key_hash = hash_api_key(&key);
+    db::insert_api_key(
+        &conn,
+        &NewApiKey {
+            name: &name,
+            key_prefix: &key_prefix,
+            key_hash: &key_hash,
+            scope,
+            account_ids: (!account_ids.is_empty()).then_some(account_ids.as_slice()),
+        },
+    )?;
+    info!(target: "audit", name = name.as_str(), scope:% = scope; "API key created");
+
+    println!("Created API key '{}' with scope '{}'.", name, scope);
+    if !accounts.is_empty() {
+        println!("Restricted to accounts: {}", accounts.join(", "));
+    }
+    println!();
+    println!("{}", key);
+    println!();
+    println!("Store this key now, it cannot be shown again.");
+    Ok(())
+}
+
+/// Prints all API keys with their scope, account restrictions and usage.
+pub fn handle_list_api_keys(database_file: PathBuf, network: Network) -> Result<(), anyhow::Error> {
+    let pool = init_db(database_file, network)?;
+    let conn = pool.get()?;
+
+    let keys = db::get_api_keys(&conn)?;
+    if keys.is_empty() {
+        println!("No API keys found.");
+        return Ok(());
+    }
+
+    let accounts = db::get_accounts(&conn, None)?;
+    for key in keys {
+        let account_names = match &key.account_ids {
+            None => "all".to_string(),
+            Some(ids) => accounts
+                .iter()
+                .filter(|account| ids.contains(&account.id))
+                .map(|account| account.friendly_name.as_str())
+                .collect::<Vec<_>>()
+                .join(", "),
+        };
+        let status = match &key.revoked_at {
+            Some(revoked_at) => format!("revoked {}", revoked_at),
+            None => "active".to_string(),
+        };
+        println!(
+            "{} ({}...): scope={}, accounts=[{}], created={}, last_used={}, {}",
+            key.name,
+            key.key_prefix,
+            key.scope,
+            account_names,
+            key.created_at,
+            key.last_used_at.as_deref().unwrap_or("never"),
+            status,
+        );
+    }
+    Ok(())
+}
+
+/// Revokes the active API key with the given name.
+pub fn handle_revoke_api_key(database_file: PathBuf, network: Network, name: String) -> Result<(), anyhow::Error> {
+    let pool = init_db(database_file, network)?;
+    let conn = pool.get()?;
+
+    if !db::revoke_api_key(&conn, &name)? {
+        return Err(anyhow!("No active API key named '{}'", name));
+    }
+    println!("API key '{}' revoked.", name);
+    Ok(())
+}
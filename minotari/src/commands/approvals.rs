@@ -0,0 +1,140 @@
+//! CLI handlers for the `approval-policy` commands.
+//!
+//! An approval policy requires M-of-N named approvers, each identified by their own API key,
+//! to approve an account's outgoing payments before they are signed and broadcast (see
+//! [`crate::api::accounts::api_approve_transaction`]).
+
+use std::path::PathBuf;
+
+use anyhow::anyhow;
+use log::info;
+use tari_common::configuration::Network;
+use tari_transaction_components::tari_amount::MicroMinotari;
+
+use crate::{
+    db::{self, init_db},
+    models::ApiKeyScope,
+};
+
+/// Sets the approval policy of `account`, replacing its previous policy.
+///
+/// Approvers are named by their API keys, which must be active, have at least the `spend`
+/// scope and be allowed to access the account.
+pub fn handle_set_approval_policy(
+    database_file: PathBuf,
+    network: Network,
+    account: String,
+    required: u32,
+    approvers: Vec<String>,
+    threshold: MicroMinotari,
+) -> Result<(), anyhow::Error> {
+    let pool = init_db(database_file, network)?;
+    let conn = pool.get()?;
+
+    let account_row =
+        db::get_account_by_name(&conn, &account)?.ok_or_else(|| anyhow!("Account '{}' not found", account))?;
+    let keys = db::get_api_keys(&conn)?;
+    let approver_key_ids = approvers
+        .iter()
+        .map(|name| {
+            let key = keys
+                .iter()
+                .find(|key| &key.name == name && key.revoked_at.is_none())
+                .ok_or_else(|| anyhow!("No active API key named '{}'", name))?;
+            if !key.scope.allows(ApiKeyScope::Spend) {
+                return Err(anyhow!(
+                    "API key '{}' has scope '{}', but approvers need at least '{}'",
+                    name,
+                    key.scope,
+                    ApiKeyScope::Spend
+                ));
+            }
+            if !key.allows_account(account_row.id) {
+                return Err(anyhow!("API key '{}' may not access account '{}'", name, account));
+            }
+            Ok(key.id)
+        })
+        .collect::<Result<Vec<_>, anyhow::Error>>()?;
+
+    db::set_approval_policy(&conn, account_row.id, required, threshold, &approver_key_ids)?;
+    info!(target: "audit", account = account.as_str(), required = required; "Approval policy set");
+
+    println!(
+        "Payments of at least {} from '{}' now need {} of {} approvals ({}).",
+        threshold,
+        account,
+        required,
+        approver_key_ids.len(),
+        approvers.join(", ")
+    );
+    Ok(())
+}
+
+/// Prints the approval policies of all accounts, or of `account` only.
+pub fn handle_show_approval_policies(
+    database_file: PathBuf,
+    network: Network,
+    account: Option<String>,
+) -> Result<(), anyhow::Error> {
+    let pool = init_db(database_file, network)?;
+    let conn = pool.get()?;
+
+    let accounts = db::get_accounts(&conn, account.as_deref())?;
+    if let Some(name) = &account
+        && accounts.is_empty()
+    {
+        return Err(anyhow!("Account '{}' not found", name));
+    }
+
+    let mut found = false;
+    for account in accounts {
+        let Some(policy) = db::get_approval_policy(&conn, account.id)? else {
+            continue;
+        };
+        found = true;
+        let approvers = policy
+            .approvers
+            .iter()
+            .map(|approver| {
+                if approver.revoked {
+                    format!("{} (revoked)", approver.name)
+                } else {
+                    approver.name.clone()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        let waiting = db::get_open_approval_requests(&conn, account.id)?.len();
+        println!(
+            "{}: {} of [{}] for payments of at least {}, {} awaiting approval, updated={}",
+            account.friendly_name,
+            policy.required_approvals,
+            approvers,
+            policy.threshold_amount,
+            waiting,
+            policy.updated_at,
+        );
+    }
+    if !found {
+        println!("No approval policies found.");
+    }
+    Ok(())
+}
+
+/// Removes the approval policy of `account`.
+pub fn handle_remove_approval_policy(
+    database_file: PathBuf,
+    network: Network,
+    account: String,
+) -> Result<(), anyhow::Error> {
+    let pool = init_db(database_file, network)?;
+    let conn = pool.get()?;
+
+    let account_row =
+        db::get_account_by_name(&conn, &account)?.ok_or_else(|| anyhow!("Account '{}' not found", account))?;
+    if !db::delete_approval_policy(&conn, account_row.id)? {
+        return Err(anyhow!("Account '{}' has no approval policy", account));
+    }
+    println!("Approval policy of '{}' removed.", account);
+    Ok(())
+}
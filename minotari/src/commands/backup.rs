@@ -0,0 +1,50 @@
+//! CLI handlers for the `backup` and `restore` commands.
+//!
+//! See [`crate::backup`] for the format of backup files and how they are verified.
+
+use std::path::Path;
+
+use tari_common::configuration::Network;
+
+use crate::backup::{self, BackupManifest};
+
+/// Writes an encrypted backup of the database to `output` and prints its manifest.
+pub fn handle_backup(
+    database_file: &Path,
+    network: Network,
+    password: &str,
+    output: &Path,
+) -> Result<(), anyhow::Error> {
+    let manifest = backup::backup_to_file(database_file, network, password, output)?;
+    println!("Backup written to {}", output.display());
+    print_manifest(&manifest);
+    Ok(())
+}
+
+/// Restores the database from the backup `input` and prints its manifest.
+pub fn handle_restore(
+    database_file: &Path,
+    network: Network,
+    password: &str,
+    input: &Path,
+    force: bool,
+) -> Result<(), anyhow::Error> {
+    let manifest = backup::restore_backup(input, password, database_file, network, force)?;
+    println!("Restored {} from {}", database_file.display(), input.display());
+    print_manifest(&manifest);
+    Ok(())
+}
+
+fn print_manifest(manifest: &BackupManifest) {
+    println!("  Created:        {}", manifest.created_at.to_rfc3339());
+    println!("  Network:        {}", manifest.network);
+    println!("  Schema version: {}", manifest.schema_version);
+    println!("  Wallet version: {}", manifest.wallet_version);
+    println!("  Accounts:       {}", manifest.accounts.len());
+    for account in &manifest.accounts {
+        let height = account
+            .scanned_height
+            .map_or_else(|| "not scanned".to_string(), |height| format!("scanned to {}", height));
+        println!("    {} ({}, {})", account.name, account.fingerprint, height);
+    }
+}
@@ -10,6 +10,7 @@ use anyhow::anyhow;
 use log::info;
 use tari_common::configuration::Network;
 use tari_transaction_components::tari_amount::MicroMinotari;
+use zeroize::Zeroizing;
 
 use crate::{
     db::{self, init_db},
@@ -29,7 +30,7 @@ pub async fn handle_burn_funds(
     sidechain_deployment_key: Option<String>,
     database_file: PathBuf,
     network: Network,
-    password: String,
+    password: Zeroizing<String>,
     idempotency_key: Option<String>,
     seconds_to_lock: u64,
     confirmation_window: u64,
@@ -48,7 +49,7 @@ pub async fn handle_burn_funds(
 
     let idempotency_key = idempotency_key.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
 
-    let pool = init_db(database_file)?;
+    let pool = init_db(database_file, network)?;
     let conn = pool.get()?;
 
     let account = db::get_account_by_name(&conn, &account_name)?
@@ -0,0 +1,67 @@
+//! Start-up unlocking of accounts for the `daemon` command.
+//!
+//! Accounts can be encrypted with different passwords. The daemon unlocks the accounts its
+//! start-up password decrypts and leaves the others locked, to be unlocked through the API.
+
+use std::path::Path;
+
+use anyhow::bail;
+use log::{info, warn};
+use tari_common::configuration::Network;
+
+use crate::{
+    db::{self, init_db},
+    keyring::Keyring,
+};
+
+/// Unlocks the accounts `password` decrypts into a new [`Keyring`].
+///
+/// With `account_names` only those accounts are unlocked, and each of them must decrypt.
+/// Without a password every account starts locked.
+pub fn unlock_at_startup(
+    database_file: &Path,
+    network: Network,
+    password: Option<&str>,
+    account_names: &[String],
+) -> Result<Keyring, anyhow::Error> {
+    let keyring = Keyring::new();
+    let Some(password) = password else {
+        info!("Starting with every account locked");
+        return Ok(keyring);
+    };
+
+    let pool = init_db(database_file.to_path_buf(), network)?;
+    let conn = pool.get()?;
+    let mut accounts = db::get_accounts(&conn, None)?;
+    if !account_names.is_empty() {
+        for name in account_names {
+            if !accounts.iter().any(|account| &account.friendly_name == name) {
+                bail!("Account '{}' not found", name);
+            }
+        }
+        accounts.retain(|account| account_names.contains(&account.friendly_name));
+    }
+
+    let locked = keyring.unlock_accounts(&accounts, password)?;
+    if !account_names.is_empty()
+        && let Some(account) = locked.first()
+    {
+        bail!("The password does not decrypt account '{}'", account.friendly_name);
+    }
+    if !accounts.is_empty() && locked.len() == accounts.len() {
+        bail!("The password does not decrypt any account");
+    }
+    for account in &locked {
+        warn!(
+            account = &*account.friendly_name;
+            "Account is encrypted with a different password and stays locked until unlocked through the API"
+        );
+    }
+    info!(
+        target: "audit",
+        unlocked = accounts.len() - locked.len(),
+        locked = locked.len();
+        "Accounts unlocked at start-up"
+    );
+    Ok(keyring)
+}
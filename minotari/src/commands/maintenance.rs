@@ -0,0 +1,57 @@
+//! CLI handler for the `db-maintenance` command.
+//!
+//! See [`crate::maintenance`] for what is checked and deleted.
+
+use std::path::Path;
+
+use anyhow::bail;
+use tari_common::configuration::Network;
+
+use crate::maintenance::{self, MaintenanceOptions};
+
+/// Checks, prunes and vacuums the database and prints what was done.
+pub fn handle_db_maintenance(
+    database_file: &Path,
+    network: Network,
+    options: &MaintenanceOptions,
+) -> Result<(), anyhow::Error> {
+    let report = maintenance::run_maintenance(database_file, network, options)?;
+
+    println!("Integrity check:   {}", problems(&report.integrity_errors));
+    println!("Foreign key check: {}", problems(&report.foreign_key_violations));
+    for problem in report.integrity_errors.iter().chain(&report.foreign_key_violations) {
+        println!("  {}", problem);
+    }
+    if !report.is_consistent() {
+        bail!("The database failed its consistency checks; nothing was deleted. Restore it from a backup");
+    }
+    if options.check_only {
+        return Ok(());
+    }
+
+    println!("Deleted:");
+    println!("  Wallet events:       {}", report.events);
+    println!("  Webhook deliveries:  {}", report.webhook_deliveries);
+    println!("  Reorged outputs:     {}", report.outputs);
+    println!("  Reorged inputs:      {}", report.inputs);
+    println!("  Scanned tip blocks:  {}", report.scanned_tip_blocks);
+    println!(
+        "Size: {} -> {} ({} reclaimed)",
+        format_size(report.size_before),
+        format_size(report.size_after),
+        format_size(report.reclaimed())
+    );
+    Ok(())
+}
+
+fn problems(problems: &[String]) -> String {
+    if problems.is_empty() {
+        "ok".to_string()
+    } else {
+        format!("{} problem(s)", problems.len())
+    }
+}
+
+fn format_size(bytes: u64) -> String {
+    format!("{:.1} MiB", bytes as f64 / (1024.0 * 1024.0))
+}
@@ -1,2 +1,12 @@
+pub mod account_export;
+pub mod api_keys;
+pub mod approvals;
+pub mod backup;
 pub mod burn;
+pub mod daemon;
+pub mod maintenance;
+pub mod password;
+pub mod send;
 pub mod validator_nodes;
+pub mod verify;
+pub mod webhooks;
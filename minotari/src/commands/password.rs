@@ -0,0 +1,59 @@
+//! CLI handler for the `change-password` command.
+//!
+//! Every account's wallet is encrypted under its own salt, and accounts may use different
+//! passwords. Changing a password re-encrypts one account, or every account encrypted with the
+//! current password, at once (see [`db::change_accounts_password`]).
+
+use std::path::PathBuf;
+
+use anyhow::bail;
+use log::info;
+use tari_common::configuration::Network;
+
+use crate::db::{self, AccountRow, init_db};
+
+/// Re-encrypts `account_name`, or every account `current_password` decrypts, with
+/// `new_password`. Without a new password this is a dry run that only lists the accounts.
+pub fn handle_change_password(
+    database_file: PathBuf,
+    network: Network,
+    account_name: Option<&str>,
+    current_password: &str,
+    new_password: Option<&str>,
+) -> Result<(), anyhow::Error> {
+    let pool = init_db(database_file, network)?;
+    let mut conn = pool.get()?;
+
+    let Some(new_password) = new_password else {
+        let accounts = db::find_accounts_decrypting(&conn, account_name, current_password)?;
+        println!(
+            "Dry run: the current password decrypts {} account(s): {}. Nothing was changed.",
+            accounts.len(),
+            names(&accounts)
+        );
+        return Ok(());
+    };
+    if new_password == current_password {
+        bail!("The new password must differ from the current one");
+    }
+
+    let tx = conn.transaction()?;
+    let accounts = db::change_accounts_password(&tx, account_name, current_password, new_password)?;
+    tx.commit()?;
+    info!(target: "audit", accounts = accounts.len(); "Account password changed");
+
+    println!(
+        "Re-encrypted {} account(s) with the new password: {}",
+        accounts.len(),
+        names(&accounts)
+    );
+    Ok(())
+}
+
+fn names(accounts: &[AccountRow]) -> String {
+    accounts
+        .iter()
+        .map(|account| account.friendly_name.as_str())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -0,0 +1,91 @@
+//! CLI handler for the `send` command.
+//!
+//! Prepares a one-sided payment with [`TransactionSender`], has it signed by the wallet or,
+//! for accounts marked as externally signed, by the configured
+//! [`ExternalSigner`], and broadcasts it.
+//!
+//! Payments covered by the account's approval policy are not signed until the approvers have
+//! approved them; `send` then reports what is missing, and running it again with the same
+//! idempotency key sends the approved payment.
+
+use std::path::PathBuf;
+
+use anyhow::anyhow;
+use log::warn;
+use tari_common::configuration::Network;
+use zeroize::Zeroizing;
+
+use crate::{
+    db::init_db,
+    transactions::{
+        external_signer::{ExternalSigner, sign_for_account},
+        manager::TransactionSender,
+        one_sided_transaction::Recipient,
+    },
+    webhooks::WebhookTriggerConfig,
+};
+
+/// Sends `recipient` a one-sided payment from `account_name` and broadcasts it to `base_url`.
+///
+/// The locked UTXOs are released again if the transaction cannot be signed. A transaction
+/// awaiting approval keeps its UTXOs locked until it is approved or the lock expires.
+#[allow(clippy::too_many_arguments)]
+pub async fn handle_send(
+    recipient: Recipient,
+    database_file: PathBuf,
+    account_name: String,
+    network: Network,
+    password: Zeroizing<String>,
+    idempotency_key: Option<String>,
+    seconds_to_lock: u64,
+    confirmation_window: u64,
+    webhook_config: WebhookTriggerConfig,
+    base_url: String,
+    signer: Option<&ExternalSigner>,
+) -> Result<(), anyhow::Error> {
+    let idempotency_key = idempotency_key.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let pool = init_db(database_file, network)?;
+    let mut sender = TransactionSender::new(pool, account_name, password, network, confirmation_window)?;
+    sender.set_webhook_config(Some(webhook_config));
+
+    let unsigned = sender.start_new_transaction(idempotency_key.clone(), recipient, seconds_to_lock)?;
+    if let Some(request) = sender.approval_request()?
+        && !request.is_approved()
+    {
+        if let Some(rejection) = request.rejection() {
+            sender.abandon_transaction()?;
+            return Err(anyhow!(
+                "Transaction {} was rejected by approver '{}'",
+                request.pending_transaction_id,
+                rejection.approver
+            ));
+        }
+        println!(
+            "Transaction {} is awaiting approval ({} of {} approvals).",
+            request.pending_transaction_id,
+            request.approvals(),
+            request.required_approvals
+        );
+        println!(
+            "Once it is approved, run this command again with --idempotency-key {} before {}.",
+            idempotency_key, request.expires_at
+        );
+        return Ok(());
+    }
+
+    let signed = match sign_for_account(&sender.account, &sender.password, network, signer, unsigned).await {
+        Ok(signed) => signed,
+        Err(e) => {
+            warn!(target: "audit", error:% = e; "Transaction was not signed, unlocking funds");
+            sender.abandon_transaction()?;
+            return Err(e);
+        },
+    };
+
+    let displayed = sender.finalize_transaction_and_broadcast(signed, base_url).await?;
+    println!(
+        "Transaction broadcasted. tx_id={}, amount={}",
+        displayed.id, displayed.amount
+    );
+    Ok(())
+}
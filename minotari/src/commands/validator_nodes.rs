@@ -2,6 +2,7 @@
 //!
 //! Each handler parses its CLI inputs, builds the appropriate params struct,
 //! calls the transaction constructor, then signs, persists, and broadcasts the result.
+//! Accounts marked as externally signed are signed by the configured external signer.
 
 use std::{fs, path::PathBuf};
 
@@ -9,10 +10,13 @@ use crate::{
     db::{self, AccountRow, init_db},
     http::WalletHttpClient,
     models::PendingTransactionStatus,
-    transactions::validator_node::{
-        eviction::{ValidatorNodeEvictionParams, create_validator_node_eviction_tx},
-        exit::{ValidatorNodeExitParams, create_validator_node_exit_tx},
-        registration::{ValidatorNodeRegistrationParams, create_validator_node_registration_tx},
+    transactions::{
+        external_signer::{ExternalSigner, sign_for_account},
+        validator_node::{
+            eviction::{ValidatorNodeEvictionParams, create_validator_node_eviction_tx},
+            exit::{ValidatorNodeExitParams, create_validator_node_exit_tx},
+            registration::{ValidatorNodeRegistrationParams, create_validator_node_registration_tx},
+        },
     },
 };
 use anyhow::anyhow;
@@ -24,11 +28,10 @@ use tari_common_types::{
     types::{CompressedPublicKey, CompressedSignature, PrivateKey},
 };
 use tari_transaction_components::{
-    consensus::ConsensusConstantsBuilder,
-    offline_signing::{models::PrepareOneSidedTransactionForSigningResult, sign_locked_transaction},
-    tari_amount::MicroMinotari,
+    offline_signing::models::PrepareOneSidedTransactionForSigningResult, tari_amount::MicroMinotari,
 };
 use tari_utilities::byte_array::ByteArray;
+use zeroize::Zeroizing;
 
 // ── Parsing helpers ──────────────────────────────────────────────────────────
 
@@ -78,10 +81,10 @@ async fn sign_save_and_broadcast(
     idempotency_key: &str,
     base_url: &str,
     tx_kind: &str,
+    signer: Option<&ExternalSigner>,
 ) -> Result<(), anyhow::Error> {
-    let key_manager = account.get_key_manager(password)?;
-    let consensus_constants = ConsensusConstantsBuilder::new(network).build();
-    let signed_result = sign_locked_transaction(&key_manager, consensus_constants, network, unsigned_result)
+    let signed_result = sign_for_account(account, password, network, signer, unsigned_result)
+        .await
         .map_err(|e| anyhow!("Failed to sign {} transaction: {}", tx_kind, e))?;
 
     let completed_tx_id = signed_result.signed_transaction.tx_id;
@@ -148,6 +151,7 @@ async fn run_vn_command<F>(
     confirmation_window: u64,
     base_url: &str,
     tx_kind: &str,
+    signer: Option<&ExternalSigner>,
     create_tx: F,
 ) -> Result<(), anyhow::Error>
 where
@@ -162,7 +166,7 @@ where
     ) -> Result<PrepareOneSidedTransactionForSigningResult, anyhow::Error>,
 {
     let idempotency_key = idempotency_key.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
-    let pool = init_db(database_file)?;
+    let pool = init_db(database_file, network)?;
     let conn = pool.get()?;
     let account =
         db::get_account_by_name(&conn, account_name)?.ok_or_else(|| anyhow!("Account not found: {}", account_name))?;
@@ -186,6 +190,7 @@ where
         &idempotency_key,
         base_url,
         tx_kind,
+        signer,
     )
     .await
 }
@@ -205,11 +210,12 @@ pub async fn handle_register_validator_node(
     database_file: PathBuf,
     account_name: String,
     network: Network,
-    password: String,
+    password: Zeroizing<String>,
     idempotency_key: Option<String>,
     seconds_to_lock: u64,
     confirmation_window: u64,
     base_url: String,
+    signer: Option<&ExternalSigner>,
 ) -> Result<(), anyhow::Error> {
     let (vn_public_key, vn_signature) = parse_vn_signature(&vn_public_key, &vn_sig_nonce, &vn_sig)?;
     let claim_public_key = parse_compressed_public_key(&claim_public_key, "claim-public-key")?;
@@ -235,6 +241,7 @@ pub async fn handle_register_validator_node(
         confirmation_window,
         &base_url,
         "VN registration",
+        signer,
         |account, pool, network, password, idempotency_key, seconds_to_lock, confirmation_window| {
             create_validator_node_registration_tx(
                 account,
@@ -263,11 +270,12 @@ pub async fn handle_submit_validator_node_exit(
     database_file: PathBuf,
     account_name: String,
     network: Network,
-    password: String,
+    password: Zeroizing<String>,
     idempotency_key: Option<String>,
     seconds_to_lock: u64,
     confirmation_window: u64,
     base_url: String,
+    signer: Option<&ExternalSigner>,
 ) -> Result<(), anyhow::Error> {
     let (vn_public_key, vn_signature) = parse_vn_signature(&vn_public_key, &vn_sig_nonce, &vn_sig)?;
     let sidechain_deployment_key = parse_sidechain_deployment_key(sidechain_deployment_key)?;
@@ -291,6 +299,7 @@ pub async fn handle_submit_validator_node_exit(
         confirmation_window,
         &base_url,
         "VN exit",
+        signer,
         |account, pool, network, password, idempotency_key, seconds_to_lock, confirmation_window| {
             create_validator_node_exit_tx(
                 account,
@@ -316,11 +325,12 @@ pub async fn handle_submit_validator_eviction_proof(
     database_file: PathBuf,
     account_name: String,
     network: Network,
-    password: String,
+    password: Zeroizing<String>,
     idempotency_key: Option<String>,
     seconds_to_lock: u64,
     confirmation_window: u64,
     base_url: String,
+    signer: Option<&ExternalSigner>,
 ) -> Result<(), anyhow::Error> {
     let proof_json = fs::read_to_string(&proof_file)
         .map_err(|e| anyhow!("Failed to read proof file '{}': {}", proof_file.display(), e))?;
@@ -346,6 +356,7 @@ pub async fn handle_submit_validator_eviction_proof(
         confirmation_window,
         &base_url,
         "VN eviction proof",
+        signer,
         |account, pool, network, password, idempotency_key, seconds_to_lock, confirmation_window| {
             create_validator_node_eviction_tx(
                 account,
@@ -0,0 +1,100 @@
+//! CLI handler for the `verify` command.
+//!
+//! See [`crate::verify`] for what is compared.
+
+use std::path::Path;
+
+use anyhow::bail;
+use tari_common::configuration::Network;
+
+use crate::verify::{self, AccountVerification};
+
+/// Verifies the named account, or every account, and prints the discrepancies.
+///
+/// With a repair password, the displayed transactions of accounts whose displayed balance
+/// disagrees with the ledger are regenerated and the account is verified again.
+pub fn handle_verify(
+    database_file: &Path,
+    network: Network,
+    account_name: Option<&str>,
+    repair_password: Option<&str>,
+    required_confirmations: u64,
+) -> Result<(), anyhow::Error> {
+    let mut inconsistent = 0;
+    for mut verification in verify::verify_accounts(database_file, network, account_name)? {
+        if let Some(password) = repair_password
+            && !verification.displayed_matches_ledger()
+        {
+            let repair = verify::repair_displayed_transactions(
+                database_file,
+                network,
+                &verification.account_name,
+                password,
+                required_confirmations,
+            )?;
+            println!(
+                "Account '{}': regenerated displayed transactions ({} updated, {} inserted, {} reorganized)",
+                verification.account_name, repair.updated, repair.inserted, repair.reorganized
+            );
+            if let Some(verified) = verify::verify_accounts(database_file, network, Some(&verification.account_name))?
+                .into_iter()
+                .next()
+            {
+                verification = verified;
+            }
+        }
+        print_verification(&verification);
+        if !verification.is_consistent() {
+            inconsistent += 1;
+        }
+    }
+
+    if inconsistent > 0 {
+        bail!("{} account(s) failed verification", inconsistent);
+    }
+    Ok(())
+}
+
+fn print_verification(verification: &AccountVerification) {
+    println!(
+        "Account '{}': {}",
+        verification.account_name,
+        if verification.is_consistent() {
+            "ok"
+        } else {
+            "INCONSISTENT"
+        }
+    );
+    println!("  Ledger balance:        {} µT", verification.ledger_balance);
+    println!(
+        "  Unspent outputs:       {} µT{}",
+        verification.unspent_output_value,
+        mismatch(verification.ledger_matches_outputs())
+    );
+    println!(
+        "  Displayed balance:     {} µT{}",
+        verification.displayed_balance,
+        mismatch(verification.displayed_matches_ledger())
+    );
+    for output in &verification.spent_output_mismatches {
+        println!(
+            "  Output {} is {} with {} active input(s)",
+            output.output_id, output.status, output.active_inputs
+        );
+    }
+    for lock in &verification.stale_output_locks {
+        println!(
+            "  Output {} is locked by {} pending transaction {}",
+            lock.output_id,
+            lock.pending_status.as_deref().unwrap_or("missing"),
+            lock.locked_by_request_id.as_deref().unwrap_or("-")
+        );
+    }
+    for id in &verification.unreversed_balance_changes {
+        println!("  Balance change {} is marked reversed but has no reversal", id);
+    }
+}
+
+fn mismatch(matches: bool) -> &'static str {
+    if matches { "" } else { "  (does not match the ledger)" }
+}
@@ -0,0 +1,302 @@
+//! CLI handlers for the `webhook` commands.
+//!
+//! Webhook subscriptions are stored in the wallet database and picked up by a running
+//! daemon without a restart. Signing secrets are printed when a subscription is created
+//! or its secret is rotated. Replayed and re-sent deliveries are queued in the database
+//! and sent by the daemon's webhook worker.
+
+use std::{collections::HashMap, path::PathBuf};
+
+use anyhow::anyhow;
+use chrono::{DateTime, Utc};
+use rusqlite::Connection;
+use tari_common::configuration::Network;
+
+use crate::{
+    db::{self, NewWebhookSubscription, WebhookDeliveryFilter, WebhookSubscriptionUpdate, init_db},
+    webhooks::{
+        WebhookTriggerConfig,
+        utils::{generate_webhook_secret, resend_event, validate_event_types, validate_webhook_url},
+    },
+};
+
+fn resolve_account_ids(conn: &Connection, accounts: &[String]) -> Result<Vec<i64>, anyhow::Error> {
+    accounts
+        .iter()
+        .map(|account_name| {
+            db::get_account_by_name(conn, account_name)?
+                .map(|account| account.id)
+                .ok_or_else(|| anyhow!("Account '{}' not found", account_name))
+        })
+        .collect()
+}
+
+/// Creates a webhook subscription and prints its signing secret.
+pub fn handle_create_webhook(
+    database_file: PathBuf,
+    network: Network,
+    name: String,
+    url: String,
+    secret: Option<String>,
+    event_types: Vec<String>,
+    accounts: Vec<String>,
+) -> Result<(), anyhow::Error> {
+    validate_webhook_url(&url, true).map_err(|e| anyhow!(e))?;
+    validate_event_types(&event_types).map_err(|e| anyhow!(e))?;
+
+    let pool = init_db(database_file, network)?;
+    let conn = pool.get()?;
+
+    let account_ids = resolve_account_ids(&conn, &accounts)?;
+    let secret = secret.unwrap_or_else(generate_webhook_secret);
+    db::insert_webhook_subscription(
+        &conn,
+        &NewWebhookSubscription {
+            name: &name,
+            url: &url,
+            secret: &secret,
+            event_types: (!event_types.is_empty()).then_some(event_types.as_slice()),
+            account_ids: (!account_ids.is_empty()).then_some(account_ids.as_slice()),
+        },
+    )?;
+
+    println!("Created webhook subscription '{}' for {}.", name, url);
+    println!();
+    println!("Signing secret: {}", secret);
+    Ok(())
+}
+
+/// Prints all webhook subscriptions with their filters.
+pub fn handle_list_webhooks(database_file: PathBuf, network: Network) -> Result<(), anyhow::Error> {
+    let pool = init_db(database_file, network)?;
+    let conn = pool.get()?;
+
+    let subscriptions = db::get_webhook_subscriptions(&conn)?;
+    if subscriptions.is_empty() {
+        println!("No webhook subscriptions found.");
+        return Ok(());
+    }
+
+    let accounts = db::get_accounts(&conn, None)?;
+    for subscription in subscriptions {
+        let account_names = match &subscription.account_ids {
+            None => "all".to_string(),
+            Some(ids) => accounts
+                .iter()
+                .filter(|account| ids.contains(&account.id))
+                .map(|account| account.friendly_name.as_str())
+                .collect::<Vec<_>>()
+                .join(", "),
+        };
+        let event_types = subscription
+            .event_types
+            .as_ref()
+            .map_or_else(|| "all".to_string(), |types| types.join(", "));
+        println!(
+            "{}: url={}, events=[{}], accounts=[{}], updated={}, {}",
+            subscription.name,
+            subscription.url,
+            event_types,
+            account_names,
+            subscription.updated_at,
+            if subscription.enabled { "enabled" } else { "disabled" },
+        );
+    }
+    Ok(())
+}
+
+/// Updates the URL, event type filter or account filter of a subscription.
+#[allow(clippy::too_many_arguments)]
+pub fn handle_update_webhook(
+    database_file: PathBuf,
+    network: Network,
+    name: String,
+    url: Option<String>,
+    event_types: Vec<String>,
+    all_events: bool,
+    accounts: Vec<String>,
+    all_accounts: bool,
+) -> Result<(), anyhow::Error> {
+    if let Some(url) = &url {
+        validate_webhook_url(url, true).map_err(|e| anyhow!(e))?;
+    }
+    validate_event_types(&event_types).map_err(|e| anyhow!(e))?;
+
+    let pool = init_db(database_file, network)?;
+    let conn = pool.get()?;
+
+    let account_ids = resolve_account_ids(&conn, &accounts)?;
+    let update = WebhookSubscriptionUpdate {
+        url: url.as_deref(),
+        event_types: match (all_events, event_types.is_empty()) {
+            (true, _) => Some(None),
+            (false, false) => Some(Some(event_types.as_slice())),
+            (false, true) => None,
+        },
+        account_ids: match (all_accounts, account_ids.is_empty()) {
+            (true, _) => Some(None),
+            (false, false) => Some(Some(account_ids.as_slice())),
+            (false, true) => None,
+        },
+        enabled: None,
+    };
+    if !db::update_webhook_subscription(&conn, &name, &update)? {
+        return Err(anyhow!("No webhook subscription named '{}'", name));
+    }
+    println!("Webhook subscription '{}' updated.", name);
+    Ok(())
+}
+
+/// Enables or disables deliveries to a subscription.
+pub fn handle_set_webhook_enabled(
+    database_file: PathBuf,
+    network: Network,
+    name: String,
+    enabled: bool,
+) -> Result<(), anyhow::Error> {
+    let pool = init_db(database_file, network)?;
+    let conn = pool.get()?;
+
+    let update = WebhookSubscriptionUpdate {
+        enabled: Some(enabled),
+        ..Default::default()
+    };
+    if !db::update_webhook_subscription(&conn, &name, &update)? {
+        return Err(anyhow!("No webhook subscription named '{}'", name));
+    }
+    println!(
+        "Webhook subscription '{}' {}.",
+        name,
+        if enabled { "enabled" } else { "disabled" }
+    );
+    Ok(())
+}
+
+/// Replaces the signing secret of a subscription and prints the new secret.
+pub fn handle_rotate_webhook_secret(
+    database_file: PathBuf,
+    network: Network,
+    name: String,
+) -> Result<(), anyhow::Error> {
+    let pool = init_db(database_file, network)?;
+    let conn = pool.get()?;
+
+    let secret = generate_webhook_secret();
+    if !db::rotate_webhook_subscription_secret(&conn, &name, &secret)? {
+        return Err(anyhow!("No webhook subscription named '{}'", name));
+    }
+    println!("Rotated the signing secret of webhook subscription '{}'.", name);
+    println!();
+    println!("Signing secret: {}", secret);
+    Ok(())
+}
+
+/// Prints webhook deliveries with their status, attempt count and last error.
+pub fn handle_list_webhook_deliveries(
+    database_file: PathBuf,
+    network: Network,
+    status: Option<String>,
+    event_type: Option<String>,
+    since: Option<DateTime<Utc>>,
+    limit: i64,
+) -> Result<(), anyhow::Error> {
+    let filter = WebhookDeliveryFilter {
+        status: status
+            .as_deref()
+            .map(str::parse)
+            .transpose()
+            .map_err(|e: String| anyhow!(e))?,
+        event_type,
+        since: since.map(|since| since.naive_utc()),
+        until: None,
+    };
+
+    let pool = init_db(database_file, network)?;
+    let conn = pool.get()?;
+
+    let deliveries = db::get_webhook_deliveries(&conn, &filter, limit, 0)?;
+    if deliveries.is_empty() {
+        println!("No webhook deliveries found.");
+        return Ok(());
+    }
+
+    let subscription_names: HashMap<i64, String> = db::get_webhook_subscriptions(&conn)?
+        .into_iter()
+        .map(|subscription| (subscription.id, subscription.name))
+        .collect();
+    for delivery in deliveries {
+        let receiver = delivery
+            .subscription_id
+            .and_then(|id| subscription_names.get(&id).cloned())
+            .unwrap_or_else(|| "config".to_string());
+        println!(
+            "#{} {} event={} receiver={} url={} status={} attempts={} queued={}{}",
+            delivery.id,
+            delivery.event_type,
+            delivery.event_id.map_or_else(|| "-".to_string(), |id| id.to_string()),
+            receiver,
+            delivery.target_url,
+            delivery.status,
+            delivery.attempt_count,
+            delivery.created_at,
+            delivery
+                .last_error
+                .map(|error| format!(", last error: {}", error))
+                .unwrap_or_default(),
+        );
+    }
+    Ok(())
+}
+
+/// Queues a single delivery, or all failed deliveries since a time, to be sent again.
+pub fn handle_replay_webhooks(
+    database_file: PathBuf,
+    network: Network,
+    id: Option<i64>,
+    failed_since: Option<DateTime<Utc>>,
+) -> Result<(), anyhow::Error> {
+    let pool = init_db(database_file, network)?;
+    let conn = pool.get()?;
+
+    match (id, failed_since) {
+        (Some(id), _) => {
+            if !db::requeue_webhook(&conn, id)? {
+                return Err(anyhow!("No webhook delivery with id {}", id));
+            }
+            println!("Webhook delivery #{} queued for sending.", id);
+        },
+        (None, Some(since)) => {
+            let count = db::requeue_failed_webhooks(&conn, since.naive_utc())?;
+            println!(
+                "{} failed webhook deliveries since {} queued for sending.",
+                count, since
+            );
+        },
+        (None, None) => return Err(anyhow!("Either a delivery id or --failed-since is required")),
+    }
+    println!("A running daemon sends them on its next poll.");
+    Ok(())
+}
+
+/// Queues a stored wallet event for delivery to webhook receivers again.
+pub fn handle_resend_webhook_event(
+    database_file: PathBuf,
+    network: Network,
+    event_id: i64,
+    subscription: Option<String>,
+    config: WebhookTriggerConfig,
+) -> Result<(), anyhow::Error> {
+    let pool = init_db(database_file, network)?;
+    let conn = pool.get()?;
+
+    let subscription = subscription
+        .map(|name| {
+            db::get_webhook_subscription_by_name(&conn, &name)?
+                .ok_or_else(|| anyhow!("No webhook subscription named '{}'", name))
+        })
+        .transpose()?;
+    let count = resend_event(&conn, event_id, Some(&config), subscription.as_ref())?
+        .ok_or_else(|| anyhow!("No wallet event with id {}", event_id))?;
+    println!("Wallet event {} queued for {} webhook deliveries.", event_id, count);
+    Ok(())
+}
@@ -11,10 +11,11 @@ pub fn default_burn_proofs_dir(network: Network) -> PathBuf {
 }
 
 use crate::cli::{AccountArgs, ApplyArgs, BurnArgs, DaemonArgs, DatabaseArgs, NodeArgs, TransactionArgs};
+use crate::webhooks::WebhookTriggerConfig;
 
 #[derive(Debug, Clone, Deserialize, Serialize, Default)]
 pub struct WebhookConfig {
-    /// The HTTP endpoint to post events to
+    /// The HTTP endpoint to post events to, or a `file:`, `unix:` or `exec:` event sink
     pub url: Option<String>,
     /// The secret key used for HMAC signing
     pub secret: Option<String>,
@@ -22,6 +23,108 @@ pub struct WebhookConfig {
     pub send_only_event_types: Option<Vec<String>>,
 }
 
+/// Thresholds of the alert rules the daemon evaluates after each scan cycle.
+///
+/// Rules left unset are disabled. Amounts are in µT.
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+pub struct AlertConfig {
+    /// Alert when the available balance of an account drops below this amount
+    pub low_balance_threshold: Option<u64>,
+    /// Alert when the available balance of an account rises above this amount
+    pub high_balance_threshold: Option<u64>,
+    /// Alert when an incoming payment of at least this amount is detected
+    pub large_incoming_payment_threshold: Option<u64>,
+    /// Alert when an outbound transaction stays in `Broadcast` for this many blocks
+    pub stuck_transaction_blocks: Option<u64>,
+    /// Alert when the scanner is more than this many blocks behind the chain tip
+    pub scanner_lag_blocks: Option<u64>,
+    /// Alert when more than this many webhook deliveries are waiting to be sent
+    pub webhook_backlog_threshold: Option<u64>,
+    /// Names of the accounts the rules apply to. All accounts if not set.
+    pub accounts: Option<Vec<String>>,
+}
+
+/// Thresholds of the daemon's `/health/live` and `/health/ready` endpoints.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct HealthConfig {
+    /// Report the daemon as not ready while an account that is not paused is more than this
+    /// many blocks behind the chain tip
+    pub max_scan_lag_blocks: u64,
+    /// Report a background task as stalled if it has not made progress for this many seconds.
+    /// The scan interval is added for the scanner, which is idle between scan cycles.
+    pub task_stall_timeout_secs: u64,
+}
+
+impl Default for HealthConfig {
+    fn default() -> Self {
+        Self {
+            max_scan_lag_blocks: 10,
+            task_stall_timeout_secs: 600,
+        }
+    }
+}
+
+/// TLS settings of the daemon's REST API.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ApiTlsConfig {
+    /// PEM file with the server certificate chain
+    pub cert_path: PathBuf,
+    /// PEM file with the server private key
+    pub key_path: PathBuf,
+    /// PEM file with the CA certificates used to verify client certificates.
+    /// If set, clients must present a certificate signed by one of them (mTLS).
+    pub client_ca_path: Option<PathBuf>,
+}
+
+/// Listeners of the daemon's REST API.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ApiServerConfig {
+    /// Addresses to listen on, either `ip` (combined with `api_port`) or `ip:port`.
+    /// May be empty if only a Unix socket is used.
+    pub bind_addresses: Vec<String>,
+    /// Unix domain socket to listen on, in addition to the TCP addresses
+    pub unix_socket: Option<PathBuf>,
+    /// Serve the TCP listeners over TLS. The Unix socket is always served in plain HTTP.
+    pub tls: Option<ApiTlsConfig>,
+}
+
+impl Default for ApiServerConfig {
+    fn default() -> Self {
+        Self {
+            bind_addresses: vec!["127.0.0.1".to_string()],
+            unix_socket: None,
+            tls: None,
+        }
+    }
+}
+
+/// Signer of the accounts marked as externally signed.
+///
+/// Either a command or an HTTP endpoint can be set. See
+/// [`crate::transactions::external_signer`] for the protocol.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ExternalSignerConfig {
+    /// Executable that reads a signing request on stdin and writes the response to stdout
+    pub command: Option<PathBuf>,
+    /// Arguments passed to the command
+    pub args: Vec<String>,
+    /// Local HTTP endpoint the signing request is posted to
+    pub url: Option<String>,
+    /// Seconds to wait for a response, which may include confirming the transaction on a device
+    pub timeout_secs: u64,
+}
+
+impl Default for ExternalSignerConfig {
+    fn default() -> Self {
+        Self {
+            command: None,
+            args: Vec::new(),
+            url: None,
+            timeout_secs: 300,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct WalletConfig {
     pub network: Network,
@@ -33,10 +136,25 @@ pub struct WalletConfig {
     pub confirmation_window: u64,
     pub account_name: Option<String>,
     pub webhook: WebhookConfig,
+    /// Balance and activity alert rules, delivered as wallet events
+    pub alerts: AlertConfig,
+    /// Thresholds of the health endpoints
+    pub health: HealthConfig,
     /// Directory where complete burn proof JSON files are written after a burn transaction is confirmed
     /// and the kernel merkle proof is fetched from the base node.
     /// If not set, defaults to the platform data directory: `<data_dir>/tari/<network>/burn_proofs`.
     pub burn_proofs_dir: Option<PathBuf>,
+    /// SQLite file caching the block data downloaded while scanning, so re-scans and newly
+    /// imported accounts don't download the same heights again. Disabled if not set.
+    pub block_cache_path: Option<PathBuf>,
+    /// Reject unauthenticated REST API requests even before the first API key is created.
+    /// Once an API key has been created, every request must be authenticated regardless of this
+    /// setting, even after the key is revoked.
+    pub require_api_key: bool,
+    /// Bind addresses, Unix socket and TLS settings of the REST API
+    pub api: ApiServerConfig,
+    /// Signer of the accounts whose keys are not held by the wallet
+    pub external_signer: ExternalSignerConfig,
 }
 
 impl Default for WalletConfig {
@@ -51,7 +169,13 @@ impl Default for WalletConfig {
             confirmation_window: 3,
             account_name: None,
             webhook: WebhookConfig::default(),
+            alerts: AlertConfig::default(),
+            health: HealthConfig::default(),
             burn_proofs_dir: None,
+            block_cache_path: None,
+            require_api_key: false,
+            api: ApiServerConfig::default(),
+            external_signer: ExternalSignerConfig::default(),
         }
     }
 }
@@ -62,6 +186,15 @@ impl WalletConfig {
             .clone()
             .unwrap_or_else(|| default_burn_proofs_dir(self.network))
     }
+
+    /// Settings for queuing events for the configured webhook and any webhook subscriptions.
+    pub fn webhook_trigger_config(&self) -> WebhookTriggerConfig {
+        WebhookTriggerConfig {
+            url: self.webhook.url.clone(),
+            send_only_event_types: self.webhook.send_only_event_types.clone(),
+            network: self.network,
+        }
+    }
 }
 
 impl SubConfigPath for WalletConfig {
@@ -84,6 +217,9 @@ impl ApplyArgs for WalletConfig {
         if let Some(batch_size) = args.batch_size {
             self.batch_size = batch_size;
         }
+        if let Some(block_cache_path) = &args.block_cache_path {
+            self.block_cache_path = Some(block_cache_path.clone());
+        }
     }
 
     fn apply_account(&mut self, args: &AccountArgs) {
@@ -111,5 +247,23 @@ impl ApplyArgs for WalletConfig {
         if let Some(api_port) = args.api_port {
             self.api_port = api_port;
         }
+        if args.require_api_key {
+            self.require_api_key = true;
+        }
+        if !args.api_bind_addresses.is_empty() {
+            self.api.bind_addresses = args.api_bind_addresses.clone();
+        }
+        if let Some(unix_socket) = &args.api_unix_socket {
+            self.api.unix_socket = Some(unix_socket.clone());
+        }
+        if let (Some(cert_path), Some(key_path)) = (&args.api_tls_cert, &args.api_tls_key) {
+            self.api.tls = Some(ApiTlsConfig {
+                cert_path: cert_path.clone(),
+                key_path: key_path.clone(),
+                client_ca_path: args.api_tls_client_ca.clone(),
+            });
+        } else if let (Some(tls), Some(client_ca_path)) = (&mut self.api.tls, &args.api_tls_client_ca) {
+            tls.client_ca_path = Some(client_ca_path.clone());
+        }
     }
 }
@@ -6,8 +6,9 @@
 //! # Features
 //!
 //! - **Periodic Scanning**: Automatically scans the blockchain at configurable intervals
-//! - **API Server**: Runs an HTTP API server for wallet operations
+//! - **API Server**: Runs an HTTP(S) API server for wallet operations on TCP addresses and/or a Unix socket
 //! - **Background Tasks**: Manages transaction unlocker and other periodic tasks
+//! - **Metrics**: Records scanning, balance, transaction and webhook metrics served by the API at `/metrics`
 //! - **Graceful Shutdown**: Handles Ctrl+C signals and coordinates shutdown across all tasks
 //! - **Error Recovery**: Distinguishes between fatal and intermittent errors, retrying when appropriate
 //!
@@ -16,11 +17,14 @@
 //! ```ignore
 //! use minotari::daemon::Daemon;
 //! use tari_common::configuration::Network;
+//! use minotari::keyring::Keyring;
 //! use std::path::PathBuf;
 //!
 //! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let keyring = Keyring::new();
+//! keyring.unlock_accounts(&accounts, "password")?;
 //! let daemon = Daemon::new(
-//!     "password".to_string(),
+//!     keyring,
 //!     "https://rpc.tari.com".to_string(),
 //!     PathBuf::from("wallet.db"),
 //!     100,    // max_blocks per scan
@@ -39,27 +43,44 @@
 //!
 //! The daemon coordinates three main components:
 //!
-//! 1. **Scanner Loop**: Periodically scans the blockchain for new outputs
+//! 1. **Scanner Loop**: Periodically scans the blockchain for new outputs. Scanning can be
+//!    paused, resumed and restarted per account through the API (see [`ScanControl`])
 //! 2. **API Server**: Serves HTTP endpoints for wallet operations
 //! 3. **Transaction Unlocker**: Automatically unlocks expired transaction locks
 //!
+//! The scanner and API share a [`Keyring`] holding the keys of unlocked accounts. Locked
+//! accounts are scanned with their cached view key but cannot spend until unlocked again.
+//!
 //! All components listen for shutdown signals and terminate gracefully.
 
 use std::{path::PathBuf, time::Duration};
 
 use anyhow::anyhow;
 use log::{error, info, warn};
-use tokio::{signal, sync::broadcast, time::sleep};
-
 use tari_common::configuration::Network;
+use tokio::{signal, sync::broadcast, time::sleep};
 
 use crate::{
-    api, db,
+    api,
+    config::defaults::{AlertConfig, ApiServerConfig, HealthConfig},
+    db::{self, SqlitePool},
     http::WalletHttpClient,
-    scan::{self, ScanError, ScanMode},
-    tasks::{burn_proof_worker::BurnProofWorker, unlocker::TransactionUnlocker},
+    keyring::Keyring,
+    metrics::WalletMetrics,
+    models::WalletEvent,
+    scan::{
+        self, EventBroadcaster, ScanControl, ScanError, ScanMode, ScanProgressStore, TeeEventSender,
+        reorg::rollback_from_height,
+    },
+    tasks::{
+        alerts::AlertEvaluator,
+        burn_proof_worker::BurnProofWorker,
+        health::{BURN_PROOF_TASK, HealthMonitor, SCANNER_TASK, TaskGuard, UNLOCKER_TASK, WEBHOOK_TASK},
+        unlocker::TransactionUnlocker,
+    },
     webhooks::{
         WebhookTriggerConfig,
+        sinks::EventSink,
         worker::{WebhookWorker, WebhookWorkerConfig},
     },
 };
@@ -70,7 +91,7 @@ use crate::{
 /// API server hosting, and transaction management. It handles graceful shutdown
 /// and error recovery for long-running operation.
 pub struct Daemon {
-    password: String,
+    keyring: Keyring,
     base_url: String,
     database_file: PathBuf,
     max_blocks: u64,
@@ -80,8 +101,18 @@ pub struct Daemon {
     network: Network,
     required_confirmations: u64,
     webhook_config: WebhookWorkerConfig,
-    webhook_trigger_config: Option<WebhookTriggerConfig>,
+    webhook_trigger_config: WebhookTriggerConfig,
+    alert_evaluator: std::sync::Arc<AlertEvaluator>,
     burn_proofs_dir: PathBuf,
+    block_cache_path: Option<PathBuf>,
+    require_api_key: bool,
+    api_config: ApiServerConfig,
+    scan_progress: ScanProgressStore,
+    scan_control: ScanControl,
+    event_broadcaster: EventBroadcaster,
+    metrics: WalletMetrics,
+    health: HealthMonitor,
+    task_stall_timeout: Duration,
 }
 
 impl Daemon {
@@ -89,20 +120,25 @@ impl Daemon {
     ///
     /// # Parameters
     ///
-    /// * `password` - Password for decrypting wallet keys
+    /// * `keyring` - Keys of the accounts unlocked at start-up
     /// * `base_url` - Base URL of the Tari RPC endpoint (e.g., "<https://rpc.tari.com>")
     /// * `database_file` - Path to the SQLite database file
     /// * `max_blocks` - Maximum number of blocks to scan per iteration
     /// * `batch_size` - Number of blocks to scan per batch
     /// * `scan_interval_secs` - Seconds to wait between scan cycles
-    /// * `api_port` - Port to bind the HTTP API server to
+    /// * `api_port` - Port of the HTTP API server, used for bind addresses without a port
     /// * `network` - Tari network configuration (Esmeralda, Nextnet, Mainnet, etc.)
     /// * `required_confirmations` - Required confirmations
     /// * `webhook_url` - Webhook URL
     /// * `webhook_secret` - Webhook signing secret
+    /// * `alert_config` - Alert rules evaluated after each scan cycle
+    /// * `health_config` - Thresholds of the health endpoints
+    /// * `block_cache_path` - Optional on-disk block cache used by the scanner
+    /// * `require_api_key` - Reject unauthenticated API requests even if no API key exists
+    /// * `api_config` - Bind addresses, Unix socket and TLS settings of the API server
     #[allow(clippy::too_many_arguments)]
     pub fn new(
-        password: String,
+        keyring: Keyring,
         base_url: String,
         database_file: PathBuf,
         max_blocks: u64,
@@ -114,20 +150,30 @@ impl Daemon {
         webhook_url: Option<String>,
         webhook_secret: Option<String>,
         send_only_event_types: Option<Vec<String>>,
+        alert_config: AlertConfig,
+        health_config: HealthConfig,
         burn_proofs_dir: PathBuf,
+        block_cache_path: Option<PathBuf>,
+        require_api_key: bool,
+        api_config: ApiServerConfig,
     ) -> Self {
         let webhook_worker_config = WebhookWorkerConfig {
-            enabled: webhook_url.is_some() && webhook_secret.is_some(),
+            // Local event sinks are not signed and work without a secret
+            enabled: webhook_url
+                .as_deref()
+                .is_some_and(|url| webhook_secret.is_some() || EventSink::parse(url).is_ok_and(|sink| sink.is_local())),
             secret: webhook_secret,
             send_only_event_types: send_only_event_types.clone(),
         };
-        let webhook_trigger_config = webhook_url.map(|url| WebhookTriggerConfig {
-            url,
+        let webhook_trigger_config = WebhookTriggerConfig {
+            url: webhook_url,
             send_only_event_types,
-        });
+            network,
+        };
+        let alert_evaluator = AlertEvaluator::new(alert_config, Some(webhook_trigger_config.clone()));
 
         Self {
-            password,
+            keyring,
             base_url,
             database_file,
             max_blocks,
@@ -138,7 +184,17 @@ impl Daemon {
             required_confirmations,
             webhook_config: webhook_worker_config,
             webhook_trigger_config,
+            alert_evaluator: std::sync::Arc::new(alert_evaluator),
             burn_proofs_dir,
+            block_cache_path,
+            require_api_key,
+            api_config,
+            scan_progress: ScanProgressStore::new(),
+            scan_control: ScanControl::new(),
+            event_broadcaster: EventBroadcaster::new(),
+            metrics: WalletMetrics::new(),
+            health: HealthMonitor::new(health_config.max_scan_lag_blocks),
+            task_stall_timeout: Duration::from_secs(health_config.task_stall_timeout_secs),
         }
     }
 
@@ -165,20 +221,28 @@ impl Daemon {
 
         let (shutdown_tx, _) = broadcast::channel(1);
 
-        let db_pool = db::init_db(self.database_file.clone())?;
+        let db_pool = db::init_db(self.database_file.clone(), self.network)?;
 
-        let unlocker = TransactionUnlocker::new(db_pool.clone());
+        let unlocker = TransactionUnlocker::new(db_pool.clone())
+            .with_task_guard(self.health.register(UNLOCKER_TASK, self.task_stall_timeout));
         let unlocker_task_handle = unlocker.run(shutdown_tx.subscribe());
 
-        let http_client = WalletHttpClient::new(
-            self.base_url
-                .parse()
-                .map_err(|e| ScanError::Fatal(anyhow!("Invalid base URL '{}': {}", self.base_url, e)))?,
-        )?;
-        let burn_proof_worker = BurnProofWorker::new(db_pool.clone(), http_client, self.burn_proofs_dir.clone());
+        let base_url: url::Url = self
+            .base_url
+            .parse()
+            .map_err(|e| ScanError::Fatal(anyhow!("Invalid base URL '{}': {}", self.base_url, e)))?;
+        let http_client = WalletHttpClient::new(base_url.clone())?;
+        // Fetches the chain tip for the metrics and the alert rules
+        let tip_client = WalletHttpClient::new(base_url)?;
+        let burn_proof_worker = BurnProofWorker::new(db_pool.clone(), http_client, self.burn_proofs_dir.clone())
+            .with_task_guard(self.health.register(BURN_PROOF_TASK, self.task_stall_timeout));
         let burn_proof_handle = burn_proof_worker.run(shutdown_tx.subscribe());
 
-        let webhook_worker = std::sync::Arc::new(WebhookWorker::new(db_pool.clone(), self.webhook_config.clone()));
+        let webhook_worker = std::sync::Arc::new(
+            WebhookWorker::new(db_pool.clone(), self.webhook_config.clone())
+                .with_metrics(self.metrics.clone())
+                .with_task_guard(self.health.register(WEBHOOK_TASK, self.task_stall_timeout)),
+        );
         let worker_rx = shutdown_tx.subscribe();
         let webhook_handle = tokio::spawn(async move {
             webhook_worker.run(worker_rx).await;
@@ -187,26 +251,25 @@ impl Daemon {
         let router = api::create_router(
             db_pool.clone(),
             self.network,
-            self.password.clone(),
+            self.keyring.clone(),
             self.required_confirmations,
             self.base_url.clone(),
+            self.scan_progress.clone(),
+            self.scan_control.clone(),
+            self.event_broadcaster.clone(),
+            Some(self.webhook_trigger_config.clone()),
+            self.metrics.clone(),
+            self.health.clone(),
+            self.require_api_key,
         );
-        let addr = format!("0.0.0.0:{}", self.api_port);
-        let listener = tokio::net::TcpListener::bind(&addr)
-            .await
-            .map_err(|e| ScanError::Fatal(anyhow!("Failed to bind API server to {}: {}", addr, e)))?;
-
-        info!(address = &*addr; "API server listening");
-
-        let mut shutdown_rx_api = shutdown_tx.subscribe();
-        let api_server_handle = tokio::spawn(async move {
-            axum::serve(listener, router)
-                .with_graceful_shutdown(async move {
-                    shutdown_rx_api.recv().await.ok();
-                })
-                .await
-                .unwrap();
-        });
+        let auth_enforced = self.require_api_key || db::has_api_keys(&db_pool.get().map_err(db::WalletDbError::from)?)?;
+        if !auth_enforced {
+            warn!(
+                "No API keys configured: the API accepts unauthenticated read-only requests. \
+                 Create a key with `minotari api-key create` to enable authentication and spending."
+            );
+        }
+        let api_server_handle = api::server::serve(router, &self.api_config, self.api_port, &shutdown_tx).await?;
 
         let shutdown_rx_scanner = shutdown_tx.subscribe();
 
@@ -221,7 +284,14 @@ impl Daemon {
         // HttpBlockchainScanner is marked as "NOT Send", so it is not possible to launch it in a new thread.
         // #[async_trait(?Send)]
         // impl<KM> BlockchainScanner for HttpBlockchainScanner<KM>
-        let scanner_res = self.scan_and_sleep_loop(shutdown_rx_scanner).await;
+        // The scanner is idle between cycles, so it may go a scan interval without a heartbeat
+        let scanner_guard = self
+            .health
+            .register(SCANNER_TASK, self.task_stall_timeout + self.scan_interval);
+        let scanner_res = self
+            .scan_and_sleep_loop(&db_pool, &tip_client, &scanner_guard, shutdown_rx_scanner)
+            .await;
+        drop(scanner_guard);
 
         if let Err(e) = scanner_res {
             if shutdown_tx.send(()).is_err() {
@@ -233,6 +303,7 @@ impl Daemon {
         if shutdown_tx.send(()).is_err() {
             error!("Failed to send shutdown signal. All tasks may not have received it.");
         }
+        self.event_broadcaster.close();
 
         let join_res = tokio::try_join!(
             api_server_handle,
@@ -251,45 +322,204 @@ impl Daemon {
 
     /// Performs a single scan cycle followed by a sleep interval.
     ///
-    /// Scans up to `max_blocks` in batches of `batch_size`, then sleeps for
-    /// `scan_interval` before returning. This method is called repeatedly by
-    /// the scan loop.
+    /// Applies re-scans requested through the API, then scans up to `max_blocks`
+    /// in batches of `batch_size` for all accounts that are not paused, and sleeps
+    /// for `scan_interval` before returning. Scan control requests interrupt both
+    /// the scan and the sleep so that they take effect immediately. This method is
+    /// called repeatedly by the scan loop.
     ///
     /// # Returns
     ///
     /// - `Ok(())` - Scan completed successfully and sleep finished
     /// - `Err(ScanError)` - Scan failed with a fatal or intermittent error
-    async fn scan_and_sleep(&self) -> Result<(), ScanError> {
+    async fn scan_and_sleep(
+        &self,
+        db_pool: &SqlitePool,
+        tip_client: &WalletHttpClient,
+        scanner_guard: &TaskGuard,
+    ) -> Result<(), ScanError> {
+        scanner_guard.heartbeat();
+        // Start the cycle before taking pending re-scans, so a request arriving in between
+        // interrupts this cycle instead of waiting for the next one.
+        let cancel_token = self.scan_control.start_cycle();
+        self.apply_pending_deletions(db_pool).await?;
+        self.apply_pending_rescans(db_pool).await?;
+
         info!("Starting wallet scan...");
-        let mut scanner = scan::Scanner::new(
-            &self.password,
+        let mut scanner = scan::Scanner::with_keyring(
+            self.keyring.clone(),
             &self.base_url,
             self.database_file.clone(),
+            self.network,
             self.batch_size,
             self.required_confirmations,
         )
         .mode(ScanMode::Partial {
             max_blocks: self.max_blocks,
-        });
-        if let Some(cfg) = &self.webhook_trigger_config {
-            scanner = scanner.webhook_config(cfg.clone());
+        })
+        .exclude_accounts(self.scan_control.paused_accounts())
+        .cancel_token(cancel_token.clone())
+        .webhook_config(self.webhook_trigger_config.clone());
+        if let Some(path) = &self.block_cache_path {
+            scanner = scanner.block_cache_path(path.clone());
         }
 
-        let result = scanner.run().await;
+        let event_sender = TeeEventSender::new(
+            TeeEventSender::new(self.scan_progress.clone(), self.event_broadcaster.clone()),
+            TeeEventSender::new(self.metrics.clone(), scanner_guard.event_heartbeat()),
+        );
+        let result = scanner.run_with_event_sender(event_sender).await;
         match result {
             Ok((events, _are_there_more_blocks_to_scan)) => {
                 info!(event_count = events.len(); "Scan completed successfully");
+                let tip_height = self.fetch_chain_tip(tip_client).await;
+                self.evaluate_alerts(db_pool, tip_height, events).await?;
+                scanner_guard.heartbeat();
             },
             Err(e) => {
                 error!(error:% = e; "Scan failed");
+                // Probe the base node, so the readiness check doesn't keep reporting it as reachable
+                self.fetch_chain_tip(tip_client).await;
                 return Err(e);
             },
         }
 
-        sleep(self.scan_interval).await;
+        tokio::select! {
+            _ = sleep(self.scan_interval) => {},
+            _ = cancel_token.cancelled() => {
+                info!("Scan control request received, starting next scan cycle");
+            },
+        }
         Ok(())
     }
 
+    /// Fetches the chain tip and records it, along with the base node latency, in the metrics
+    /// and the health monitor.
+    ///
+    /// Returns `None` if the base node could not be reached.
+    async fn fetch_chain_tip(&self, tip_client: &WalletHttpClient) -> Option<u64> {
+        let tip_height = match tip_client.get_tip_info().await {
+            Ok(tip) => {
+                let tip_height = tip.metadata.map(|metadata| metadata.best_block_height());
+                self.health.record_base_node(true, tip_height);
+                tip_height
+            },
+            Err(e) => {
+                warn!(error:% = e; "Failed to fetch the chain tip");
+                self.health.record_base_node(false, None);
+                None
+            },
+        };
+
+        if let Some(height) = tip_height {
+            self.metrics.set_chain_tip(height);
+        }
+        if let Some(latency) = tip_client.get_last_request_latency().await {
+            self.metrics.set_node_latency(latency);
+        }
+        tip_height
+    }
+
+    /// Evaluates the alert rules against the state after a scan cycle.
+    ///
+    /// An unknown chain tip only skips the rules that need it, and a failed evaluation is
+    /// logged rather than stopping the daemon.
+    async fn evaluate_alerts(
+        &self,
+        db_pool: &SqlitePool,
+        tip_height: Option<u64>,
+        cycle_events: Vec<WalletEvent>,
+    ) -> Result<(), ScanError> {
+        if !self.alert_evaluator.is_enabled() {
+            return Ok(());
+        }
+
+        let pool = db_pool.clone();
+        let evaluator = self.alert_evaluator.clone();
+        let result = tokio::task::spawn_blocking(move || {
+            let mut conn = pool.get().map_err(db::WalletDbError::from)?;
+            let tx = conn.transaction()?;
+            let alerts = evaluator.evaluate(&tx, tip_height, &cycle_events)?;
+            tx.commit()?;
+            Ok::<_, db::WalletDbError>(alerts)
+        })
+        .await
+        .map_err(|e| ScanError::Fatal(anyhow!("Alert evaluation task panicked: {}", e)))?;
+
+        match result {
+            Ok(alerts) if !alerts.is_empty() => info!(alert_count = alerts.len(); "Alerts triggered"),
+            Ok(_) => {},
+            Err(e) => error!(error:% = e; "Failed to evaluate alert rules"),
+        }
+        Ok(())
+    }
+
+    /// Deletes every account whose deletion was requested through the API.
+    ///
+    /// Deletions are applied between scan cycles so that the scanner never writes to an
+    /// account that no longer exists. A failed deletion is logged and skipped.
+    async fn apply_pending_deletions(&self, db_pool: &SqlitePool) -> Result<(), ScanError> {
+        let deletions = self.scan_control.take_pending_deletions();
+        if deletions.is_empty() {
+            return Ok(());
+        }
+
+        let pool = db_pool.clone();
+        let keyring = self.keyring.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut conn = pool.get().map_err(|e| ScanError::DbError(e.into()))?;
+            for account_id in deletions {
+                let Some(account) = db::get_account_by_id(&conn, account_id)? else {
+                    continue;
+                };
+                let tx = conn.transaction().map_err(|e| ScanError::DbError(e.into()))?;
+                match db::delete_account(&tx, &account.friendly_name) {
+                    Ok(()) => {
+                        tx.commit().map_err(|e| ScanError::DbError(e.into()))?;
+                        keyring.remove(account_id);
+                    },
+                    Err(e) => error!(account_id = account_id, error:% = e; "Requested account deletion failed"),
+                }
+            }
+            Ok(())
+        })
+        .await
+        .map_err(|e| ScanError::Fatal(anyhow!("Account deletion task panicked: {}", e)))?
+    }
+
+    /// Rolls back every account with a re-scan requested through the API.
+    ///
+    /// A failed rollback is logged and skipped rather than stopping the daemon; the
+    /// operator can request the re-scan again.
+    async fn apply_pending_rescans(&self, db_pool: &SqlitePool) -> Result<(), ScanError> {
+        let rescans = self.scan_control.take_pending_rescans();
+        if rescans.is_empty() {
+            return Ok(());
+        }
+
+        let pool = db_pool.clone();
+        let webhook_config = self.webhook_trigger_config.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut conn = pool.get().map_err(|e| ScanError::DbError(e.into()))?;
+            for (account_id, from_height) in rescans {
+                info!(
+                    target: "audit",
+                    account_id = account_id,
+                    height = from_height;
+                    "Rolling back account for requested re-scan"
+                );
+                let tx = conn.transaction().map_err(|e| ScanError::DbError(e.into()))?;
+                match rollback_from_height(&tx, account_id, from_height, Some(webhook_config.clone())) {
+                    Ok(_) => tx.commit().map_err(|e| ScanError::DbError(e.into()))?,
+                    Err(e) => error!(account_id = account_id, error:% = e; "Requested re-scan failed"),
+                }
+            }
+            Ok(())
+        })
+        .await
+        .map_err(|e| ScanError::Fatal(anyhow!("Re-scan task panicked: {}", e)))?
+    }
+
     /// Main scanning loop that runs until shutdown or fatal error.
     ///
     /// This loop continuously calls `scan_and_sleep()` while listening for shutdown signals.
@@ -301,20 +531,29 @@ impl Daemon {
     ///
     /// # Parameters
     ///
+    /// * `db_pool` - Connection pool used to apply requested re-scans
+    /// * `tip_client` - HTTP client used to fetch the chain tip for the metrics and the alert rules
+    /// * `scanner_guard` - Health registration of the scan loop, receiving a heartbeat per cycle and event
     /// * `shutdown_rx` - Broadcast receiver for shutdown signals from other tasks
     ///
     /// # Returns
     ///
     /// - `Ok(())` - Shutdown signal received, exiting gracefully
     /// - `Err(ScanError::Fatal)` - Fatal error occurred, daemon should stop
-    async fn scan_and_sleep_loop(&self, mut shutdown_rx: broadcast::Receiver<()>) -> Result<(), ScanError> {
+    async fn scan_and_sleep_loop(
+        &self,
+        db_pool: &SqlitePool,
+        tip_client: &WalletHttpClient,
+        scanner_guard: &TaskGuard,
+        mut shutdown_rx: broadcast::Receiver<()>,
+    ) -> Result<(), ScanError> {
         loop {
             tokio::select! {
                 _ = shutdown_rx.recv() => {
                     info!("Scanner task received shutdown signal. Exiting gracefully.");
                     break;
                 }
-                res = self.scan_and_sleep() => {
+                res = self.scan_and_sleep(db_pool, tip_client, scanner_guard) => {
                     if let Err(e) = res {
                         match &e {
                             ScanError::Fatal(_) => {
@@ -356,3 +595,52 @@ impl Daemon {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn test_failed_scan_records_unreachable_base_node() {
+        let temp = tempdir().unwrap();
+        let database_file = temp.path().join("daemon.db");
+        let db_pool = db::init_db(database_file.clone(), Network::Esmeralda).unwrap();
+        // Nothing listens on the base node port
+        let base_url = "http://127.0.0.1:1".to_string();
+        let tip_client = WalletHttpClient::with_config(base_url.parse().unwrap(), 0, Duration::from_secs(2)).unwrap();
+        // The database belongs to another network, so the scan fails right away
+        let daemon = Daemon::new(
+            Keyring::new(),
+            base_url,
+            database_file,
+            100,
+            10,
+            60,
+            0,
+            Network::MainNet,
+            3,
+            None,
+            None,
+            None,
+            AlertConfig::default(),
+            HealthConfig::default(),
+            temp.path().join("burn_proofs"),
+            None,
+            false,
+            ApiServerConfig::default(),
+        );
+        daemon.health.record_base_node(true, Some(100));
+        let scanner_guard = daemon.health.register(SCANNER_TASK, Duration::from_secs(60));
+
+        assert!(
+            daemon
+                .scan_and_sleep(&db_pool, &tip_client, &scanner_guard)
+                .await
+                .is_err()
+        );
+        let base_node = daemon.health.base_node().unwrap();
+        assert!(!base_node.reachable);
+        assert_eq!(base_node.tip_height, None);
+    }
+}
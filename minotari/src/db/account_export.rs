@@ -0,0 +1,272 @@
+//! Copying an account's rows between attached databases.
+//!
+//! Account exports carry the account's history as a small SQLite database holding only that
+//! account. [`export_account_data`] fills such a database, attached to the wallet's connection,
+//! and [`import_account_data`] copies its rows into the wallet under a new account id.
+//!
+//! Integer ids other rows refer to (outputs, inputs and balance changes) are shifted past the
+//! ids already used in the target, so that the references stay intact. Transaction ids and
+//! pending transaction ids are kept, as they are unique across wallets. Rows nothing refers to
+//! get new ids.
+
+use std::collections::HashMap;
+
+use log::{debug, info};
+use rusqlite::{Connection, named_params};
+use serde_json::Value;
+
+use crate::db::error::{WalletDbError, WalletDbResult};
+
+/// How the `id` column of a copied table is filled.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Ids {
+    /// Copied unchanged
+    Keep,
+    /// Shifted past the largest id in the target table
+    Shift,
+    /// Assigned by the target table
+    Assign,
+}
+
+struct TableCopy {
+    name: &'static str,
+    /// Column holding the account id
+    account_column: &'static str,
+    ids: Ids,
+    /// Columns referring to the shifted ids of another table
+    references: &'static [(&'static str, &'static str)],
+}
+
+/// Tables holding an account's history and alert state, parents before the tables referring to
+/// them.
+const HISTORY_TABLES: [TableCopy; 11] = [
+    TableCopy {
+        name: "scanned_tip_blocks",
+        account_column: "account_id",
+        ids: Ids::Assign,
+        references: &[],
+    },
+    TableCopy {
+        name: "outputs",
+        account_column: "account_id",
+        ids: Ids::Shift,
+        references: &[],
+    },
+    TableCopy {
+        name: "inputs",
+        account_column: "account_id",
+        ids: Ids::Shift,
+        references: &[("output_id", "outputs")],
+    },
+    TableCopy {
+        name: "balance_changes",
+        account_column: "account_id",
+        ids: Ids::Shift,
+        references: &[
+            ("caused_by_output_id", "outputs"),
+            ("caused_by_input_id", "inputs"),
+            ("reversal_of_balance_change_id", "balance_changes"),
+        ],
+    },
+    TableCopy {
+        name: "pending_transactions",
+        account_column: "account_id",
+        ids: Ids::Keep,
+        references: &[],
+    },
+    TableCopy {
+        name: "completed_transactions",
+        account_column: "account_id",
+        ids: Ids::Keep,
+        references: &[],
+    },
+    TableCopy {
+        name: "displayed_transactions",
+        account_column: "account_id",
+        ids: Ids::Keep,
+        references: &[],
+    },
+    TableCopy {
+        name: "events",
+        account_column: "account_id",
+        ids: Ids::Assign,
+        references: &[],
+    },
+    TableCopy {
+        name: "burn_proofs",
+        account_column: "account_id",
+        ids: Ids::Assign,
+        references: &[],
+    },
+    TableCopy {
+        name: "payref_history",
+        account_column: "account_id",
+        ids: Ids::Assign,
+        references: &[],
+    },
+    TableCopy {
+        name: "alert_states",
+        account_column: "account_id",
+        ids: Ids::Keep,
+        references: &[],
+    },
+];
+
+const ACCOUNTS_TABLE: TableCopy = TableCopy {
+    name: "accounts",
+    account_column: "id",
+    ids: Ids::Keep,
+    references: &[],
+};
+
+/// Copies the account `account_id` and its history from `main` into the empty database
+/// attached as `schema`.
+///
+/// Callers should run this inside a transaction, so that the copy is consistent while the
+/// wallet keeps being written to.
+pub fn export_account_data(conn: &Connection, schema: &str, account_id: i64) -> WalletDbResult<()> {
+    debug!(account_id = account_id, schema = schema; "DB: Exporting account data");
+
+    let mut shifts = HashMap::new();
+    for table in std::iter::once(&ACCOUNTS_TABLE).chain(&HISTORY_TABLES) {
+        copy_table(conn, table, "main", schema, account_id, account_id, &mut shifts)?;
+    }
+    Ok(())
+}
+
+/// Copies the history of the single account in the database attached as `schema` into `main`,
+/// assigning it to the existing account `account_id`.
+///
+/// The attached database must have the same schema version as `main`. Callers should run this
+/// inside a transaction together with creating the account, so that a failure leaves no
+/// partial account behind.
+pub fn import_account_data(conn: &Connection, schema: &str, account_id: i64) -> WalletDbResult<()> {
+    let mut stmt = conn.prepare(&format!("SELECT id FROM {}.accounts", schema))?;
+    let source_ids = stmt
+        .query_map([], |row| row.get::<_, i64>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    let &[source_account_id] = source_ids.as_slice() else {
+        return Err(WalletDbError::InvalidInput(format!(
+            "The export holds {} accounts instead of one",
+            source_ids.len()
+        )));
+    };
+
+    let mut shifts = HashMap::new();
+    let mut rows = 0;
+    for table in &HISTORY_TABLES {
+        rows += copy_table(conn, table, schema, "main", source_account_id, account_id, &mut shifts)?;
+    }
+    let output_shift = shifts.get("outputs").copied().unwrap_or(0);
+    rewrite_displayed_transactions(conn, account_id, output_shift)?;
+
+    info!(target: "audit", account_id = account_id, rows = rows; "DB: Imported account history");
+    Ok(())
+}
+
+fn columns(conn: &Connection, schema: &str, table: &str) -> WalletDbResult<Vec<String>> {
+    let mut stmt = conn.prepare(&format!("PRAGMA {}.table_info({})", schema, table))?;
+    let columns = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(columns)
+}
+
+/// Copies the rows of one account from `from.table` to `to.table` and returns how many were
+/// copied. Records the id shift of tables with [`Ids::Shift`] in `shifts`.
+fn copy_table(
+    conn: &Connection,
+    table: &TableCopy,
+    from: &str,
+    to: &str,
+    from_account_id: i64,
+    to_account_id: i64,
+    shifts: &mut HashMap<&'static str, i64>,
+) -> WalletDbResult<usize> {
+    if table.ids == Ids::Shift {
+        let max_id: i64 = conn.query_row(
+            &format!("SELECT COALESCE(MAX(id), 0) FROM {}.{}", to, table.name),
+            [],
+            |row| row.get(0),
+        )?;
+        shifts.insert(table.name, max_id);
+    }
+
+    let mut targets = Vec::new();
+    let mut values = Vec::new();
+    for column in columns(conn, to, table.name)? {
+        let value = if column == table.account_column {
+            ":account_id".to_string()
+        } else if column == "id" {
+            match table.ids {
+                Ids::Keep => column.clone(),
+                Ids::Shift => format!("id + {}", shifts.get(table.name).copied().unwrap_or(0)),
+                Ids::Assign => continue,
+            }
+        } else if let Some((_, referenced)) = table.references.iter().find(|(name, _)| *name == column) {
+            format!("{} + {}", column, shifts.get(referenced).copied().unwrap_or(0))
+        } else {
+            column.clone()
+        };
+        targets.push(column);
+        values.push(value);
+    }
+
+    let sql = format!(
+        "INSERT INTO {to}.{table} ({targets}) SELECT {values} FROM {from}.{table} WHERE {account} = :source_account_id",
+        table = table.name,
+        targets = targets.join(", "),
+        values = values.join(", "),
+        account = table.account_column,
+    );
+    let rows = conn.execute(
+        &sql,
+        named_params! {
+            ":account_id": to_account_id,
+            ":source_account_id": from_account_id,
+        },
+    )?;
+    debug!(table = table.name, rows = rows; "DB: Copied account rows");
+    Ok(rows)
+}
+
+/// Points the account id and matched output ids stored inside the JSON of displayed
+/// transactions at the imported rows.
+fn rewrite_displayed_transactions(conn: &Connection, account_id: i64, output_shift: i64) -> WalletDbResult<()> {
+    let mut stmt =
+        conn.prepare("SELECT id, transaction_json FROM displayed_transactions WHERE account_id = :account_id")?;
+    let transactions = stmt
+        .query_map(named_params! { ":account_id": account_id }, |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    for (id, json) in transactions {
+        let mut transaction: Value = serde_json::from_str(&json)?;
+        if let Some(details) = transaction.get_mut("details").and_then(Value::as_object_mut) {
+            details.insert("account_id".to_string(), account_id.into());
+            let inputs = details
+                .get_mut("inputs")
+                .and_then(Value::as_array_mut)
+                .into_iter()
+                .flatten()
+                .filter_map(Value::as_object_mut);
+            for input in inputs {
+                if let Some(output_id) = input.get("matched_output_id").and_then(Value::as_i64)
+                    && output_id > 0
+                {
+                    input.insert("matched_output_id".to_string(), (output_id + output_shift).into());
+                }
+            }
+        }
+
+        conn.execute(
+            "UPDATE displayed_transactions SET transaction_json = :json WHERE id = :id",
+            named_params! {
+                ":json": transaction.to_string(),
+                ":id": id,
+            },
+        )?;
+    }
+    Ok(())
+}
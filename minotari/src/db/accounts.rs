@@ -4,10 +4,7 @@ use serde::{Deserialize, Serialize};
 use serde_rusqlite::from_rows;
 use tari_common::configuration::Network;
 use tari_common_types::{
-    seeds::{
-        mnemonic::{Mnemonic, MnemonicLanguage},
-        seed_words::SeedWords,
-    },
+    seeds::{mnemonic::Mnemonic, seed_words::SeedWords},
     tari_address::{TariAddress, TariAddressFeatures},
 };
 use tari_transaction_components::MicroMinotari;
@@ -19,10 +16,11 @@ use crate::db::outputs::get_output_totals_for_account;
 use crate::utils::{
     crypto::{decrypt_data, encrypt_data},
     fingerprint::calculate_fingerprint,
+    seed_words::SeedLanguage,
     timestamp::format_timestamp,
 };
 use crate::{db::balance_changes::get_balance_aggregates_for_account, utils::crypto::FullEncryptedData};
-use tari_utilities::hex::Hex;
+use tari_utilities::{SafePassword, hex::Hex};
 use utoipa::openapi::{Object, Schema, Type};
 
 pub fn micro_minotari_schema() -> Schema {
@@ -38,10 +36,12 @@ pub fn create_account(
     friendly_name: &str,
     wallet: &WalletType,
     password: &str,
+    network: Network,
 ) -> WalletDbResult<()> {
     info!(
         target: "audit",
-        account = friendly_name;
+        account = friendly_name,
+        network:% = network;
         "DB: Creating new account"
     );
 
@@ -61,7 +61,8 @@ pub fn create_account(
             encrypted_wallet,
             cipher_nonce,
             salt,
-            birthday
+            birthday,
+            network
         )
         VALUES (
             :name,
@@ -69,7 +70,8 @@ pub fn create_account(
             :enc_wallet,
             :nonce,
             :salt,
-            :birthday
+            :birthday,
+            :network
         )
         "#,
         named_params! {
@@ -79,6 +81,7 @@ pub fn create_account(
             ":nonce": encrypted_data.nonce,
             ":salt": encrypted_data.salt_bytes,
             ":birthday": birthday,
+            ":network": network.as_key_str(),
         },
     )?;
 
@@ -99,7 +102,9 @@ pub fn get_account_by_name(conn: &Connection, friendly_name: &str) -> WalletDbRe
             encrypted_wallet,
             cipher_nonce,
             salt,
-            birthday
+            birthday,
+            network,
+            external_signer
         FROM accounts
         WHERE friendly_name = :name
         "#,
@@ -111,6 +116,34 @@ pub fn get_account_by_name(conn: &Connection, friendly_name: &str) -> WalletDbRe
     Ok(row)
 }
 
+pub fn get_account_by_id(conn: &Connection, id: i64) -> WalletDbResult<Option<AccountRow>> {
+    debug!(
+        account_id = id;
+        "DB: Fetching account by id"
+    );
+
+    let mut stmt = conn.prepare_cached(
+        r#"
+        SELECT id,
+            friendly_name,
+            fingerprint,
+            encrypted_wallet,
+            cipher_nonce,
+            salt,
+            birthday,
+            network,
+            external_signer
+        FROM accounts
+        WHERE id = :id
+        "#,
+    )?;
+
+    let rows = stmt.query(named_params! { ":id": id })?;
+    let row = from_rows::<AccountRow>(rows).next().transpose()?;
+
+    Ok(row)
+}
+
 pub fn get_accounts(conn: &Connection, friendly_name: Option<&str>) -> WalletDbResult<Vec<AccountRow>> {
     if let Some(name) = friendly_name {
         debug!(
@@ -125,7 +158,9 @@ pub fn get_accounts(conn: &Connection, friendly_name: Option<&str>) -> WalletDbR
               encrypted_wallet,
               cipher_nonce,
               salt,
-              birthday
+              birthday,
+              network,
+              external_signer
             FROM accounts
             WHERE friendly_name = :name
             ORDER BY friendly_name
@@ -144,7 +179,9 @@ pub fn get_accounts(conn: &Connection, friendly_name: Option<&str>) -> WalletDbR
               encrypted_wallet,
               cipher_nonce,
               salt,
-              birthday
+              birthday,
+              network,
+              external_signer
             FROM accounts
             ORDER BY friendly_name
             "#,
@@ -164,6 +201,10 @@ pub struct AccountRow {
     pub cipher_nonce: Vec<u8>,
     pub salt: Vec<u8>,
     pub birthday: i64,
+    /// Key string of the network the account belongs to (e.g. `mainnet`)
+    pub network: String,
+    /// Whether transactions of the account are signed by the configured external signer
+    pub external_signer: bool,
 }
 
 impl AccountRow {
@@ -184,7 +225,19 @@ impl AccountRow {
         Ok(wallet)
     }
 
+    /// Fails with [`WalletDbError::NetworkMismatch`] unless the account belongs to `network`.
+    pub fn check_network(&self, network: Network) -> WalletDbResult<()> {
+        if self.network != network.as_key_str() {
+            return Err(WalletDbError::NetworkMismatch(format!(
+                "Account '{}' belongs to {}, not {}",
+                self.friendly_name, self.network, network
+            )));
+        }
+        Ok(())
+    }
+
     pub fn get_address(&self, network: Network, password: &str) -> WalletDbResult<TariAddress> {
+        self.check_network(network)?;
         let wallet = self.decrypt_wallet_type(password)?;
 
         let view_public_key = wallet.get_public_view_key();
@@ -208,6 +261,7 @@ impl AccountRow {
         password: &str,
         payment_id: &[u8],
     ) -> WalletDbResult<TariAddress> {
+        self.check_network(network)?;
         let wallet = self.decrypt_wallet_type(password)?;
 
         let view_public_key = wallet.get_public_view_key();
@@ -233,13 +287,20 @@ impl AccountRow {
         Ok(key_manager)
     }
 
-    pub fn get_seed_words(&self, password: &str) -> WalletDbResult<Option<SeedWords>> {
+    /// Returns the seed words of a seed wallet in `language`, enciphered with `passphrase` if given.
+    pub fn get_seed_words(
+        &self,
+        password: &str,
+        language: SeedLanguage,
+        passphrase: Option<&str>,
+    ) -> WalletDbResult<Option<SeedWords>> {
         let wallet = self.decrypt_wallet_type(password)?;
 
         match wallet {
             WalletType::SeedWords(seed_wallet) => {
                 let cipher_seed = seed_wallet.cipher_seed();
-                let mnemonic = cipher_seed.to_mnemonic(MnemonicLanguage::English, None).map_err(|e| {
+                let passphrase = passphrase.map(|passphrase| SafePassword::from(passphrase.to_string()));
+                let mnemonic = cipher_seed.to_mnemonic(language.into(), passphrase).map_err(|e| {
                     warn!(error:? = e; "DB: Failed to convert seed to mnemonic");
                     WalletDbError::Unexpected(format!("Failed to generate mnemonic: {}", e))
                 })?;
@@ -336,6 +397,13 @@ pub fn delete_account(conn: &Connection, friendly_name: &str) -> WalletDbResult<
     // The order of table deletion is important to respect foreign key constraints.
     // The tables are ordered from child to parent.
     let tables_to_clear = [
+        "alert_states",
+        "transaction_approvals",
+        "approval_requests",
+        "approval_policy_approvers",
+        "approval_policies",
+        "burn_proofs",
+        "payref_history",
         "balance_changes",
         "inputs",
         "outputs",
@@ -405,3 +473,223 @@ pub fn update_account_name(conn: &Connection, current_name: &str, new_name: &str
 
     Ok(())
 }
+
+/// Marks an account as signed by the configured external signer, or by the wallet itself.
+pub fn set_account_external_signer(
+    conn: &Connection,
+    friendly_name: &str,
+    external_signer: bool,
+) -> WalletDbResult<()> {
+    info!(
+        target: "audit",
+        account = friendly_name,
+        external_signer = external_signer;
+        "DB: Setting account signer"
+    );
+
+    let affected_rows = conn.execute(
+        "UPDATE accounts SET external_signer = :external_signer WHERE friendly_name = :name",
+        named_params! {
+            ":external_signer": external_signer,
+            ":name": friendly_name,
+        },
+    )?;
+
+    if affected_rows == 0 {
+        return Err(WalletDbError::InvalidInput(format!(
+            "Account '{}' not found",
+            friendly_name
+        )));
+    }
+
+    Ok(())
+}
+
+/// Decrypts the accounts selected by `account_name` (all accounts if `None`) with `password`.
+///
+/// A named account must decrypt. Without a name the accounts encrypted with other passwords
+/// are left out, but at least one account must decrypt, so that a mistyped password is noticed.
+fn decrypt_accounts(
+    conn: &Connection,
+    account_name: Option<&str>,
+    password: &str,
+) -> WalletDbResult<Vec<(AccountRow, WalletType)>> {
+    let accounts = get_accounts(conn, account_name)?;
+    if let Some(name) = account_name
+        && accounts.is_empty()
+    {
+        return Err(WalletDbError::InvalidInput(format!("Account '{}' not found", name)));
+    }
+
+    let mut decrypted = Vec::with_capacity(accounts.len());
+    for account in accounts {
+        match account.decrypt_wallet_type(password) {
+            Ok(wallet) => decrypted.push((account, wallet)),
+            Err(WalletDbError::DecryptionFailed(_)) if account_name.is_none() => {},
+            Err(WalletDbError::DecryptionFailed(_)) => {
+                return Err(WalletDbError::DecryptionFailed(format!(
+                    "The current password does not decrypt account '{}'",
+                    account.friendly_name
+                )));
+            },
+            Err(e) => return Err(e),
+        }
+    }
+    if decrypted.is_empty() {
+        return Err(WalletDbError::DecryptionFailed(
+            "The current password does not decrypt any account".to_string(),
+        ));
+    }
+    Ok(decrypted)
+}
+
+/// Returns the accounts [`change_accounts_password`] would re-encrypt, without modifying
+/// anything. Fails the same way if the password does not decrypt them.
+pub fn find_accounts_decrypting(
+    conn: &Connection,
+    account_name: Option<&str>,
+    password: &str,
+) -> WalletDbResult<Vec<AccountRow>> {
+    debug!(account:? = account_name; "DB: Checking the password against accounts");
+
+    Ok(decrypt_accounts(conn, account_name, password)?
+        .into_iter()
+        .map(|(account, _)| account)
+        .collect())
+}
+
+/// Re-encrypts the wallet of accounts from `old_password` to `new_password`.
+///
+/// With `account_name` only that account is re-encrypted. Otherwise every account that
+/// decrypts with `old_password` is, while accounts encrypted with other passwords are left
+/// untouched. Each account gets a fresh salt and nonce. Fails with
+/// [`WalletDbError::DecryptionFailed`] before anything is written if the named account, or no
+/// account at all, decrypts with `old_password`. Callers should run this inside a transaction
+/// so that a failure while writing leaves every account encrypted with the old password.
+///
+/// Returns the re-encrypted accounts as they were loaded before the change.
+pub fn change_accounts_password(
+    conn: &Connection,
+    account_name: Option<&str>,
+    old_password: &str,
+    new_password: &str,
+) -> WalletDbResult<Vec<AccountRow>> {
+    info!(target: "audit", account:? = account_name; "DB: Changing the password of accounts");
+
+    let wallets = decrypt_accounts(conn, account_name, old_password)?;
+    for (account, wallet) in &wallets {
+        let wallet_json = serde_json::to_string(wallet)
+            .map_err(|e| WalletDbError::Unexpected(format!("Serialization failed: {}", e)))?;
+        let encrypted_data = encrypt_data(wallet_json.as_bytes(), new_password)
+            .map_err(|e| WalletDbError::Unexpected(format!("Encryption failed: {}", e)))?;
+
+        conn.execute(
+            r#"
+            UPDATE accounts
+            SET encrypted_wallet = :enc_wallet,
+                cipher_nonce = :nonce,
+                salt = :salt
+            WHERE id = :id
+            "#,
+            named_params! {
+                ":enc_wallet": encrypted_data.ciphertext,
+                ":nonce": encrypted_data.nonce,
+                ":salt": encrypted_data.salt_bytes,
+                ":id": account.id,
+            },
+        )?;
+    }
+
+    info!(target: "audit", accounts = wallets.len(); "DB: Account password changed");
+    Ok(wallets.into_iter().map(|(account, _)| account).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use tari_common_types::seeds::cipher_seed::CipherSeed;
+    use tari_transaction_components::key_manager::wallet_types::SeedWordsWallet;
+
+    use super::*;
+    use crate::db::init_db;
+
+    fn names(accounts: Vec<AccountRow>) -> Vec<String> {
+        accounts.into_iter().map(|account| account.friendly_name).collect()
+    }
+
+    #[test]
+    fn test_change_accounts_password() {
+        let dir = tempfile::tempdir().unwrap();
+        let pool = init_db(dir.path().join("wallet.db"), Network::Esmeralda).unwrap();
+        let mut conn = pool.get().unwrap();
+        for (name, password) in [
+            ("first", "old password"),
+            ("second", "old password"),
+            ("third", "other"),
+        ] {
+            let wallet = WalletType::SeedWords(SeedWordsWallet::construct_new(CipherSeed::random()).unwrap());
+            create_account(&conn, name, &wallet, password, Network::Esmeralda).unwrap();
+        }
+
+        assert_eq!(
+            names(find_accounts_decrypting(&conn, None, "old password").unwrap()),
+            vec!["first".to_string(), "second".to_string()]
+        );
+        assert!(matches!(
+            find_accounts_decrypting(&conn, Some("third"), "old password"),
+            Err(WalletDbError::DecryptionFailed(_))
+        ));
+        assert!(matches!(
+            change_accounts_password(&conn, None, "wrong password", "new password"),
+            Err(WalletDbError::DecryptionFailed(_))
+        ));
+        assert!(matches!(
+            change_accounts_password(&conn, Some("missing"), "old password", "new password"),
+            Err(WalletDbError::InvalidInput(_))
+        ));
+
+        let tx = conn.transaction().unwrap();
+        assert_eq!(
+            names(change_accounts_password(&tx, None, "old password", "new password").unwrap()),
+            vec!["first".to_string(), "second".to_string()]
+        );
+        assert_eq!(
+            names(change_accounts_password(&tx, Some("first"), "new password", "first password").unwrap()),
+            vec!["first".to_string()]
+        );
+        tx.commit().unwrap();
+
+        assert_eq!(
+            names(find_accounts_decrypting(&conn, None, "new password").unwrap()),
+            vec!["second".to_string()]
+        );
+        assert_eq!(
+            names(find_accounts_decrypting(&conn, None, "first password").unwrap()),
+            vec!["first".to_string()]
+        );
+        assert_eq!(
+            names(find_accounts_decrypting(&conn, None, "other").unwrap()),
+            vec!["third".to_string()]
+        );
+        assert!(find_accounts_decrypting(&conn, None, "old password").is_err());
+    }
+
+    #[test]
+    fn test_set_account_external_signer() {
+        let dir = tempfile::tempdir().unwrap();
+        let pool = init_db(dir.path().join("wallet.db"), Network::Esmeralda).unwrap();
+        let conn = pool.get().unwrap();
+        let wallet = WalletType::SeedWords(SeedWordsWallet::construct_new(CipherSeed::random()).unwrap());
+        create_account(&conn, "cold", &wallet, "password", Network::Esmeralda).unwrap();
+        assert!(!get_account_by_name(&conn, "cold").unwrap().unwrap().external_signer);
+
+        set_account_external_signer(&conn, "cold", true).unwrap();
+        assert!(get_account_by_name(&conn, "cold").unwrap().unwrap().external_signer);
+        set_account_external_signer(&conn, "cold", false).unwrap();
+        assert!(!get_accounts(&conn, None).unwrap().first().unwrap().external_signer);
+
+        assert!(matches!(
+            set_account_external_signer(&conn, "missing", true),
+            Err(WalletDbError::InvalidInput(_))
+        ));
+    }
+}
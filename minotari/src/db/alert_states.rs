@@ -0,0 +1,84 @@
+use log::debug;
+use rusqlite::{Connection, named_params};
+use serde::Deserialize;
+use serde_rusqlite::from_rows;
+
+use crate::db::error::WalletDbResult;
+
+/// State of an alert rule for one account and subject.
+///
+/// `rule` is the event type key of the alert, `subject` identifies what the alert is about
+/// (e.g. a transaction id) and is empty for account-wide alerts.
+#[derive(Debug, Clone, Deserialize)]
+pub struct DbAlertState {
+    pub account_id: i64,
+    pub rule: String,
+    pub subject: String,
+    /// Tip height at which the subject was first observed, for rules measured in blocks
+    pub first_seen_height: Option<u64>,
+    /// Whether the alert has fired. Untriggered rows are observations only.
+    pub triggered: bool,
+}
+
+/// Returns the states of a rule for an account.
+pub fn get_alert_states(conn: &Connection, account_id: i64, rule: &str) -> WalletDbResult<Vec<DbAlertState>> {
+    let mut stmt = conn.prepare_cached(
+        r#"
+        SELECT account_id, rule, subject, first_seen_height, triggered
+        FROM alert_states
+        WHERE account_id = :account_id AND rule = :rule
+        ORDER BY subject
+        "#,
+    )?;
+    let rows = stmt.query(named_params! { ":account_id": account_id, ":rule": rule })?;
+    let states = from_rows::<DbAlertState>(rows).collect::<Result<Vec<_>, _>>()?;
+    Ok(states)
+}
+
+/// Records an observation or a triggered alert. An existing state is left unchanged.
+pub fn insert_alert_state(
+    conn: &Connection,
+    account_id: i64,
+    rule: &str,
+    subject: &str,
+    first_seen_height: Option<u64>,
+    triggered: bool,
+) -> WalletDbResult<()> {
+    debug!(account_id = account_id, rule = rule, subject = subject; "DB: Inserting alert state");
+    conn.execute(
+        r#"
+        INSERT OR IGNORE INTO alert_states (account_id, rule, subject, first_seen_height, triggered)
+        VALUES (:account_id, :rule, :subject, :first_seen_height, :triggered)
+        "#,
+        named_params! {
+            ":account_id": account_id,
+            ":rule": rule,
+            ":subject": subject,
+            ":first_seen_height": first_seen_height.map(|h| h as i64),
+            ":triggered": triggered,
+        },
+    )?;
+    Ok(())
+}
+
+/// Marks an observed alert as fired.
+pub fn mark_alert_state_triggered(conn: &Connection, account_id: i64, rule: &str, subject: &str) -> WalletDbResult<()> {
+    conn.execute(
+        r#"
+        UPDATE alert_states SET triggered = 1
+        WHERE account_id = :account_id AND rule = :rule AND subject = :subject
+        "#,
+        named_params! { ":account_id": account_id, ":rule": rule, ":subject": subject },
+    )?;
+    Ok(())
+}
+
+/// Removes the state of an alert whose condition no longer holds, so it can fire again.
+pub fn delete_alert_state(conn: &Connection, account_id: i64, rule: &str, subject: &str) -> WalletDbResult<()> {
+    debug!(account_id = account_id, rule = rule, subject = subject; "DB: Clearing alert state");
+    conn.execute(
+        "DELETE FROM alert_states WHERE account_id = :account_id AND rule = :rule AND subject = :subject",
+        named_params! { ":account_id": account_id, ":rule": rule, ":subject": subject },
+    )?;
+    Ok(())
+}
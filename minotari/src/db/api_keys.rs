@@ -0,0 +1,314 @@
+use std::str::FromStr;
+
+use chrono::{Duration, NaiveDateTime, Utc};
+use log::info;
+use rusqlite::{Connection, named_params};
+use serde::Deserialize;
+use serde_rusqlite::from_rows;
+
+use crate::{
+    db::error::{WalletDbError, WalletDbResult},
+    models::ApiKeyScope,
+};
+
+/// How many seconds `last_used_at` may lag behind the actual last use of a key.
+const LAST_USED_AT_RESOLUTION_SECS: i64 = 60;
+
+/// Data needed to store a newly generated API key.
+pub struct NewApiKey<'a> {
+    pub name: &'a str,
+    /// Leading characters of the key, kept to help operators identify it
+    pub key_prefix: &'a str,
+    pub key_hash: &'a [u8],
+    pub scope: ApiKeyScope,
+    /// Accounts the key is restricted to. `None` grants access to all accounts.
+    pub account_ids: Option<&'a [i64]>,
+}
+
+/// An API key record fetched from the database. Never contains the key itself.
+#[derive(Debug, Clone)]
+pub struct DbApiKey {
+    pub id: i64,
+    pub name: String,
+    pub key_prefix: String,
+    pub scope: ApiKeyScope,
+    /// Accounts the key is restricted to, or `None` if it may access all accounts.
+    pub account_ids: Option<Vec<i64>>,
+    pub created_at: String,
+    pub last_used_at: Option<String>,
+    pub revoked_at: Option<String>,
+}
+
+impl DbApiKey {
+    /// Returns `true` if the key may access the given account.
+    pub fn allows_account(&self, account_id: i64) -> bool {
+        self.account_ids.as_ref().is_none_or(|ids| ids.contains(&account_id))
+    }
+}
+
+#[derive(Deserialize)]
+struct ApiKeyRow {
+    id: i64,
+    name: String,
+    key_prefix: String,
+    scope: String,
+    account_restricted: bool,
+    created_at: String,
+    last_used_at: Option<String>,
+    revoked_at: Option<String>,
+}
+
+const SELECT_API_KEY: &str = r#"
+    SELECT id, name, key_prefix, scope, account_restricted, created_at, last_used_at, revoked_at
+    FROM api_keys
+"#;
+
+fn get_api_key_account_ids(conn: &Connection, api_key_id: i64) -> WalletDbResult<Vec<i64>> {
+    let mut stmt =
+        conn.prepare_cached("SELECT account_id FROM api_key_accounts WHERE api_key_id = :id ORDER BY account_id")?;
+    let ids = stmt
+        .query_map(named_params! { ":id": api_key_id }, |row| row.get(0))?
+        .collect::<Result<Vec<i64>, _>>()?;
+    Ok(ids)
+}
+
+fn into_api_key(conn: &Connection, row: ApiKeyRow) -> WalletDbResult<DbApiKey> {
+    let scope = ApiKeyScope::from_str(&row.scope).map_err(WalletDbError::Decoding)?;
+    let account_ids = if row.account_restricted {
+        Some(get_api_key_account_ids(conn, row.id)?)
+    } else {
+        None
+    };
+    Ok(DbApiKey {
+        id: row.id,
+        name: row.name,
+        key_prefix: row.key_prefix,
+        scope,
+        account_ids,
+        created_at: row.created_at,
+        last_used_at: row.last_used_at,
+        revoked_at: row.revoked_at,
+    })
+}
+
+/// Stores a new API key together with its account restrictions.
+///
+/// Fails with [`WalletDbError::DuplicateEntry`] if an active key with the same name exists.
+pub fn insert_api_key(conn: &Connection, key: &NewApiKey) -> WalletDbResult<i64> {
+    info!(
+        target: "audit",
+        name = key.name,
+        scope:% = key.scope;
+        "DB: Inserting API key"
+    );
+
+    let tx = conn.unchecked_transaction()?;
+    let exists: bool = tx.query_row(
+        "SELECT EXISTS(SELECT 1 FROM api_keys WHERE name = :name AND revoked_at IS NULL)",
+        named_params! { ":name": key.name },
+        |row| row.get(0),
+    )?;
+    if exists {
+        return Err(WalletDbError::DuplicateEntry(format!(
+            "An active API key named '{}' already exists",
+            key.name
+        )));
+    }
+
+    tx.execute(
+        r#"
+        INSERT INTO api_keys (name, key_prefix, key_hash, scope, account_restricted)
+        VALUES (:name, :key_prefix, :key_hash, :scope, :account_restricted)
+        "#,
+        named_params! {
+            ":name": key.name,
+            ":key_prefix": key.key_prefix,
+            ":key_hash": key.key_hash,
+            ":scope": key.scope.to_string(),
+            ":account_restricted": key.account_ids.is_some(),
+        },
+    )?;
+    let id = tx.last_insert_rowid();
+
+    for account_id in key.account_ids.unwrap_or_default() {
+        tx.execute(
+            "INSERT OR IGNORE INTO api_key_accounts (api_key_id, account_id) VALUES (:api_key_id, :account_id)",
+            named_params! { ":api_key_id": id, ":account_id": account_id },
+        )?;
+    }
+    tx.commit()?;
+
+    Ok(id)
+}
+
+/// Looks up an active (not revoked) API key by the hash of the presented key.
+pub fn get_active_api_key_by_hash(conn: &Connection, key_hash: &[u8]) -> WalletDbResult<Option<DbApiKey>> {
+    let mut stmt = conn.prepare_cached(&format!(
+        "{SELECT_API_KEY} WHERE key_hash = :key_hash AND revoked_at IS NULL"
+    ))?;
+    let rows = stmt.query(named_params! { ":key_hash": key_hash })?;
+    let row: Option<ApiKeyRow> = from_rows(rows).next().transpose()?;
+    row.map(|row| into_api_key(conn, row)).transpose()
+}
+
+/// Returns all API keys, including revoked ones, oldest first.
+pub fn get_api_keys(conn: &Connection) -> WalletDbResult<Vec<DbApiKey>> {
+    let mut stmt = conn.prepare_cached(&format!("{SELECT_API_KEY} ORDER BY id ASC"))?;
+    let rows = stmt.query([])?;
+    let rows: Vec<ApiKeyRow> = from_rows(rows).collect::<Result<Vec<_>, _>>()?;
+    rows.into_iter().map(|row| into_api_key(conn, row)).collect()
+}
+
+/// Returns `true` if an API key has ever been created, even if it has since been revoked.
+pub fn has_api_keys(conn: &Connection) -> WalletDbResult<bool> {
+    let exists = conn.query_row("SELECT EXISTS(SELECT 1 FROM api_keys)", [], |row| row.get(0))?;
+    Ok(exists)
+}
+
+/// Revokes the active API key with the given name. Returns `false` if there is none.
+pub fn revoke_api_key(conn: &Connection, name: &str) -> WalletDbResult<bool> {
+    info!(target: "audit", name = name; "DB: Revoking API key");
+
+    let updated = conn.execute(
+        "UPDATE api_keys SET revoked_at = CURRENT_TIMESTAMP WHERE name = :name AND revoked_at IS NULL",
+        named_params! { ":name": name },
+    )?;
+    Ok(updated > 0)
+}
+
+/// Records that an API key has just been used.
+///
+/// `last_used_at` is only updated once it is more than a minute old, so that authenticating a
+/// request does not write to the database every time.
+pub fn touch_api_key(conn: &Connection, api_key: &DbApiKey) -> WalletDbResult<()> {
+    let recently_used = api_key
+        .last_used_at
+        .as_deref()
+        .and_then(|last_used_at| NaiveDateTime::parse_from_str(last_used_at, "%Y-%m-%d %H:%M:%S").ok())
+        .is_some_and(|last_used_at| {
+            Utc::now().naive_utc() - last_used_at < Duration::seconds(LAST_USED_AT_RESOLUTION_SECS)
+        });
+    if recently_used {
+        return Ok(());
+    }
+
+    conn.execute(
+        "UPDATE api_keys SET last_used_at = CURRENT_TIMESTAMP WHERE id = :id",
+        named_params! { ":id": api_key.id },
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::indexing_slicing)]
+    use super::*;
+    use crate::db::{create_account, get_account_by_name, init_db};
+    use tari_common::configuration::Network;
+    use tari_common_types::seeds::cipher_seed::CipherSeed;
+    use tari_transaction_components::key_manager::wallet_types::{SeedWordsWallet, WalletType};
+    use tempfile::tempdir;
+
+    fn new_key<'a>(name: &'a str, hash: &'a [u8], account_ids: Option<&'a [i64]>) -> NewApiKey<'a> {
+        NewApiKey {
+            name,
+            key_prefix: "mtk_0000",
+            key_hash: hash,
+            scope: ApiKeyScope::Spend,
+            account_ids,
+        }
+    }
+
+    #[test]
+    fn test_api_key_lifecycle() {
+        let temp = tempdir().expect("temp dir");
+        let pool = init_db(temp.path().join("api_keys.db"), Network::Esmeralda).expect("init db");
+        let conn = pool.get().expect("get connection");
+        assert!(!has_api_keys(&conn).unwrap());
+
+        insert_api_key(&conn, &new_key("ops", b"hash-1", None)).expect("insert key");
+        assert!(has_api_keys(&conn).unwrap());
+
+        let key = get_active_api_key_by_hash(&conn, b"hash-1")
+            .unwrap()
+            .expect("key found");
+        assert_eq!(key.name, "ops");
+        assert_eq!(key.scope, ApiKeyScope::Spend);
+        assert!(key.allows_account(42), "unrestricted key allows every account");
+        assert!(get_active_api_key_by_hash(&conn, b"unknown").unwrap().is_none());
+
+        // Names are unique among active keys only
+        let duplicate = insert_api_key(&conn, &new_key("ops", b"hash-2", None));
+        assert!(matches!(duplicate, Err(WalletDbError::DuplicateEntry(_))));
+
+        assert!(revoke_api_key(&conn, "ops").unwrap());
+        assert!(!revoke_api_key(&conn, "ops").unwrap());
+        assert!(get_active_api_key_by_hash(&conn, b"hash-1").unwrap().is_none());
+        // Revoking the last key does not reopen the API to unauthenticated requests
+        assert!(has_api_keys(&conn).unwrap());
+
+        insert_api_key(&conn, &new_key("ops", b"hash-2", None)).expect("reuse revoked name");
+        let keys = get_api_keys(&conn).unwrap();
+        assert_eq!(keys.len(), 2);
+        assert!(keys[0].revoked_at.is_some());
+        assert!(keys[1].revoked_at.is_none());
+    }
+
+    #[test]
+    fn test_touch_api_key_is_throttled() {
+        let temp = tempdir().expect("temp dir");
+        let pool = init_db(temp.path().join("api_keys_touch.db"), Network::Esmeralda).expect("init db");
+        let conn = pool.get().expect("get connection");
+        let id = insert_api_key(&conn, &new_key("ops", b"hash-4", None)).expect("insert key");
+        let set_last_used_at = |value: &str| {
+            conn.execute(
+                "UPDATE api_keys SET last_used_at = :value WHERE id = :id",
+                named_params! { ":value": value, ":id": id },
+            )
+            .unwrap();
+        };
+        let last_used_at = || {
+            get_active_api_key_by_hash(&conn, b"hash-4")
+                .unwrap()
+                .unwrap()
+                .last_used_at
+        };
+
+        // A key that was never used, or not for a while, is updated
+        let key = get_active_api_key_by_hash(&conn, b"hash-4").unwrap().unwrap();
+        touch_api_key(&conn, &key).unwrap();
+        assert!(last_used_at().is_some());
+        set_last_used_at("2000-01-01 00:00:00");
+        let key = get_active_api_key_by_hash(&conn, b"hash-4").unwrap().unwrap();
+        touch_api_key(&conn, &key).unwrap();
+        assert_ne!(last_used_at().as_deref(), Some("2000-01-01 00:00:00"));
+
+        // A key used within the last minute is not written again
+        let key = get_active_api_key_by_hash(&conn, b"hash-4").unwrap().unwrap();
+        set_last_used_at("2000-01-01 00:00:00");
+        touch_api_key(&conn, &key).unwrap();
+        assert_eq!(last_used_at().as_deref(), Some("2000-01-01 00:00:00"));
+    }
+
+    #[test]
+    fn test_restricted_api_key_stays_restricted_when_account_is_deleted() {
+        let temp = tempdir().expect("temp dir");
+        let pool = init_db(temp.path().join("api_keys_restricted.db"), Network::Esmeralda).expect("init db");
+        let conn = pool.get().expect("get connection");
+
+        let wallet_type = WalletType::SeedWords(SeedWordsWallet::construct_new(CipherSeed::random()).unwrap());
+        create_account(&conn, "default", &wallet_type, "pw", Network::Esmeralda).expect("create account");
+        let account = get_account_by_name(&conn, "default").unwrap().unwrap();
+
+        let account_ids = [account.id];
+        insert_api_key(&conn, &new_key("merchant", b"hash-3", Some(&account_ids))).expect("insert key");
+        let key = get_active_api_key_by_hash(&conn, b"hash-3").unwrap().unwrap();
+        assert!(key.allows_account(account.id));
+        assert!(!key.allows_account(account.id + 1));
+
+        crate::db::delete_account(&conn, "default").expect("delete account");
+        let key = get_active_api_key_by_hash(&conn, b"hash-3").unwrap().unwrap();
+        assert_eq!(key.account_ids, Some(vec![]));
+        assert!(!key.allows_account(account.id));
+    }
+}
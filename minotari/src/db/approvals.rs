@@ -0,0 +1,628 @@
+use std::str::FromStr;
+
+use log::info;
+use rusqlite::{Connection, named_params};
+use serde::Deserialize;
+use serde_rusqlite::from_rows;
+use tari_transaction_components::tari_amount::MicroMinotari;
+
+use crate::{
+    db::error::{WalletDbError, WalletDbResult},
+    log::mask_amount,
+    models::{ApprovalDecision, PendingTransactionStatus},
+};
+
+/// An API key that may approve the payments of an account.
+#[derive(Debug, Clone, Deserialize)]
+pub struct DbApprover {
+    pub api_key_id: i64,
+    pub name: String,
+    /// Revoked keys can no longer authenticate, so they cannot approve anything
+    pub revoked: bool,
+}
+
+/// An account's approval policy, fetched from the database.
+#[derive(Debug, Clone)]
+pub struct DbApprovalPolicy {
+    pub account_id: i64,
+    /// Number of distinct approvers that have to approve a payment
+    pub required_approvals: u32,
+    /// Payments of at least this amount need approval; smaller ones are sent right away
+    pub threshold_amount: MicroMinotari,
+    pub approvers: Vec<DbApprover>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl DbApprovalPolicy {
+    /// Returns `true` if a payment of `amount` needs approval under this policy.
+    pub fn applies_to(&self, amount: MicroMinotari) -> bool {
+        amount >= self.threshold_amount
+    }
+
+    /// Returns `true` if the API key is one of the policy's approvers.
+    pub fn is_approver(&self, api_key_id: i64) -> bool {
+        self.approvers.iter().any(|approver| approver.api_key_id == api_key_id)
+    }
+}
+
+/// Data needed to put a pending transaction up for approval.
+pub struct NewApprovalRequest<'a> {
+    pub pending_transaction_id: &'a str,
+    pub account_id: i64,
+    /// Base58 address of the payment's recipient
+    pub recipient_address: &'a str,
+    pub amount: MicroMinotari,
+    pub payment_id: Option<&'a str>,
+    pub required_approvals: u32,
+}
+
+/// A decision of an approver on a pending transaction.
+#[derive(Debug, Clone)]
+pub struct DbApprovalDecision {
+    pub api_key_id: i64,
+    /// Name of the approver's API key
+    pub approver: String,
+    pub decision: ApprovalDecision,
+    pub comment: Option<String>,
+    /// `false` once the approver's API key was revoked or removed from the account's approval
+    /// policy, which withdraws an approval
+    pub counted: bool,
+    pub created_at: String,
+}
+
+/// A pending transaction put up for approval, with the decisions taken so far.
+#[derive(Debug, Clone)]
+pub struct DbApprovalRequest {
+    pub pending_transaction_id: String,
+    pub account_id: i64,
+    pub recipient_address: String,
+    pub amount: MicroMinotari,
+    pub payment_id: Option<String>,
+    /// Approvals needed, copied from the policy when the request was created
+    pub required_approvals: u32,
+    /// Status of the pending transaction
+    pub status: PendingTransactionStatus,
+    /// End of the pending transaction's UTXO lock, after which the request lapses
+    pub expires_at: String,
+    /// `true` once `expires_at` has passed
+    pub expired: bool,
+    pub created_at: String,
+    pub decisions: Vec<DbApprovalDecision>,
+}
+
+impl DbApprovalRequest {
+    /// Number of approvals received from approvers that are still active approvers.
+    pub fn approvals(&self) -> u32 {
+        let approvals = self
+            .decisions
+            .iter()
+            .filter(|decision| decision.decision == ApprovalDecision::Approved && decision.counted)
+            .count();
+        u32::try_from(approvals).unwrap_or(u32::MAX)
+    }
+
+    /// Returns the rejection of the transaction, if an approver rejected it.
+    pub fn rejection(&self) -> Option<&DbApprovalDecision> {
+        self.decisions
+            .iter()
+            .find(|decision| decision.decision == ApprovalDecision::Rejected)
+    }
+
+    /// Returns `true` if the transaction received the required approvals and no rejection.
+    pub fn is_approved(&self) -> bool {
+        self.rejection().is_none() && self.approvals() >= self.required_approvals
+    }
+
+    /// Returns `true` if the request was made for a payment of `amount` to `recipient_address`
+    /// with `payment_id`, i.e. if its decisions apply to that payment.
+    pub fn is_for_payment(&self, recipient_address: &str, amount: MicroMinotari, payment_id: Option<&str>) -> bool {
+        self.recipient_address == recipient_address && self.amount == amount && self.payment_id.as_deref() == payment_id
+    }
+
+    /// Returns `true` while approvers may still decide on the transaction, i.e. while it is
+    /// pending, its lock has not expired and nobody has rejected it.
+    pub fn is_open(&self) -> bool {
+        self.status == PendingTransactionStatus::Pending && !self.expired && self.rejection().is_none()
+    }
+}
+
+#[derive(Deserialize)]
+struct ApprovalPolicyRow {
+    account_id: i64,
+    required_approvals: i64,
+    threshold_amount: i64,
+    created_at: String,
+    updated_at: String,
+}
+
+#[derive(Deserialize)]
+struct ApprovalRequestRow {
+    pending_transaction_id: String,
+    account_id: i64,
+    recipient_address: String,
+    amount: i64,
+    payment_id: Option<String>,
+    required_approvals: i64,
+    status: String,
+    expires_at: String,
+    expired: bool,
+    created_at: String,
+}
+
+#[derive(Deserialize)]
+struct ApprovalDecisionRow {
+    api_key_id: i64,
+    approver: String,
+    decision: String,
+    comment: Option<String>,
+    counted: bool,
+    created_at: String,
+}
+
+const SELECT_APPROVAL_REQUEST: &str = r#"
+    SELECT
+        r.pending_transaction_id,
+        r.account_id,
+        r.recipient_address,
+        r.amount,
+        r.payment_id,
+        r.required_approvals,
+        p.status,
+        p.expires_at,
+        p.expires_at < CURRENT_TIMESTAMP AS expired,
+        r.created_at
+    FROM approval_requests r
+    JOIN pending_transactions p ON p.id = r.pending_transaction_id
+"#;
+
+/// Sets the approval policy of an account, replacing its previous policy and approvers.
+///
+/// Fails with [`WalletDbError::InvalidInput`] if no approval is required or the approvers
+/// could never reach the required number of approvals.
+pub fn set_approval_policy(
+    conn: &Connection,
+    account_id: i64,
+    required_approvals: u32,
+    threshold_amount: MicroMinotari,
+    approver_key_ids: &[i64],
+) -> WalletDbResult<()> {
+    info!(
+        target: "audit",
+        account_id = account_id,
+        required_approvals = required_approvals,
+        approvers = approver_key_ids.len(),
+        threshold_amount = &*mask_amount(threshold_amount);
+        "DB: Setting approval policy"
+    );
+
+    let mut approver_key_ids = approver_key_ids.to_vec();
+    approver_key_ids.sort_unstable();
+    approver_key_ids.dedup();
+    if required_approvals == 0 {
+        return Err(WalletDbError::InvalidInput(
+            "An approval policy must require at least one approval".to_string(),
+        ));
+    }
+    if approver_key_ids.len() < required_approvals as usize {
+        return Err(WalletDbError::InvalidInput(format!(
+            "The policy requires {} approvals but has only {} approvers",
+            required_approvals,
+            approver_key_ids.len()
+        )));
+    }
+
+    #[allow(clippy::cast_possible_wrap)]
+    let threshold_amount = threshold_amount.as_u64() as i64;
+    let tx = conn.unchecked_transaction()?;
+    tx.execute(
+        r#"
+        INSERT INTO approval_policies (account_id, required_approvals, threshold_amount)
+        VALUES (:account_id, :required_approvals, :threshold_amount)
+        ON CONFLICT (account_id) DO UPDATE SET
+            required_approvals = excluded.required_approvals,
+            threshold_amount = excluded.threshold_amount,
+            updated_at = CURRENT_TIMESTAMP
+        "#,
+        named_params! {
+            ":account_id": account_id,
+            ":required_approvals": required_approvals,
+            ":threshold_amount": threshold_amount,
+        },
+    )?;
+    tx.execute(
+        "DELETE FROM approval_policy_approvers WHERE account_id = :account_id",
+        named_params! { ":account_id": account_id },
+    )?;
+    for api_key_id in approver_key_ids {
+        tx.execute(
+            "INSERT INTO approval_policy_approvers (account_id, api_key_id) VALUES (:account_id, :api_key_id)",
+            named_params! { ":account_id": account_id, ":api_key_id": api_key_id },
+        )?;
+    }
+    tx.commit()?;
+
+    Ok(())
+}
+
+/// Returns the approval policy of an account, or `None` if its payments need no approval.
+pub fn get_approval_policy(conn: &Connection, account_id: i64) -> WalletDbResult<Option<DbApprovalPolicy>> {
+    let mut stmt = conn.prepare_cached(
+        r#"
+        SELECT account_id, required_approvals, threshold_amount, created_at, updated_at
+        FROM approval_policies
+        WHERE account_id = :account_id
+        "#,
+    )?;
+    let rows = stmt.query(named_params! { ":account_id": account_id })?;
+    let Some(row) = from_rows::<ApprovalPolicyRow>(rows).next().transpose()? else {
+        return Ok(None);
+    };
+
+    let mut stmt = conn.prepare_cached(
+        r#"
+        SELECT k.id AS api_key_id, k.name, k.revoked_at IS NOT NULL AS revoked
+        FROM approval_policy_approvers a
+        JOIN api_keys k ON k.id = a.api_key_id
+        WHERE a.account_id = :account_id
+        ORDER BY k.name
+        "#,
+    )?;
+    let rows = stmt.query(named_params! { ":account_id": account_id })?;
+    let approvers = from_rows::<DbApprover>(rows).collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Some(DbApprovalPolicy {
+        account_id: row.account_id,
+        required_approvals: u32::try_from(row.required_approvals)
+            .map_err(|e| WalletDbError::Decoding(format!("Invalid required approvals: {}", e)))?,
+        threshold_amount: MicroMinotari::from(row.threshold_amount as u64),
+        approvers,
+        created_at: row.created_at,
+        updated_at: row.updated_at,
+    }))
+}
+
+/// Removes the approval policy of an account. Returns `false` if it had none.
+///
+/// Approvals already given no longer count, as their approvers left the policy, so
+/// transactions waiting for approval lapse with their lock.
+pub fn delete_approval_policy(conn: &Connection, account_id: i64) -> WalletDbResult<bool> {
+    info!(target: "audit", account_id = account_id; "DB: Deleting approval policy");
+
+    let deleted = conn.execute(
+        "DELETE FROM approval_policies WHERE account_id = :account_id",
+        named_params! { ":account_id": account_id },
+    )?;
+    Ok(deleted > 0)
+}
+
+/// Puts a pending transaction up for approval.
+///
+/// Returns `false` if the transaction already has an approval request, e.g. because it was
+/// resumed with the same idempotency key.
+pub fn create_approval_request(conn: &Connection, request: &NewApprovalRequest) -> WalletDbResult<bool> {
+    info!(
+        target: "audit",
+        id = request.pending_transaction_id,
+        account_id = request.account_id,
+        required_approvals = request.required_approvals,
+        amount = &*mask_amount(request.amount);
+        "DB: Creating approval request"
+    );
+
+    #[allow(clippy::cast_possible_wrap)]
+    let amount = request.amount.as_u64() as i64;
+    let inserted = conn.execute(
+        r#"
+        INSERT OR IGNORE INTO approval_requests (
+            pending_transaction_id,
+            account_id,
+            recipient_address,
+            amount,
+            payment_id,
+            required_approvals
+        )
+        VALUES (:id, :account_id, :recipient_address, :amount, :payment_id, :required_approvals)
+        "#,
+        named_params! {
+            ":id": request.pending_transaction_id,
+            ":account_id": request.account_id,
+            ":recipient_address": request.recipient_address,
+            ":amount": amount,
+            ":payment_id": request.payment_id,
+            ":required_approvals": request.required_approvals,
+        },
+    )?;
+    Ok(inserted > 0)
+}
+
+fn get_approval_decisions(conn: &Connection, pending_transaction_id: &str) -> WalletDbResult<Vec<DbApprovalDecision>> {
+    let mut stmt = conn.prepare_cached(
+        r#"
+        SELECT
+            a.api_key_id,
+            k.name AS approver,
+            a.decision,
+            a.comment,
+            k.revoked_at IS NULL AND EXISTS (
+                SELECT 1 FROM approval_policy_approvers p
+                WHERE p.account_id = a.account_id AND p.api_key_id = a.api_key_id
+            ) AS counted,
+            a.created_at
+        FROM transaction_approvals a
+        JOIN api_keys k ON k.id = a.api_key_id
+        WHERE a.pending_transaction_id = :id
+        ORDER BY a.id
+        "#,
+    )?;
+    let rows = stmt.query(named_params! { ":id": pending_transaction_id })?;
+    from_rows::<ApprovalDecisionRow>(rows)
+        .map(|row| {
+            let row = row?;
+            Ok(DbApprovalDecision {
+                api_key_id: row.api_key_id,
+                approver: row.approver,
+                decision: ApprovalDecision::from_str(&row.decision).map_err(WalletDbError::Decoding)?,
+                comment: row.comment,
+                counted: row.counted,
+                created_at: row.created_at,
+            })
+        })
+        .collect()
+}
+
+fn into_approval_request(conn: &Connection, row: ApprovalRequestRow) -> WalletDbResult<DbApprovalRequest> {
+    Ok(DbApprovalRequest {
+        decisions: get_approval_decisions(conn, &row.pending_transaction_id)?,
+        pending_transaction_id: row.pending_transaction_id,
+        account_id: row.account_id,
+        recipient_address: row.recipient_address,
+        amount: MicroMinotari::from(row.amount as u64),
+        payment_id: row.payment_id,
+        required_approvals: u32::try_from(row.required_approvals)
+            .map_err(|e| WalletDbError::Decoding(format!("Invalid required approvals: {}", e)))?,
+        status: PendingTransactionStatus::from_str(&row.status).map_err(WalletDbError::Decoding)?,
+        expires_at: row.expires_at,
+        expired: row.expired,
+        created_at: row.created_at,
+    })
+}
+
+/// Returns the approval request of a pending transaction, or `None` if it needs no approval.
+pub fn get_approval_request(
+    conn: &Connection,
+    pending_transaction_id: &str,
+) -> WalletDbResult<Option<DbApprovalRequest>> {
+    let mut stmt = conn.prepare_cached(&format!(
+        "{SELECT_APPROVAL_REQUEST} WHERE r.pending_transaction_id = :id"
+    ))?;
+    let rows = stmt.query(named_params! { ":id": pending_transaction_id })?;
+    let row: Option<ApprovalRequestRow> = from_rows(rows).next().transpose()?;
+    row.map(|row| into_approval_request(conn, row)).transpose()
+}
+
+/// Returns the approval requests of an account that approvers may still decide on, oldest first.
+///
+/// Requests of transactions that were sent, cancelled or whose lock expired are left out.
+pub fn get_open_approval_requests(conn: &Connection, account_id: i64) -> WalletDbResult<Vec<DbApprovalRequest>> {
+    let mut stmt = conn.prepare_cached(&format!(
+        r#"{SELECT_APPROVAL_REQUEST}
+        WHERE r.account_id = :account_id
+          AND p.status = :status
+          AND p.expires_at >= CURRENT_TIMESTAMP
+          AND NOT EXISTS (
+              SELECT 1 FROM transaction_approvals a
+              WHERE a.pending_transaction_id = r.pending_transaction_id AND a.decision = :rejected
+          )
+        ORDER BY r.created_at ASC, r.rowid ASC"#
+    ))?;
+    let rows = stmt.query(named_params! {
+        ":account_id": account_id,
+        ":status": PendingTransactionStatus::Pending.to_string(),
+        ":rejected": ApprovalDecision::Rejected.to_string(),
+    })?;
+    let rows: Vec<ApprovalRequestRow> = from_rows(rows).collect::<Result<Vec<_>, _>>()?;
+    rows.into_iter().map(|row| into_approval_request(conn, row)).collect()
+}
+
+/// Records an approver's decision on a pending transaction.
+///
+/// Fails with [`WalletDbError::DuplicateEntry`] if the API key already decided on it.
+pub fn record_approval_decision(
+    conn: &Connection,
+    request: &DbApprovalRequest,
+    api_key_id: i64,
+    decision: ApprovalDecision,
+    comment: Option<&str>,
+) -> WalletDbResult<()> {
+    info!(
+        target: "audit",
+        id = &*request.pending_transaction_id,
+        api_key_id = api_key_id,
+        decision:% = decision;
+        "DB: Recording approval decision"
+    );
+
+    let res = conn.execute(
+        r#"
+        INSERT INTO transaction_approvals (pending_transaction_id, account_id, api_key_id, decision, comment)
+        VALUES (:id, :account_id, :api_key_id, :decision, :comment)
+        "#,
+        named_params! {
+            ":id": request.pending_transaction_id,
+            ":account_id": request.account_id,
+            ":api_key_id": api_key_id,
+            ":decision": decision.to_string(),
+            ":comment": comment,
+        },
+    );
+
+    match res {
+        Ok(_) => Ok(()),
+        Err(rusqlite::Error::SqliteFailure(err, _)) if err.code == rusqlite::ErrorCode::ConstraintViolation => {
+            Err(WalletDbError::DuplicateEntry(format!(
+                "The API key has already decided on transaction {}",
+                request.pending_transaction_id
+            )))
+        },
+        Err(e) => Err(WalletDbError::Rusqlite(e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::indexing_slicing)]
+    use super::*;
+    use crate::db::{
+        NewApiKey, create_account, create_pending_transaction, get_account_by_name, init_db, insert_api_key,
+        revoke_api_key, update_pending_transaction_status,
+    };
+    use crate::models::ApiKeyScope;
+    use chrono::{Duration, Utc};
+    use tari_common::configuration::Network;
+    use tari_common_types::seeds::cipher_seed::CipherSeed;
+    use tari_transaction_components::key_manager::wallet_types::{SeedWordsWallet, WalletType};
+    use tempfile::tempdir;
+
+    fn insert_key(conn: &Connection, name: &str) -> i64 {
+        insert_api_key(
+            conn,
+            &NewApiKey {
+                name,
+                key_prefix: "mtk_0000",
+                key_hash: name.as_bytes(),
+                scope: ApiKeyScope::Spend,
+                account_ids: None,
+            },
+        )
+        .expect("insert key")
+    }
+
+    fn request_approval(conn: &Connection, account_id: i64, key: &str, expires_in: Duration) -> String {
+        let id = create_pending_transaction(
+            conn,
+            key,
+            account_id,
+            false,
+            MicroMinotari(10_000),
+            MicroMinotari(10),
+            MicroMinotari(20),
+            Utc::now() + expires_in,
+        )
+        .expect("create pending transaction");
+        let created = create_approval_request(
+            conn,
+            &NewApprovalRequest {
+                pending_transaction_id: &id,
+                account_id,
+                recipient_address: "f4recipient",
+                amount: MicroMinotari(5_000),
+                payment_id: Some("invoice-1"),
+                required_approvals: 2,
+            },
+        )
+        .expect("create approval request");
+        assert!(created);
+        id
+    }
+
+    #[test]
+    fn test_approval_policy_lifecycle() {
+        let temp = tempdir().expect("temp dir");
+        let pool = init_db(temp.path().join("approval_policy.db"), Network::Esmeralda).expect("init db");
+        let conn = pool.get().expect("get connection");
+        let wallet_type = WalletType::SeedWords(SeedWordsWallet::construct_new(CipherSeed::random()).unwrap());
+        create_account(&conn, "treasury", &wallet_type, "pw", Network::Esmeralda).expect("create account");
+        let account = get_account_by_name(&conn, "treasury").unwrap().unwrap();
+        let alice = insert_key(&conn, "alice");
+        let bob = insert_key(&conn, "bob");
+        assert!(get_approval_policy(&conn, account.id).unwrap().is_none());
+
+        let unreachable = set_approval_policy(&conn, account.id, 2, MicroMinotari(1_000), &[alice, alice]);
+        assert!(matches!(unreachable, Err(WalletDbError::InvalidInput(_))));
+        let no_approvals = set_approval_policy(&conn, account.id, 0, MicroMinotari(1_000), &[alice]);
+        assert!(matches!(no_approvals, Err(WalletDbError::InvalidInput(_))));
+
+        set_approval_policy(&conn, account.id, 2, MicroMinotari(1_000), &[bob, alice]).expect("set policy");
+        let policy = get_approval_policy(&conn, account.id).unwrap().expect("policy");
+        assert_eq!(policy.required_approvals, 2);
+        assert_eq!(policy.approvers.len(), 2);
+        assert_eq!(policy.approvers[0].name, "alice");
+        assert!(policy.is_approver(bob));
+        assert!(policy.applies_to(MicroMinotari(1_000)));
+        assert!(!policy.applies_to(MicroMinotari(999)));
+
+        set_approval_policy(&conn, account.id, 1, MicroMinotari(0), &[bob]).expect("replace policy");
+        let policy = get_approval_policy(&conn, account.id).unwrap().expect("policy");
+        assert_eq!(policy.required_approvals, 1);
+        assert!(!policy.is_approver(alice));
+
+        assert!(delete_approval_policy(&conn, account.id).unwrap());
+        assert!(!delete_approval_policy(&conn, account.id).unwrap());
+        assert!(get_approval_policy(&conn, account.id).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_approval_requests_collect_decisions_until_they_lapse() {
+        let temp = tempdir().expect("temp dir");
+        let pool = init_db(temp.path().join("approval_requests.db"), Network::Esmeralda).expect("init db");
+        let conn = pool.get().expect("get connection");
+        let wallet_type = WalletType::SeedWords(SeedWordsWallet::construct_new(CipherSeed::random()).unwrap());
+        create_account(&conn, "treasury", &wallet_type, "pw", Network::Esmeralda).expect("create account");
+        let account = get_account_by_name(&conn, "treasury").unwrap().unwrap();
+        let alice = insert_key(&conn, "alice");
+        let bob = insert_key(&conn, "bob");
+        let carol = insert_key(&conn, "carol");
+        set_approval_policy(&conn, account.id, 2, MicroMinotari(1_000), &[alice, bob]).expect("set policy");
+
+        let open_id = request_approval(&conn, account.id, "open", Duration::hours(1));
+        let expired_id = request_approval(&conn, account.id, "expired", Duration::hours(-1));
+        let rejected_id = request_approval(&conn, account.id, "rejected", Duration::hours(1));
+        let sent_id = request_approval(&conn, account.id, "sent", Duration::hours(1));
+        update_pending_transaction_status(&conn, &sent_id, PendingTransactionStatus::Completed).unwrap();
+
+        let rejected = get_approval_request(&conn, &rejected_id).unwrap().unwrap();
+        record_approval_decision(&conn, &rejected, bob, ApprovalDecision::Rejected, Some("unknown payee")).unwrap();
+        let rejected = get_approval_request(&conn, &rejected_id).unwrap().unwrap();
+        assert!(!rejected.is_open());
+        assert_eq!(rejected.rejection().unwrap().comment.as_deref(), Some("unknown payee"));
+        assert!(get_approval_request(&conn, &expired_id).unwrap().unwrap().expired);
+
+        let open = get_open_approval_requests(&conn, account.id).unwrap();
+        assert_eq!(open.len(), 1);
+        assert_eq!(open[0].pending_transaction_id, open_id);
+        assert_eq!(open[0].amount, MicroMinotari(5_000));
+        assert!(open[0].is_open());
+        assert!(open[0].is_for_payment("f4recipient", MicroMinotari(5_000), Some("invoice-1")));
+        assert!(!open[0].is_for_payment("f4attacker", MicroMinotari(5_000), Some("invoice-1")));
+        assert!(!open[0].is_for_payment("f4recipient", MicroMinotari(50_000), Some("invoice-1")));
+        assert!(!open[0].is_for_payment("f4recipient", MicroMinotari(5_000), None));
+
+        let request = &open[0];
+        record_approval_decision(&conn, request, alice, ApprovalDecision::Approved, None).unwrap();
+        let duplicate = record_approval_decision(&conn, request, alice, ApprovalDecision::Approved, None);
+        assert!(matches!(duplicate, Err(WalletDbError::DuplicateEntry(_))));
+        let request = get_approval_request(&conn, &open_id).unwrap().unwrap();
+        assert_eq!(request.approvals(), 1);
+        assert!(!request.is_approved());
+
+        record_approval_decision(&conn, &request, bob, ApprovalDecision::Approved, None).unwrap();
+        let request = get_approval_request(&conn, &open_id).unwrap().unwrap();
+        assert_eq!(request.approvals(), 2);
+        assert!(request.is_approved());
+        assert_eq!(request.decisions[0].approver, "alice");
+
+        // Revoking an approver's key or removing it from the policy withdraws its approval
+        assert!(revoke_api_key(&conn, "bob").unwrap());
+        let request = get_approval_request(&conn, &open_id).unwrap().unwrap();
+        assert_eq!(request.approvals(), 1);
+        assert!(!request.is_approved());
+        assert!(!request.decisions[1].counted);
+        set_approval_policy(&conn, account.id, 1, MicroMinotari(1_000), &[carol]).expect("replace policy");
+        let request = get_approval_request(&conn, &open_id).unwrap().unwrap();
+        assert_eq!(request.approvals(), 0);
+        assert!(!request.decisions[0].counted);
+
+        assert!(get_approval_request(&conn, "unknown").unwrap().is_none());
+    }
+}
@@ -196,6 +196,41 @@ pub fn get_completed_transactions_by_status(
     Ok(rows.collect::<Result<Vec<_>, _>>()?)
 }
 
+/// Number of completed transactions of an account in one status.
+#[derive(Debug, Clone)]
+pub struct CompletedTransactionCounts {
+    pub status: String,
+    pub count: u64,
+    /// Sum of the broadcast attempts of these transactions
+    pub broadcast_attempts: u64,
+}
+
+/// Counts the completed transactions of an account per status.
+pub fn get_completed_transaction_counts(
+    conn: &Connection,
+    account_id: i64,
+) -> WalletDbResult<Vec<CompletedTransactionCounts>> {
+    let mut stmt = conn.prepare_cached(
+        r#"
+        SELECT status, COUNT(*), COALESCE(SUM(broadcast_attempts), 0)
+        FROM completed_transactions
+        WHERE account_id = :account_id
+        GROUP BY status
+        ORDER BY status
+        "#,
+    )?;
+
+    let rows = stmt.query_map(named_params! { ":account_id": account_id }, |row| {
+        Ok(CompletedTransactionCounts {
+            status: row.get(0)?,
+            count: row.get::<_, i64>(1)? as u64,
+            broadcast_attempts: row.get::<_, i64>(2)? as u64,
+        })
+    })?;
+
+    Ok(rows.collect::<Result<Vec<_>, _>>()?)
+}
+
 pub fn update_completed_transaction_status(
     conn: &Connection,
     tx_id: TxId,
@@ -264,6 +264,48 @@ pub fn get_displayed_transaction_by_id(conn: &Connection, id: &str) -> WalletDbR
     Ok(row.and_then(|json| serde_json::from_str(&json).ok()))
 }
 
+/// Find the transaction of an account that created or spent the given output.
+///
+/// Only transactions mined at `block_height` are searched, which is the height the
+/// output was mined at for transactions that received it.
+pub fn find_displayed_transaction_by_output_hash(
+    conn: &Connection,
+    account_id: Id,
+    block_height: u64,
+    output_hash: &FixedHash,
+) -> WalletDbResult<Option<DisplayedTransaction>> {
+    let found = get_displayed_transactions_at_height(conn, account_id, block_height)?
+        .into_iter()
+        .find(|tx| {
+            tx.details.outputs.iter().any(|output| &output.hash == output_hash)
+                || tx.details.sent_output_hashes.contains(output_hash)
+                || tx.details.inputs.iter().any(|input| &input.output_hash == output_hash)
+        });
+
+    Ok(found)
+}
+
+fn get_displayed_transactions_at_height(
+    conn: &Connection,
+    account_id: Id,
+    block_height: u64,
+) -> WalletDbResult<Vec<DisplayedTransaction>> {
+    let mut stmt = conn.prepare_cached(
+        r#"
+        SELECT transaction_json
+        FROM displayed_transactions
+        WHERE account_id = :account_id AND block_height = :height
+        "#,
+    )?;
+    #[allow(clippy::cast_possible_wrap)]
+    let rows = stmt.query(named_params! {
+        ":account_id": account_id,
+        ":height": block_height as i64
+    })?;
+
+    process_json_rows(from_rows::<TransactionJsonRow>(rows))
+}
+
 /// Find an existing pending outbound transaction that matches the given output hash.
 /// Used by BlockProcessor to detect if a scanned transaction already has a pending record.
 pub fn find_pending_outbound_by_output_hash(
@@ -32,6 +32,12 @@ pub enum WalletDbError {
     #[error("Duplicate entry: {0}")]
     DuplicateEntry(String),
 
+    #[error("Network mismatch: {0}")]
+    NetworkMismatch(String),
+
+    #[error("Network not confirmed: {0}")]
+    NetworkUnconfirmed(String),
+
     #[error("Key decryption failed: {0}")]
     DecryptionFailed(String),
 
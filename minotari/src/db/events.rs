@@ -1,6 +1,6 @@
 use chrono::NaiveDateTime;
 use log::{debug, info};
-use rusqlite::{Connection, named_params};
+use rusqlite::{Connection, OptionalExtension, named_params};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
@@ -107,3 +107,48 @@ pub fn get_events_by_account_id(
 
     Ok(rows.collect::<Result<Vec<_>, _>>()?)
 }
+
+pub fn get_wallet_event_by_id(conn: &Connection, id: i64) -> WalletDbResult<Option<DbWalletEvent>> {
+    debug!(id = id; "DB: Fetching event by id");
+
+    let mut stmt = conn.prepare_cached(
+        r#"
+        SELECT
+            id,
+            account_id,
+            event_type,
+            description,
+            data_json,
+            REPLACE(created_at, ' ', 'T') as created_at
+        FROM events
+        WHERE id = :id
+        "#,
+    )?;
+
+    let event = stmt
+        .query_row(named_params! { ":id": id }, |row| {
+            Ok(DbWalletEvent {
+                id: row.get(0)?,
+                account_id: row.get(1)?,
+                event_type: row.get(2)?,
+                description: row.get(3)?,
+                data_json: row.get(4)?,
+                created_at: row.get(5)?,
+            })
+        })
+        .optional()?;
+
+    Ok(event)
+}
+
+/// Deletes events created before `timestamp` and returns how many were deleted.
+///
+/// Webhook deliveries of the deleted events are kept without their event reference.
+pub fn delete_events_older_than(conn: &Connection, timestamp: NaiveDateTime) -> WalletDbResult<usize> {
+    let count = conn.execute(
+        "DELETE FROM events WHERE created_at < :timestamp",
+        named_params! { ":timestamp": timestamp.to_string() },
+    )?;
+    info!(target: "audit", count = count; "DB: Deleted old wallet events");
+    Ok(count)
+}
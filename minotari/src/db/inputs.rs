@@ -4,7 +4,7 @@ use crate::db::balance_changes::{
 use crate::db::error::{WalletDbError, WalletDbResult};
 use crate::models::BalanceChange;
 use chrono::{DateTime, NaiveDateTime, Utc};
-use log::{debug, warn};
+use log::{debug, info, warn};
 use rusqlite::{Connection, named_params};
 use serde::Deserialize;
 use serde_rusqlite::from_rows;
@@ -176,3 +176,38 @@ pub fn soft_delete_inputs_from_height(conn: &Connection, account_id: i64, height
 
     Ok(())
 }
+
+/// Permanently deletes inputs of `account_id` that were soft deleted by a reorg below `height`.
+///
+/// Balance changes keep their amounts and descriptions, but no longer point at the deleted
+/// inputs. Returns the number of deleted inputs.
+pub fn purge_deleted_inputs_below_height(conn: &Connection, account_id: i64, height: u64) -> WalletDbResult<usize> {
+    #[allow(clippy::cast_possible_wrap)]
+    let height = height as i64;
+    let params = named_params! {
+        ":account_id": account_id,
+        ":height": height,
+    };
+
+    conn.execute(
+        r#"
+        UPDATE balance_changes
+        SET caused_by_input_id = NULL
+        WHERE caused_by_input_id IN (
+            SELECT id FROM inputs
+            WHERE account_id = :account_id AND deleted_at IS NOT NULL AND deleted_in_block_height < :height
+        )
+        "#,
+        params,
+    )?;
+    let purged = conn.execute(
+        r#"
+        DELETE FROM inputs
+        WHERE account_id = :account_id AND deleted_at IS NOT NULL AND deleted_in_block_height < :height
+        "#,
+        params,
+    )?;
+
+    info!(target: "audit", account_id = account_id, height = height, inputs = purged; "DB: Purged soft deleted inputs");
+    Ok(purged)
+}
@@ -0,0 +1,62 @@
+//! Consistency checks and compaction of the database file.
+//!
+//! Pruning of old rows lives next to the queries of each table; [`crate::maintenance`] runs
+//! them together with the checks here.
+
+use log::{debug, info};
+use rusqlite::Connection;
+
+use crate::db::error::WalletDbResult;
+
+/// Runs SQLite's integrity check and returns the problems it found, if any.
+pub fn integrity_check(conn: &Connection) -> WalletDbResult<Vec<String>> {
+    debug!("DB: Running integrity check");
+
+    let mut stmt = conn.prepare("PRAGMA integrity_check")?;
+    let messages = stmt
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(messages.into_iter().filter(|message| message != "ok").collect())
+}
+
+/// Returns the rows whose foreign keys refer to missing rows, as `table row id -> parent`.
+pub fn foreign_key_check(conn: &Connection) -> WalletDbResult<Vec<String>> {
+    debug!("DB: Running foreign key check");
+
+    let mut stmt = conn.prepare("PRAGMA foreign_key_check")?;
+    let violations = stmt
+        .query_map([], |row| {
+            let table: String = row.get(0)?;
+            let row_id: Option<i64> = row.get(1)?;
+            let parent: String = row.get(2)?;
+            Ok(format!(
+                "{} row {} -> {}",
+                table,
+                row_id.map_or_else(|| "?".to_string(), |id| id.to_string()),
+                parent
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(violations)
+}
+
+/// Returns the size of the database file in bytes, including free pages.
+pub fn database_size(conn: &Connection) -> WalletDbResult<u64> {
+    let (page_count, page_size): (u64, u64) = conn.query_row(
+        "SELECT page_count, page_size FROM pragma_page_count, pragma_page_size",
+        [],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
+    Ok(page_count * page_size)
+}
+
+/// Rebuilds the database file without free pages and truncates the WAL file.
+///
+/// Waits for, and fails after the busy timeout on, transactions of other connections.
+pub fn vacuum(conn: &Connection) -> WalletDbResult<()> {
+    info!(target: "audit", "DB: Vacuuming database");
+
+    conn.execute_batch("VACUUM;")?;
+    conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))?;
+    Ok(())
+}
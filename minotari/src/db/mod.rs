@@ -17,21 +17,36 @@
 //! - **pending_transactions** - Transactions being constructed
 //! - **completed_transactions** - Broadcasted transactions and their status
 //! - **displayed_transactions** - User-friendly transaction view
+//! - **api_keys** - Hashed REST API keys with their scopes and account restrictions
+//! - **approval_policies** - Per-account M-of-N approval policies for outgoing payments
+//! - **approval_requests** / **transaction_approvals** - Payments awaiting approval and the approvers' decisions
+//! - **webhook_subscriptions** - Webhook endpoints with their secrets, event filters and account scopes
+//! - **alert_states** - Fired alerts and pending observations of the alert rules
+//! - **wallet_metadata** - Settings of the database as a whole, e.g. its network
 //!
 //! # Migrations
 //!
 //! Database migrations are managed by rusqlite_migration and automatically applied on initialization.
 //! Migration files are located in the `migrations/` directory.
 //!
+//! # Networks
+//!
+//! The database and each account record the network they belong to. [`init_db`] refuses to
+//! open a database for another network, so that a mainnet wallet is never used with testnet
+//! addresses or scanned against the wrong chain (see [`check_network`]). A database from before
+//! networks were recorded whose network could not be inferred has to be opened once with
+//! [`init_db_confirming_network`].
+//!
 //! # Usage Example
 //!
 //! ```ignore
 //! use minotari::db::{init_db, get_accounts, get_balance};
 //! use std::path::PathBuf;
+//! use tari_common::configuration::Network;
 //!
 //! # fn example() -> Result<(), anyhow::Error> {
 //! // Initialize database and run migrations
-//! let pool = init_db(PathBuf::from("wallet.db"))?;
+//! let pool = init_db(PathBuf::from("wallet.db"), Network::MainNet)?;
 //! let conn = pool.get()?;
 //!
 //! // Query accounts
@@ -53,22 +68,27 @@ use log::{debug, error, info};
 use r2d2::Pool;
 use r2d2_sqlite::SqliteConnectionManager;
 use rusqlite::Connection;
-use rusqlite_migration::Migrations;
+use rusqlite_migration::{Migrations, MigrationsBuilder};
+use tari_common::configuration::Network;
 
 mod error;
 pub use error::{WalletDbError, WalletDbResult};
 
 mod accounts;
 pub use accounts::{
-    AccountBalance, AccountRow, create_account, delete_account, get_account_by_name, get_accounts, get_balance,
+    AccountBalance, AccountRow, change_accounts_password, create_account, delete_account, find_accounts_decrypting,
+    get_account_by_id, get_account_by_name, get_accounts, get_balance, set_account_external_signer,
     update_account_name,
 };
 
+mod account_export;
+pub use account_export::{export_account_data, import_account_data};
+
 mod scanned_tip_blocks;
 pub use scanned_tip_blocks::{
-    LatestScannedBlock, delete_scanned_tip_blocks_from_height, get_latest_scanned_block_with_timestamp,
-    get_latest_scanned_tip_block_by_account, get_scanned_tip_blocks_by_account, insert_scanned_tip_block,
-    prune_scanned_tip_blocks,
+    LatestScannedBlock, RECENT_BLOCKS_TO_KEEP, delete_scanned_tip_blocks_from_height,
+    get_latest_scanned_block_with_timestamp, get_latest_scanned_tip_block_by_account,
+    get_scanned_tip_blocks_by_account, insert_scanned_tip_block, prune_scanned_tip_blocks,
 };
 
 mod outputs;
@@ -76,33 +96,66 @@ pub use outputs::{
     DbOutput, DbWalletOutput, fetch_outputs_by_lock_request_id, fetch_unspent_outputs, get_active_outputs_from_height,
     get_output_by_id, get_output_info_by_hash, get_output_info_by_hash_for_account, get_output_totals_for_account,
     get_total_unspent_balance, get_unconfirmed_outputs, insert_output, lock_output, mark_output_confirmed,
-    soft_delete_outputs_from_height, unlock_outputs_for_request,
+    purge_deleted_outputs_below_height, soft_delete_outputs_from_height, unlock_outputs_for_request,
     unlock_outputs_for_request as unlock_outputs_for_pending_transaction, update_output_status,
 };
 
 mod pending_transactions;
 pub use pending_transactions::{
     PendingTransaction, cancel_pending_transactions_by_ids, check_if_transaction_is_expired_by_idempotency_key,
-    check_if_transaction_was_already_completed_by_idempotency_key, create_pending_transaction,
-    find_expired_pending_transactions, find_pending_transaction_by_idempotency_key,
+    check_if_transaction_was_already_completed_by_idempotency_key, count_pending_transactions,
+    create_pending_transaction, find_expired_pending_transactions, find_pending_transaction_by_idempotency_key,
     find_pending_transaction_locked_funds_by_idempotency_key, update_pending_transaction_status,
 };
 
 mod completed_transactions;
 pub use completed_transactions::{
-    CompletedTransaction, CompletedTransactionStatus, create_completed_transaction, get_completed_transaction_by_id,
-    get_completed_transaction_by_payref, get_completed_transactions_by_account, get_completed_transactions_by_status,
-    get_pending_completed_transactions, mark_completed_transaction_as_broadcasted,
-    mark_completed_transaction_as_confirmed, mark_completed_transaction_as_mined_unconfirmed,
-    mark_completed_transaction_as_rejected, reset_mined_completed_transactions_from_height,
-    revert_completed_transaction_to_completed, update_completed_transaction_status,
+    CompletedTransaction, CompletedTransactionCounts, CompletedTransactionStatus, create_completed_transaction,
+    get_completed_transaction_by_id, get_completed_transaction_by_payref, get_completed_transaction_counts,
+    get_completed_transactions_by_account, get_completed_transactions_by_status, get_pending_completed_transactions,
+    mark_completed_transaction_as_broadcasted, mark_completed_transaction_as_confirmed,
+    mark_completed_transaction_as_mined_unconfirmed, mark_completed_transaction_as_rejected,
+    reset_mined_completed_transactions_from_height, revert_completed_transaction_to_completed,
+    update_completed_transaction_status,
 };
 
 mod events;
-pub use events::{DbWalletEvent, get_events_by_account_id, insert_wallet_event};
+pub use events::{
+    DbWalletEvent, delete_events_older_than, get_events_by_account_id, get_wallet_event_by_id, insert_wallet_event,
+};
 
 mod webhooks;
-pub use webhooks::{delete_webhooks_older_than, enqueue_webhook, fetch_due_webhooks, update_webhook_status};
+pub use webhooks::{
+    WebhookDeliveryFilter, count_undelivered_webhooks, count_webhooks_by_status, delete_webhooks_older_than,
+    enqueue_webhook, fetch_due_webhooks, get_webhook_deliveries, get_webhook_delivery, requeue_failed_webhooks,
+    requeue_webhook, update_webhook_status,
+};
+
+mod webhook_subscriptions;
+pub use webhook_subscriptions::{
+    DbWebhookSubscription, NewWebhookSubscription, WebhookSubscriptionUpdate, get_matching_webhook_subscriptions,
+    get_webhook_subscription_by_name, get_webhook_subscriptions, insert_webhook_subscription,
+    rotate_webhook_subscription_secret, update_webhook_subscription,
+};
+
+mod wallet_metadata;
+pub use wallet_metadata::{
+    NETWORK_KEY, check_network, confirm_network, delete_metadata, get_metadata, get_wallet_network, set_metadata,
+};
+
+mod maintenance;
+pub use maintenance::{database_size, foreign_key_check, integrity_check, vacuum};
+
+mod verification;
+pub use verification::{
+    SpentOutputMismatch, StaleOutputLock, find_reversed_balance_changes_without_reversal, find_spent_output_mismatches,
+    find_stale_output_locks, get_displayed_net_amount, get_unspent_output_value,
+};
+
+mod alert_states;
+pub use alert_states::{
+    DbAlertState, delete_alert_state, get_alert_states, insert_alert_state, mark_alert_state_triggered,
+};
 
 mod balance_changes;
 pub use balance_changes::{
@@ -110,7 +163,9 @@ pub use balance_changes::{
 };
 
 mod inputs;
-pub use inputs::{DbInput, get_input_by_id, insert_input, soft_delete_inputs_from_height};
+pub use inputs::{
+    DbInput, get_input_by_id, insert_input, purge_deleted_inputs_below_height, soft_delete_inputs_from_height,
+};
 
 mod burn_proofs;
 pub use burn_proofs::{
@@ -118,6 +173,19 @@ pub use burn_proofs::{
     mark_burn_proof_complete,
 };
 
+mod api_keys;
+pub use api_keys::{
+    DbApiKey, NewApiKey, get_active_api_key_by_hash, get_api_keys, has_api_keys, insert_api_key, revoke_api_key,
+    touch_api_key,
+};
+
+mod approvals;
+pub use approvals::{
+    DbApprovalDecision, DbApprovalPolicy, DbApprovalRequest, DbApprover, NewApprovalRequest, create_approval_request,
+    delete_approval_policy, get_approval_policy, get_approval_request, get_open_approval_requests,
+    record_approval_decision, set_approval_policy,
+};
+
 mod payref_history;
 pub use payref_history::{
     get_transaction_id_by_historical_payref, save_completed_transaction_payrefs_before_reorg,
@@ -126,8 +194,8 @@ pub use payref_history::{
 
 mod displayed_transactions;
 pub use displayed_transactions::{
-    find_pending_outbound_by_output_hash, get_displayed_transaction_by_id, get_displayed_transactions_by_account,
-    get_displayed_transactions_by_payref, get_displayed_transactions_by_status,
+    find_displayed_transaction_by_output_hash, find_pending_outbound_by_output_hash, get_displayed_transaction_by_id,
+    get_displayed_transactions_by_account, get_displayed_transactions_by_payref, get_displayed_transactions_by_status,
     get_displayed_transactions_excluding_reorged, get_displayed_transactions_from_height,
     get_displayed_transactions_needing_confirmation_update, get_displayed_transactions_paginated,
     insert_displayed_transaction, mark_displayed_transaction_rejected, mark_displayed_transactions_reorganized,
@@ -142,9 +210,29 @@ pub type SqlitePool = Pool<SqliteConnectionManager>;
 static MIGRATIONS_DIR: Dir = include_dir!("$CARGO_MANIFEST_DIR/migrations");
 
 static MIGRATIONS: LazyLock<Migrations<'static>> = LazyLock::new(|| {
-    Migrations::from_directory(&MIGRATIONS_DIR).expect("Failed to load migrations from embedded directory")
+    MigrationsBuilder::from_directory(&MIGRATIONS_DIR)
+        .expect("Failed to load migrations from embedded directory")
+        // 00034-add_network_to_accounts
+        .edit(34, |m| m.set_up_hook(wallet_metadata::infer_networks))
+        .finalize()
 });
 
+/// Returns the schema version of the database, i.e. the number of applied migrations.
+pub fn schema_version(conn: &Connection) -> WalletDbResult<i64> {
+    Ok(conn.query_row("PRAGMA user_version", [], |row| row.get(0))?)
+}
+
+/// Returns the schema version a database has once every embedded migration is applied.
+pub fn latest_schema_version() -> i64 {
+    i64::try_from(MIGRATIONS_DIR.dirs().count()).unwrap_or(i64::MAX)
+}
+
+/// Applies the pending migrations to a database opened outside of [`init_db`].
+pub fn migrate(conn: &mut Connection) -> WalletDbResult<()> {
+    MIGRATIONS.to_latest(conn)?;
+    Ok(())
+}
+
 /// Initializes the SQLite database and runs migrations.
 ///
 /// This function:
@@ -153,10 +241,12 @@ static MIGRATIONS: LazyLock<Migrations<'static>> = LazyLock::new(|| {
 /// 3. Creates the database file if it doesn't exist
 /// 4. Creates a connection pool with up to 5 connections
 /// 5. Runs all pending migrations from `migrations/` directory
+/// 6. Checks that the database and its accounts belong to `network`
 ///
 /// # Parameters
 ///
 /// * `db_path` - Path to the SQLite database file (relative or absolute)
+/// * `network` - Network the wallet is configured for
 ///
 /// # Returns
 ///
@@ -166,24 +256,40 @@ static MIGRATIONS: LazyLock<Migrations<'static>> = LazyLock::new(|| {
 /// - Database file creation fails
 /// - Connection fails
 /// - Migrations fail
+/// - The database or one of its accounts belongs to another network
+/// - The database's network could not be inferred and was never confirmed
 ///
 /// # Example
 ///
 /// ```ignore
 /// use minotari::db::init_db;
 /// use std::path::PathBuf;
+/// use tari_common::configuration::Network;
 ///
 /// # fn example() -> Result<(), anyhow::Error> {
 /// // Initialize database in current directory
-/// let pool = init_db(PathBuf::from("wallet.db"))?;
+/// let pool = init_db(PathBuf::from("wallet.db"), Network::MainNet)?;
 ///
 /// // Or use absolute path
-/// let pool = init_db(PathBuf::from("/path/to/wallet.db"))?;
+/// let pool = init_db(PathBuf::from("/path/to/wallet.db"), Network::MainNet)?;
 /// # Ok(())
 /// # }
 /// ```
-pub fn init_db(db_path: PathBuf) -> WalletDbResult<SqlitePool> {
-    debug!(path:? = db_path; "Initializing database");
+pub fn init_db(db_path: PathBuf, network: Network) -> WalletDbResult<SqlitePool> {
+    open_db(db_path, network, false)
+}
+
+/// Initializes the database like [`init_db`], after recording `network` as its network if it
+/// has none yet.
+///
+/// This is the explicit confirmation needed for a database from before networks were recorded
+/// whose network could not be inferred from its history (see [`confirm_network`]).
+pub fn init_db_confirming_network(db_path: PathBuf, network: Network) -> WalletDbResult<SqlitePool> {
+    open_db(db_path, network, true)
+}
+
+fn open_db(db_path: PathBuf, network: Network, confirm: bool) -> WalletDbResult<SqlitePool> {
+    debug!(path:? = db_path, network:% = network; "Initializing database");
 
     let mut path = db_path;
     if path.is_relative() {
@@ -195,19 +301,29 @@ pub fn init_db(db_path: PathBuf) -> WalletDbResult<SqlitePool> {
         .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "Invalid database file path"))?;
     fs::create_dir_all(parent)?;
 
-    match connect_and_migrate(&path) {
-        Ok(pool) => Ok(pool),
+    let pool = match connect_and_migrate(&path) {
+        Ok(pool) => pool,
         Err(e) => {
             error!(
                 error:% = e,
                 path:? = &path;
                 "Database migration failed. Please, remove database manually"
             );
-            Err(WalletDbError::Unexpected(
+            return Err(WalletDbError::Unexpected(
                 "Unexpected error occurred. It is possibly a migration from sqlx that has failed. Consider removing database file and try again".to_string(),
-            ))
+            ));
         },
+    };
+
+    let mut conn = pool.get()?;
+    let tx = conn.transaction()?;
+    if confirm {
+        confirm_network(&tx, network)?;
     }
+    check_network(&tx, network)?;
+    tx.commit()?;
+
+    Ok(pool)
 }
 
 fn connect_and_migrate(path: &PathBuf) -> WalletDbResult<SqlitePool> {
@@ -311,6 +311,44 @@ pub fn soft_delete_outputs_from_height(conn: &Connection, account_id: i64, heigh
     Ok(())
 }
 
+/// Permanently deletes outputs of `account_id` that were soft deleted by a reorg below `height`.
+///
+/// Outputs still spent by an input are kept. Balance changes keep their amounts and
+/// descriptions, but no longer point at the deleted outputs. Returns the number of deleted
+/// outputs.
+pub fn purge_deleted_outputs_below_height(conn: &Connection, account_id: i64, height: u64) -> WalletDbResult<usize> {
+    #[allow(clippy::cast_possible_wrap)]
+    let height = height as i64;
+    let params = named_params! {
+        ":account_id": account_id,
+        ":height": height,
+    };
+
+    conn.execute(
+        r#"
+        UPDATE balance_changes
+        SET caused_by_output_id = NULL
+        WHERE caused_by_output_id IN (
+            SELECT o.id FROM outputs o
+            WHERE o.account_id = :account_id AND o.deleted_at IS NOT NULL AND o.deleted_in_block_height < :height
+              AND NOT EXISTS (SELECT 1 FROM inputs i WHERE i.output_id = o.id)
+        )
+        "#,
+        params,
+    )?;
+    let purged = conn.execute(
+        r#"
+        DELETE FROM outputs
+        WHERE account_id = :account_id AND deleted_at IS NOT NULL AND deleted_in_block_height < :height
+          AND NOT EXISTS (SELECT 1 FROM inputs i WHERE i.output_id = outputs.id)
+        "#,
+        params,
+    )?;
+
+    info!(target: "audit", account_id = account_id, height = height, outputs = purged; "DB: Purged soft deleted outputs");
+    Ok(purged)
+}
+
 pub fn update_output_status(conn: &Connection, output_id: i64, status: OutputStatus) -> WalletDbResult<()> {
     debug!(
         output_id = output_id,
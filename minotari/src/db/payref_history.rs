@@ -209,6 +209,7 @@ mod tests {
         DisplayedTransactionBuilder, TransactionDirection, TransactionDisplayStatus, TransactionSource,
     };
     use chrono::NaiveDateTime;
+    use tari_common::configuration::Network;
     use tari_common_types::seeds::cipher_seed::CipherSeed;
     use tari_common_types::types::FixedHash;
     use tari_transaction_components::MicroMinotari;
@@ -250,14 +251,14 @@ mod tests {
         // behaviour end-to-end against the real SQLite schema.
         let temp = tempdir().expect("temp dir");
         let db_path = temp.path().join("payref_history_displayed.db");
-        let pool = init_db(db_path).expect("init db");
+        let pool = init_db(db_path, Network::Esmeralda).expect("init db");
         let conn = pool.get().expect("get connection");
 
         let seeds = CipherSeed::random();
         let seed_wallet = SeedWordsWallet::construct_new(seeds).unwrap();
         let wallet_type = WalletType::SeedWords(seed_wallet);
         let password = "correct horse battery staple";
-        create_account(&conn, "default", &wallet_type, password).expect("create account");
+        create_account(&conn, "default", &wallet_type, password, Network::Esmeralda).expect("create account");
         let account = get_account_by_name(&conn, "default")
             .expect("query account")
             .expect("account exists");
@@ -297,13 +298,13 @@ mod tests {
         // pointing at different TxIds and assert the second one wins.
         let temp = tempdir().expect("temp dir");
         let db_path = temp.path().join("payref_history_duplicates.db");
-        let pool = init_db(db_path).expect("init db");
+        let pool = init_db(db_path, Network::Esmeralda).expect("init db");
         let conn = pool.get().expect("get connection");
 
         let seeds = CipherSeed::random();
         let seed_wallet = SeedWordsWallet::construct_new(seeds).unwrap();
         let wallet_type = WalletType::SeedWords(seed_wallet);
-        create_account(&conn, "default", &wallet_type, "pw").expect("create account");
+        create_account(&conn, "default", &wallet_type, "pw", Network::Esmeralda).expect("create account");
         let account = get_account_by_name(&conn, "default").unwrap().unwrap();
 
         let first = TxId::from(1u64);
@@ -366,3 +366,17 @@ pub fn cancel_pending_transactions_by_ids(
 
     Ok(())
 }
+
+/// Counts the pending transactions of an account in the given status.
+pub fn count_pending_transactions(
+    conn: &Connection,
+    account_id: i64,
+    status: PendingTransactionStatus,
+) -> WalletDbResult<u64> {
+    let count: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM pending_transactions WHERE account_id = :account_id AND status = :status",
+        named_params! { ":account_id": account_id, ":status": status.to_string() },
+        |row| row.get(0),
+    )?;
+    Ok(count as u64)
+}
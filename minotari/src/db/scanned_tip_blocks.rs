@@ -6,7 +6,9 @@ use serde_rusqlite::from_rows;
 use crate::db::error::WalletDbResult;
 use crate::models::ScannedTipBlock;
 
-const RECENT_BLOCKS_TO_KEEP: u64 = 1000;
+/// Number of most recent scanned blocks kept for every account, and so the deepest reorg the
+/// wallet can roll back precisely.
+pub const RECENT_BLOCKS_TO_KEEP: u64 = 1000;
 const OLD_BLOCKS_PRUNING_INTERVAL: u64 = 500;
 
 #[derive(Deserialize)]
@@ -157,7 +159,10 @@ pub fn delete_scanned_tip_blocks_from_height(conn: &Connection, account_id: i64,
     Ok(())
 }
 
-pub fn prune_scanned_tip_blocks(conn: &Connection, account_id: i64, current_tip_height: u64) -> WalletDbResult<()> {
+/// Deletes scanned blocks of `account_id` more than [`RECENT_BLOCKS_TO_KEEP`] below
+/// `current_tip_height`, except every `OLD_BLOCKS_PRUNING_INTERVAL`th block. Returns the
+/// number of deleted blocks.
+pub fn prune_scanned_tip_blocks(conn: &Connection, account_id: i64, current_tip_height: u64) -> WalletDbResult<usize> {
     debug!(
         account_id = account_id,
         tip = current_tip_height;
@@ -171,7 +176,7 @@ pub fn prune_scanned_tip_blocks(conn: &Connection, account_id: i64, current_tip_
     let interval = OLD_BLOCKS_PRUNING_INTERVAL as i64;
 
     // Delete blocks older than min_height_for_recent that are not at the pruning interval
-    conn.execute(
+    let pruned = conn.execute(
         r#"
         DELETE FROM scanned_tip_blocks
         WHERE account_id = :account_id
@@ -186,5 +191,5 @@ pub fn prune_scanned_tip_blocks(conn: &Connection, account_id: i64, current_tip_
         },
     )?;
 
-    Ok(())
+    Ok(pruned)
 }
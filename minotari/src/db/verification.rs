@@ -0,0 +1,162 @@
+//! Queries that cross-check the tables a balance is derived from.
+//!
+//! The ledger in `balance_changes`, the outputs and inputs it was recorded from and the
+//! `displayed_transactions` shown to users are written separately while scanning and
+//! reorging. [`crate::verify`] runs these queries to find where they disagree.
+
+use log::debug;
+use rusqlite::{Connection, named_params};
+use serde::Deserialize;
+use serde_rusqlite::from_rows;
+
+use crate::db::error::WalletDbResult;
+use crate::models::{OutputStatus, PendingTransactionStatus};
+use crate::transactions::{TransactionDirection, TransactionDisplayStatus};
+
+/// An active output whose status disagrees with the active inputs spending it.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SpentOutputMismatch {
+    pub output_id: i64,
+    pub status: String,
+    pub active_inputs: i64,
+}
+
+/// A locked output whose pending transaction is missing or no longer live.
+#[derive(Debug, Clone, Deserialize)]
+pub struct StaleOutputLock {
+    pub output_id: i64,
+    pub locked_by_request_id: Option<String>,
+    pub pending_status: Option<String>,
+}
+
+/// Returns the total value of the active outputs of an account that no active input spends.
+///
+/// Burn outputs are included, as they are credited in `balance_changes` like any other output.
+pub fn get_unspent_output_value(conn: &Connection, account_id: i64) -> WalletDbResult<i64> {
+    debug!(account_id = account_id; "DB: Summing unspent outputs");
+
+    let mut stmt = conn.prepare_cached(
+        r#"
+        SELECT COALESCE(SUM(o.value), 0)
+        FROM outputs o
+        WHERE o.account_id = :account_id
+          AND o.deleted_at IS NULL
+          AND NOT EXISTS (
+            SELECT 1 FROM inputs i WHERE i.output_id = o.id AND i.deleted_at IS NULL
+          )
+        "#,
+    )?;
+
+    let total = stmt.query_row(named_params! { ":account_id": account_id }, |row| row.get(0))?;
+    Ok(total)
+}
+
+/// Returns incoming minus outgoing amounts of the mined displayed transactions of an account.
+pub fn get_displayed_net_amount(conn: &Connection, account_id: i64) -> WalletDbResult<i64> {
+    debug!(account_id = account_id; "DB: Summing displayed transactions");
+
+    let incoming = format!("{:?}", TransactionDirection::Incoming).to_lowercase();
+    let unconfirmed = format!("{:?}", TransactionDisplayStatus::Unconfirmed).to_lowercase();
+    let confirmed = format!("{:?}", TransactionDisplayStatus::Confirmed).to_lowercase();
+
+    let mut stmt = conn.prepare_cached(
+        r#"
+        SELECT COALESCE(SUM(CASE WHEN direction = :incoming THEN amount ELSE -amount END), 0)
+        FROM displayed_transactions
+        WHERE account_id = :account_id AND status IN (:unconfirmed, :confirmed)
+        "#,
+    )?;
+
+    let total = stmt.query_row(
+        named_params! {
+            ":account_id": account_id,
+            ":incoming": incoming,
+            ":unconfirmed": unconfirmed,
+            ":confirmed": confirmed,
+        },
+        |row| row.get(0),
+    )?;
+    Ok(total)
+}
+
+/// Returns active outputs marked spent without exactly one active input, and unspent or
+/// locked outputs that an active input spends.
+pub fn find_spent_output_mismatches(conn: &Connection, account_id: i64) -> WalletDbResult<Vec<SpentOutputMismatch>> {
+    debug!(account_id = account_id; "DB: Checking spent outputs against inputs");
+
+    let spent = OutputStatus::Spent.to_string();
+
+    let mut stmt = conn.prepare_cached(
+        r#"
+        SELECT o.id AS output_id, o.status, COUNT(i.id) AS active_inputs
+        FROM outputs o
+        LEFT JOIN inputs i ON i.output_id = o.id AND i.deleted_at IS NULL
+        WHERE o.account_id = :account_id AND o.deleted_at IS NULL
+        GROUP BY o.id, o.status
+        HAVING (o.status = :spent AND COUNT(i.id) != 1) OR (o.status != :spent AND COUNT(i.id) > 0)
+        ORDER BY o.id
+        "#,
+    )?;
+
+    let rows = stmt.query(named_params! {
+        ":account_id": account_id,
+        ":spent": spent,
+    })?;
+    let mismatches = from_rows::<SpentOutputMismatch>(rows).collect::<Result<Vec<_>, _>>()?;
+    Ok(mismatches)
+}
+
+/// Returns locked outputs whose pending transaction does not exist or was cancelled or expired.
+pub fn find_stale_output_locks(conn: &Connection, account_id: i64) -> WalletDbResult<Vec<StaleOutputLock>> {
+    debug!(account_id = account_id; "DB: Checking output locks");
+
+    let locked = OutputStatus::Locked.to_string();
+    let pending = PendingTransactionStatus::Pending.to_string();
+    let completed = PendingTransactionStatus::Completed.to_string();
+
+    let mut stmt = conn.prepare_cached(
+        r#"
+        SELECT o.id AS output_id, o.locked_by_request_id, p.status AS pending_status
+        FROM outputs o
+        LEFT JOIN pending_transactions p ON p.id = o.locked_by_request_id
+        WHERE o.account_id = :account_id
+          AND o.deleted_at IS NULL
+          AND o.status = :locked
+          AND (p.id IS NULL OR p.status NOT IN (:pending, :completed))
+        ORDER BY o.id
+        "#,
+    )?;
+
+    let rows = stmt.query(named_params! {
+        ":account_id": account_id,
+        ":locked": locked,
+        ":pending": pending,
+        ":completed": completed,
+    })?;
+    let locks = from_rows::<StaleOutputLock>(rows).collect::<Result<Vec<_>, _>>()?;
+    Ok(locks)
+}
+
+/// Returns the ids of balance changes marked reversed that no reversal refers to.
+pub fn find_reversed_balance_changes_without_reversal(conn: &Connection, account_id: i64) -> WalletDbResult<Vec<i64>> {
+    debug!(account_id = account_id; "DB: Checking reversed balance changes");
+
+    let mut stmt = conn.prepare_cached(
+        r#"
+        SELECT b.id
+        FROM balance_changes b
+        WHERE b.account_id = :account_id
+          AND b.is_reversed = TRUE
+          AND NOT EXISTS (
+            SELECT 1 FROM balance_changes r
+            WHERE r.reversal_of_balance_change_id = b.id AND r.is_reversal = TRUE
+          )
+        ORDER BY b.id
+        "#,
+    )?;
+
+    let ids = stmt
+        .query_map(named_params! { ":account_id": account_id }, |row| row.get(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(ids)
+}
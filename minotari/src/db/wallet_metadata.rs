@@ -0,0 +1,473 @@
+//! Wallet-wide metadata and the network the database belongs to.
+//!
+//! The network is recorded in the `wallet_metadata` table and on every account, so that a
+//! database is never opened for another network than the one its addresses and scanned
+//! blocks belong to. [`check_network`] runs whenever [`init_db`](super::init_db) opens a
+//! database.
+//!
+//! Databases created before networks were recorded have theirs inferred by the migration
+//! adding them (see [`infer_networks`]). If there is nothing to infer it from, the network has
+//! to be confirmed with [`confirm_network`]; it is never taken from the configuration alone.
+
+use std::str::FromStr;
+
+use log::{debug, info, warn};
+use rusqlite::{Connection, OptionalExtension, Transaction, named_params};
+use rusqlite_migration::{HookError, HookResult};
+use tari_common::configuration::Network;
+use tari_common_types::tari_address::TariAddress;
+
+use crate::{
+    db::error::{WalletDbError, WalletDbResult},
+    utils::fingerprint::is_address_of,
+};
+
+/// Metadata key of the network the database belongs to.
+pub const NETWORK_KEY: &str = "network";
+
+/// Number of balance changes of an account whose claimed addresses are looked at to infer its
+/// network.
+const INFERENCE_SAMPLE: i64 = 20;
+
+pub fn get_metadata(conn: &Connection, key: &str) -> WalletDbResult<Option<String>> {
+    let value = conn
+        .query_row(
+            "SELECT value FROM wallet_metadata WHERE key = :key",
+            named_params! { ":key": key },
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(value)
+}
+
+pub fn set_metadata(conn: &Connection, key: &str, value: &str) -> WalletDbResult<()> {
+    debug!(key = key, value = value; "DB: Setting wallet metadata");
+
+    conn.execute(
+        r#"
+        INSERT INTO wallet_metadata (key, value)
+        VALUES (:key, :value)
+        ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = CURRENT_TIMESTAMP
+        "#,
+        named_params! {
+            ":key": key,
+            ":value": value,
+        },
+    )?;
+    Ok(())
+}
+
+pub fn delete_metadata(conn: &Connection, key: &str) -> WalletDbResult<()> {
+    debug!(key = key; "DB: Deleting wallet metadata");
+
+    conn.execute(
+        "DELETE FROM wallet_metadata WHERE key = :key",
+        named_params! { ":key": key },
+    )?;
+    Ok(())
+}
+
+/// Returns the network recorded for the database, if any.
+pub fn get_wallet_network(conn: &Connection) -> WalletDbResult<Option<Network>> {
+    get_metadata(conn, NETWORK_KEY)?
+        .map(|value| parse_network(&value))
+        .transpose()
+}
+
+fn parse_network(value: &str) -> WalletDbResult<Network> {
+    Network::from_str(value).map_err(|e| WalletDbError::Decoding(format!("Invalid network '{}': {}", value, e)))
+}
+
+/// Checks that the database and all of its accounts belong to `network`.
+///
+/// A new, empty database records `network`. Accounts without a network, i.e. accounts created
+/// before networks were recorded that had no history to infer it from, take the network of
+/// the database. Callers should run this inside a transaction.
+///
+/// # Errors
+///
+/// Fails with [`WalletDbError::NetworkMismatch`] if the database or an account belongs to
+/// another network, and with [`WalletDbError::NetworkUnconfirmed`] if the database has
+/// accounts but its network could not be inferred and was not confirmed.
+pub fn check_network(conn: &Connection, network: Network) -> WalletDbResult<()> {
+    let wallet_network = match get_wallet_network(conn)? {
+        Some(wallet_network) => wallet_network,
+        None => {
+            let wallet_network = match account_networks(conn)?.as_slice() {
+                [] if has_accounts(conn)? => {
+                    return Err(WalletDbError::NetworkUnconfirmed(format!(
+                        "The network of the database could not be inferred, as its accounts have no history. \
+                         If it belongs to {network}, record it with `minotari --network {network} confirm-network \
+                         --confirm-network {network}`"
+                    )));
+                },
+                [] => network,
+                [account_network] => parse_network(account_network)?,
+                account_networks => {
+                    return Err(WalletDbError::NetworkMismatch(format!(
+                        "The database holds accounts of several networks: {}",
+                        account_networks.join(", ")
+                    )));
+                },
+            };
+            info!(target: "audit", network:% = wallet_network; "DB: Recording the network of the database");
+            set_metadata(conn, NETWORK_KEY, wallet_network.as_key_str())?;
+            wallet_network
+        },
+    };
+    if wallet_network != network {
+        return Err(WalletDbError::NetworkMismatch(format!(
+            "The database belongs to {}, but the wallet is configured for {}",
+            wallet_network, network
+        )));
+    }
+
+    let adopted = conn.execute(
+        "UPDATE accounts SET network = :network WHERE network IS NULL",
+        named_params! { ":network": wallet_network.as_key_str() },
+    )?;
+    if adopted > 0 {
+        info!(
+            target: "audit",
+            accounts = adopted,
+            network:% = wallet_network;
+            "DB: Recorded the network of the database for accounts without history to infer it from"
+        );
+    }
+
+    let mut stmt = conn.prepare("SELECT friendly_name, network FROM accounts WHERE network != :network")?;
+    let mismatched = stmt
+        .query_map(named_params! { ":network": network.as_key_str() }, |row| {
+            Ok(format!("'{}' ({})", row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    if !mismatched.is_empty() {
+        return Err(WalletDbError::NetworkMismatch(format!(
+            "Accounts {} do not belong to {}",
+            mismatched.join(", "),
+            network
+        )));
+    }
+    Ok(())
+}
+
+/// Records `network` as the network of a database whose network could not be inferred.
+///
+/// This is the explicit confirmation [`check_network`] asks for; it does nothing if the
+/// database already records `network`. Callers should run this inside a transaction.
+///
+/// # Errors
+///
+/// Fails with [`WalletDbError::NetworkMismatch`] if the database or one of its accounts
+/// already records another network.
+pub fn confirm_network(conn: &Connection, network: Network) -> WalletDbResult<()> {
+    match get_wallet_network(conn)? {
+        Some(wallet_network) if wallet_network == network => return Ok(()),
+        Some(wallet_network) => {
+            return Err(WalletDbError::NetworkMismatch(format!(
+                "The database already belongs to {}",
+                wallet_network
+            )));
+        },
+        None => {},
+    }
+    let account_networks = account_networks(conn)?;
+    if account_networks
+        .iter()
+        .any(|account_network| account_network != network.as_key_str())
+    {
+        return Err(WalletDbError::NetworkMismatch(format!(
+            "The database holds accounts of {}",
+            account_networks.join(", ")
+        )));
+    }
+
+    warn!(target: "audit", network:% = network; "DB: Recording the confirmed network of the database");
+    set_metadata(conn, NETWORK_KEY, network.as_key_str())
+}
+
+/// Migration hook inferring the networks of a database created before they were recorded.
+///
+/// Each account's network is inferred from its own addresses in its balance history, and the
+/// database takes the network of its accounts if they agree. Accounts without such addresses or
+/// whose addresses disagree, and databases without accounts to infer it from, are left without
+/// a network. A database with an account whose addresses disagree is left without one too, so
+/// that its network must be confirmed rather than adopted by that account.
+pub(crate) fn infer_networks(tx: &Transaction) -> HookResult {
+    let disagreeing = infer_account_networks(tx).map_err(|e| HookError::Hook(e.to_string()))?;
+    if disagreeing {
+        return Ok(());
+    }
+    if let [network] = account_networks(tx)
+        .map_err(|e| HookError::Hook(e.to_string()))?
+        .as_slice()
+    {
+        info!(target: "audit", network = network.as_str(); "DB: Inferred the network of the database");
+        set_metadata(tx, NETWORK_KEY, network).map_err(|e| HookError::Hook(e.to_string()))?;
+    }
+    Ok(())
+}
+
+/// Returns the distinct networks recorded on accounts.
+fn account_networks(conn: &Connection) -> WalletDbResult<Vec<String>> {
+    let mut stmt = conn.prepare("SELECT DISTINCT network FROM accounts WHERE network IS NOT NULL ORDER BY network")?;
+    let networks = stmt
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(networks)
+}
+
+fn has_accounts(conn: &Connection) -> WalletDbResult<bool> {
+    Ok(conn.query_row("SELECT EXISTS (SELECT 1 FROM accounts)", [], |row| row.get(0))?)
+}
+
+/// Records the network of accounts without one, judging by the account's own addresses in their
+/// history.
+///
+/// Claimed addresses can be a counterparty's, so only addresses carrying the account's keys, as
+/// recorded in its fingerprint, are considered. If they belong to several networks, the account
+/// is left without one. Returns whether any account was left without one for that reason.
+fn infer_account_networks(conn: &Connection) -> WalletDbResult<bool> {
+    let mut stmt = conn.prepare("SELECT id, friendly_name, fingerprint FROM accounts WHERE network IS NULL")?;
+    let accounts = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, Vec<u8>>(2)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut disagreeing = false;
+    for (id, name, fingerprint) in accounts {
+        let mut stmt = conn.prepare_cached(
+            r#"
+            SELECT claimed_recipient_address, claimed_sender_address
+            FROM balance_changes
+            WHERE account_id = :account_id
+              AND (claimed_recipient_address IS NOT NULL OR claimed_sender_address IS NOT NULL)
+            LIMIT :limit
+            "#,
+        )?;
+        let addresses = stmt
+            .query_map(named_params! { ":account_id": id, ":limit": INFERENCE_SAMPLE }, |row| {
+                Ok([row.get::<_, Option<String>>(0)?, row.get::<_, Option<String>>(1)?])
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut networks = Vec::new();
+        let own_addresses = addresses
+            .iter()
+            .flatten()
+            .flatten()
+            .filter_map(|address| TariAddress::from_base58(address).ok())
+            .filter(|address| is_address_of(address, &fingerprint));
+        for address in own_addresses {
+            if !networks.contains(&address.network()) {
+                networks.push(address.network());
+            }
+        }
+        let network = match networks.as_slice() {
+            [] => continue,
+            [network] => *network,
+            _ => {
+                warn!(
+                    target: "audit",
+                    account = &*name,
+                    networks:? = networks;
+                    "DB: Addresses of the account belong to several networks, its network must be confirmed"
+                );
+                disagreeing = true;
+                continue;
+            },
+        };
+
+        info!(target: "audit", account = &*name, network:% = network; "DB: Inferred the network of account");
+        conn.execute(
+            "UPDATE accounts SET network = :network WHERE id = :id",
+            named_params! {
+                ":network": network.as_key_str(),
+                ":id": id,
+            },
+        )?;
+    }
+    Ok(disagreeing)
+}
+
+#[cfg(test)]
+mod tests {
+    use tari_common_types::seeds::cipher_seed::CipherSeed;
+    use tari_transaction_components::key_manager::wallet_types::{SeedWordsWallet, WalletType};
+
+    use super::*;
+    use crate::db::{create_account, get_account_by_name, init_db, init_db_confirming_network};
+
+    #[test]
+    fn test_check_network() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("wallet.db");
+        let pool = init_db(path.clone(), Network::Esmeralda).unwrap();
+        let conn = pool.get().unwrap();
+        assert_eq!(get_wallet_network(&conn).unwrap(), Some(Network::Esmeralda));
+
+        let wallet = WalletType::SeedWords(SeedWordsWallet::construct_new(CipherSeed::random()).unwrap());
+        create_account(&conn, "default", &wallet, "password", Network::Esmeralda).unwrap();
+        let account = get_account_by_name(&conn, "default").unwrap().unwrap();
+        assert_eq!(account.network, Network::Esmeralda.as_key_str());
+        assert!(account.check_network(Network::MainNet).is_err());
+
+        assert!(matches!(
+            init_db(path.clone(), Network::MainNet),
+            Err(WalletDbError::NetworkMismatch(_))
+        ));
+        assert!(matches!(
+            check_network(&conn, Network::MainNet),
+            Err(WalletDbError::NetworkMismatch(_))
+        ));
+
+        // An account of another network is refused even if the database itself matches
+        conn.execute(
+            "UPDATE accounts SET network = :network",
+            named_params! { ":network": Network::MainNet.as_key_str() },
+        )
+        .unwrap();
+        assert!(matches!(
+            check_network(&conn, Network::Esmeralda),
+            Err(WalletDbError::NetworkMismatch(_))
+        ));
+
+        // Accounts created before networks were recorded adopt the database's network
+        conn.execute("UPDATE accounts SET network = NULL", []).unwrap();
+        check_network(&conn, Network::Esmeralda).unwrap();
+        let account = get_account_by_name(&conn, "default").unwrap().unwrap();
+        assert_eq!(account.network, Network::Esmeralda.as_key_str());
+    }
+
+    /// Turns the database into one from before networks were recorded.
+    fn forget_networks(conn: &Connection) {
+        conn.execute_batch("UPDATE accounts SET network = NULL; DELETE FROM wallet_metadata;")
+            .unwrap();
+    }
+
+    #[test]
+    fn test_migration_infers_networks_from_history() {
+        let dir = tempfile::tempdir().unwrap();
+        let pool = init_db(dir.path().join("wallet.db"), Network::Esmeralda).unwrap();
+        let mut conn = pool.get().unwrap();
+        let wallet = WalletType::SeedWords(SeedWordsWallet::construct_new(CipherSeed::random()).unwrap());
+        create_account(&conn, "default", &wallet, "password", Network::Esmeralda).unwrap();
+        let account = get_account_by_name(&conn, "default").unwrap().unwrap();
+        let address = account.get_address(Network::Esmeralda, "password").unwrap();
+        // A counterparty's address claiming another network is ignored
+        let counterparty = counterparty_address(Network::MainNet);
+        insert_claimed_addresses(&conn, account.id, &counterparty, &address);
+        forget_networks(&conn);
+
+        let tx = conn.transaction().unwrap();
+        infer_networks(&tx).unwrap();
+        tx.commit().unwrap();
+        assert_eq!(get_wallet_network(&conn).unwrap(), Some(Network::Esmeralda));
+        let account = get_account_by_name(&conn, "default").unwrap().unwrap();
+        assert_eq!(account.network, Network::Esmeralda.as_key_str());
+    }
+
+    fn counterparty_address(network: Network) -> TariAddress {
+        let wallet = WalletType::SeedWords(SeedWordsWallet::construct_new(CipherSeed::random()).unwrap());
+        let mut conn = Connection::open_in_memory().unwrap();
+        crate::db::migrate(&mut conn).unwrap();
+        create_account(&conn, "counterparty", &wallet, "password", network).unwrap();
+        let account = get_account_by_name(&conn, "counterparty").unwrap().unwrap();
+        account.get_address(network, "password").unwrap()
+    }
+
+    fn insert_claimed_addresses(conn: &Connection, account_id: i64, recipient: &TariAddress, sender: &TariAddress) {
+        conn.execute(
+            r#"
+            INSERT INTO balance_changes (
+                account_id, description, balance_debit, balance_credit, effective_date, effective_height,
+                claimed_recipient_address, claimed_sender_address
+            )
+            VALUES (:account_id, 'Received', 0, 1000, CURRENT_TIMESTAMP, 1, :recipient, :sender)
+            "#,
+            named_params! {
+                ":account_id": account_id,
+                ":recipient": recipient.to_base58(),
+                ":sender": sender.to_base58(),
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_disagreeing_addresses_leave_network_unset() {
+        let dir = tempfile::tempdir().unwrap();
+        let pool = init_db(dir.path().join("wallet.db"), Network::Esmeralda).unwrap();
+        let mut conn = pool.get().unwrap();
+        let wallet = WalletType::SeedWords(SeedWordsWallet::construct_new(CipherSeed::random()).unwrap());
+        create_account(&conn, "default", &wallet, "password", Network::Esmeralda).unwrap();
+        let account = get_account_by_name(&conn, "default").unwrap().unwrap();
+        let esmeralda = account.get_address(Network::Esmeralda, "password").unwrap();
+        let mainnet = account.get_address(Network::MainNet, "password").unwrap();
+        insert_claimed_addresses(&conn, account.id, &esmeralda, &mainnet);
+        // Another account whose addresses agree does not settle the network of the database
+        let other_wallet = WalletType::SeedWords(SeedWordsWallet::construct_new(CipherSeed::random()).unwrap());
+        create_account(&conn, "savings", &other_wallet, "password", Network::Esmeralda).unwrap();
+        let other = get_account_by_name(&conn, "savings").unwrap().unwrap();
+        let other_address = other.get_address(Network::Esmeralda, "password").unwrap();
+        insert_claimed_addresses(&conn, other.id, &other_address, &other_address);
+        forget_networks(&conn);
+
+        let tx = conn.transaction().unwrap();
+        infer_networks(&tx).unwrap();
+        tx.commit().unwrap();
+        assert_eq!(get_wallet_network(&conn).unwrap(), None);
+        let network: Option<String> = conn
+            .query_row("SELECT network FROM accounts WHERE id = ?1", [account.id], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(network, None);
+        let network: Option<String> = conn
+            .query_row("SELECT network FROM accounts WHERE id = ?1", [other.id], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(network.as_deref(), Some(Network::Esmeralda.as_key_str()));
+    }
+
+    #[test]
+    fn test_network_without_history_must_be_confirmed() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("wallet.db");
+        let pool = init_db(path.clone(), Network::Esmeralda).unwrap();
+        let mut conn = pool.get().unwrap();
+        let wallet = WalletType::SeedWords(SeedWordsWallet::construct_new(CipherSeed::random()).unwrap());
+        create_account(&conn, "default", &wallet, "password", Network::Esmeralda).unwrap();
+        forget_networks(&conn);
+
+        // Without history there is nothing to infer the network from, and the configured
+        // network is not adopted
+        let tx = conn.transaction().unwrap();
+        infer_networks(&tx).unwrap();
+        tx.commit().unwrap();
+        assert_eq!(get_wallet_network(&conn).unwrap(), None);
+        assert!(matches!(
+            init_db(path.clone(), Network::MainNet),
+            Err(WalletDbError::NetworkUnconfirmed(_))
+        ));
+        assert_eq!(get_wallet_network(&conn).unwrap(), None);
+
+        init_db_confirming_network(path.clone(), Network::Esmeralda).unwrap();
+        assert_eq!(get_wallet_network(&conn).unwrap(), Some(Network::Esmeralda));
+        let account = get_account_by_name(&conn, "default").unwrap().unwrap();
+        assert_eq!(account.network, Network::Esmeralda.as_key_str());
+        init_db(path.clone(), Network::Esmeralda).unwrap();
+
+        // A recorded network cannot be confirmed as another one
+        assert!(matches!(
+            init_db_confirming_network(path, Network::MainNet),
+            Err(WalletDbError::NetworkMismatch(_))
+        ));
+    }
+}
@@ -0,0 +1,394 @@
+use log::info;
+use rusqlite::{Connection, OptionalExtension, named_params};
+use serde::Deserialize;
+use serde_rusqlite::from_rows;
+
+use crate::db::error::{WalletDbError, WalletDbResult};
+
+/// Data needed to create a webhook subscription.
+pub struct NewWebhookSubscription<'a> {
+    pub name: &'a str,
+    pub url: &'a str,
+    /// Secret used to sign the payloads delivered to `url`
+    pub secret: &'a str,
+    /// Event types to deliver. `None` delivers all event types.
+    pub event_types: Option<&'a [String]>,
+    /// Accounts whose events are delivered. `None` delivers events of all accounts.
+    pub account_ids: Option<&'a [i64]>,
+}
+
+/// Changes to a webhook subscription. Fields left as `None` are not modified.
+#[derive(Default)]
+pub struct WebhookSubscriptionUpdate<'a> {
+    pub url: Option<&'a str>,
+    /// New event type filter; `Some(None)` removes the filter.
+    pub event_types: Option<Option<&'a [String]>>,
+    /// New account restriction; `Some(None)` removes the restriction.
+    pub account_ids: Option<Option<&'a [i64]>>,
+    pub enabled: Option<bool>,
+}
+
+/// A webhook subscription fetched from the database.
+#[derive(Debug, Clone)]
+pub struct DbWebhookSubscription {
+    pub id: i64,
+    pub name: String,
+    pub url: String,
+    pub secret: String,
+    /// Event types delivered to this subscription, or `None` for all event types.
+    pub event_types: Option<Vec<String>>,
+    /// Accounts whose events are delivered, or `None` for all accounts.
+    pub account_ids: Option<Vec<i64>>,
+    pub enabled: bool,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl DbWebhookSubscription {
+    /// Returns `true` if events of the given type and account are delivered to this subscription.
+    pub fn matches(&self, account_id: i64, event_type: &str) -> bool {
+        self.enabled
+            && self.allows_account(account_id)
+            && self
+                .event_types
+                .as_ref()
+                .is_none_or(|types| types.iter().any(|t| t == event_type))
+    }
+
+    /// Returns `true` if the subscription may receive events of the given account.
+    pub fn allows_account(&self, account_id: i64) -> bool {
+        self.account_ids.as_ref().is_none_or(|ids| ids.contains(&account_id))
+    }
+}
+
+#[derive(Deserialize)]
+struct WebhookSubscriptionRow {
+    id: i64,
+    name: String,
+    url: String,
+    secret: String,
+    event_types: Option<String>,
+    account_restricted: bool,
+    enabled: bool,
+    created_at: String,
+    updated_at: String,
+}
+
+const SELECT_WEBHOOK_SUBSCRIPTION: &str = r#"
+    SELECT id, name, url, secret, event_types, account_restricted, enabled, created_at, updated_at
+    FROM webhook_subscriptions
+"#;
+
+fn get_subscription_account_ids(conn: &Connection, subscription_id: i64) -> WalletDbResult<Vec<i64>> {
+    let mut stmt = conn.prepare_cached(
+        "SELECT account_id FROM webhook_subscription_accounts WHERE subscription_id = :id ORDER BY account_id",
+    )?;
+    let ids = stmt
+        .query_map(named_params! { ":id": subscription_id }, |row| row.get(0))?
+        .collect::<Result<Vec<i64>, _>>()?;
+    Ok(ids)
+}
+
+fn set_subscription_accounts(
+    conn: &Connection,
+    subscription_id: i64,
+    account_ids: Option<&[i64]>,
+) -> WalletDbResult<()> {
+    conn.execute(
+        "DELETE FROM webhook_subscription_accounts WHERE subscription_id = :id",
+        named_params! { ":id": subscription_id },
+    )?;
+    conn.execute(
+        "UPDATE webhook_subscriptions SET account_restricted = :restricted WHERE id = :id",
+        named_params! { ":id": subscription_id, ":restricted": account_ids.is_some() },
+    )?;
+    for account_id in account_ids.unwrap_or_default() {
+        conn.execute(
+            r#"
+            INSERT OR IGNORE INTO webhook_subscription_accounts (subscription_id, account_id)
+            VALUES (:subscription_id, :account_id)
+            "#,
+            named_params! { ":subscription_id": subscription_id, ":account_id": account_id },
+        )?;
+    }
+    Ok(())
+}
+
+fn encode_event_types(event_types: Option<&[String]>) -> WalletDbResult<Option<String>> {
+    Ok(event_types.map(serde_json::to_string).transpose()?)
+}
+
+fn into_subscription(conn: &Connection, row: WebhookSubscriptionRow) -> WalletDbResult<DbWebhookSubscription> {
+    let event_types = row.event_types.as_deref().map(serde_json::from_str).transpose()?;
+    let account_ids = if row.account_restricted {
+        Some(get_subscription_account_ids(conn, row.id)?)
+    } else {
+        None
+    };
+    Ok(DbWebhookSubscription {
+        id: row.id,
+        name: row.name,
+        url: row.url,
+        secret: row.secret,
+        event_types,
+        account_ids,
+        enabled: row.enabled,
+        created_at: row.created_at,
+        updated_at: row.updated_at,
+    })
+}
+
+/// Stores a new webhook subscription together with its account restrictions.
+///
+/// Fails with [`WalletDbError::DuplicateEntry`] if a subscription with the same name exists.
+pub fn insert_webhook_subscription(conn: &Connection, subscription: &NewWebhookSubscription) -> WalletDbResult<i64> {
+    info!(
+        target: "audit",
+        name = subscription.name,
+        url = subscription.url;
+        "DB: Inserting webhook subscription"
+    );
+
+    let tx = conn.unchecked_transaction()?;
+    let exists: bool = tx.query_row(
+        "SELECT EXISTS(SELECT 1 FROM webhook_subscriptions WHERE name = :name)",
+        named_params! { ":name": subscription.name },
+        |row| row.get(0),
+    )?;
+    if exists {
+        return Err(WalletDbError::DuplicateEntry(format!(
+            "A webhook subscription named '{}' already exists",
+            subscription.name
+        )));
+    }
+
+    tx.execute(
+        r#"
+        INSERT INTO webhook_subscriptions (name, url, secret, event_types)
+        VALUES (:name, :url, :secret, :event_types)
+        "#,
+        named_params! {
+            ":name": subscription.name,
+            ":url": subscription.url,
+            ":secret": subscription.secret,
+            ":event_types": encode_event_types(subscription.event_types)?,
+        },
+    )?;
+    let id = tx.last_insert_rowid();
+    set_subscription_accounts(&tx, id, subscription.account_ids)?;
+    tx.commit()?;
+
+    Ok(id)
+}
+
+/// Returns all webhook subscriptions, including disabled ones, oldest first.
+pub fn get_webhook_subscriptions(conn: &Connection) -> WalletDbResult<Vec<DbWebhookSubscription>> {
+    let mut stmt = conn.prepare_cached(&format!("{SELECT_WEBHOOK_SUBSCRIPTION} ORDER BY id ASC"))?;
+    let rows = stmt.query([])?;
+    let rows: Vec<WebhookSubscriptionRow> = from_rows(rows).collect::<Result<Vec<_>, _>>()?;
+    rows.into_iter().map(|row| into_subscription(conn, row)).collect()
+}
+
+/// Looks up a webhook subscription by name.
+pub fn get_webhook_subscription_by_name(
+    conn: &Connection,
+    name: &str,
+) -> WalletDbResult<Option<DbWebhookSubscription>> {
+    let mut stmt = conn.prepare_cached(&format!("{SELECT_WEBHOOK_SUBSCRIPTION} WHERE name = :name"))?;
+    let rows = stmt.query(named_params! { ":name": name })?;
+    let row: Option<WebhookSubscriptionRow> = from_rows(rows).next().transpose()?;
+    row.map(|row| into_subscription(conn, row)).transpose()
+}
+
+/// Returns the enabled subscriptions that should receive an event of the given type and account.
+pub fn get_matching_webhook_subscriptions(
+    conn: &Connection,
+    account_id: i64,
+    event_type: &str,
+) -> WalletDbResult<Vec<DbWebhookSubscription>> {
+    let mut stmt = conn.prepare_cached(&format!(
+        "{SELECT_WEBHOOK_SUBSCRIPTION} WHERE enabled = 1 ORDER BY id ASC"
+    ))?;
+    let rows = stmt.query([])?;
+    let rows: Vec<WebhookSubscriptionRow> = from_rows(rows).collect::<Result<Vec<_>, _>>()?;
+    let subscriptions = rows
+        .into_iter()
+        .map(|row| into_subscription(conn, row))
+        .collect::<WalletDbResult<Vec<_>>>()?;
+    Ok(subscriptions
+        .into_iter()
+        .filter(|subscription| subscription.matches(account_id, event_type))
+        .collect())
+}
+
+/// Applies `update` to the subscription with the given name. Returns `false` if there is none.
+pub fn update_webhook_subscription(
+    conn: &Connection,
+    name: &str,
+    update: &WebhookSubscriptionUpdate,
+) -> WalletDbResult<bool> {
+    info!(
+        target: "audit",
+        name = name,
+        url:? = update.url,
+        enabled:? = update.enabled;
+        "DB: Updating webhook subscription"
+    );
+
+    let tx = conn.unchecked_transaction()?;
+    let id: Option<i64> = tx
+        .query_row(
+            "SELECT id FROM webhook_subscriptions WHERE name = :name",
+            named_params! { ":name": name },
+            |row| row.get(0),
+        )
+        .optional()?;
+    let Some(id) = id else {
+        return Ok(false);
+    };
+
+    if let Some(url) = update.url {
+        tx.execute(
+            "UPDATE webhook_subscriptions SET url = :url WHERE id = :id",
+            named_params! { ":id": id, ":url": url },
+        )?;
+    }
+    if let Some(event_types) = update.event_types {
+        tx.execute(
+            "UPDATE webhook_subscriptions SET event_types = :event_types WHERE id = :id",
+            named_params! { ":id": id, ":event_types": encode_event_types(event_types)? },
+        )?;
+    }
+    if let Some(account_ids) = update.account_ids {
+        set_subscription_accounts(&tx, id, account_ids)?;
+    }
+    if let Some(enabled) = update.enabled {
+        tx.execute(
+            "UPDATE webhook_subscriptions SET enabled = :enabled WHERE id = :id",
+            named_params! { ":id": id, ":enabled": enabled },
+        )?;
+    }
+    tx.execute(
+        "UPDATE webhook_subscriptions SET updated_at = CURRENT_TIMESTAMP WHERE id = :id",
+        named_params! { ":id": id },
+    )?;
+    tx.commit()?;
+
+    Ok(true)
+}
+
+/// Replaces the signing secret of a subscription. Returns `false` if there is none.
+///
+/// Queued deliveries that have not been sent yet are signed with the new secret.
+pub fn rotate_webhook_subscription_secret(conn: &Connection, name: &str, secret: &str) -> WalletDbResult<bool> {
+    info!(target: "audit", name = name; "DB: Rotating webhook subscription secret");
+
+    let updated = conn.execute(
+        "UPDATE webhook_subscriptions SET secret = :secret, updated_at = CURRENT_TIMESTAMP WHERE name = :name",
+        named_params! { ":name": name, ":secret": secret },
+    )?;
+    Ok(updated > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::indexing_slicing)]
+    use super::*;
+    use crate::db::{create_account, get_account_by_name, init_db};
+    use tari_common::configuration::Network;
+    use tari_common_types::seeds::cipher_seed::CipherSeed;
+    use tari_transaction_components::key_manager::wallet_types::{SeedWordsWallet, WalletType};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_webhook_subscription_lifecycle() {
+        let temp_dir = tempdir().unwrap();
+        let pool = init_db(temp_dir.path().join("webhook_subscriptions.db"), Network::Esmeralda).unwrap();
+        let conn = pool.get().unwrap();
+
+        let wallet_type = WalletType::SeedWords(SeedWordsWallet::construct_new(CipherSeed::random()).unwrap());
+        create_account(&conn, "default", &wallet_type, "password", Network::Esmeralda).unwrap();
+        let account = get_account_by_name(&conn, "default").unwrap().unwrap();
+
+        let event_types = vec!["TransactionConfirmed".to_string()];
+        let account_ids = [account.id];
+        insert_webhook_subscription(
+            &conn,
+            &NewWebhookSubscription {
+                name: "accounting",
+                url: "https://accounting.example.com/hook",
+                secret: "secret-1",
+                event_types: Some(&event_types),
+                account_ids: Some(&account_ids),
+            },
+        )
+        .unwrap();
+        insert_webhook_subscription(
+            &conn,
+            &NewWebhookSubscription {
+                name: "fraud",
+                url: "https://fraud.example.com/hook",
+                secret: "secret-2",
+                event_types: None,
+                account_ids: None,
+            },
+        )
+        .unwrap();
+
+        let duplicate = insert_webhook_subscription(
+            &conn,
+            &NewWebhookSubscription {
+                name: "fraud",
+                url: "https://other.example.com",
+                secret: "secret-3",
+                event_types: None,
+                account_ids: None,
+            },
+        );
+        assert!(matches!(duplicate, Err(WalletDbError::DuplicateEntry(_))));
+
+        let names = |event_type: &str, account_id: i64| {
+            get_matching_webhook_subscriptions(&conn, account_id, event_type)
+                .unwrap()
+                .into_iter()
+                .map(|s| s.name)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names("TransactionConfirmed", account.id), vec!["accounting", "fraud"]);
+        assert_eq!(names("OutputDetected", account.id), vec!["fraud"]);
+        assert_eq!(names("TransactionConfirmed", account.id + 1), vec!["fraud"]);
+
+        let updated = update_webhook_subscription(
+            &conn,
+            "fraud",
+            &WebhookSubscriptionUpdate {
+                enabled: Some(false),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert!(updated);
+        assert_eq!(names("TransactionConfirmed", account.id), vec!["accounting"]);
+
+        update_webhook_subscription(
+            &conn,
+            "accounting",
+            &WebhookSubscriptionUpdate {
+                event_types: Some(None),
+                account_ids: Some(None),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(names("OutputDetected", account.id + 1), vec!["accounting"]);
+
+        assert!(rotate_webhook_subscription_secret(&conn, "accounting", "secret-new").unwrap());
+        let accounting = get_webhook_subscription_by_name(&conn, "accounting").unwrap().unwrap();
+        assert_eq!(accounting.secret, "secret-new");
+        assert_eq!(accounting.event_types, None);
+        assert_eq!(accounting.account_ids, None);
+
+        assert!(!rotate_webhook_subscription_secret(&conn, "missing", "x").unwrap());
+        assert!(!update_webhook_subscription(&conn, "missing", &WebhookSubscriptionUpdate::default()).unwrap());
+    }
+}
@@ -1,22 +1,25 @@
 use crate::db::WalletDbResult;
 use crate::webhooks::models::{WebhookQueueItem, WebhookStatus};
 use chrono::NaiveDateTime;
-use log::debug;
-use rusqlite::{Connection, named_params};
+use log::{debug, info};
+use rusqlite::{Connection, OptionalExtension, named_params};
 
 /// Inserts a new webhook into the queue.
 ///
-/// This is should be called within the same transaction that inserts the `WalletEvent`
+/// This is should be called within the same transaction that inserts the `WalletEvent`.
+/// `subscription_id` is `None` for the webhook configured in the config file.
 pub fn enqueue_webhook(
     conn: &Connection,
     event_id: Option<i64>,
     event_type: &str,
     payload: &str,
     target_url: &str,
+    subscription_id: Option<i64>,
 ) -> WalletDbResult<i64> {
     debug!(
         event_type = event_type,
-        target_url = target_url;
+        target_url = target_url,
+        subscription_id:? = subscription_id;
         "DB: Enqueuing webhook"
     );
 
@@ -27,6 +30,7 @@ pub fn enqueue_webhook(
             event_type,
             payload,
             target_url,
+            subscription_id,
             status,
             attempt_count,
             next_retry_at,
@@ -36,6 +40,7 @@ pub fn enqueue_webhook(
             :event_type,
             :payload,
             :target_url,
+            :subscription_id,
             :status,
             0,
             datetime('now'),
@@ -47,6 +52,7 @@ pub fn enqueue_webhook(
             ":event_type": event_type,
             ":payload": payload,
             ":target_url": target_url,
+            ":subscription_id": subscription_id,
             ":status": WebhookStatus::Pending.to_string(),
         },
     )?;
@@ -54,50 +60,64 @@ pub fn enqueue_webhook(
     Ok(conn.last_insert_rowid())
 }
 
+const SELECT_QUEUE_ITEM: &str = r#"
+    SELECT
+        q.id,
+        q.event_id,
+        q.event_type,
+        q.payload,
+        COALESCE(s.url, q.target_url) AS target_url,
+        q.status,
+        q.attempt_count,
+        REPLACE(q.next_retry_at, ' ', 'T') as next_retry_at,
+        REPLACE(q.created_at, ' ', 'T') as created_at,
+        q.last_error,
+        q.subscription_id,
+        s.secret
+    FROM webhook_queue q
+    LEFT JOIN webhook_subscriptions s ON s.id = q.subscription_id
+"#;
+
+fn map_queue_item(row: &rusqlite::Row) -> rusqlite::Result<WebhookQueueItem> {
+    let status_str: String = row.get(5)?;
+    let status = status_str.parse().unwrap_or(WebhookStatus::Failed); // Fallback safe
+
+    Ok(WebhookQueueItem {
+        id: row.get(0)?,
+        event_id: row.get(1)?,
+        event_type: row.get(2)?,
+        payload: row.get(3)?,
+        target_url: row.get(4)?,
+        status,
+        attempt_count: row.get(6)?,
+        next_retry_at: row.get(7)?,
+        created_at: row.get(8)?,
+        last_error: row.get(9)?,
+        subscription_id: row.get(10)?,
+        secret: row.get(11)?,
+    })
+}
+
 /// Fetches webhooks that are ready to be processed.
 ///
 /// This includes:
 /// 1. New items (`pending`)
 /// 2. Failed items ready for retry (`failed` AND `next_retry_at` <= now)
+///
+/// Items of disabled subscriptions are held back until the subscription is enabled again.
 pub fn fetch_due_webhooks(conn: &Connection, limit: i64) -> WalletDbResult<Vec<WebhookQueueItem>> {
-    let mut stmt = conn.prepare_cached(
+    let mut stmt = conn.prepare_cached(&format!(
         r#"
-        SELECT
-            id,
-            event_id,
-            event_type,
-            payload,
-            target_url,
-            status,
-            attempt_count,
-            REPLACE(next_retry_at, ' ', 'T') as next_retry_at,
-            REPLACE(created_at, ' ', 'T') as created_at,
-            last_error
-        FROM webhook_queue
-        WHERE status IN ('pending', 'failed')
-          AND next_retry_at <= datetime('now')
-        ORDER BY next_retry_at ASC
+        {SELECT_QUEUE_ITEM}
+        WHERE q.status IN ('pending', 'failed')
+          AND q.next_retry_at <= datetime('now')
+          AND (q.subscription_id IS NULL OR s.enabled = 1)
+        ORDER BY q.next_retry_at ASC
         LIMIT :limit
-        "#,
-    )?;
+        "#
+    ))?;
 
-    let rows = stmt.query_map(named_params! { ":limit": limit }, |row| {
-        let status_str: String = row.get(5)?;
-        let status = status_str.parse().unwrap_or(WebhookStatus::Failed); // Fallback safe
-
-        Ok(WebhookQueueItem {
-            id: row.get(0)?,
-            event_id: row.get(1)?,
-            event_type: row.get(2)?,
-            payload: row.get(3)?,
-            target_url: row.get(4)?,
-            status,
-            attempt_count: row.get(6)?,
-            next_retry_at: row.get(7)?,
-            created_at: row.get(8)?,
-            last_error: row.get(9)?,
-        })
-    })?;
+    let rows = stmt.query_map(named_params! { ":limit": limit }, map_queue_item)?;
 
     let mut items = Vec::new();
     for row in rows {
@@ -107,6 +127,117 @@ pub fn fetch_due_webhooks(conn: &Connection, limit: i64) -> WalletDbResult<Vec<W
     Ok(items)
 }
 
+/// Counts the deliveries still waiting to be sent or retried.
+pub fn count_undelivered_webhooks(conn: &Connection) -> WalletDbResult<u64> {
+    let count: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM webhook_queue WHERE status IN ('pending', 'failed')",
+        [],
+        |row| row.get(0),
+    )?;
+    Ok(count as u64)
+}
+
+/// Counts the queued deliveries per status.
+pub fn count_webhooks_by_status(conn: &Connection) -> WalletDbResult<Vec<(String, u64)>> {
+    let mut stmt = conn.prepare_cached("SELECT status, COUNT(*) FROM webhook_queue GROUP BY status ORDER BY status")?;
+    let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get::<_, i64>(1)? as u64)))?;
+    Ok(rows.collect::<Result<Vec<_>, _>>()?)
+}
+
+/// Filter for [`get_webhook_deliveries`]. Unset fields match all deliveries.
+#[derive(Debug, Clone, Default)]
+pub struct WebhookDeliveryFilter {
+    pub status: Option<WebhookStatus>,
+    pub event_type: Option<String>,
+    /// Only deliveries queued at or after this time
+    pub since: Option<NaiveDateTime>,
+    /// Only deliveries queued before this time
+    pub until: Option<NaiveDateTime>,
+}
+
+/// Lists queued and past webhook deliveries, newest first.
+pub fn get_webhook_deliveries(
+    conn: &Connection,
+    filter: &WebhookDeliveryFilter,
+    limit: i64,
+    offset: i64,
+) -> WalletDbResult<Vec<WebhookQueueItem>> {
+    debug!(
+        status:? = filter.status,
+        event_type:? = filter.event_type,
+        limit = limit,
+        offset = offset;
+        "DB: Fetching webhook deliveries"
+    );
+
+    let mut stmt = conn.prepare_cached(&format!(
+        r#"
+        {SELECT_QUEUE_ITEM}
+        WHERE (:status IS NULL OR q.status = :status)
+          AND (:event_type IS NULL OR q.event_type = :event_type)
+          AND (:since IS NULL OR q.created_at >= :since)
+          AND (:until IS NULL OR q.created_at < :until)
+        ORDER BY q.created_at DESC, q.id DESC
+        LIMIT :limit OFFSET :offset
+        "#
+    ))?;
+
+    let rows = stmt.query_map(
+        named_params! {
+            ":status": filter.status.map(|status| status.to_string()),
+            ":event_type": filter.event_type,
+            ":since": filter.since.map(|since| since.to_string()),
+            ":until": filter.until.map(|until| until.to_string()),
+            ":limit": limit,
+            ":offset": offset,
+        },
+        map_queue_item,
+    )?;
+
+    Ok(rows.collect::<Result<Vec<_>, _>>()?)
+}
+
+/// Fetches a single webhook delivery.
+pub fn get_webhook_delivery(conn: &Connection, id: i64) -> WalletDbResult<Option<WebhookQueueItem>> {
+    let mut stmt = conn.prepare_cached(&format!("{SELECT_QUEUE_ITEM} WHERE q.id = :id"))?;
+    Ok(stmt.query_row(named_params! { ":id": id }, map_queue_item).optional()?)
+}
+
+/// Queues a delivery to be sent again on the worker's next poll, resetting its attempt count.
+///
+/// Returns `false` if the delivery does not exist.
+pub fn requeue_webhook(conn: &Connection, id: i64) -> WalletDbResult<bool> {
+    info!(target: "audit", id = id; "DB: Re-queuing webhook delivery");
+
+    let updated = conn.execute(
+        r#"
+        UPDATE webhook_queue
+        SET status = :status, attempt_count = 0, next_retry_at = datetime('now')
+        WHERE id = :id
+        "#,
+        named_params! { ":id": id, ":status": WebhookStatus::Pending.to_string() },
+    )?;
+    Ok(updated > 0)
+}
+
+/// Re-queues all failed deliveries (`failed` or `permanent_failure`) queued at or after `since`.
+///
+/// Returns the number of re-queued deliveries.
+pub fn requeue_failed_webhooks(conn: &Connection, since: NaiveDateTime) -> WalletDbResult<usize> {
+    info!(target: "audit", since:% = since; "DB: Re-queuing failed webhook deliveries");
+
+    let updated = conn.execute(
+        r#"
+        UPDATE webhook_queue
+        SET status = :status, attempt_count = 0, next_retry_at = datetime('now')
+        WHERE status IN ('failed', 'permanent_failure')
+          AND created_at >= :since
+        "#,
+        named_params! { ":since": since.to_string(), ":status": WebhookStatus::Pending.to_string() },
+    )?;
+    Ok(updated)
+}
+
 /// Updates the status of a webhook after a delivery attempt.
 ///
 /// Use this to mark as `Success`, `PermanentFailure`, or schedule a retry
@@ -147,12 +278,20 @@ pub fn update_webhook_status(
     Ok(())
 }
 
-/// Delete old webhooks
-pub fn delete_webhooks_older_than(conn: &Connection, timestamp: NaiveDateTime) -> WalletDbResult<usize> {
+/// Deletes deliveries with `status` queued before `timestamp` and returns how many were deleted.
+pub fn delete_webhooks_older_than(
+    conn: &Connection,
+    status: WebhookStatus,
+    timestamp: NaiveDateTime,
+) -> WalletDbResult<usize> {
     let count = conn.execute(
-        "DELETE FROM webhook_queue WHERE created_at < :timestamp",
-        named_params! { ":timestamp": timestamp.to_string() },
+        "DELETE FROM webhook_queue WHERE status = :status AND created_at < :timestamp",
+        named_params! {
+            ":status": status.to_string(),
+            ":timestamp": timestamp.to_string(),
+        },
     )?;
+    info!(target: "audit", status:% = status, count = count; "DB: Deleted old webhook deliveries");
     Ok(count)
 }
 
@@ -164,6 +303,7 @@ mod tests {
     use crate::models::{WalletEvent, WalletEventType};
     use crate::webhooks::models::WebhookStatus;
     use chrono::{Duration, Utc};
+    use tari_common::configuration::Network;
     use tari_common_types::seeds::cipher_seed::CipherSeed;
     use tari_transaction_components::key_manager::wallet_types::{SeedWordsWallet, WalletType};
     use tempfile::tempdir;
@@ -172,7 +312,7 @@ mod tests {
     fn test_webhook_queue_lifecycle() {
         let temp_dir = tempdir().expect("Failed to create temp dir");
         let db_path = temp_dir.path().join("test_webhook_lifecycle.db");
-        let pool = init_db(db_path).expect("Failed to init DB");
+        let pool = init_db(db_path, Network::Esmeralda).expect("Failed to init DB");
         let conn = pool.get().expect("Failed to get connection");
         let password = "very_secure_password_123";
 
@@ -180,7 +320,8 @@ mod tests {
         let seed_wallet = SeedWordsWallet::construct_new(seeds).unwrap();
         let wallet_type = WalletType::SeedWords(seed_wallet);
 
-        create_account(&conn, "test_account", &wallet_type, password).expect("Failed to create account");
+        create_account(&conn, "test_account", &wallet_type, password, Network::Esmeralda)
+            .expect("Failed to create account");
         let account = get_account_by_name(&conn, "test_account")
             .unwrap()
             .expect("Account not found");
@@ -204,8 +345,15 @@ mod tests {
         // Enqueue Webhook
         let payload = r#"{"amount": 1000}"#;
         let target_url = "https://api.example.com/webhook";
-        let webhook_id = enqueue_webhook(&conn, Some(event_id), &event_type.to_key_string(), payload, target_url)
-            .expect("Failed to enqueue");
+        let webhook_id = enqueue_webhook(
+            &conn,
+            Some(event_id),
+            &event_type.to_key_string(),
+            payload,
+            target_url,
+            None,
+        )
+        .expect("Failed to enqueue");
 
         // Verification Cycle: Immediate Fetch
         let items = fetch_due_webhooks(&conn, 10).unwrap();
@@ -257,4 +405,61 @@ mod tests {
         let items = fetch_due_webhooks(&conn, 10).unwrap();
         assert!(items.is_empty(), "Successful webhooks should not be fetched");
     }
+
+    #[test]
+    fn test_list_and_requeue_failed_deliveries() {
+        let temp_dir = tempdir().expect("Failed to create temp dir");
+        let pool =
+            init_db(temp_dir.path().join("test_webhook_deliveries.db"), Network::Esmeralda).expect("Failed to init DB");
+        let conn = pool.get().expect("Failed to get connection");
+
+        let url = "https://api.example.com/webhook";
+        let delivered = enqueue_webhook(&conn, None, "OutputDetected", "{}", url, None).unwrap();
+        let dead = enqueue_webhook(&conn, None, "OutputRolledBack", "{}", url, None).unwrap();
+        let now = Utc::now().naive_utc();
+        update_webhook_status(&conn, delivered, WebhookStatus::Success, 1, now, None).unwrap();
+        update_webhook_status(&conn, dead, WebhookStatus::PermanentFailure, 5, now, Some("HTTP 500")).unwrap();
+
+        let all = get_webhook_deliveries(&conn, &WebhookDeliveryFilter::default(), 10, 0).unwrap();
+        assert_eq!(all.len(), 2);
+
+        let filter = WebhookDeliveryFilter {
+            status: Some(WebhookStatus::PermanentFailure),
+            ..Default::default()
+        };
+        let failed = get_webhook_deliveries(&conn, &filter, 10, 0).unwrap();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].id, dead);
+        assert_eq!(failed[0].attempt_count, 5);
+        assert_eq!(failed[0].last_error.as_deref(), Some("HTTP 500"));
+
+        let filter = WebhookDeliveryFilter {
+            event_type: Some("OutputDetected".to_string()),
+            since: Some(now - Duration::hours(1)),
+            ..Default::default()
+        };
+        let detected = get_webhook_deliveries(&conn, &filter, 10, 0).unwrap();
+        assert_eq!(detected.len(), 1);
+        assert_eq!(detected[0].id, delivered);
+
+        // Permanent failures are never fetched until re-queued
+        assert!(fetch_due_webhooks(&conn, 10).unwrap().is_empty());
+        let requeued = requeue_failed_webhooks(&conn, now + Duration::hours(1)).unwrap();
+        assert_eq!(requeued, 0, "Deliveries queued before `since` must not be re-queued");
+        let requeued = requeue_failed_webhooks(&conn, now - Duration::hours(1)).unwrap();
+        assert_eq!(requeued, 1);
+
+        let due = fetch_due_webhooks(&conn, 10).unwrap();
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].id, dead);
+        assert_eq!(due[0].attempt_count, 0);
+
+        assert!(requeue_webhook(&conn, delivered).unwrap());
+        assert!(!requeue_webhook(&conn, 9999).unwrap());
+        assert_eq!(
+            get_webhook_delivery(&conn, delivered).unwrap().unwrap().status,
+            WebhookStatus::Pending
+        );
+        assert!(get_webhook_delivery(&conn, 9999).unwrap().is_none());
+    }
 }
@@ -0,0 +1,222 @@
+//! In-memory keys of unlocked accounts.
+//!
+//! Accounts in one database can be encrypted with different passwords, e.g. one per tenant.
+//! The daemon unlocks the accounts its start-up password decrypts, and further accounts are
+//! unlocked through the API with their own password. While an account is unlocked its
+//! password is held in the [`Keyring`], so that transactions can be built and signed.
+//!
+//! Locking an account wipes its password from memory. The account's private view key and
+//! public spend key are cached separately when it is unlocked, so a locked account keeps being
+//! scanned with a view-only key manager but cannot spend. Accounts that were not unlocked since
+//! the daemon started have no cached view key and are not scanned.
+
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{Arc, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard},
+};
+
+use tari_common_types::types::{CompressedPublicKey, PrivateKey};
+use tari_transaction_components::key_manager::{
+    KeyManager,
+    wallet_types::{ViewWallet, WalletType},
+};
+use zeroize::Zeroizing;
+
+use crate::db::{AccountRow, WalletDbError, WalletDbResult};
+
+/// View key of an account, enough to scan but not to spend.
+#[derive(Clone)]
+struct CachedViewKey {
+    spend_public_key: CompressedPublicKey,
+    view_key: PrivateKey,
+    birthday: Option<u16>,
+}
+
+#[derive(Default)]
+struct KeyringState {
+    passwords: HashMap<i64, Zeroizing<String>>,
+    view_keys: HashMap<i64, CachedViewKey>,
+}
+
+/// Passwords of unlocked accounts and view keys of every account unlocked since start-up.
+///
+/// Cloning is cheap and every clone refers to the same keys.
+#[derive(Clone, Default)]
+pub struct Keyring {
+    state: Arc<RwLock<KeyringState>>,
+}
+
+impl fmt::Debug for Keyring {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.read();
+        f.debug_struct("Keyring")
+            .field("unlocked", &state.passwords.len())
+            .field("view_keys", &state.view_keys.len())
+            .finish()
+    }
+}
+
+impl Keyring {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, KeyringState> {
+        self.state.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, KeyringState> {
+        self.state.write().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Unlocks the account if `password` decrypts it.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`WalletDbError::DecryptionFailed`] if the password is wrong.
+    pub fn unlock(&self, account: &AccountRow, password: &str) -> WalletDbResult<()> {
+        let wallet = account.decrypt_wallet_type(password)?;
+        self.insert(account.id, password, &wallet);
+        Ok(())
+    }
+
+    /// Unlocks an account whose wallet has already been decrypted, e.g. a newly created one.
+    pub fn insert(&self, account_id: i64, password: &str, wallet: &WalletType) {
+        let view_key = CachedViewKey {
+            spend_public_key: wallet.get_public_spend_key(),
+            view_key: wallet.get_view_key().clone(),
+            birthday: wallet.get_birthday(),
+        };
+        let mut state = self.write();
+        state.passwords.insert(account_id, Zeroizing::new(password.to_string()));
+        state.view_keys.insert(account_id, view_key);
+    }
+
+    /// Unlocks every account `password` decrypts and returns the accounts left locked.
+    ///
+    /// # Errors
+    ///
+    /// Fails on errors other than a wrong password, e.g. a corrupt wallet.
+    pub fn unlock_accounts<'a>(
+        &self,
+        accounts: &'a [AccountRow],
+        password: &str,
+    ) -> WalletDbResult<Vec<&'a AccountRow>> {
+        let mut locked = Vec::new();
+        for account in accounts {
+            match self.unlock(account, password) {
+                Ok(()) => {},
+                Err(WalletDbError::DecryptionFailed(_)) => locked.push(account),
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(locked)
+    }
+
+    /// Locks the account, wiping its password. Its view key stays cached for scanning.
+    ///
+    /// Returns `false` if the account was not unlocked.
+    pub fn lock(&self, account_id: i64) -> bool {
+        self.write().passwords.remove(&account_id).is_some()
+    }
+
+    /// Forgets every key of the account, e.g. once it has been deleted.
+    pub fn remove(&self, account_id: i64) {
+        let mut state = self.write();
+        state.passwords.remove(&account_id);
+        state.view_keys.remove(&account_id);
+    }
+
+    pub fn is_unlocked(&self, account_id: i64) -> bool {
+        self.read().passwords.contains_key(&account_id)
+    }
+
+    /// Whether the account's view key is cached, so that it can be scanned.
+    pub fn can_scan(&self, account_id: i64) -> bool {
+        self.read().view_keys.contains_key(&account_id)
+    }
+
+    /// Returns the password of an unlocked account.
+    pub fn password(&self, account_id: i64) -> Option<Zeroizing<String>> {
+        self.read().passwords.get(&account_id).cloned()
+    }
+
+    /// Replaces the password of an unlocked account after it was re-encrypted.
+    pub fn update_password(&self, account_id: i64, password: &str) {
+        if let Some(current) = self.write().passwords.get_mut(&account_id) {
+            *current = Zeroizing::new(password.to_string());
+        }
+    }
+
+    /// Returns a view-only key manager for scanning the account, if its view key is cached.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the key manager cannot be created from the cached keys.
+    pub fn scan_key_manager(&self, account_id: i64) -> WalletDbResult<Option<KeyManager>> {
+        let Some(view_key) = self.read().view_keys.get(&account_id).cloned() else {
+            return Ok(None);
+        };
+        let wallet = WalletType::ViewWallet(ViewWallet::new(
+            view_key.spend_public_key,
+            view_key.view_key,
+            view_key.birthday,
+        ));
+        KeyManager::new(wallet)
+            .map(Some)
+            .map_err(|e| WalletDbError::Unexpected(format!("Failed to create key manager: {}", e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::indexing_slicing)]
+    use tari_common::configuration::Network;
+    use tari_common_types::seeds::cipher_seed::CipherSeed;
+    use tari_transaction_components::key_manager::{TransactionKeyManagerInterface, wallet_types::SeedWordsWallet};
+
+    use super::*;
+    use crate::db::{self, init_db};
+
+    #[test]
+    fn test_lock_and_unlock_accounts() {
+        let dir = tempfile::tempdir().unwrap();
+        let pool = init_db(dir.path().join("wallet.db"), Network::Esmeralda).unwrap();
+        let conn = pool.get().unwrap();
+        for (name, password) in [("tenant-a", "password a"), ("tenant-b", "password b")] {
+            let wallet = WalletType::SeedWords(SeedWordsWallet::construct_new(CipherSeed::random()).unwrap());
+            db::create_account(&conn, name, &wallet, password, Network::Esmeralda).unwrap();
+        }
+        let accounts = db::get_accounts(&conn, None).unwrap();
+        let (a, b) = (&accounts[0], &accounts[1]);
+
+        let keyring = Keyring::new();
+        let locked = keyring.unlock_accounts(&accounts, "password a").unwrap();
+        assert_eq!(locked.len(), 1);
+        assert_eq!(locked[0].id, b.id);
+        assert!(keyring.is_unlocked(a.id) && keyring.can_scan(a.id));
+        assert!(!keyring.is_unlocked(b.id) && !keyring.can_scan(b.id));
+        assert!(keyring.scan_key_manager(b.id).unwrap().is_none());
+        assert!(matches!(
+            keyring.unlock(b, "password a"),
+            Err(WalletDbError::DecryptionFailed(_))
+        ));
+
+        // A locked account keeps its view key for scanning
+        assert!(keyring.lock(a.id));
+        assert!(!keyring.lock(a.id));
+        assert!(keyring.password(a.id).is_none());
+        let key_manager = keyring.scan_key_manager(a.id).unwrap().unwrap();
+        let full_key_manager = a.get_key_manager("password a").unwrap();
+        assert_eq!(
+            key_manager.get_private_view_key(),
+            full_key_manager.get_private_view_key()
+        );
+
+        keyring.unlock(b, "password b").unwrap();
+        assert_eq!(keyring.password(b.id).unwrap().as_str(), "password b");
+        keyring.remove(b.id);
+        assert!(!keyring.is_unlocked(b.id) && !keyring.can_scan(b.id));
+    }
+}
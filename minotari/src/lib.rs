@@ -22,16 +22,22 @@
 //!
 //! The library is organized into the following modules:
 //!
+//! - [`account_export`]: Portable exports of single accounts between databases
 //! - [`api`]: OpenAPI specification and documentation for the HTTP API
+//! - [`backup`]: Encrypted backups of the wallet database
 //! - [`daemon`]: Background daemon mode for continuous blockchain scanning
 //! - [`db`]: Database layer with SQLite queries for accounts, outputs, inputs, and balance changes
 //! - [`http`]: HTTP server and REST API endpoints for wallet operations
+//! - [`keyring`]: In-memory keys of unlocked accounts
 //! - [`log`]: Log handling
+//! - [`maintenance`]: Integrity checks, pruning and vacuuming of the wallet database
+//! - [`metrics`]: Prometheus metrics served by the daemon at `/metrics`
 //! - [`models`]: Data models including wallet events, balance changes, and output statuses
 //! - [`scan`]: Core blockchain scanning logic with batch processing and reorg handling
 //! - [`tasks`]: Background task management for periodic operations
 //! - [`transactions`]: Transaction history service and display formatting
 //! - [`utils`]: Utility functions for wallet initialization and key management
+//! - [`verify`]: Reconciliation of balances against outputs, inputs and displayed transactions
 //! - [`webhooks`]: Webhook management
 //!
 //! # Getting Started
@@ -43,6 +49,7 @@
 //! ```ignore
 //! use minotari::utils::init_wallet::init_with_view_key;
 //! use std::path::Path;
+//! use tari_common::configuration::Network;
 //!
 //! # fn example() -> anyhow::Result<()> {
 //! // Import a view-only wallet
@@ -51,6 +58,7 @@
 //!     "spend_public_key_hex",
 //!     "secure_password",
 //!     Path::new("wallet.db"),
+//!     Network::MainNet,
 //!     0,         // birthday height
 //!     Some("default"),
 //! )?;
@@ -65,6 +73,7 @@
 //! ```ignore
 //! use minotari::{Scanner, ScanMode};
 //! use std::path::PathBuf;
+//! use tari_common::configuration::Network;
 //!
 //! # async fn example() -> anyhow::Result<()> {
 //! // Create and run scanner
@@ -72,7 +81,9 @@
 //!     "password",
 //!     "https://rpc.tari.com",
 //!     PathBuf::from("wallet.db"),
+//!     Network::MainNet,
 //!     100, // batch_size
+//!     3,   // required_confirmations
 //! )
 //! .mode(ScanMode::Full)
 //! .run()
@@ -88,9 +99,10 @@
 //! ```ignore
 //! use minotari::{get_balance, init_db};
 //! use std::path::PathBuf;
+//! use tari_common::configuration::Network;
 //!
 //! # fn example() -> anyhow::Result<()> {
-//! let db = init_db(PathBuf::from("wallet.db"))?;
+//! let db = init_db(PathBuf::from("wallet.db"), Network::MainNet)?;
 //! let conn = db.get()?;
 //! let balance = get_balance(&conn, 1)?;  // account_id = 1
 //! println!("Available balance: {} µT", balance.available);
@@ -118,19 +130,25 @@
 //!
 //! Database migrations are handled automatically via SQLx.
 
+pub mod account_export;
 pub mod api;
+pub mod backup;
 pub mod cli;
 pub mod commands;
 pub mod config;
 pub mod daemon;
 pub mod db;
 pub mod http;
+pub mod keyring;
 pub mod log;
+pub mod maintenance;
+pub mod metrics;
 pub mod models;
 pub mod scan;
 pub mod tasks;
 pub mod transactions;
 pub mod utils;
+pub mod verify;
 pub mod webhooks;
 
 pub use crate::api::ApiDoc;
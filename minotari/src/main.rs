@@ -31,12 +31,12 @@
 //!
 //! Scan the blockchain for transactions:
 //! ```bash
-//! tari scan --password "my_password" --database-file wallet.db
+//! tari scan --password-file ~/.minotari/password --database-file wallet.db
 //! ```
 //!
 //! Run the wallet daemon with API server:
 //! ```bash
-//! tari daemon --password "my_password" --api-port 9000
+//! MINOTARI_PASSWORD="my_password" tari daemon --api-port 9000
 //! ```
 //!
 //! # Data Storage
@@ -47,6 +47,7 @@
 
 use std::{
     fs::{self, create_dir_all},
+    io::{IsTerminal, Write},
     path::{Path, PathBuf},
 };
 
@@ -57,29 +58,29 @@ use log::info;
 use minotari::{
     ScanError,
     api::accounts::LockFundsRequest,
-    cli::{ApplyArgs, Cli, Commands, DaemonArgs},
-    commands::{burn::handle_burn_funds, validator_nodes},
+    cli::{ApiKeyCommand, ApplyArgs, ApprovalPolicyCommand, Cli, Commands, DaemonArgs, WebhookCommand},
+    commands::{
+        account_export, api_keys, approvals, backup, burn::handle_burn_funds, daemon::unlock_at_startup, maintenance,
+        password, send, validator_nodes, verify, webhooks,
+    },
     config::{defaults::WalletConfig, loader::load_configuration},
     daemon,
     db::{self, WalletDbError, get_accounts, get_balance, init_db},
     log::{init_logging, mask_string},
+    maintenance::MaintenanceOptions,
     models::WalletEvent,
-    scan::{self, reorg::rollback_from_height},
+    scan::{self, ProcessingEvent, ScanStatusEvent, progress::format_progress_line, reorg::rollback_from_height},
     transactions::{
+        external_signer::ExternalSigner,
         fund_locker::FundLocker,
         one_sided_transaction::{OneSidedTransaction, Recipient},
     },
     utils,
     webhooks::WebhookTriggerConfig,
 };
-use std::str::FromStr;
 use tari_common::{DefaultConfigLoader, configuration::Network};
 use tari_common_types::{
-    seeds::{
-        cipher_seed::CipherSeed,
-        mnemonic::{Mnemonic, MnemonicLanguage},
-        seed_words::SeedWords,
-    },
+    seeds::cipher_seed::CipherSeed,
     tari_address::{TariAddress, TariAddressFeatures},
 };
 use tari_crypto::compressed_key::CompressedKey;
@@ -89,6 +90,7 @@ use tari_transaction_components::key_manager::wallet_types::SeedWordsWallet;
 use tari_transaction_components::key_manager::wallet_types::WalletType;
 use tari_transaction_components::tari_amount::MicroMinotari;
 use tari_utilities::byte_array::ByteArray;
+use zeroize::Zeroizing;
 
 #[allow(clippy::too_many_lines)]
 #[tokio::main]
@@ -99,12 +101,23 @@ async fn main() -> Result<(), anyhow::Error> {
     let mut wallet_config = WalletConfig::load_from(&config_obj)?;
 
     match cli.command {
-        Commands::CreateAddress { password, output_file } => {
-            info!(target: "audit", "Creating new address...");
+        Commands::CreateAddress {
+            password,
+            seed_passphrase,
+            seed_language,
+            output_file,
+        } => {
+            info!(target: "audit", seed_language:? = seed_language; "Creating new address...");
 
             let seeds = CipherSeed::random();
             let birthday = seeds.birthday();
-            let seed_words = seeds.to_mnemonic(MnemonicLanguage::English, None)?.join(" ");
+            let seed_passphrase = seed_passphrase.read_passphrase(true)?;
+            let seed_words = utils::seed_words::to_seed_words(
+                &seeds,
+                seed_language,
+                seed_passphrase.as_deref().map(String::as_str),
+            )?
+            .join(" ");
             let seed_wallet = SeedWordsWallet::construct_new(seeds).map_err(|_| anyhow::anyhow!("Invalid seeds"))?;
             let wallet = WalletType::SeedWords(seed_wallet);
             let key_manager = KeyManager::new(wallet)?;
@@ -174,11 +187,13 @@ async fn main() -> Result<(), anyhow::Error> {
             security,
             db,
             birthday,
+            external_signer,
         } => {
             info!(
                 target: "audit",
                 view_key = &*mask_string(&view_private_key),
-                spend_key = &*mask_string(&spend_public_key);
+                spend_key = &*mask_string(&spend_public_key),
+                external_signer = external_signer;
                 "Importing wallet"
             );
 
@@ -187,16 +202,20 @@ async fn main() -> Result<(), anyhow::Error> {
             init_with_view_key(
                 &view_private_key,
                 &spend_public_key,
-                &security.password,
+                &security.read_new_password()?,
                 &wallet_config.database_path,
+                wallet_config.network,
                 birthday,
+                external_signer,
             )
         },
         Commands::Create {
             security,
             db,
             account,
+            seed_passphrase,
             seed_words,
+            seed_language,
         } => {
             info!(target: "audit", "Initializing Seed Words Wallet...");
 
@@ -206,9 +225,12 @@ async fn main() -> Result<(), anyhow::Error> {
             let cipher_seed = match seed_words {
                 Some(words_str) => {
                     info!("Restoring from provided seed words...");
-                    let mnemonic =
-                        SeedWords::from_str(&words_str).map_err(|e| anyhow!("Invalid seed words format: {}", e))?;
-                    CipherSeed::from_mnemonic(&mnemonic, None)?
+                    let seed_passphrase = seed_passphrase.read_passphrase(false)?;
+                    utils::seed_words::from_seed_words(
+                        &words_str,
+                        seed_language,
+                        seed_passphrase.as_deref().map(String::as_str),
+                    )?
                 },
                 None => {
                     info!("Generating new random seed...");
@@ -218,8 +240,9 @@ async fn main() -> Result<(), anyhow::Error> {
 
             utils::init_wallet::init_with_seed_words(
                 cipher_seed,
-                &security.password,
+                &security.read_new_password()?,
                 &wallet_config.database_path,
+                wallet_config.network,
                 wallet_config.account_name.as_deref(),
             )?;
 
@@ -227,25 +250,36 @@ async fn main() -> Result<(), anyhow::Error> {
             Ok(())
         },
 
-        Commands::ShowSeedWords { security, db, account } => {
+        Commands::ShowSeedWords {
+            security,
+            db,
+            account,
+            seed_passphrase,
+            seed_language,
+        } => {
             info!("Retrieving seed words...");
 
             wallet_config.apply_database(&db);
             wallet_config.apply_account(&account);
 
-            let pool = init_db(wallet_config.database_path.clone())?;
+            let pool = init_db(wallet_config.database_path.clone(), wallet_config.network)?;
             let conn = pool.get()?;
 
             let name = wallet_config.account_name.as_deref().unwrap_or("default");
             let account =
                 db::get_account_by_name(&conn, name)?.ok_or_else(|| anyhow!("Account '{}' not found", name))?;
 
-            match account.get_seed_words(&security.password)? {
+            let password = security.read_password()?;
+            let seed_passphrase = seed_passphrase.read_passphrase(true)?;
+            match account.get_seed_words(&password, seed_language, seed_passphrase.as_deref().map(String::as_str))? {
                 Some(words) => {
                     let seed_words = words.join(" ");
                     println!("---------------------------------------------------------");
                     println!("Wallet: {}", account.friendly_name);
                     println!("Seed Words: {}", seed_words.reveal());
+                    if seed_passphrase.is_some() {
+                        println!("The seed passphrase is needed to restore these words.");
+                    }
                     println!("---------------------------------------------------------");
                     println!("WARNING: Keep these words safe. Anyone with them can spend your funds.");
                 },
@@ -264,14 +298,14 @@ async fn main() -> Result<(), anyhow::Error> {
             wallet_config.apply_database(&db);
             wallet_config.apply_account(&account);
 
-            let pool = init_db(wallet_config.database_path.clone())?;
+            let pool = init_db(wallet_config.database_path.clone(), wallet_config.network)?;
             let conn = pool.get()?;
 
             let name = wallet_config.account_name.as_deref().unwrap_or("default");
             let account =
                 db::get_account_by_name(&conn, name)?.ok_or_else(|| anyhow!("Account '{}' not found", name))?;
 
-            let (view_key_hex, spend_key_hex) = account.get_keys_hex(&security.password)?;
+            let (view_key_hex, spend_key_hex) = account.get_keys_hex(&security.read_password()?)?;
 
             println!("---------------------------------------------------------");
             println!("Wallet           : {}", account.friendly_name);
@@ -295,7 +329,7 @@ async fn main() -> Result<(), anyhow::Error> {
             wallet_config.apply_account(&account);
 
             let (events, _more_blocks_to_scan) = scan(
-                &security.password,
+                &security.read_password()?,
                 &wallet_config,
                 max_blocks_to_scan,
                 wallet_config.account_name.as_deref(),
@@ -316,19 +350,12 @@ async fn main() -> Result<(), anyhow::Error> {
             wallet_config.apply_node(&node);
             wallet_config.apply_database(&db);
 
-            let webhook_url = wallet_config.webhook.url.clone();
-            let send_only_event_types = wallet_config.webhook.send_only_event_types.clone();
-            let webhook_trigger_config = webhook_url.map(|url| WebhookTriggerConfig {
-                url,
-                send_only_event_types: send_only_event_types.clone(),
-            });
-
             let (events, _more_blocks_to_scan) = rescan(
-                &security.password,
+                &security.read_password()?,
                 &wallet_config,
                 &account_name,
                 rescan_from_height,
-                webhook_trigger_config,
+                wallet_config.webhook_trigger_config(),
             )
             .await?;
             info!(event_count = events.len(); "Re-scan complete");
@@ -336,10 +363,13 @@ async fn main() -> Result<(), anyhow::Error> {
         },
         Commands::Daemon {
             security,
+            unlock,
             node,
             db,
             scan_interval_secs,
             api_port,
+            require_api_key,
+            api_listen,
         } => {
             info!("Starting Tari wallet daemon...");
 
@@ -348,6 +378,12 @@ async fn main() -> Result<(), anyhow::Error> {
             wallet_config.apply_daemon(&DaemonArgs {
                 scan_interval_secs,
                 api_port,
+                require_api_key,
+                api_bind_addresses: api_listen.bind_addresses,
+                api_unix_socket: api_listen.api_unix_socket,
+                api_tls_cert: api_listen.api_tls_cert,
+                api_tls_key: api_listen.api_tls_key,
+                api_tls_client_ca: api_listen.api_tls_client_ca,
             });
 
             let webhook_url = wallet_config.webhook.url.clone();
@@ -355,9 +391,22 @@ async fn main() -> Result<(), anyhow::Error> {
             let send_only_event_types = wallet_config.webhook.send_only_event_types.clone();
             let burn_proofs_dir = wallet_config.effective_burn_proofs_dir();
 
+            let password = if unlock.start_locked {
+                None
+            } else {
+                Some(security.read_password()?)
+            };
+            let keyring = unlock_at_startup(
+                &wallet_config.database_path,
+                wallet_config.network,
+                password.as_deref().map(String::as_str),
+                &unlock.unlock_accounts,
+            )?;
+            drop(password);
+
             let max_blocks_to_scan = u64::MAX;
             let daemon = daemon::Daemon::new(
-                security.password,
+                keyring,
                 wallet_config.base_url,
                 wallet_config.database_path,
                 max_blocks_to_scan,
@@ -369,7 +418,12 @@ async fn main() -> Result<(), anyhow::Error> {
                 webhook_url,
                 webhook_secret,
                 send_only_event_types,
+                wallet_config.alerts,
+                wallet_config.health,
                 burn_proofs_dir,
+                wallet_config.block_cache_path,
+                wallet_config.require_api_key,
+                wallet_config.api,
             );
             daemon.run().await?;
             Ok(())
@@ -402,13 +456,44 @@ async fn main() -> Result<(), anyhow::Error> {
                 wallet_config.database_path.clone(),
                 account_name,
                 wallet_config.network,
-                security.password,
+                security.read_password()?,
                 tx.idempotency_key,
                 seconds_to_lock,
                 wallet_config.confirmation_window,
                 output_file,
             )
         },
+        Commands::Send {
+            security,
+            node,
+            db,
+            tx,
+            account_name,
+            recipient,
+            seconds_to_lock,
+        } => {
+            info!(target: "audit", account = account_name.as_str(); "Sending transaction...");
+
+            wallet_config.apply_node(&node);
+            wallet_config.apply_database(&db);
+            wallet_config.apply_transaction(&tx);
+            let signer = ExternalSigner::from_config(&wallet_config.external_signer)?;
+
+            send::handle_send(
+                recipient.parse()?,
+                wallet_config.database_path.clone(),
+                account_name,
+                wallet_config.network,
+                security.read_password()?,
+                tx.idempotency_key,
+                seconds_to_lock,
+                wallet_config.confirmation_window,
+                wallet_config.webhook_trigger_config(),
+                wallet_config.base_url,
+                signer.as_ref(),
+            )
+            .await
+        },
         Commands::LockFunds {
             account_name,
             output_file,
@@ -436,7 +521,13 @@ async fn main() -> Result<(), anyhow::Error> {
                 idempotency_key: tx.idempotency_key,
                 confirmation_window,
             };
-            handle_lock_funds(wallet_config.database_path.clone(), account_name, output_file, request)
+            handle_lock_funds(
+                wallet_config.database_path.clone(),
+                wallet_config.network,
+                account_name,
+                output_file,
+                request,
+            )
         },
         Commands::RegisterValidatorNode {
             security,
@@ -459,6 +550,7 @@ async fn main() -> Result<(), anyhow::Error> {
             wallet_config.apply_node(&node);
             wallet_config.apply_database(&db);
             wallet_config.apply_transaction(&tx);
+            let signer = ExternalSigner::from_config(&wallet_config.external_signer)?;
 
             validator_nodes::handle_register_validator_node(
                 vn_public_key,
@@ -472,11 +564,12 @@ async fn main() -> Result<(), anyhow::Error> {
                 wallet_config.database_path.clone(),
                 account_name,
                 wallet_config.network,
-                security.password,
+                security.read_password()?,
                 tx.idempotency_key,
                 seconds_to_lock,
                 wallet_config.confirmation_window,
                 wallet_config.base_url,
+                signer.as_ref(),
             )
             .await
         },
@@ -500,6 +593,7 @@ async fn main() -> Result<(), anyhow::Error> {
             wallet_config.apply_node(&node);
             wallet_config.apply_database(&db);
             wallet_config.apply_transaction(&tx);
+            let signer = ExternalSigner::from_config(&wallet_config.external_signer)?;
 
             validator_nodes::handle_submit_validator_node_exit(
                 vn_public_key,
@@ -512,11 +606,12 @@ async fn main() -> Result<(), anyhow::Error> {
                 wallet_config.database_path.clone(),
                 account_name,
                 wallet_config.network,
-                security.password,
+                security.read_password()?,
                 tx.idempotency_key,
                 seconds_to_lock,
                 wallet_config.confirmation_window,
                 wallet_config.base_url,
+                signer.as_ref(),
             )
             .await
         },
@@ -537,6 +632,7 @@ async fn main() -> Result<(), anyhow::Error> {
             wallet_config.apply_node(&node);
             wallet_config.apply_database(&db);
             wallet_config.apply_transaction(&tx);
+            let signer = ExternalSigner::from_config(&wallet_config.external_signer)?;
 
             validator_nodes::handle_submit_validator_eviction_proof(
                 proof_file,
@@ -546,21 +642,181 @@ async fn main() -> Result<(), anyhow::Error> {
                 wallet_config.database_path.clone(),
                 account_name,
                 wallet_config.network,
-                security.password,
+                security.read_password()?,
                 tx.idempotency_key,
                 seconds_to_lock,
                 wallet_config.confirmation_window,
                 wallet_config.base_url,
+                signer.as_ref(),
             )
             .await
         },
+        Commands::ChangePassword {
+            security,
+            new_password,
+            db,
+            account,
+            dry_run,
+        } => {
+            info!(target: "audit", account:? = account.account_name, dry_run = dry_run; "Changing wallet password...");
+
+            wallet_config.apply_database(&db);
+
+            let current_password = security.read_password()?;
+            let new_password = if dry_run {
+                None
+            } else {
+                Some(new_password.read_password()?)
+            };
+            password::handle_change_password(
+                wallet_config.database_path.clone(),
+                wallet_config.network,
+                account.account_name.as_deref(),
+                &current_password,
+                new_password.as_deref().map(String::as_str),
+            )
+        },
+        Commands::Backup { security, db, output } => {
+            info!(target: "audit", output:? = output; "Backing up wallet database...");
+
+            wallet_config.apply_database(&db);
+
+            let password = security.read_backup_password(true)?;
+            backup::handle_backup(&wallet_config.database_path, wallet_config.network, &password, &output)
+        },
+        Commands::Restore {
+            security,
+            db,
+            input,
+            force,
+        } => {
+            info!(target: "audit", input:? = input, force = force; "Restoring wallet database...");
+
+            wallet_config.apply_database(&db);
+
+            let password = security.read_backup_password(false)?;
+            backup::handle_restore(
+                &wallet_config.database_path,
+                wallet_config.network,
+                &password,
+                &input,
+                force,
+            )
+        },
+        Commands::ExportAccount {
+            security,
+            export_password,
+            db,
+            account,
+            output,
+            include_history,
+        } => {
+            let name = account.account_name.as_deref().unwrap_or("default");
+            info!(target: "audit", account = name, output:? = output; "Exporting account...");
+
+            wallet_config.apply_database(&db);
+
+            let password = security.read_password()?;
+            let export_password = export_password.read_password(true)?;
+            account_export::handle_export_account(
+                &wallet_config.database_path,
+                name,
+                &password,
+                &export_password,
+                wallet_config.network,
+                include_history,
+                &output,
+            )
+        },
+        Commands::ImportAccount {
+            security,
+            export_password,
+            db,
+            account,
+            input,
+        } => {
+            info!(target: "audit", account:? = account.account_name, input:? = input; "Importing account...");
+
+            wallet_config.apply_database(&db);
+
+            let export_password = export_password.read_password(false)?;
+            let password = security.read_new_password()?;
+            account_export::handle_import_account(
+                &wallet_config.database_path,
+                &input,
+                &export_password,
+                &password,
+                account.account_name.as_deref(),
+                wallet_config.network,
+            )
+        },
+        Commands::DbMaintenance {
+            db,
+            check_only,
+            event_retention_days,
+            webhook_retention_days,
+            reorg_horizon,
+            no_vacuum,
+        } => {
+            info!(target: "audit", check_only = check_only; "Running database maintenance...");
+
+            wallet_config.apply_database(&db);
+
+            let options = MaintenanceOptions {
+                event_retention: chrono::Duration::days(event_retention_days.into()),
+                webhook_retention: chrono::Duration::days(webhook_retention_days.into()),
+                reorg_horizon,
+                check_only,
+                vacuum: !no_vacuum,
+            };
+            maintenance::handle_db_maintenance(&wallet_config.database_path, wallet_config.network, &options)
+        },
+        Commands::ConfirmNetwork { db, confirm_network } => {
+            wallet_config.apply_database(&db);
+            if confirm_network != wallet_config.network {
+                return Err(anyhow!(
+                    "--confirm-network {} does not match the configured network {}",
+                    confirm_network,
+                    wallet_config.network
+                ));
+            }
+            info!(target: "audit", network:% = confirm_network; "Confirming the network of the database...");
+
+            db::init_db_confirming_network(wallet_config.database_path.clone(), confirm_network)?;
+            println!(
+                "{} is recorded as the network of {}.",
+                confirm_network,
+                wallet_config.database_path.display()
+            );
+            Ok(())
+        },
+        Commands::Verify {
+            security,
+            db,
+            account,
+            repair,
+        } => {
+            info!(target: "audit", repair = repair; "Verifying wallet...");
+
+            wallet_config.apply_database(&db);
+            wallet_config.apply_account(&account);
+
+            let password = if repair { Some(security.read_password()?) } else { None };
+            verify::handle_verify(
+                &wallet_config.database_path,
+                wallet_config.network,
+                wallet_config.account_name.as_deref(),
+                password.as_deref().map(String::as_str),
+                wallet_config.confirmation_window,
+            )
+        },
         Commands::Delete { db, account } => {
             let name = account.account_name.as_deref().unwrap_or("default");
             info!(target: "audit", account = name; "Deleting wallet...");
 
             wallet_config.apply_database(&db);
 
-            utils::delete_wallet::delete_wallet(&wallet_config.database_path, name)?;
+            utils::delete_wallet::delete_wallet(&wallet_config.database_path, wallet_config.network, name)?;
             println!("Wallet account '{}' deleted successfully.", name);
             Ok(())
         },
@@ -595,7 +851,7 @@ async fn main() -> Result<(), anyhow::Error> {
                 sidechain_deployment_key,
                 wallet_config.database_path.clone(),
                 wallet_config.network,
-                security.password,
+                security.read_password()?,
                 tx.idempotency_key,
                 seconds_to_lock,
                 wallet_config.confirmation_window,
@@ -603,11 +859,207 @@ async fn main() -> Result<(), anyhow::Error> {
             )
             .await
         },
+        Commands::ApiKey { command } => match command {
+            ApiKeyCommand::Create {
+                db,
+                name,
+                scope,
+                accounts,
+            } => {
+                info!(target: "audit", name = name.as_str(); "Creating API key...");
+
+                wallet_config.apply_database(&db);
+                api_keys::handle_create_api_key(
+                    wallet_config.database_path.clone(),
+                    wallet_config.network,
+                    name,
+                    scope,
+                    accounts,
+                )
+            },
+            ApiKeyCommand::List { db } => {
+                wallet_config.apply_database(&db);
+                api_keys::handle_list_api_keys(wallet_config.database_path.clone(), wallet_config.network)
+            },
+            ApiKeyCommand::Revoke { db, name } => {
+                info!(target: "audit", name = name.as_str(); "Revoking API key...");
+
+                wallet_config.apply_database(&db);
+                api_keys::handle_revoke_api_key(wallet_config.database_path.clone(), wallet_config.network, name)
+            },
+        },
+        Commands::Webhook { command } => match command {
+            WebhookCommand::Create {
+                db,
+                name,
+                url,
+                secret,
+                event_types,
+                accounts,
+            } => {
+                info!(target: "audit", name = name.as_str(), url = url.as_str(); "Creating webhook subscription...");
+
+                wallet_config.apply_database(&db);
+                webhooks::handle_create_webhook(
+                    wallet_config.database_path.clone(),
+                    wallet_config.network,
+                    name,
+                    url,
+                    secret,
+                    event_types,
+                    accounts,
+                )
+            },
+            WebhookCommand::List { db } => {
+                wallet_config.apply_database(&db);
+                webhooks::handle_list_webhooks(wallet_config.database_path.clone(), wallet_config.network)
+            },
+            WebhookCommand::Update {
+                db,
+                name,
+                url,
+                event_types,
+                all_events,
+                accounts,
+                all_accounts,
+            } => {
+                info!(target: "audit", name = name.as_str(); "Updating webhook subscription...");
+
+                wallet_config.apply_database(&db);
+                webhooks::handle_update_webhook(
+                    wallet_config.database_path.clone(),
+                    wallet_config.network,
+                    name,
+                    url,
+                    event_types,
+                    all_events,
+                    accounts,
+                    all_accounts,
+                )
+            },
+            WebhookCommand::Enable { db, name } => {
+                info!(target: "audit", name = name.as_str(); "Enabling webhook subscription...");
+
+                wallet_config.apply_database(&db);
+                webhooks::handle_set_webhook_enabled(
+                    wallet_config.database_path.clone(),
+                    wallet_config.network,
+                    name,
+                    true,
+                )
+            },
+            WebhookCommand::Disable { db, name } => {
+                info!(target: "audit", name = name.as_str(); "Disabling webhook subscription...");
+
+                wallet_config.apply_database(&db);
+                webhooks::handle_set_webhook_enabled(
+                    wallet_config.database_path.clone(),
+                    wallet_config.network,
+                    name,
+                    false,
+                )
+            },
+            WebhookCommand::RotateSecret { db, name } => {
+                info!(target: "audit", name = name.as_str(); "Rotating webhook subscription secret...");
+
+                wallet_config.apply_database(&db);
+                webhooks::handle_rotate_webhook_secret(wallet_config.database_path.clone(), wallet_config.network, name)
+            },
+            WebhookCommand::Deliveries {
+                db,
+                status,
+                event_type,
+                since,
+                limit,
+            } => {
+                wallet_config.apply_database(&db);
+                webhooks::handle_list_webhook_deliveries(
+                    wallet_config.database_path.clone(),
+                    wallet_config.network,
+                    status,
+                    event_type,
+                    since,
+                    limit,
+                )
+            },
+            WebhookCommand::Replay { db, id, failed_since } => {
+                info!(target: "audit", id:? = id, failed_since:? = failed_since; "Replaying webhook deliveries...");
+
+                wallet_config.apply_database(&db);
+                webhooks::handle_replay_webhooks(
+                    wallet_config.database_path.clone(),
+                    wallet_config.network,
+                    id,
+                    failed_since,
+                )
+            },
+            WebhookCommand::ResendEvent {
+                db,
+                event_id,
+                subscription,
+            } => {
+                info!(target: "audit", event_id = event_id, subscription:? = subscription; "Re-sending wallet event...");
+
+                wallet_config.apply_database(&db);
+                webhooks::handle_resend_webhook_event(
+                    wallet_config.database_path.clone(),
+                    wallet_config.network,
+                    event_id,
+                    subscription,
+                    wallet_config.webhook_trigger_config(),
+                )
+            },
+        },
+        Commands::ApprovalPolicy { command } => match command {
+            ApprovalPolicyCommand::Set {
+                db,
+                account,
+                required,
+                approvers,
+                threshold,
+            } => {
+                info!(
+                    target: "audit",
+                    account = account.as_str(),
+                    required = required,
+                    approvers:? = approvers;
+                    "Setting approval policy..."
+                );
+
+                wallet_config.apply_database(&db);
+                approvals::handle_set_approval_policy(
+                    wallet_config.database_path.clone(),
+                    wallet_config.network,
+                    account,
+                    required,
+                    approvers,
+                    threshold,
+                )
+            },
+            ApprovalPolicyCommand::Show { db, account } => {
+                wallet_config.apply_database(&db);
+                approvals::handle_show_approval_policies(
+                    wallet_config.database_path.clone(),
+                    wallet_config.network,
+                    account,
+                )
+            },
+            ApprovalPolicyCommand::Remove { db, account } => {
+                info!(target: "audit", account = account.as_str(); "Removing approval policy...");
+
+                wallet_config.apply_database(&db);
+                approvals::handle_remove_approval_policy(
+                    wallet_config.database_path.clone(),
+                    wallet_config.network,
+                    account,
+                )
+            },
+        },
     }
 }
 
 fn handle_balance(config: &WalletConfig) -> Result<(), anyhow::Error> {
-    let pool = init_db(config.database_path.clone())?;
+    let pool = init_db(config.database_path.clone(), config.network)?;
     let conn = pool.get()?;
     let accounts = get_accounts(&conn, config.account_name.as_deref())?;
     for account in accounts {
@@ -628,37 +1080,17 @@ fn handle_create_unsigned_transaction(
     database_file: PathBuf,
     account_name: String,
     network: Network,
-    password: String,
+    password: Zeroizing<String>,
     idempotency_key: Option<String>,
     seconds_to_lock: u64,
     confirmation_window: u64,
     output_file: String,
 ) -> Result<(), anyhow::Error> {
-    let recipients: Result<Vec<Recipient>, anyhow::Error> = recipient
-        .into_iter()
-        .map(|r_str| {
-            let parts: Vec<&str> = r_str.split("::").collect();
-            if parts.len() < 2 || parts.len() > 3 {
-                return Err(anyhow!(
-                    "Invalid recipient format. Expected 'address::amount' or 'address::amount::payment_id'"
-                ));
-            }
-            let address = TariAddress::from_str(parts.first().expect("Already checked"))?;
-            let amount = MicroMinotari::from_str(parts.get(1).expect("Already checked"))?;
-            let payment_id = if parts.len() == 3 {
-                Some(parts.get(2).expect("Already checked").to_string())
-            } else {
-                None
-            };
-            Ok(Recipient {
-                address,
-                amount,
-                payment_id,
-            })
-        })
-        .collect();
-    let recipients = recipients?;
-    let pool = init_db(database_file)?;
+    let recipients = recipient
+        .iter()
+        .map(|r_str| r_str.parse::<Recipient>())
+        .collect::<Result<Vec<_>, _>>()?;
+    let pool = init_db(database_file, network)?;
     let conn = pool.get()?;
     let account =
         db::get_account_by_name(&conn, &account_name)?.ok_or_else(|| anyhow!("Account not found: {}", account_name))?;
@@ -682,7 +1114,7 @@ fn handle_create_unsigned_transaction(
         )
         .map_err(|e| anyhow!("Failed to lock funds: {}", e))?;
 
-    let one_sided_tx = OneSidedTransaction::new(pool.clone(), network, password.clone());
+    let one_sided_tx = OneSidedTransaction::new(pool.clone(), network, password);
     let result = one_sided_tx
         .create_unsigned_transaction(&account, locked_funds, recipients, fee_per_gram)
         .map_err(|e| anyhow!("Failed to create an unsigned transaction: {}", e))?;
@@ -697,11 +1129,12 @@ fn handle_create_unsigned_transaction(
 #[allow(clippy::too_many_arguments)]
 fn handle_lock_funds(
     database_file: PathBuf,
+    network: Network,
     account_name: String,
     output_file: String,
     request: LockFundsRequest,
 ) -> Result<(), anyhow::Error> {
-    let pool = init_db(database_file)?;
+    let pool = init_db(database_file, network)?;
     let conn = pool.get()?;
     let account =
         db::get_account_by_name(&conn, &account_name)?.ok_or_else(|| anyhow!("Account not found: {}", account_name))?;
@@ -736,6 +1169,7 @@ async fn scan(
         password,
         &config.base_url,
         config.database_path.clone(),
+        config.network,
         config.batch_size,
         config.confirmation_window,
     )
@@ -745,15 +1179,13 @@ async fn scan(
         scanner = scanner.account(name);
     }
 
-    if let Some(url) = &config.webhook.url {
-        let trigger_config = WebhookTriggerConfig {
-            url: url.clone(),
-            send_only_event_types: config.webhook.send_only_event_types.clone(),
-        };
-        scanner = scanner.webhook_config(trigger_config);
+    if let Some(path) = &config.block_cache_path {
+        scanner = scanner.block_cache_path(path.clone());
     }
 
-    scanner.run().await
+    scanner = scanner.webhook_config(config.webhook_trigger_config());
+
+    run_with_progress(scanner).await
 }
 
 async fn rescan(
@@ -761,14 +1193,15 @@ async fn rescan(
     config: &WalletConfig,
     account_name: &str,
     rescan_from_height: u64,
-    webhook_config: Option<WebhookTriggerConfig>,
+    webhook_config: WebhookTriggerConfig,
 ) -> Result<(Vec<WalletEvent>, bool), ScanError> {
     let db_file_clone = config.database_path.clone();
+    let network = config.network;
     let account_name_clone = account_name.to_string();
 
     let webhook_config_cloned = webhook_config.clone();
     tokio::task::spawn_blocking(move || {
-        let pool = init_db(db_file_clone).map_err(|e| format!("Failed to init db: {}", e))?;
+        let pool = init_db(db_file_clone, network).map_err(|e| format!("Failed to init db: {}", e))?;
 
         let conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
 
@@ -776,7 +1209,7 @@ async fn rescan(
             .map_err(|e| format!("DB error querying account: {}", e))?
             .ok_or_else(|| format!("Account not found: {}", account_name_clone))?;
 
-        rollback_from_height(&conn, account.id, rescan_from_height, webhook_config_cloned)
+        rollback_from_height(&conn, account.id, rescan_from_height, Some(webhook_config_cloned))
             .map_err(|e| format!("Rollback failed: {}", e))?;
 
         Ok::<(), String>(())
@@ -790,17 +1223,58 @@ async fn rescan(
         password,
         &config.base_url,
         config.database_path.clone(),
+        config.network,
         config.batch_size,
         config.confirmation_window,
     )
     .mode(scan::ScanMode::Partial {
         max_blocks: max_blocks_to_scan,
     });
-    if let Some(cfg) = webhook_config {
-        scanner = scanner.webhook_config(cfg);
+    scanner = scanner.webhook_config(webhook_config);
+    if let Some(path) = &config.block_cache_path {
+        scanner = scanner.block_cache_path(path.clone());
     }
     scanner = scanner.account(account_name);
-    scanner.run().await
+    run_with_progress(scanner).await
+}
+
+/// Runs the scanner while rendering a live progress line on stderr.
+///
+/// The progress line is only rendered when stderr is a terminal, so piped or
+/// redirected output is left untouched.
+async fn run_with_progress(scanner: scan::Scanner) -> Result<(Vec<WalletEvent>, bool), ScanError> {
+    if !std::io::stderr().is_terminal() {
+        return scanner.run().await;
+    }
+
+    let (mut event_rx, scan_future) = scanner.run_with_events();
+    let render_handle = tokio::spawn(async move {
+        let mut stderr = std::io::stderr();
+        let mut rendered = false;
+        while let Some(event) = event_rx.recv().await {
+            if let ProcessingEvent::ScanStatus(ScanStatusEvent::Progress {
+                current_height,
+                tip_height,
+                blocks_per_second,
+                outputs_found,
+                eta,
+                ..
+            }) = event
+            {
+                let line = format_progress_line(current_height, tip_height, blocks_per_second, outputs_found, eta);
+                let _ = write!(stderr, "\r\x1b[2K{}", line);
+                let _ = stderr.flush();
+                rendered = true;
+            }
+        }
+        if rendered {
+            let _ = writeln!(stderr);
+        }
+    });
+
+    let result = scan_future.await;
+    let _ = render_handle.await;
+    result
 }
 
 fn init_with_view_key(
@@ -808,14 +1282,22 @@ fn init_with_view_key(
     spend_public_key: &str,
     password: &str,
     database_file: &Path,
+    network: Network,
     birthday: u16,
+    external_signer: bool,
 ) -> Result<(), anyhow::Error> {
     utils::init_wallet::init_with_view_key(
         view_private_key,
         spend_public_key,
         password,
         database_file,
+        network,
         birthday,
         None,
-    )
+    )?;
+    if external_signer {
+        let pool = init_db(database_file.to_path_buf(), network)?;
+        db::set_account_external_signer(&pool.get()?, "default", true)?;
+    }
+    Ok(())
 }
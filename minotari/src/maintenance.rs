@@ -0,0 +1,240 @@
+//! Maintenance of long-running wallet databases.
+//!
+//! Reorgs leave soft deleted outputs and inputs behind, and events, webhook deliveries and
+//! scanned blocks accumulate for as long as the wallet runs. [`run_maintenance`] checks the
+//! database with SQLite's integrity and foreign key checks, deletes rows the wallet no longer
+//! needs and vacuums the file, so that the freed space is returned to the file system.
+//!
+//! Nothing is deleted from a database that fails a check. Vacuuming waits for the
+//! transactions of a running daemon, so maintenance is best run while it is stopped.
+
+use std::path::Path;
+
+use chrono::{Duration, Utc};
+use log::info;
+use tari_common::configuration::Network;
+
+use crate::{db, webhooks::models::WebhookStatus};
+
+/// What [`run_maintenance`] deletes.
+#[derive(Debug, Clone)]
+pub struct MaintenanceOptions {
+    /// Wallet events older than this are deleted
+    pub event_retention: Duration,
+    /// Delivered webhooks older than this are deleted
+    pub webhook_retention: Duration,
+    /// Soft deleted outputs and inputs are kept for this many blocks below the scanned tip of
+    /// their account, in case a deeper reorg needs them
+    pub reorg_horizon: u64,
+    /// Only run the checks
+    pub check_only: bool,
+    /// Vacuum the database after deleting
+    pub vacuum: bool,
+}
+
+/// Results of a [`run_maintenance`] run.
+#[derive(Debug, Default)]
+pub struct MaintenanceReport {
+    /// Problems found by SQLite's integrity check
+    pub integrity_errors: Vec<String>,
+    /// Rows whose foreign keys refer to missing rows
+    pub foreign_key_violations: Vec<String>,
+    /// Deleted wallet events
+    pub events: usize,
+    /// Deleted webhook deliveries
+    pub webhook_deliveries: usize,
+    /// Deleted soft deleted outputs
+    pub outputs: usize,
+    /// Deleted soft deleted inputs
+    pub inputs: usize,
+    /// Deleted scanned tip blocks
+    pub scanned_tip_blocks: usize,
+    /// Size of the database file before maintenance, in bytes
+    pub size_before: u64,
+    /// Size of the database file after maintenance, in bytes
+    pub size_after: u64,
+}
+
+impl MaintenanceReport {
+    /// Whether the database passed the integrity and foreign key checks.
+    pub fn is_consistent(&self) -> bool {
+        self.integrity_errors.is_empty() && self.foreign_key_violations.is_empty()
+    }
+
+    /// Bytes returned to the file system.
+    pub fn reclaimed(&self) -> u64 {
+        self.size_before.saturating_sub(self.size_after)
+    }
+}
+
+/// Checks the database and, unless a check fails or `options.check_only` is set, prunes and
+/// vacuums it.
+pub fn run_maintenance(
+    database_file: &Path,
+    network: Network,
+    options: &MaintenanceOptions,
+) -> anyhow::Result<MaintenanceReport> {
+    let pool = db::init_db(database_file.to_path_buf(), network)?;
+    let mut conn = pool.get()?;
+
+    let size_before = db::database_size(&conn)?;
+    let mut report = MaintenanceReport {
+        integrity_errors: db::integrity_check(&conn)?,
+        foreign_key_violations: db::foreign_key_check(&conn)?,
+        size_before,
+        size_after: size_before,
+        ..Default::default()
+    };
+    if !report.is_consistent() || options.check_only {
+        return Ok(report);
+    }
+
+    let now = Utc::now().naive_utc();
+    let tx = conn.transaction()?;
+    report.events = db::delete_events_older_than(&tx, now - options.event_retention)?;
+    report.webhook_deliveries =
+        db::delete_webhooks_older_than(&tx, WebhookStatus::Success, now - options.webhook_retention)?;
+    for account in db::get_accounts(&tx, None)? {
+        let Some(tip) = db::get_latest_scanned_tip_block_by_account(&tx, account.id)? else {
+            continue;
+        };
+        let horizon = tip.height.saturating_sub(options.reorg_horizon);
+        // Inputs first, as they refer to the outputs
+        report.inputs += db::purge_deleted_inputs_below_height(&tx, account.id, horizon)?;
+        report.outputs += db::purge_deleted_outputs_below_height(&tx, account.id, horizon)?;
+        report.scanned_tip_blocks += db::prune_scanned_tip_blocks(&tx, account.id, tip.height)?;
+    }
+    tx.commit()?;
+
+    if options.vacuum {
+        db::vacuum(&conn)?;
+    }
+    report.size_after = db::database_size(&conn)?;
+
+    info!(
+        target: "audit",
+        events = report.events,
+        webhook_deliveries = report.webhook_deliveries,
+        outputs = report.outputs,
+        inputs = report.inputs,
+        scanned_tip_blocks = report.scanned_tip_blocks,
+        reclaimed = report.reclaimed();
+        "Database maintenance finished"
+    );
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use rusqlite::{Connection, named_params};
+    use tari_common_types::seeds::cipher_seed::CipherSeed;
+    use tari_transaction_components::key_manager::wallet_types::{SeedWordsWallet, WalletType};
+
+    use super::*;
+
+    const OLD: &str = "2000-01-01 00:00:00";
+
+    fn insert_output(conn: &Connection, account_id: i64, tx_id: i64, deleted_in_block_height: Option<i64>) -> i64 {
+        conn.execute(
+            r#"
+            INSERT INTO outputs (account_id, tx_id, output_hash, mined_in_block_hash, mined_in_block_height, value,
+                wallet_output_json, mined_timestamp, deleted_at, deleted_in_block_height)
+            VALUES (:account_id, :tx_id, :hash, :hash, 1, 100, '{}', CURRENT_TIMESTAMP,
+                CASE WHEN :deleted IS NULL THEN NULL ELSE CURRENT_TIMESTAMP END, :deleted)
+            "#,
+            named_params! {
+                ":account_id": account_id,
+                ":tx_id": tx_id,
+                ":hash": tx_id.to_le_bytes().to_vec(),
+                ":deleted": deleted_in_block_height,
+            },
+        )
+        .unwrap();
+        conn.last_insert_rowid()
+    }
+
+    fn count(conn: &Connection, sql: &str) -> i64 {
+        conn.query_row(sql, [], |row| row.get(0)).unwrap()
+    }
+
+    #[test]
+    fn test_run_maintenance() {
+        let dir = tempfile::tempdir().unwrap();
+        let database_file = dir.path().join("wallet.db");
+        let pool = db::init_db(database_file.clone(), Network::Esmeralda).unwrap();
+        let conn = pool.get().unwrap();
+        let wallet = WalletType::SeedWords(SeedWordsWallet::construct_new(CipherSeed::random()).unwrap());
+        db::create_account(&conn, "default", &wallet, "password", Network::Esmeralda).unwrap();
+        let account = db::get_account_by_name(&conn, "default").unwrap().unwrap();
+
+        for height in [100, 500, 2999, 3000] {
+            db::insert_scanned_tip_block(&conn, account.id, height, &[0; 32]).unwrap();
+        }
+        let purged = insert_output(&conn, account.id, 1, Some(1500));
+        insert_output(&conn, account.id, 2, Some(2500));
+        insert_output(&conn, account.id, 3, None);
+        conn.execute(
+            r#"
+            INSERT INTO balance_changes (account_id, caused_by_output_id, description, balance_debit,
+                balance_credit, effective_date, effective_height)
+            VALUES (:account_id, :output_id, 'Reorged output', 0, 100, CURRENT_TIMESTAMP, 1)
+            "#,
+            named_params! { ":account_id": account.id, ":output_id": purged },
+        )
+        .unwrap();
+        for created_at in [OLD, "9999-01-01 00:00:00"] {
+            conn.execute(
+                r#"
+                INSERT INTO events (account_id, event_type, description, created_at)
+                VALUES (:account_id, 'OutputDetected', 'Output detected', :created_at)
+                "#,
+                named_params! { ":account_id": account.id, ":created_at": created_at },
+            )
+            .unwrap();
+        }
+        for status in [WebhookStatus::Success, WebhookStatus::Failed] {
+            conn.execute(
+                r#"
+                INSERT INTO webhook_queue (event_type, payload, target_url, status, created_at)
+                VALUES ('OutputDetected', '{}', 'https://example.com', :status, :created_at)
+                "#,
+                named_params! { ":status": status.to_string(), ":created_at": OLD },
+            )
+            .unwrap();
+        }
+
+        let mut options = MaintenanceOptions {
+            event_retention: Duration::days(90),
+            webhook_retention: Duration::days(30),
+            reorg_horizon: 1000,
+            check_only: true,
+            vacuum: true,
+        };
+        let report = run_maintenance(&database_file, Network::Esmeralda, &options).unwrap();
+        assert!(report.is_consistent());
+        assert_eq!(report.events, 0);
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM outputs"), 3);
+
+        options.check_only = false;
+        let report = run_maintenance(&database_file, Network::Esmeralda, &options).unwrap();
+        assert!(report.is_consistent());
+        assert_eq!(report.events, 1);
+        assert_eq!(report.webhook_deliveries, 1);
+        assert_eq!(report.outputs, 1);
+        assert_eq!(report.inputs, 0);
+        assert_eq!(report.scanned_tip_blocks, 1);
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM outputs"), 2);
+        assert_eq!(
+            count(&conn, "SELECT COUNT(*) FROM webhook_queue WHERE status = 'failed'"),
+            1
+        );
+        assert_eq!(
+            count(
+                &conn,
+                "SELECT COUNT(*) FROM balance_changes WHERE caused_by_output_id IS NULL"
+            ),
+            1
+        );
+        assert!(db::foreign_key_check(&conn).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,408 @@
+//! Prometheus metrics for the daemon.
+//!
+//! [`WalletMetrics`] collects two kinds of metrics:
+//!
+//! - **Counters and live gauges** updated as things happen: blocks scanned, outputs detected and
+//!   reorgs (fed by the scanner through [`EventSender`]), webhook delivery outcomes, the chain tip
+//!   and base node latency, and API request durations.
+//! - **Database gauges** (scanned height, balances, transaction counts, webhook queue depth)
+//!   recomputed from the database by [`WalletMetrics::refresh_from_db`] whenever `/metrics` is
+//!   scraped, so they are always consistent with the wallet state.
+//!
+//! All metric names are prefixed with `minotari_`. Amounts are reported in µT.
+
+use std::time::Duration;
+
+use prometheus::{
+    Encoder, Gauge, Histogram, HistogramOpts, HistogramVec, IntCounterVec, IntGauge, IntGaugeVec, Opts, Registry,
+    TextEncoder, core::Collector,
+};
+use rusqlite::Connection;
+
+use crate::{
+    db::{self, WalletDbResult},
+    models::PendingTransactionStatus,
+    scan::{EventSender, ProcessingEvent, ScanStatusEvent},
+};
+
+/// Buckets of the reorg depth histogram, in blocks.
+const REORG_DEPTH_BUCKETS: [f64; 8] = [1.0, 2.0, 3.0, 5.0, 10.0, 20.0, 50.0, 100.0];
+
+/// Buckets of the API request duration histogram, in seconds.
+const API_DURATION_BUCKETS: [f64; 10] = [0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 10.0];
+
+/// Metrics registry shared by the scanner, the webhook worker and the API.
+///
+/// Cloning is cheap and every clone updates the same metrics.
+#[derive(Clone)]
+pub struct WalletMetrics {
+    registry: Registry,
+    chain_tip_height: IntGauge,
+    node_request_latency_seconds: Gauge,
+    account_info: IntGaugeVec,
+    scanned_height: IntGaugeVec,
+    blocks_scanned_total: IntCounterVec,
+    outputs_detected_total: IntCounterVec,
+    reorgs_total: IntCounterVec,
+    reorg_depth_blocks: Histogram,
+    balance_microtari: IntGaugeVec,
+    transactions: IntGaugeVec,
+    broadcast_attempts: IntGaugeVec,
+    webhook_queue: IntGaugeVec,
+    webhook_deliveries_total: IntCounterVec,
+    api_request_duration_seconds: HistogramVec,
+}
+
+impl WalletMetrics {
+    pub fn new() -> Self {
+        let registry = Registry::new();
+
+        let chain_tip_height = register(
+            &registry,
+            IntGauge::new(
+                "minotari_chain_tip_height",
+                "Best block height reported by the base node",
+            ),
+        );
+        let node_request_latency_seconds = register(
+            &registry,
+            Gauge::new(
+                "minotari_node_request_latency_seconds",
+                "Round-trip time of the most recent request to the base node",
+            ),
+        );
+        let account_info = register(
+            &registry,
+            IntGaugeVec::new(
+                Opts::new("minotari_account_info", "Maps account ids to account names, always 1"),
+                &["account_id", "account"],
+            ),
+        );
+        let scanned_height = register(
+            &registry,
+            IntGaugeVec::new(
+                Opts::new(
+                    "minotari_scanned_height",
+                    "Height of the last block scanned for an account",
+                ),
+                &["account_id"],
+            ),
+        );
+        let blocks_scanned_total = register(
+            &registry,
+            IntCounterVec::new(
+                Opts::new(
+                    "minotari_blocks_scanned_total",
+                    "Blocks scanned since the daemon started",
+                ),
+                &["account_id"],
+            ),
+        );
+        let outputs_detected_total = register(
+            &registry,
+            IntCounterVec::new(
+                Opts::new(
+                    "minotari_outputs_detected_total",
+                    "Wallet outputs detected since the daemon started",
+                ),
+                &["account_id"],
+            ),
+        );
+        let reorgs_total = register(
+            &registry,
+            IntCounterVec::new(
+                Opts::new(
+                    "minotari_reorgs_total",
+                    "Chain reorganizations handled since the daemon started",
+                ),
+                &["account_id"],
+            ),
+        );
+        let reorg_depth_blocks = register(
+            &registry,
+            Histogram::with_opts(
+                HistogramOpts::new(
+                    "minotari_reorg_depth_blocks",
+                    "Number of blocks rolled back per reorganization",
+                )
+                .buckets(REORG_DEPTH_BUCKETS.to_vec()),
+            ),
+        );
+        let balance_microtari = register(
+            &registry,
+            IntGaugeVec::new(
+                Opts::new(
+                    "minotari_balance_microtari",
+                    "Account balance in µT by kind (available, locked, unconfirmed, total)",
+                ),
+                &["account_id", "kind"],
+            ),
+        );
+        let transactions = register(
+            &registry,
+            IntGaugeVec::new(
+                Opts::new(
+                    "minotari_transactions",
+                    "Number of transactions by status, `pending` being transactions not yet signed",
+                ),
+                &["account_id", "status"],
+            ),
+        );
+        let broadcast_attempts = register(
+            &registry,
+            IntGaugeVec::new(
+                Opts::new(
+                    "minotari_broadcast_attempts",
+                    "Broadcast attempts of the completed transactions of an account",
+                ),
+                &["account_id"],
+            ),
+        );
+        let webhook_queue = register(
+            &registry,
+            IntGaugeVec::new(
+                Opts::new("minotari_webhook_queue", "Webhook deliveries in the queue by status"),
+                &["status"],
+            ),
+        );
+        let webhook_deliveries_total = register(
+            &registry,
+            IntCounterVec::new(
+                Opts::new(
+                    "minotari_webhook_deliveries_total",
+                    "Webhook delivery attempts since the daemon started by outcome",
+                ),
+                &["outcome"],
+            ),
+        );
+        let api_request_duration_seconds = register(
+            &registry,
+            HistogramVec::new(
+                HistogramOpts::new("minotari_api_request_duration_seconds", "Duration of API requests")
+                    .buckets(API_DURATION_BUCKETS.to_vec()),
+                &["method", "path", "status"],
+            ),
+        );
+
+        Self {
+            registry,
+            chain_tip_height,
+            node_request_latency_seconds,
+            account_info,
+            scanned_height,
+            blocks_scanned_total,
+            outputs_detected_total,
+            reorgs_total,
+            reorg_depth_blocks,
+            balance_microtari,
+            transactions,
+            broadcast_attempts,
+            webhook_queue,
+            webhook_deliveries_total,
+            api_request_duration_seconds,
+        }
+    }
+
+    /// Sets the best block height reported by the base node.
+    pub fn set_chain_tip(&self, height: u64) {
+        self.chain_tip_height.set(height as i64);
+    }
+
+    /// Sets the latency of the most recent base node request.
+    pub fn set_node_latency(&self, latency: Duration) {
+        self.node_request_latency_seconds.set(latency.as_secs_f64());
+    }
+
+    /// Counts a webhook delivery attempt by the status it left the delivery in.
+    pub fn record_webhook_delivery(&self, outcome: &str) {
+        self.webhook_deliveries_total.with_label_values(&[outcome]).inc();
+    }
+
+    /// Records the duration of an API request. `path` is the route template, not the request path.
+    pub fn observe_api_request(&self, method: &str, path: &str, status: u16, duration: Duration) {
+        self.api_request_duration_seconds
+            .with_label_values(&[method, path, &status.to_string()])
+            .observe(duration.as_secs_f64());
+    }
+
+    /// Recomputes the database gauges.
+    ///
+    /// Gauges are reset first so that deleted accounts and emptied statuses disappear.
+    pub fn refresh_from_db(&self, conn: &Connection) -> WalletDbResult<()> {
+        self.account_info.reset();
+        self.scanned_height.reset();
+        self.balance_microtari.reset();
+        self.transactions.reset();
+        self.broadcast_attempts.reset();
+        self.webhook_queue.reset();
+
+        for account in db::get_accounts(conn, None)? {
+            let account_id = account.id.to_string();
+            self.account_info
+                .with_label_values(&[account_id.as_str(), account.friendly_name.as_str()])
+                .set(1);
+
+            if let Some(scanned) = db::get_latest_scanned_tip_block_by_account(conn, account.id)? {
+                self.scanned_height
+                    .with_label_values(&[account_id.as_str()])
+                    .set(scanned.height as i64);
+            }
+
+            let balance = db::get_balance(conn, account.id)?;
+            for (kind, amount) in [
+                ("available", balance.available),
+                ("locked", balance.locked),
+                ("unconfirmed", balance.unconfirmed),
+                ("total", balance.total),
+            ] {
+                self.balance_microtari
+                    .with_label_values(&[account_id.as_str(), kind])
+                    .set(amount.as_u64() as i64);
+            }
+
+            let mut broadcast_attempts = 0;
+            for counts in db::get_completed_transaction_counts(conn, account.id)? {
+                self.transactions
+                    .with_label_values(&[account_id.as_str(), counts.status.as_str()])
+                    .set(counts.count as i64);
+                broadcast_attempts += counts.broadcast_attempts;
+            }
+            self.broadcast_attempts
+                .with_label_values(&[account_id.as_str()])
+                .set(broadcast_attempts as i64);
+
+            let pending = db::count_pending_transactions(conn, account.id, PendingTransactionStatus::Pending)?;
+            self.transactions
+                .with_label_values(&[account_id.as_str(), "pending"])
+                .set(pending as i64);
+        }
+
+        for (status, count) in db::count_webhooks_by_status(conn)? {
+            self.webhook_queue
+                .with_label_values(&[status.as_str()])
+                .set(count as i64);
+        }
+
+        Ok(())
+    }
+
+    /// Encodes all metrics in the Prometheus text exposition format.
+    pub fn encode(&self) -> Result<String, prometheus::Error> {
+        let encoder = TextEncoder::new();
+        let mut buffer = String::new();
+        encoder.encode_utf8(&self.registry.gather(), &mut buffer)?;
+        Ok(buffer)
+    }
+
+    /// Content type of [`encode`](Self::encode)'s output.
+    pub fn content_type() -> &'static str {
+        TextEncoder::new().format_type()
+    }
+}
+
+impl Default for WalletMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EventSender for WalletMetrics {
+    fn send(&self, event: ProcessingEvent) -> bool {
+        match event {
+            ProcessingEvent::BlockProcessed(block) => {
+                let account_id = block.account_id.to_string();
+                self.blocks_scanned_total
+                    .with_label_values(&[account_id.as_str()])
+                    .inc();
+                self.outputs_detected_total
+                    .with_label_values(&[account_id.as_str()])
+                    .inc_by(block.outputs_detected.len() as u64);
+            },
+            ProcessingEvent::ScanStatus(ScanStatusEvent::Progress {
+                tip_height: Some(tip_height),
+                ..
+            }) => self.set_chain_tip(tip_height),
+            ProcessingEvent::ReorgDetected(reorg) => {
+                self.reorgs_total
+                    .with_label_values(&[reorg.account_id.to_string().as_str()])
+                    .inc();
+                self.reorg_depth_blocks.observe(reorg.blocks_rolled_back as f64);
+            },
+            _ => {},
+        }
+        true
+    }
+}
+
+/// Registers a metric whose options are known to be valid.
+fn register<T: Collector + Clone + 'static>(registry: &Registry, metric: prometheus::Result<T>) -> T {
+    let metric = metric.expect("Metric options are valid");
+    registry
+        .register(Box::new(metric.clone()))
+        .expect("Metric names are unique");
+    metric
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{create_account, get_account_by_name, init_db, insert_scanned_tip_block};
+    use crate::scan::{BlockProcessedEvent, ReorgDetectedEvent};
+    use tari_common::configuration::Network;
+    use tari_common_types::seeds::cipher_seed::CipherSeed;
+    use tari_transaction_components::key_manager::wallet_types::{SeedWordsWallet, WalletType};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_metrics_from_events_and_database() {
+        let temp_dir = tempdir().unwrap();
+        let pool = init_db(temp_dir.path().join("metrics.db"), Network::Esmeralda).unwrap();
+        let conn = pool.get().unwrap();
+        let seeds = CipherSeed::random();
+        let wallet_type = WalletType::SeedWords(SeedWordsWallet::construct_new(seeds).unwrap());
+        create_account(&conn, "default", &wallet_type, "password", Network::Esmeralda).unwrap();
+        let account = get_account_by_name(&conn, "default").unwrap().unwrap();
+        insert_scanned_tip_block(&conn, account.id, 120, &[1u8; 32]).unwrap();
+
+        let metrics = WalletMetrics::new();
+        for height in 119..=120 {
+            metrics.send(ProcessingEvent::BlockProcessed(BlockProcessedEvent {
+                account_id: account.id,
+                height,
+                block_hash: vec![0u8; 32],
+                outputs_detected: vec![],
+                inputs_spent: vec![],
+                balance_changes: vec![],
+            }));
+        }
+        metrics.send(ProcessingEvent::ReorgDetected(ReorgDetectedEvent {
+            account_id: account.id,
+            reorg_from_height: 118,
+            new_height: 120,
+            blocks_rolled_back: 2,
+            invalidated_output_hashes: vec![],
+            cancelled_transaction_ids: vec![],
+            reorganized_displayed_transactions: vec![],
+        }));
+        metrics.set_chain_tip(125);
+        metrics.record_webhook_delivery("success");
+        metrics.refresh_from_db(&conn).unwrap();
+
+        let text = metrics.encode().unwrap();
+        let account_id = account.id;
+        assert!(text.contains(&format!(
+            "minotari_blocks_scanned_total{{account_id=\"{account_id}\"}} 2"
+        )));
+        assert!(text.contains(&format!("minotari_reorgs_total{{account_id=\"{account_id}\"}} 1")));
+        assert!(text.contains("minotari_reorg_depth_blocks_sum 2"));
+        assert!(text.contains("minotari_chain_tip_height 125"));
+        assert!(text.contains(&format!("minotari_scanned_height{{account_id=\"{account_id}\"}} 120")));
+        assert!(text.contains(&format!(
+            "minotari_balance_microtari{{account_id=\"{account_id}\",kind=\"available\"}} 0"
+        )));
+        assert!(text.contains(&format!(
+            "minotari_transactions{{account_id=\"{account_id}\",status=\"pending\"}} 0"
+        )));
+        assert!(text.contains("minotari_webhook_deliveries_total{outcome=\"success\"} 1"));
+    }
+}
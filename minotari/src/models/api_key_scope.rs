@@ -0,0 +1,50 @@
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// Permission level of a REST API key.
+///
+/// Scopes are ordered: a key with a higher scope may call every endpoint a lower scope may.
+///
+/// - `ReadOnly`: balances, addresses, transactions, events, scan status and fee estimates
+/// - `Spend`: additionally locks funds, creates transactions, burns funds and approves payments
+/// - `Admin`: additionally controls the daemon's scanner
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "kebab-case")]
+pub enum ApiKeyScope {
+    ReadOnly,
+    Spend,
+    Admin,
+}
+
+impl ApiKeyScope {
+    /// Returns `true` if a key with this scope may call endpoints that require `required`.
+    pub fn allows(self, required: ApiKeyScope) -> bool {
+        self >= required
+    }
+}
+
+impl std::fmt::Display for ApiKeyScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ApiKeyScope::ReadOnly => write!(f, "read-only"),
+            ApiKeyScope::Spend => write!(f, "spend"),
+            ApiKeyScope::Admin => write!(f, "admin"),
+        }
+    }
+}
+
+impl FromStr for ApiKeyScope {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "read-only" => Ok(ApiKeyScope::ReadOnly),
+            "spend" => Ok(ApiKeyScope::Spend),
+            "admin" => Ok(ApiKeyScope::Admin),
+            _ => Err(format!(
+                "Invalid ApiKeyScope: {} (expected read-only, spend or admin)",
+                s
+            )),
+        }
+    }
+}
@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use utoipa::ToSchema;
+
+/// Decision of an approver on a transaction awaiting approval (see [`crate::db::DbApprovalRequest`]).
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum ApprovalDecision {
+    Approved,
+    Rejected,
+}
+
+impl std::fmt::Display for ApprovalDecision {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ApprovalDecision::Approved => write!(f, "APPROVED"),
+            ApprovalDecision::Rejected => write!(f, "REJECTED"),
+        }
+    }
+}
+
+impl FromStr for ApprovalDecision {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "APPROVED" => Ok(ApprovalDecision::Approved),
+            "REJECTED" => Ok(ApprovalDecision::Rejected),
+            _ => Err(format!("Invalid ApprovalDecision: {}", s)),
+        }
+    }
+}
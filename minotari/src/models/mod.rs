@@ -11,6 +11,9 @@
 //! - [`BalanceChange`] - Represents a credit or debit to the wallet balance
 //! - [`OutputStatus`] - Status of a UTXO (unconfirmed, confirmed, locked, spent)
 //! - [`PendingTransactionStatus`] - Status of transactions being constructed
+//! - [`ApiKeyScope`] - Permission level of a REST API key
+//! - [`ApprovalDecision`] - Decision of an approver on a transaction awaiting approval
+//! - [`WebhookPayload`] - Versioned JSON body delivered to webhook receivers
 //!
 //! # Event System
 //!
@@ -26,7 +29,14 @@ use tari_common_types::tari_address::TariAddress;
 use tari_common_types::transaction::TxId;
 use tari_common_types::types::FixedHash;
 use tari_transaction_components::MicroMinotari;
+use utoipa::ToSchema;
 
+use crate::transactions::{TransactionDirection, TransactionDisplayStatus, TransactionSource};
+
+pub mod api_key_scope;
+pub use api_key_scope::ApiKeyScope;
+pub mod approval_decision;
+pub use approval_decision::ApprovalDecision;
 pub mod output_status;
 pub use output_status::OutputStatus;
 pub mod pending_transactions_status;
@@ -119,9 +129,85 @@ pub enum WalletEventType {
         tx_id: TxId,
         original_mined_height: u64,
     },
+    /// The available balance dropped below the configured threshold.
+    LowBalanceAlert {
+        available: MicroMinotari,
+        threshold: MicroMinotari,
+    },
+    /// The available balance rose above the configured threshold.
+    HighBalanceAlert {
+        available: MicroMinotari,
+        threshold: MicroMinotari,
+    },
+    /// An incoming payment of at least the configured amount was received.
+    LargeIncomingPaymentAlert {
+        tx_id: TxId,
+        amount: MicroMinotari,
+        threshold: MicroMinotari,
+        block_height: u64,
+    },
+    /// An outbound transaction has been waiting in `Broadcast` for too many blocks.
+    TransactionStuckAlert {
+        tx_id: TxId,
+        first_seen_height: u64,
+        blocks_pending: u64,
+    },
+    /// The scanner is further behind the chain tip than the configured number of blocks.
+    ScannerLagAlert {
+        scanned_height: u64,
+        tip_height: u64,
+        lag_blocks: u64,
+    },
+    /// More webhook deliveries are waiting to be sent than the configured threshold.
+    WebhookBacklogAlert {
+        undelivered: u64,
+        threshold: u64,
+    },
+    /// An outgoing payment needs approvals under the account's approval policy before it is signed.
+    TransactionApprovalRequested {
+        tx_id: String,
+        amount: MicroMinotari,
+        required_approvals: u32,
+    },
+    /// An approver approved a payment awaiting approval.
+    TransactionApproved {
+        tx_id: String,
+        approver: String,
+        approvals: u32,
+        required_approvals: u32,
+    },
+    /// An approver rejected a payment awaiting approval; it can no longer be sent.
+    TransactionApprovalRejected {
+        tx_id: String,
+        approver: String,
+        comment: Option<String>,
+    },
 }
 
 impl WalletEventType {
+    /// All keys returned by [`WalletEventType::to_key_string`], e.g. for validating event filters.
+    pub const KEY_STRINGS: [&'static str; 19] = [
+        "BlockRolledBack",
+        "OutputDetected",
+        "OutputConfirmed",
+        "OutputRolledBack",
+        "PendingTransactionCancelled",
+        "TransactionBroadcast",
+        "TransactionUnconfirmed",
+        "TransactionConfirmed",
+        "TransactionRejected",
+        "TransactionReorged",
+        "LowBalanceAlert",
+        "HighBalanceAlert",
+        "LargeIncomingPaymentAlert",
+        "TransactionStuckAlert",
+        "ScannerLagAlert",
+        "WebhookBacklogAlert",
+        "TransactionApprovalRequested",
+        "TransactionApproved",
+        "TransactionApprovalRejected",
+    ];
+
     /// Returns a string key representing the event type (without data).
     ///
     /// This is useful for filtering and categorizing events in the database.
@@ -137,6 +223,15 @@ impl WalletEventType {
             WalletEventType::TransactionConfirmed { .. } => "TransactionConfirmed".to_string(),
             WalletEventType::TransactionRejected { .. } => "TransactionRejected".to_string(),
             WalletEventType::TransactionReorged { .. } => "TransactionReorged".to_string(),
+            WalletEventType::LowBalanceAlert { .. } => "LowBalanceAlert".to_string(),
+            WalletEventType::HighBalanceAlert { .. } => "HighBalanceAlert".to_string(),
+            WalletEventType::LargeIncomingPaymentAlert { .. } => "LargeIncomingPaymentAlert".to_string(),
+            WalletEventType::TransactionStuckAlert { .. } => "TransactionStuckAlert".to_string(),
+            WalletEventType::ScannerLagAlert { .. } => "ScannerLagAlert".to_string(),
+            WalletEventType::WebhookBacklogAlert { .. } => "WebhookBacklogAlert".to_string(),
+            WalletEventType::TransactionApprovalRequested { .. } => "TransactionApprovalRequested".to_string(),
+            WalletEventType::TransactionApproved { .. } => "TransactionApproved".to_string(),
+            WalletEventType::TransactionApprovalRejected { .. } => "TransactionApprovalRejected".to_string(),
         }
     }
 }
@@ -203,24 +298,99 @@ impl BalanceChange {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Version of the [`WebhookPayload`] schema.
+///
+/// Version 1 payloads had no `schema_version` field and only contained `event_id`,
+/// `event_type`, `created_at`, `balance` and `data`. Fields are only added within a version;
+/// removing or changing a field increments it.
+pub const WEBHOOK_PAYLOAD_SCHEMA_VERSION: u32 = 2;
+
+/// JSON body posted to webhook receivers for every wallet event.
+///
+/// # JSON Example
+///
+/// ```json
+/// {
+///   "schema_version": 2,
+///   "event_id": 42,
+///   "event_type": "OutputConfirmed",
+///   "created_at": "2025-06-01T12:00:00+00:00",
+///   "account_id": 1,
+///   "account_name": "default",
+///   "network": "mainnet",
+///   "balance": {"available": 8000, "pending_incoming": 0, "pending_outgoing": 0},
+///   "transaction": {
+///     "id": 1234567890,
+///     "direction": "incoming",
+///     "source": "one_sided",
+///     "status": "confirmed",
+///     "amount": 5000,
+///     "fee": null,
+///     "counterparty": "12PCb9...",
+///     "memo": "Invoice 1001",
+///     "payrefs": ["6f1c..."],
+///     "block_height": 150000,
+///     "confirmations": 3
+///   },
+///   "data": {"OutputConfirmed": {"hash": "...", "block_height": 150000, "confirmation_height": 150003}}
+/// }
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct WebhookPayload {
+    /// Version of this payload schema, see [`WEBHOOK_PAYLOAD_SCHEMA_VERSION`]
+    pub schema_version: u32,
     /// The unique ID of the event in the wallet DB
     pub event_id: i64,
     /// The specific type of event (string representation)
     pub event_type: String,
     /// ISO 8601 Timestamp
     pub created_at: String,
+    /// Account the event belongs to
+    pub account_id: i64,
+    /// Name of the account the event belongs to
+    pub account_name: String,
+    /// Network the wallet runs on (e.g. `mainnet`, `esmeralda`), `null` if not configured
+    pub network: Option<String>,
     /// Snapshot of the account balance at the time of the event
     pub balance: Option<WebhookBalanceSnapshot>,
-    /// The actual event data
+    /// The transaction the event concerns, if it concerns an output or transaction
+    /// that is known to the wallet
+    pub transaction: Option<WebhookTransaction>,
+    /// The actual event data, keyed by the event type
+    #[schema(value_type = Object)]
     pub data: WalletEventType,
 }
 
 /// A simplified balance view for the webhook payload
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct WebhookBalanceSnapshot {
+    /// Spendable balance in µT
     pub available: u64,
+    /// Unconfirmed incoming funds in µT
     pub pending_incoming: u64,
+    /// Funds locked by outgoing transactions in µT
     pub pending_outgoing: u64,
 }
+
+/// Summary of the transaction a webhook event concerns.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct WebhookTransaction {
+    /// Transaction id, as used by the `displayed_transactions` endpoints
+    #[schema(value_type = u64)]
+    pub id: TxId,
+    pub direction: TransactionDirection,
+    pub source: TransactionSource,
+    pub status: TransactionDisplayStatus,
+    /// Net amount in µT (always positive, use `direction` for the sign)
+    pub amount: u64,
+    /// Fee in µT, only set for outgoing transactions
+    pub fee: Option<u64>,
+    /// Base58 address of the sender or recipient, if known
+    pub counterparty: Option<String>,
+    /// Parsed memo (payment id)
+    pub memo: Option<String>,
+    /// Hex encoded payment references of the outputs received or sent
+    pub payrefs: Vec<String>,
+    pub block_height: u64,
+    pub confirmations: u64,
+}
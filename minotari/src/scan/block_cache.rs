@@ -0,0 +1,204 @@
+//! SQLite-backed cache of downloaded block data.
+//!
+//! Scanning a range of blocks for one account downloads the full UTXO-by-block data for
+//! that range, which is identical for every other account. [`SqliteBlockCache`] keeps those
+//! responses in a separate SQLite file so that re-scans, and scans of newly imported accounts,
+//! can be served locally instead of from the base node.
+//!
+//! The cache lives outside the wallet database on purpose: it holds no secrets, can be shared
+//! between wallets on the same network and can be deleted at any time.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use log::debug;
+use minotari_scanning::{BlockCache, WalletError, WalletResult};
+use r2d2::Pool;
+use r2d2_sqlite::SqliteConnectionManager;
+use rusqlite::params;
+use tari_transaction_components::rpc::models::BlockUtxoInfo;
+
+use crate::db::{SqlitePool, WalletDbResult};
+
+const CACHE_POOL_SIZE: u32 = 2;
+
+/// [`BlockCache`] implementation storing [`BlockUtxoInfo`] entries in a SQLite file.
+///
+/// Entries are keyed by block hash with a unique height index, so writing a block from a
+/// different fork replaces whatever was cached at that height.
+#[derive(Clone)]
+pub struct SqliteBlockCache {
+    pool: SqlitePool,
+}
+
+impl SqliteBlockCache {
+    /// Opens the cache at `path`, creating the file and its schema if needed.
+    pub fn open(path: &Path) -> WalletDbResult<Self> {
+        let mut path = PathBuf::from(path);
+        if path.is_relative() {
+            path = std::env::current_dir()?.join(path);
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        debug!(path:? = path; "Opening block cache");
+
+        let manager = SqliteConnectionManager::file(&path).with_init(|c| {
+            c.execute_batch(
+                "PRAGMA journal_mode = WAL;
+                 PRAGMA synchronous = NORMAL;
+                 PRAGMA busy_timeout = 5000;",
+            )
+        });
+        let pool = Pool::builder().max_size(CACHE_POOL_SIZE).build(manager)?;
+        pool.get()?.execute_batch(
+            "CREATE TABLE IF NOT EXISTS cached_blocks (
+                header_hash BLOB PRIMARY KEY NOT NULL,
+                height INTEGER NOT NULL UNIQUE,
+                data TEXT NOT NULL
+            );",
+        )?;
+
+        Ok(Self { pool })
+    }
+
+    fn connection(&self) -> WalletResult<r2d2::PooledConnection<SqliteConnectionManager>> {
+        self.pool.get().map_err(storage_error)
+    }
+}
+
+impl BlockCache for SqliteBlockCache {
+    fn get_blocks_from_height(&self, start_height: u64, limit: u64) -> WalletResult<Vec<BlockUtxoInfo>> {
+        let conn = self.connection()?;
+        let mut stmt = conn
+            .prepare_cached("SELECT height, data FROM cached_blocks WHERE height >= ?1 ORDER BY height ASC LIMIT ?2")
+            .map_err(storage_error)?;
+        let rows = stmt
+            .query_map(params![start_height as i64, limit as i64], |row| {
+                Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+            })
+            .map_err(storage_error)?;
+
+        let mut blocks = Vec::new();
+        let mut expected_height = start_height;
+        for row in rows {
+            let (height, data) = row.map_err(storage_error)?;
+            // Stop at the first gap, the caller has to fetch the missing block from the base node
+            if height as u64 != expected_height {
+                break;
+            }
+            let block: BlockUtxoInfo = serde_json::from_str(&data).map_err(storage_error)?;
+            blocks.push(block);
+            expected_height += 1;
+        }
+        Ok(blocks)
+    }
+
+    fn insert_blocks(&self, blocks: &[BlockUtxoInfo]) -> WalletResult<()> {
+        let mut conn = self.connection()?;
+        let tx = conn.transaction().map_err(storage_error)?;
+        {
+            let mut stmt = tx
+                .prepare_cached("INSERT OR REPLACE INTO cached_blocks (header_hash, height, data) VALUES (?1, ?2, ?3)")
+                .map_err(storage_error)?;
+            for block in blocks {
+                let data = serde_json::to_string(block).map_err(storage_error)?;
+                stmt.execute(params![block.header_hash, block.height as i64, data])
+                    .map_err(storage_error)?;
+            }
+        }
+        tx.commit().map_err(storage_error)
+    }
+
+    fn invalidate_from_height(&self, height: u64) -> WalletResult<()> {
+        let conn = self.connection()?;
+        let removed = conn
+            .execute("DELETE FROM cached_blocks WHERE height >= ?1", params![height as i64])
+            .map_err(storage_error)?;
+        debug!(height = height, removed = removed; "Invalidated block cache");
+        Ok(())
+    }
+}
+
+fn storage_error<E: std::fmt::Display>(e: E) -> WalletError {
+    WalletError::StorageError(format!("Block cache: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn block(height: u64, hash_byte: u8) -> BlockUtxoInfo {
+        BlockUtxoInfo {
+            outputs: Vec::new(),
+            inputs: Vec::new(),
+            height,
+            header_hash: vec![hash_byte; 32],
+            mined_timestamp: 1_700_000_000 + height,
+        }
+    }
+
+    fn heights(blocks: &[BlockUtxoInfo]) -> Vec<u64> {
+        blocks.iter().map(|b| b.height).collect()
+    }
+
+    #[test]
+    fn test_get_blocks_stops_at_gap() {
+        let dir = tempdir().unwrap();
+        let cache = SqliteBlockCache::open(&dir.path().join("cache.sqlite")).unwrap();
+        cache
+            .insert_blocks(&[block(10, 1), block(11, 2), block(12, 3), block(14, 5)])
+            .unwrap();
+
+        assert_eq!(
+            heights(&cache.get_blocks_from_height(10, 50).unwrap()),
+            vec![10, 11, 12]
+        );
+        assert_eq!(heights(&cache.get_blocks_from_height(11, 1).unwrap()), vec![11]);
+        assert_eq!(heights(&cache.get_blocks_from_height(14, 50).unwrap()), vec![14]);
+        // The start height itself is not cached
+        assert!(cache.get_blocks_from_height(13, 50).unwrap().is_empty());
+        assert!(cache.get_blocks_from_height(9, 50).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_insert_replaces_block_at_same_height() {
+        let dir = tempdir().unwrap();
+        let cache = SqliteBlockCache::open(&dir.path().join("cache.sqlite")).unwrap();
+        cache.insert_blocks(&[block(10, 1), block(11, 2)]).unwrap();
+
+        // A block from another fork at height 11 replaces the cached one
+        cache.insert_blocks(&[block(11, 9)]).unwrap();
+        let blocks = cache.get_blocks_from_height(10, 50).unwrap();
+        assert_eq!(heights(&blocks), vec![10, 11]);
+        assert_eq!(blocks.last().unwrap().header_hash, vec![9; 32]);
+
+        // Writing the same block again is harmless
+        cache.insert_blocks(&[block(11, 9)]).unwrap();
+        let blocks = cache.get_blocks_from_height(10, 50).unwrap();
+        assert_eq!(heights(&blocks), vec![10, 11]);
+        assert_eq!(blocks.last().unwrap().header_hash, vec![9; 32]);
+    }
+
+    #[test]
+    fn test_invalidate_from_height() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("cache.sqlite");
+        let cache = SqliteBlockCache::open(&path).unwrap();
+        cache
+            .insert_blocks(&[block(10, 1), block(11, 2), block(12, 3), block(13, 4)])
+            .unwrap();
+
+        cache.invalidate_from_height(12).unwrap();
+        assert_eq!(heights(&cache.get_blocks_from_height(10, 50).unwrap()), vec![10, 11]);
+        assert!(cache.get_blocks_from_height(12, 50).unwrap().is_empty());
+
+        // The cache persists across reopening
+        drop(cache);
+        let cache = SqliteBlockCache::open(&path).unwrap();
+        assert_eq!(heights(&cache.get_blocks_from_height(10, 50).unwrap()), vec![10, 11]);
+    }
+}
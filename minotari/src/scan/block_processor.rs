@@ -96,6 +96,8 @@ pub struct BlockProcessor<E: EventSender = NoopEventSender> {
     required_confirmations: u64,
     /// Webhook Configuration                                                                                        
     webhook_config: Option<WebhookTriggerConfig>,
+    /// Events of the current block awaiting webhook delivery, queued once its transactions are saved.
+    pending_webhooks: Vec<(i64, WalletEvent)>,
 }
 
 impl BlockProcessor<NoopEventSender> {
@@ -120,6 +122,7 @@ impl BlockProcessor<NoopEventSender> {
             has_pending_outbound: false,
             required_confirmations,
             webhook_config: None,
+            pending_webhooks: Vec::new(),
         }
     }
 }
@@ -153,6 +156,7 @@ impl<E: EventSender> BlockProcessor<E> {
             has_pending_outbound,
             required_confirmations,
             webhook_config: None,
+            pending_webhooks: Vec::new(),
         }
     }
 
@@ -210,6 +214,8 @@ impl<E: EventSender> BlockProcessor<E> {
     ) -> Result<(), BlockProcessorError> {
         let account_view_key = self.account_view_key(account_id)?.clone();
         self.current_tip_height = block.height;
+        // Left over if processing of a previous block failed and its DB transaction was rolled back
+        self.pending_webhooks.clear();
 
         self.current_block = Some(BlockEventAccumulator::new(
             account_id,
@@ -231,6 +237,11 @@ impl<E: EventSender> BlockProcessor<E> {
             self.event_sender.send(ProcessingEvent::BlockProcessed(block_event));
         }
 
+        // Webhooks are queued after the displayed transactions so that payloads can include them
+        for (event_id, event) in std::mem::take(&mut self.pending_webhooks) {
+            trigger_webhook_with_balance(tx, account_id, event_id, &event, self.webhook_config.as_ref())?;
+        }
+
         Ok(())
     }
 
@@ -314,8 +325,6 @@ impl<E: EventSender> BlockProcessor<E> {
         account_id: i64,
         account_view_key: &PrivateKey,
     ) -> Result<(), BlockProcessorError> {
-        let mut generated_events: Vec<(i64, WalletEvent)> = Vec::new();
-
         for (hash, output, wallet_idx) in &block.wallet_outputs {
             let Some(mapped_account_id) = self.wallet_index_to_account_id.get(*wallet_idx).copied() else {
                 continue;
@@ -360,7 +369,7 @@ impl<E: EventSender> BlockProcessor<E> {
             )?;
 
             let event_id = db::insert_wallet_event(tx, account_id, &event)?;
-            generated_events.push((event_id, event));
+            self.pending_webhooks.push((event_id, event));
 
             let balance_change = self.record_output_balance_change(tx, account_id, output_id, block, output)?;
 
@@ -376,12 +385,6 @@ impl<E: EventSender> BlockProcessor<E> {
             }
         }
 
-        if let Some(config) = &self.webhook_config {
-            for (event_id, event) in generated_events {
-                trigger_webhook_with_balance(tx, account_id, event_id, &event, config)?;
-            }
-        }
-
         Ok(())
     }
 
@@ -542,8 +545,6 @@ impl<E: EventSender> BlockProcessor<E> {
         block: &BlockScanResult,
         account_id: i64,
     ) -> Result<(), BlockProcessorError> {
-        let mut generated_events: Vec<(i64, WalletEvent)> = Vec::new();
-
         let unconfirmed_outputs =
             db::get_unconfirmed_outputs(tx, account_id, block.height, self.required_confirmations)?;
 
@@ -568,7 +569,7 @@ impl<E: EventSender> BlockProcessor<E> {
 
             self.wallet_events.push(event.clone());
             let event_id = db::insert_wallet_event(tx, account_id, &event)?;
-            generated_events.push((event_id, event));
+            self.pending_webhooks.push((event_id, event));
 
             db::mark_output_confirmed(
                 tx,
@@ -602,12 +603,6 @@ impl<E: EventSender> BlockProcessor<E> {
             }
         }
 
-        if let Some(config) = &self.webhook_config {
-            for (event_id, event) in generated_events {
-                trigger_webhook_with_balance(tx, account_id, event_id, &event, config)?;
-            }
-        }
-
         Ok(())
     }
 
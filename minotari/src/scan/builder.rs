@@ -1,12 +1,18 @@
-use std::{path::PathBuf, time::Duration};
+use std::{collections::HashSet, path::PathBuf, sync::Arc, time::Duration};
 
+use log::{debug, warn};
+use tari_common::configuration::Network;
+use tari_transaction_components::key_manager::KeyManager;
 use tokio::sync::mpsc;
 use tokio_util::sync::CancellationToken;
+use zeroize::Zeroizing;
 
 use crate::{
-    ProcessingEvent, ScanMode, WalletEvent, db,
+    ProcessingEvent, ScanMode, WalletEvent,
+    db::{self, AccountRow, WalletDbError},
+    keyring::Keyring,
     scan::{
-        ChannelEventSender, EventSender, NoopEventSender,
+        ChannelEventSender, EventSender, NoopEventSender, SqliteBlockCache,
         config::{DEFAULT_SCANNING_OFFSET_DAYS, OPTIMAL_SCANNING_THREADS, ScanRetryConfig, ScanTimeoutConfig},
         coordinator::ScanCoordinator,
         types::ScanError,
@@ -28,7 +34,7 @@ use crate::{
 ///
 /// let cancel_token = CancellationToken::new();
 ///
-/// let scanner = Scanner::new("wallet_password", "http://localhost:18142", "wallet.db", 100)
+/// let scanner = Scanner::new("wallet_password", "http://localhost:18142", "wallet.db", Network::MainNet, 100, 3)
 ///     .account("primary")
 ///     .mode(ScanMode::Continuous {
 ///         poll_interval: Duration::from_secs(30),
@@ -51,14 +57,18 @@ use crate::{
 /// The `Scanner` is not designed to be shared across threads. Create separate
 /// scanner instances for concurrent scanning of different accounts.
 pub struct Scanner {
-    /// Password for decrypting account key managers.
-    password: String,
+    /// Where the key managers of the scanned accounts come from.
+    keys: ScanKeys,
     /// Base URL for the blockchain node HTTP API.
     base_url: String,
     /// Path to the SQLite database file.
     database_file: PathBuf,
+    /// Network the wallet is configured for; the database must belong to it.
+    network: Network,
     /// Optional account name filter. If `None`, scans all accounts.
     account_name: Option<String>,
+    /// Accounts that are skipped even if they match the account filter.
+    excluded_account_ids: HashSet<i64>,
     /// Number of blocks to fetch per scan batch.
     batch_size: u64,
     /// Number of parallel threads for output detection.
@@ -77,16 +87,30 @@ pub struct Scanner {
     required_confirmations: u64,
     /// Webhook Configuration
     webhook_config: Option<WebhookTriggerConfig>,
+    /// Optional path to the on-disk block cache.
+    block_cache_path: Option<PathBuf>,
+}
+
+/// Source of the key managers used for scanning.
+enum ScanKeys {
+    /// Accounts are decrypted with the password; accounts it does not decrypt are skipped.
+    Password(Zeroizing<String>),
+    /// View keys cached in the daemon's keyring; accounts without one are skipped.
+    Keyring(Keyring),
 }
 
 impl Scanner {
     /// Creates a new scanner with essential configuration.
     ///
+    /// Accounts encrypted with a different password are skipped. The scan fails if the
+    /// password decrypts no account, or not the account set with [`Scanner::account`].
+    ///
     /// # Arguments
     ///
     /// * `password` - Password for decrypting account key managers
     /// * `base_url` - Base URL for the blockchain node HTTP API (e.g., "http://localhost:18142")
     /// * `database_file` - Path to the SQLite database file
+    /// * `network` - Network the database must belong to
     /// * `batch_size` - Number of blocks to fetch per scan batch (recommended: 50-200)
     ///
     /// # Defaults
@@ -99,20 +123,63 @@ impl Scanner {
     /// # Example
     ///
     /// ```rust,ignore
-    /// let scanner = Scanner::new("password", "http://localhost:18142", "wallet.db", 100);
+    /// let scanner = Scanner::new("password", "http://localhost:18142", "wallet.db", Network::MainNet, 100, 3);
     /// ```
     pub fn new(
         password: &str,
         base_url: &str,
         database_file: PathBuf,
+        network: Network,
+        batch_size: u64,
+        required_confirmations: u64,
+    ) -> Self {
+        Self::with_keys(
+            ScanKeys::Password(Zeroizing::new(password.to_string())),
+            base_url,
+            database_file,
+            network,
+            batch_size,
+            required_confirmations,
+        )
+    }
+
+    /// Creates a new scanner that scans with the view keys cached in a [`Keyring`].
+    ///
+    /// Accounts that were not unlocked since the keyring was created are skipped. Used by the
+    /// daemon, so that locked accounts keep being scanned without their password.
+    pub fn with_keyring(
+        keyring: Keyring,
+        base_url: &str,
+        database_file: PathBuf,
+        network: Network,
+        batch_size: u64,
+        required_confirmations: u64,
+    ) -> Self {
+        Self::with_keys(
+            ScanKeys::Keyring(keyring),
+            base_url,
+            database_file,
+            network,
+            batch_size,
+            required_confirmations,
+        )
+    }
+
+    fn with_keys(
+        keys: ScanKeys,
+        base_url: &str,
+        database_file: PathBuf,
+        network: Network,
         batch_size: u64,
         required_confirmations: u64,
     ) -> Self {
         Self {
-            password: password.to_string(),
+            keys,
             base_url: base_url.to_string(),
             database_file,
+            network,
             account_name: None,
+            excluded_account_ids: HashSet::new(),
             batch_size,
             processing_threads: OPTIMAL_SCANNING_THREADS,
             scanning_offset: DEFAULT_SCANNING_OFFSET_DAYS,
@@ -122,6 +189,7 @@ impl Scanner {
             cancel_token: None,
             required_confirmations,
             webhook_config: None,
+            block_cache_path: None,
         }
     }
 
@@ -137,6 +205,16 @@ impl Scanner {
         self
     }
 
+    /// Skips the given accounts, e.g. accounts whose scanning has been paused.
+    ///
+    /// # Arguments
+    ///
+    /// * `account_ids` - Database IDs of the accounts to skip
+    pub fn exclude_accounts(mut self, account_ids: HashSet<i64>) -> Self {
+        self.excluded_account_ids = account_ids;
+        self
+    }
+
     /// Sets the scanning mode.
     ///
     /// See [`ScanMode`] for available options.
@@ -258,15 +336,31 @@ impl Scanner {
         self
     }
 
+    /// Enables the on-disk block cache stored at the given path.
+    ///
+    /// Downloaded block data is written to the cache, and later scans of the same
+    /// heights (re-scans, newly imported accounts) are served from it instead of the
+    /// base node. See [`SqliteBlockCache`].
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to the SQLite cache file, created if it does not exist
+    pub fn block_cache_path(mut self, path: PathBuf) -> Self {
+        self.block_cache_path = Some(path);
+        self
+    }
+
     async fn run_internal<E: EventSender + Clone + Send + 'static>(
         self,
         event_sender: E,
     ) -> Result<(Vec<WalletEvent>, bool), ScanError> {
-        let pool = db::init_db(self.database_file.clone())?;
+        let pool = db::init_db(self.database_file.clone(), self.network)?;
         let conn = pool.get().map_err(|e| ScanError::DbError(e.into()))?;
 
-        let accounts = db::get_accounts(&conn, self.account_name.as_deref())?;
-        let coordinator = ScanCoordinator::new(
+        let mut accounts = db::get_accounts(&conn, self.account_name.as_deref())?;
+        accounts.retain(|account| !self.excluded_account_ids.contains(&account.id));
+        let accounts = self.key_managers(accounts)?;
+        let mut coordinator = ScanCoordinator::new(
             pool,
             self.base_url,
             event_sender,
@@ -277,18 +371,52 @@ impl Scanner {
             self.reorg_check_interval,
             self.batch_size,
         )?;
+        if let Some(path) = &self.block_cache_path {
+            let cache = SqliteBlockCache::open(path)?;
+            coordinator = coordinator.with_block_cache(Arc::new(cache));
+        }
 
         coordinator
-            .run(
-                accounts,
-                &self.password,
-                self.mode,
-                self.scanning_offset,
-                self.cancel_token,
-            )
+            .run(accounts, self.mode, self.scanning_offset, self.cancel_token)
             .await
     }
 
+    /// Pairs every account that can be scanned with a view-only key manager.
+    fn key_managers(&self, accounts: Vec<AccountRow>) -> Result<Vec<(AccountRow, KeyManager)>, ScanError> {
+        let keyring = match &self.keys {
+            ScanKeys::Keyring(keyring) => keyring.clone(),
+            ScanKeys::Password(password) => {
+                let keyring = Keyring::new();
+                let locked = keyring.unlock_accounts(&accounts, password)?;
+                if let Some(account) = locked.first()
+                    && (self.account_name.is_some() || locked.len() == accounts.len())
+                {
+                    return Err(WalletDbError::DecryptionFailed(format!(
+                        "The password does not decrypt account '{}'",
+                        account.friendly_name
+                    ))
+                    .into());
+                }
+                for account in locked {
+                    warn!(
+                        account = &*account.friendly_name;
+                        "Skipping account encrypted with a different password"
+                    );
+                }
+                keyring
+            },
+        };
+
+        let mut scannable = Vec::with_capacity(accounts.len());
+        for account in accounts {
+            match keyring.scan_key_manager(account.id)? {
+                Some(key_manager) => scannable.push((account, key_manager)),
+                None => debug!(account = &*account.friendly_name; "Skipping locked account without a cached view key"),
+            }
+        }
+        Ok(scannable)
+    }
+
     /// Runs the scanner without real-time event streaming.
     ///
     /// # Returns
@@ -305,6 +433,17 @@ impl Scanner {
         self.run_internal(NoopEventSender).await
     }
 
+    /// Runs the scanner, forwarding real-time events to the given [`EventSender`].
+    ///
+    /// Useful when events should be consumed synchronously, e.g. by a
+    /// [`ScanProgressStore`](crate::scan::ScanProgressStore) shared with the API.
+    pub async fn run_with_event_sender<E: EventSender + Clone + Send + 'static>(
+        self,
+        event_sender: E,
+    ) -> Result<(Vec<WalletEvent>, bool), ScanError> {
+        self.run_internal(event_sender).await
+    }
+
     /// Runs the scanner with real-time event streaming.
     ///
     /// Returns an unbounded channel receiver for events and a future that
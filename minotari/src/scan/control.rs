@@ -0,0 +1,205 @@
+//! Runtime control of the daemon's scanner.
+//!
+//! The daemon runs one scan cycle after another. [`ScanControl`] is shared between the daemon
+//! and the API so that operators can pause or resume scanning of individual accounts, schedule
+//! re-scans and add or delete accounts without stopping the service.
+//!
+//! Requests are never applied while a scan is writing to the database. Instead, every request
+//! cancels the current cycle through its [`CancellationToken`]; the daemon then applies pending
+//! deletions and re-scans (see [`rollback_from_height`](crate::scan::reorg::rollback_from_height))
+//! and starts a fresh cycle that picks up new accounts and skips paused ones.
+//!
+//! Control state is held in memory and does not survive a daemon restart.
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex},
+};
+
+use tokio_util::sync::CancellationToken;
+
+#[derive(Debug, Default)]
+struct ScanControlState {
+    paused_accounts: HashSet<i64>,
+    pending_rescans: HashMap<i64, u64>,
+    pending_deletions: HashSet<i64>,
+    cycle_token: CancellationToken,
+}
+
+/// Shared handle for pausing, resuming and re-scanning accounts in a running daemon.
+#[derive(Debug, Clone, Default)]
+pub struct ScanControl {
+    state: Arc<Mutex<ScanControlState>>,
+}
+
+impl ScanControl {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, ScanControlState> {
+        self.state.lock().expect("scan control lock should not be poisoned")
+    }
+
+    /// Starts a new scan cycle, returning the token that interrupts it.
+    pub fn start_cycle(&self) -> CancellationToken {
+        let token = CancellationToken::new();
+        self.lock().cycle_token = token.clone();
+        token
+    }
+
+    /// Schedules a re-scan of the account from `from_height` and interrupts the running cycle.
+    ///
+    /// If a re-scan is already pending for the account, the lower of the two heights wins.
+    /// Returns the height the re-scan will start from.
+    pub fn request_rescan(&self, account_id: i64, from_height: u64) -> u64 {
+        let mut state = self.lock();
+        let height = state
+            .pending_rescans
+            .entry(account_id)
+            .and_modify(|height| *height = (*height).min(from_height))
+            .or_insert(from_height);
+        let height = *height;
+        state.cycle_token.cancel();
+        height
+    }
+
+    /// Removes and returns all pending re-scans as `(account_id, from_height)` pairs.
+    pub fn take_pending_rescans(&self) -> Vec<(i64, u64)> {
+        self.lock().pending_rescans.drain().collect()
+    }
+
+    /// Returns the height a pending re-scan of the account will start from, if any.
+    pub fn pending_rescan(&self, account_id: i64) -> Option<u64> {
+        self.lock().pending_rescans.get(&account_id).copied()
+    }
+
+    /// Interrupts the running cycle so that the next one picks up newly created accounts.
+    pub fn request_new_cycle(&self) {
+        self.lock().cycle_token.cancel();
+    }
+
+    /// Schedules deletion of the account and interrupts the running cycle.
+    ///
+    /// Pending re-scans of the account are dropped. Returns `false` if a deletion was already pending.
+    pub fn request_deletion(&self, account_id: i64) -> bool {
+        let mut state = self.lock();
+        state.pending_rescans.remove(&account_id);
+        let changed = state.pending_deletions.insert(account_id);
+        state.cycle_token.cancel();
+        changed
+    }
+
+    /// Removes and returns the ids of all accounts scheduled for deletion.
+    ///
+    /// The accounts are also resumed, so a new account reusing an id is not paused.
+    pub fn take_pending_deletions(&self) -> Vec<i64> {
+        let mut state = self.lock();
+        let deletions: Vec<i64> = state.pending_deletions.drain().collect();
+        for account_id in &deletions {
+            state.paused_accounts.remove(account_id);
+        }
+        deletions
+    }
+
+    pub fn is_deletion_pending(&self, account_id: i64) -> bool {
+        self.lock().pending_deletions.contains(&account_id)
+    }
+
+    /// Pauses scanning of the account. Returns `false` if it was already paused.
+    pub fn pause(&self, account_id: i64) -> bool {
+        let mut state = self.lock();
+        let changed = state.paused_accounts.insert(account_id);
+        if changed {
+            state.cycle_token.cancel();
+        }
+        changed
+    }
+
+    /// Resumes scanning of the account. Returns `false` if it was not paused.
+    pub fn resume(&self, account_id: i64) -> bool {
+        let mut state = self.lock();
+        let changed = state.paused_accounts.remove(&account_id);
+        if changed {
+            state.cycle_token.cancel();
+        }
+        changed
+    }
+
+    pub fn is_paused(&self, account_id: i64) -> bool {
+        self.lock().paused_accounts.contains(&account_id)
+    }
+
+    pub fn paused_accounts(&self) -> HashSet<i64> {
+        self.lock().paused_accounts.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rescan_requests_keep_lowest_height() {
+        let control = ScanControl::new();
+        assert_eq!(control.request_rescan(1, 500), 500);
+        assert_eq!(control.request_rescan(1, 800), 500);
+        assert_eq!(control.request_rescan(1, 100), 100);
+        control.request_rescan(2, 0);
+
+        let mut pending = control.take_pending_rescans();
+        pending.sort();
+        assert_eq!(pending, vec![(1, 100), (2, 0)]);
+        assert!(control.take_pending_rescans().is_empty());
+    }
+
+    #[test]
+    fn test_requests_cancel_current_cycle() {
+        let control = ScanControl::new();
+
+        let token = control.start_cycle();
+        assert!(control.pause(1));
+        assert!(token.is_cancelled());
+
+        let token = control.start_cycle();
+        assert!(!control.pause(1));
+        assert!(!token.is_cancelled(), "pausing twice must not interrupt the scan");
+        assert!(control.resume(1));
+        assert!(token.is_cancelled());
+
+        let token = control.start_cycle();
+        control.request_rescan(1, 10);
+        assert!(token.is_cancelled());
+    }
+
+    #[test]
+    fn test_deletion_requests() {
+        let control = ScanControl::new();
+        control.pause(1);
+        control.request_rescan(1, 10);
+
+        let token = control.start_cycle();
+        assert!(control.request_deletion(1));
+        assert!(!control.request_deletion(1));
+        assert!(token.is_cancelled());
+        assert!(control.is_deletion_pending(1));
+        assert!(control.take_pending_rescans().is_empty());
+
+        assert_eq!(control.take_pending_deletions(), vec![1]);
+        assert!(!control.is_deletion_pending(1));
+        assert!(!control.is_paused(1));
+        assert!(control.take_pending_deletions().is_empty());
+    }
+
+    #[test]
+    fn test_pause_and_resume() {
+        let control = ScanControl::new();
+        control.pause(1);
+        control.pause(2);
+        assert!(control.is_paused(1));
+        assert!(control.resume(1));
+        assert!(!control.is_paused(1));
+        assert!(!control.resume(1));
+        assert_eq!(control.paused_accounts(), HashSet::from([2]));
+    }
+}
@@ -1,5 +1,5 @@
-use log::info;
-use minotari_scanning::{HttpBlockchainScanner, scanning::BlockchainScanner};
+use log::{info, warn};
+use minotari_scanning::{BlockCache, HttpBlockchainScanner, scanning::BlockchainScanner};
 use std::{collections::VecDeque, sync::Arc};
 use tari_common_types::{seeds::cipher_seed::BIRTHDAY_GENESIS_FROM_UNIX_EPOCH, types::PrivateKey};
 use tari_transaction_components::key_manager::KeyManager;
@@ -9,12 +9,13 @@ use crate::{
     PauseReason, ScanStatusEvent,
     db::{AccountRow, SqlitePool},
     http::WalletHttpClient,
-    models::WalletEvent,
+    models::{WalletEvent, WalletEventType},
     scan::{
         DisplayedTransactionsEvent, ReorgDetectedEvent, ScanError, ScanMode, ScanRetryConfig, TransactionsUpdatedEvent,
         block_processor::BlockProcessor,
         config::OPTIMAL_SCANNING_THREADS,
         events::{EventSender, ProcessingEvent},
+        progress::ScanProgressTracker,
         reorg,
         scan_db_handler::ScanDbHandler,
         scanner_state_manager::ScannerStateManager,
@@ -34,6 +35,7 @@ pub(crate) struct AccountSyncTarget {
     pub(crate) view_key: PrivateKey,
     pub(crate) next_block_to_scan: u64,
     pub(crate) transaction_monitor: TransactionMonitor,
+    pub(crate) progress: ScanProgressTracker,
 }
 
 pub struct ScanCoordinator<E: EventSender> {
@@ -47,6 +49,7 @@ pub struct ScanCoordinator<E: EventSender> {
     processing_threads: usize,
     reorg_check_interval: u64,
     batch_size: u64,
+    block_cache: Option<Arc<dyn BlockCache>>,
 }
 
 impl<E: EventSender + Clone + Send + 'static> ScanCoordinator<E> {
@@ -79,37 +82,41 @@ impl<E: EventSender + Clone + Send + 'static> ScanCoordinator<E> {
             processing_threads,
             reorg_check_interval,
             batch_size,
+            block_cache: None,
         })
     }
 
+    /// Serves previously downloaded blocks from the given cache and keeps it in sync with reorgs.
+    pub fn with_block_cache(mut self, cache: Arc<dyn BlockCache>) -> Self {
+        self.block_cache = Some(cache);
+        self
+    }
+
     /// The main entry point for the multi-account scan.
+    ///
+    /// Each account is scanned with the given key manager, which only needs its view key.
     pub async fn run(
         &self,
-        accounts: Vec<AccountRow>,
-        password: &str,
+        accounts: Vec<(AccountRow, KeyManager)>,
         mode: ScanMode,
         scanning_offset: u64,
         cancel_token: Option<CancellationToken>,
     ) -> Result<(Vec<WalletEvent>, bool), ScanError> {
-        if accounts.is_empty() {
+        let Some((_, first_km)) = accounts.first() else {
             return Ok((Vec::new(), false));
-        }
+        };
 
         let mut conn = self.pool.get().map_err(|e| ScanError::DbError(e.into()))?;
 
-        let first_km = accounts
-            .first()
-            .expect("is already checked")
-            .get_key_manager(password)?;
-        let mut shared_reorg_scanner = self.create_reorg_scanner(first_km).await?;
+        let mut shared_reorg_scanner = self.create_reorg_scanner(first_km.clone()).await?;
 
         let mut sync_targets = Vec::with_capacity(accounts.len());
 
-        for account in accounts {
+        for (account, key_manager) in accounts {
             let target = self
                 .prepare_target(
                     account,
-                    password,
+                    key_manager,
                     &self.client,
                     scanning_offset,
                     &mut conn,
@@ -126,17 +133,19 @@ impl<E: EventSender + Clone + Send + 'static> ScanCoordinator<E> {
     async fn prepare_target(
         &self,
         account: AccountRow,
-        password: &str,
+        key_manager: KeyManager,
         wallet_client: &WalletHttpClient,
         scanning_offset: u64,
         conn: &mut rusqlite::Connection,
         scanner: &mut HttpBlockchainScanner<KeyManager>,
     ) -> Result<AccountSyncTarget, ScanError> {
-        let key_manager = account.get_key_manager(password)?;
         let view_key = key_manager.get_private_view_key();
         let reorg_result = reorg::handle_reorgs(scanner, conn, account.id, self.webhook_config.clone())
             .await
             .map_err(ScanError::Fatal)?;
+        if let Some(info) = &reorg_result.reorg_information {
+            self.invalidate_block_cache(info.rolled_back_from_height);
+        }
 
         let mut next_block = reorg_result.resume_height;
 
@@ -162,6 +171,7 @@ impl<E: EventSender + Clone + Send + 'static> ScanCoordinator<E> {
             view_key,
             next_block_to_scan: next_block,
             transaction_monitor,
+            progress: ScanProgressTracker::default(),
         })
     }
 
@@ -191,7 +201,14 @@ impl<E: EventSender + Clone + Send + 'static> ScanCoordinator<E> {
         let mut total_scanned_globally = 0;
         let mut blocks_since_reorg_check = 0;
 
-        let mut state_manager = ScannerStateManager::new();
+        let mut state_manager = ScannerStateManager::new(self.block_cache.clone());
+        for target in &targets {
+            self.event_sender
+                .send(ProcessingEvent::ScanStatus(ScanStatusEvent::Started {
+                    account_id: target.account.id,
+                    from_height: target.next_block_to_scan,
+                }));
+        }
         loop {
             if let Some(token) = &cancel_token
                 && token.is_cancelled()
@@ -241,6 +258,13 @@ impl<E: EventSender + Clone + Send + 'static> ScanCoordinator<E> {
                     &self.retry_config,
                 )
                 .await?;
+
+            // The tip is only used for progress reporting, so a failure here must not abort the scan.
+            let tip_height = scanner.get_tip_info().await.ok().map(|tip| tip.best_block_height);
+            for target in targets.iter_mut() {
+                target.progress.set_tip_height(tip_height);
+            }
+
             let mut utxo_stream = scanner
                 .scan_blocks(&scanner_config)
                 .await
@@ -248,6 +272,12 @@ impl<E: EventSender + Clone + Send + 'static> ScanCoordinator<E> {
             let mut is_batch_empty = false;
             let mut max_new_height_in_batch = 0;
             while let Some(response) = utxo_stream.recv().await {
+                // A single stream can cover thousands of blocks, so honour cancellation between batches too.
+                if let Some(token) = &cancel_token
+                    && token.is_cancelled()
+                {
+                    return self.handle_pause(&targets, PauseReason::Cancelled, all_events);
+                }
                 let scanned_blocks = response.map_err(|e| ScanError::Intermittent(e.to_string()))?;
                 //let (scanned_blocks, mut more_blocks) = self.scan_blocks_with_timeout(scanner, &scanner_config).await?;
                 let new_blocks_count = scanned_blocks.len() as u64;
@@ -286,9 +316,16 @@ impl<E: EventSender + Clone + Send + 'static> ScanCoordinator<E> {
                             target.next_block_to_scan,
                         )
                         .await?;
+                    let outputs_found = events
+                        .iter()
+                        .filter(|event| matches!(event.event_type, WalletEventType::OutputDetected { .. }))
+                        .count() as u64;
+                    target.progress.add_outputs_found(outputs_found);
                     Self::push_events_with_limit(&mut all_events, events, max_buffered_events);
                     if let Some(last) = shared_blocks.last() {
                         target.next_block_to_scan = last.height + 1;
+                        target.progress.record_height(last.height);
+                        self.emit_progress_event(target, last.height, total_scanned_globally + new_blocks_count);
                     }
                     if let Some(last) = shared_blocks.last()
                         && last.height >= target.next_block_to_scan
@@ -311,13 +348,6 @@ impl<E: EventSender + Clone + Send + 'static> ScanCoordinator<E> {
                         self.emit_monitor_events(target.account.id, last.height, monitor_res);
 
                         db_handler.prune_tips(target.account.id, last.height).await?;
-
-                        self.event_sender
-                            .send(ProcessingEvent::ScanStatus(ScanStatusEvent::Progress {
-                                account_id: target.account.id,
-                                current_height: last.height,
-                                blocks_scanned: total_scanned_globally,
-                            }));
                     }
                 }
 
@@ -396,6 +426,7 @@ impl<E: EventSender + Clone + Send + 'static> ScanCoordinator<E> {
                 .map_err(ScanError::Fatal)?;
 
             if let Some(info) = res.reorg_information {
+                self.invalidate_block_cache(info.rolled_back_from_height);
                 self.emit_reorg_event(target.account.id, info, res.resume_height);
                 target.next_block_to_scan = res.resume_height;
             }
@@ -413,6 +444,8 @@ impl<E: EventSender + Clone + Send + 'static> ScanCoordinator<E> {
     ) -> Result<(), ScanError> {
         for target in targets.iter_mut() {
             target.next_block_to_scan = target.next_block_to_scan.saturating_sub(1);
+            // Idle time between poll cycles must not drag down the measured throughput.
+            target.progress.reset_throughput();
 
             self.event_sender
                 .send(ProcessingEvent::ScanStatus(ScanStatusEvent::Waiting {
@@ -450,6 +483,29 @@ impl<E: EventSender + Clone + Send + 'static> ScanCoordinator<E> {
         Ok((events.into_iter().collect(), true))
     }
 
+    fn invalidate_block_cache(&self, height: u64) {
+        if let Some(cache) = &self.block_cache
+            && let Err(e) = cache.invalidate_from_height(height)
+        {
+            // Stale entries are also caught when cached blocks are verified, so this is not fatal.
+            warn!(height = height, error:% = e; "Failed to invalidate block cache after reorg");
+        }
+    }
+
+    fn emit_progress_event(&self, target: &AccountSyncTarget, current_height: u64, blocks_scanned: u64) {
+        let tip_height = target.progress.tip_height().map(|tip| tip.max(current_height));
+        self.event_sender
+            .send(ProcessingEvent::ScanStatus(ScanStatusEvent::Progress {
+                account_id: target.account.id,
+                current_height,
+                blocks_scanned,
+                tip_height,
+                blocks_per_second: target.progress.blocks_per_second(),
+                outputs_found: target.progress.outputs_found(),
+                eta: target.progress.eta(current_height),
+            }));
+    }
+
     fn emit_monitor_events(&self, account_id: i64, height: u64, res: MonitoringResult) {
         if !res.updated_displayed_transactions.is_empty() {
             self.event_sender
@@ -0,0 +1,408 @@
+//! Live fan-out of scanner events to API subscribers.
+//!
+//! [`EventBroadcaster`] is an [`EventSender`] that turns every [`ProcessingEvent`] into a
+//! JSON-friendly [`StreamEvent`], numbers it and publishes it to any number of subscribers.
+//! The daemon feeds it from its scan loop and the API exposes it as Server-Sent Events and
+//! WebSocket streams.
+//!
+//! The most recent events are kept in memory so that clients reconnecting with the id of the
+//! last event they saw receive everything they missed. Ids have the form `<epoch>-<sequence>`,
+//! where the epoch is chosen randomly when the daemon starts, so ids from before a restart are
+//! never mistaken for current ones. A client whose id cannot be resumed, because it belongs to
+//! another epoch, is ahead of the latest event or is older than the buffer, receives a
+//! [`STREAM_RESET_EVENT`] followed by all buffered events, as it may have missed others.
+
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+};
+
+use serde::Serialize;
+use serde_json::{Value, json};
+use tokio::sync::broadcast;
+use tokio_util::sync::CancellationToken;
+
+use crate::scan::events::{EventSender, PauseReason, ProcessingEvent, ScanStatusEvent};
+
+/// Number of recent events kept for clients resuming a stream.
+const REPLAY_BUFFER_SIZE: usize = 1000;
+
+/// Capacity of the live channel. Subscribers falling further behind are disconnected.
+const CHANNEL_CAPACITY: usize = 1024;
+
+/// Type of the event sent first when a stream cannot be resumed from the requested id.
+pub const STREAM_RESET_EVENT: &str = "StreamReset";
+
+/// A scanner event as delivered to stream subscribers.
+///
+/// # JSON Example
+///
+/// ```json
+/// {
+///   "id": "5f3a9c1e-42",
+///   "account_id": 1,
+///   "event_type": "ScanStatus",
+///   "data": { "status": "Progress", "current_height": 150120, "tip_height": 150500 }
+/// }
+/// ```
+#[derive(Debug, Clone, Serialize, utoipa::ToSchema)]
+pub struct StreamEvent {
+    /// `<epoch>-<sequence>`, where the sequence increases by one for every event published since
+    /// the daemon started
+    pub id: String,
+    /// Account the event belongs to
+    pub account_id: i64,
+    /// `BlockProcessed`, `ScanStatus`, `TransactionsReady`, `TransactionsUpdated`, `ReorgDetected`
+    /// or `StreamReset`
+    pub event_type: String,
+    /// Event details, depending on `event_type`
+    #[schema(value_type = Object)]
+    pub data: Value,
+}
+
+/// A subscription returned by [`EventBroadcaster::subscribe`].
+pub struct EventSubscription {
+    /// Buffered events newer than the requested id, oldest first
+    pub replay: Vec<Arc<StreamEvent>>,
+    /// Set when the requested id could not be resumed, to the id to announce the reset under.
+    /// The replay then holds all buffered events.
+    pub reset_id: Option<String>,
+    /// Live events published after the subscription was created
+    pub receiver: broadcast::Receiver<Arc<StreamEvent>>,
+    /// Cancelled when the broadcaster is closed; streams should end then
+    pub closed: CancellationToken,
+}
+
+#[derive(Debug)]
+struct BroadcasterState {
+    epoch: String,
+    next_seq: u64,
+    recent: VecDeque<(u64, Arc<StreamEvent>)>,
+}
+
+/// Shared [`EventSender`] publishing scanner events to stream subscribers.
+#[derive(Debug, Clone)]
+pub struct EventBroadcaster {
+    state: Arc<Mutex<BroadcasterState>>,
+    sender: broadcast::Sender<Arc<StreamEvent>>,
+    closed: CancellationToken,
+}
+
+impl Default for EventBroadcaster {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EventBroadcaster {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+        Self {
+            state: Arc::new(Mutex::new(BroadcasterState {
+                epoch: format!("{:08x}", rand::random::<u32>()),
+                next_seq: 1,
+                recent: VecDeque::with_capacity(REPLAY_BUFFER_SIZE),
+            })),
+            sender,
+            closed: CancellationToken::new(),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BroadcasterState> {
+        self.state
+            .lock()
+            .expect("event broadcaster lock should not be poisoned")
+    }
+
+    /// Publishes an event, assigning it the next id.
+    pub fn publish(&self, account_id: i64, event_type: &str, data: Value) -> String {
+        let mut state = self.lock();
+        let seq = state.next_seq;
+        let event = Arc::new(StreamEvent {
+            id: format!("{}-{}", state.epoch, seq),
+            account_id,
+            event_type: event_type.to_string(),
+            data,
+        });
+        state.next_seq += 1;
+        if state.recent.len() == REPLAY_BUFFER_SIZE {
+            state.recent.pop_front();
+        }
+        state.recent.push_back((seq, event.clone()));
+        // Sending fails only when nobody is subscribed
+        let _unused = self.sender.send(event.clone());
+        event.id.clone()
+    }
+
+    /// Subscribes to all events, replaying buffered events newer than `last_event_id`.
+    ///
+    /// If `last_event_id` cannot be resumed, all buffered events are replayed and
+    /// [`EventSubscription::reset_id`] is set. The replay and the live receiver are taken under
+    /// the same lock, so no event is missed or delivered twice between them.
+    pub fn subscribe(&self, last_event_id: Option<&str>) -> EventSubscription {
+        let state = self.lock();
+        let oldest_seq = state.recent.front().map_or(state.next_seq, |(seq, _)| *seq);
+        let (replay_after, reset_id) = match last_event_id.map(parse_event_id) {
+            None => (state.next_seq, None),
+            // The id is from this epoch and every event after it is still buffered
+            Some(Some((epoch, seq))) if epoch == state.epoch && seq < state.next_seq && seq + 1 >= oldest_seq => {
+                (seq, None)
+            },
+            // Another epoch, an id ahead of the latest event, one older than the buffer or not an id
+            Some(_) => {
+                let replay_after = oldest_seq.saturating_sub(1);
+                (replay_after, Some(format!("{}-{}", state.epoch, replay_after)))
+            },
+        };
+        let replay = state
+            .recent
+            .iter()
+            .filter(|(seq, _)| *seq > replay_after)
+            .map(|(_, event)| event.clone())
+            .collect();
+        EventSubscription {
+            replay,
+            reset_id,
+            receiver: self.sender.subscribe(),
+            closed: self.closed.clone(),
+        }
+    }
+
+    /// Ends all open streams. Long-lived connections would otherwise keep the API server
+    /// from shutting down gracefully.
+    pub fn close(&self) {
+        self.closed.cancel();
+    }
+}
+
+/// Splits an event id into its epoch and sequence number.
+fn parse_event_id(id: &str) -> Option<(&str, u64)> {
+    let (epoch, seq) = id.trim().rsplit_once('-')?;
+    Some((epoch, seq.parse().ok()?))
+}
+
+impl EventSender for EventBroadcaster {
+    fn send(&self, event: ProcessingEvent) -> bool {
+        let (account_id, event_type, data) = to_stream_payload(&event);
+        self.publish(account_id, event_type, data);
+        true
+    }
+}
+
+fn to_stream_payload(event: &ProcessingEvent) -> (i64, &'static str, Value) {
+    match event {
+        ProcessingEvent::BlockProcessed(e) => (
+            e.account_id,
+            "BlockProcessed",
+            json!({
+                "height": e.height,
+                "block_hash": hex::encode(&e.block_hash),
+                "outputs_detected": e.outputs_detected.iter().map(|o| json!({
+                    "height": o.height,
+                    "value": o.output.value().as_u64(),
+                })).collect::<Vec<_>>(),
+                "inputs_spent": e.inputs_spent.iter().map(|i| json!({
+                    "output_id": i.output_id,
+                    "value": i.output.value().as_u64(),
+                })).collect::<Vec<_>>(),
+                "balance_changes": e.balance_changes.iter().map(|c| json!({
+                    "credit": c.credit.as_u64(),
+                    "debit": c.debit.as_u64(),
+                    "description": c.description,
+                })).collect::<Vec<_>>(),
+            }),
+        ),
+        ProcessingEvent::ScanStatus(status) => {
+            let (account_id, data) = scan_status_payload(status);
+            (account_id, "ScanStatus", data)
+        },
+        ProcessingEvent::TransactionsReady(e) => (
+            e.account_id,
+            "TransactionsReady",
+            json!({
+                "transactions": e.transactions,
+                "block_height": e.block_height,
+                "is_initial_sync": e.is_initial_sync,
+            }),
+        ),
+        ProcessingEvent::TransactionsUpdated(e) => (
+            e.account_id,
+            "TransactionsUpdated",
+            json!({ "transactions": e.updated_transactions }),
+        ),
+        ProcessingEvent::ReorgDetected(e) => (
+            e.account_id,
+            "ReorgDetected",
+            json!({
+                "reorg_from_height": e.reorg_from_height,
+                "new_height": e.new_height,
+                "blocks_rolled_back": e.blocks_rolled_back,
+                "invalidated_output_hashes": e.invalidated_output_hashes
+                    .iter()
+                    .map(|h| hex::encode(h.as_slice()))
+                    .collect::<Vec<_>>(),
+                "cancelled_transaction_ids": e.cancelled_transaction_ids,
+                "reorganized_transactions": e.reorganized_displayed_transactions,
+            }),
+        ),
+    }
+}
+
+fn scan_status_payload(status: &ScanStatusEvent) -> (i64, Value) {
+    match status {
+        ScanStatusEvent::Started {
+            account_id,
+            from_height,
+        } => (*account_id, json!({ "status": "Started", "from_height": from_height })),
+        ScanStatusEvent::Progress {
+            account_id,
+            current_height,
+            blocks_scanned,
+            tip_height,
+            blocks_per_second,
+            outputs_found,
+            eta,
+        } => (
+            *account_id,
+            json!({
+                "status": "Progress",
+                "current_height": current_height,
+                "blocks_scanned": blocks_scanned,
+                "tip_height": tip_height,
+                "blocks_per_second": blocks_per_second,
+                "outputs_found": outputs_found,
+                "eta_seconds": eta.map(|eta| eta.as_secs()),
+            }),
+        ),
+        ScanStatusEvent::MoreBlocksAvailable {
+            account_id,
+            last_scanned_height,
+        } => (
+            *account_id,
+            json!({ "status": "MoreBlocksAvailable", "last_scanned_height": last_scanned_height }),
+        ),
+        ScanStatusEvent::Completed {
+            account_id,
+            final_height,
+            total_blocks_scanned,
+        } => (
+            *account_id,
+            json!({
+                "status": "Completed",
+                "final_height": final_height,
+                "total_blocks_scanned": total_blocks_scanned,
+            }),
+        ),
+        ScanStatusEvent::Waiting { account_id, resume_in } => (
+            *account_id,
+            json!({ "status": "Waiting", "resume_in_seconds": resume_in.as_secs() }),
+        ),
+        ScanStatusEvent::Paused {
+            account_id,
+            last_scanned_height,
+            reason,
+        } => {
+            let reason = match reason {
+                PauseReason::MaxBlocksReached { limit } => json!({ "MaxBlocksReached": { "limit": limit } }),
+                PauseReason::Cancelled => json!("Cancelled"),
+            };
+            (
+                *account_id,
+                json!({
+                    "status": "Paused",
+                    "last_scanned_height": last_scanned_height,
+                    "reason": reason,
+                }),
+            )
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seqs(events: &[Arc<StreamEvent>]) -> Vec<u64> {
+        events
+            .iter()
+            .filter_map(|event| parse_event_id(&event.id).map(|(_, seq)| seq))
+            .collect()
+    }
+
+    #[test]
+    fn test_subscribe_replays_events_after_last_id() {
+        let broadcaster = EventBroadcaster::new();
+        for height in 0..5 {
+            broadcaster.send(ProcessingEvent::ScanStatus(ScanStatusEvent::Started {
+                account_id: 1,
+                from_height: height,
+            }));
+        }
+        let ids = broadcaster
+            .subscribe(Some("not an id"))
+            .replay
+            .iter()
+            .map(|event| event.id.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(ids.len(), 5);
+
+        let live_only = broadcaster.subscribe(None);
+        assert!(live_only.replay.is_empty());
+        assert!(live_only.reset_id.is_none());
+
+        let resumed = broadcaster.subscribe(ids.get(2).map(String::as_str));
+        assert_eq!(seqs(&resumed.replay), vec![4, 5]);
+        assert!(resumed.reset_id.is_none());
+        assert!(broadcaster.subscribe(ids.last().map(String::as_str)).replay.is_empty());
+    }
+
+    #[test]
+    fn test_unresumable_ids_reset_the_stream() {
+        let broadcaster = EventBroadcaster::new();
+        let first_id = broadcaster.publish(1, "ScanStatus", json!({}));
+        broadcaster.publish(1, "ScanStatus", json!({}));
+        let (epoch, _) = parse_event_id(&first_id).unwrap();
+
+        // An id from before a restart, one ahead of the latest event and one that is not an id
+        let other_epoch = if epoch == "00000000" { "00000001" } else { "00000000" };
+        for id in [format!("{}-1", other_epoch), format!("{}-500", epoch), "42".to_string()] {
+            let subscription = broadcaster.subscribe(Some(&id));
+            assert_eq!(seqs(&subscription.replay), vec![1, 2], "{}", id);
+            let reset_id = subscription.reset_id.unwrap();
+            assert_eq!(reset_id, format!("{}-0", epoch));
+
+            // Resuming after the reset replays the same events without another reset
+            let resumed = broadcaster.subscribe(Some(&reset_id));
+            assert_eq!(seqs(&resumed.replay), vec![1, 2]);
+            assert!(resumed.reset_id.is_none());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_live_events_follow_replay() {
+        let broadcaster = EventBroadcaster::new();
+        let first_id = broadcaster.publish(1, "ScanStatus", json!({}));
+        let (epoch, _) = parse_event_id(&first_id).unwrap();
+        let mut subscription = broadcaster.subscribe(Some(&format!("{}-0", epoch)));
+        let second_id = broadcaster.publish(2, "ScanStatus", json!({}));
+
+        assert_eq!(seqs(&subscription.replay), vec![1]);
+        let live = subscription.receiver.recv().await.unwrap();
+        assert_eq!((&*live.id, live.account_id), (&*second_id, 2));
+    }
+
+    #[test]
+    fn test_replay_buffer_is_bounded() {
+        let broadcaster = EventBroadcaster::new();
+        let first_id = broadcaster.publish(1, "ScanStatus", json!({}));
+        for _ in 1..REPLAY_BUFFER_SIZE + 10 {
+            broadcaster.publish(1, "ScanStatus", json!({}));
+        }
+
+        // Events after the first one have partly left the buffer
+        let subscription = broadcaster.subscribe(Some(&first_id));
+        assert_eq!(subscription.replay.len(), REPLAY_BUFFER_SIZE);
+        assert_eq!(seqs(&subscription.replay).first(), Some(&11));
+        assert!(subscription.reset_id.is_some());
+    }
+}
@@ -131,6 +131,14 @@ pub enum ScanStatusEvent {
         account_id: i64,
         current_height: u64,
         blocks_scanned: u64,
+        /// Chain tip height reported by the base node, if known.
+        tip_height: Option<u64>,
+        /// Scan throughput measured over a sliding window.
+        blocks_per_second: f64,
+        /// Number of wallet outputs detected for the account during this scan.
+        outputs_found: u64,
+        /// Estimated time until the scan reaches `tip_height`.
+        eta: Option<Duration>,
     },
     MoreBlocksAvailable {
         account_id: i64,
@@ -198,3 +206,24 @@ impl EventSender for ChannelEventSender {
         self.sender.send(event).is_ok()
     }
 }
+
+/// [`EventSender`] forwarding every event to two senders, e.g. a progress store and a broadcaster.
+#[derive(Debug, Clone)]
+pub struct TeeEventSender<A, B> {
+    first: A,
+    second: B,
+}
+
+impl<A: EventSender, B: EventSender> TeeEventSender<A, B> {
+    pub fn new(first: A, second: B) -> Self {
+        Self { first, second }
+    }
+}
+
+impl<A: EventSender, B: EventSender> EventSender for TeeEventSender<A, B> {
+    fn send(&self, event: ProcessingEvent) -> bool {
+        let first = self.first.send(event.clone());
+        let second = self.second.send(event);
+        first && second
+    }
+}
@@ -33,7 +33,8 @@
 //! The scanning system can emit events through the [`EventSender`] trait:
 //!
 //! - [`ProcessingEvent::BlockProcessed`] - Emitted after each block is processed
-//! - [`ProcessingEvent::ScanStatus`] - Progress updates and status changes
+//! - [`ProcessingEvent::ScanStatus`] - Progress updates and status changes, including
+//!   tip height, throughput and ETA (see [`progress`])
 //! - [`ProcessingEvent::TransactionsReady`] - Newly detected transactions
 //! - [`ProcessingEvent::ReorgDetected`] - Chain reorganization detected and handled
 //!
@@ -44,7 +45,7 @@
 //! use std::time::Duration;
 //!
 //! // Create a scanner for continuous monitoring
-//! let scanner = Scanner::new("password", "http://localhost:18142", "wallet.db", 100)
+//! let scanner = Scanner::new("password", "http://localhost:18142", "wallet.db", Network::MainNet, 100, 3)
 //!     .account("default")
 //!     .mode(ScanMode::Continuous { poll_interval: Duration::from_secs(30) })
 //!     .processing_threads(4);
@@ -74,6 +75,14 @@
 //! 4. Balance changes are reversed
 //! 5. Scanning resumes from the fork point
 //!
+//! # Block Cache
+//!
+//! Downloaded block data can optionally be kept in a [`SqliteBlockCache`] (see
+//! [`Scanner::block_cache_path`]). Re-scans and scans of newly imported accounts are then
+//! served from disk for every height that has been fetched before. Each cached block is checked
+//! against the header of the current chain at its height before use, and the cache is dropped
+//! from the first block that no longer matches.
+//!
 //! # Error Handling
 //!
 //! The module distinguishes between:
@@ -84,9 +93,13 @@
 //!
 //! Retry behavior is configurable via [`ScanRetryConfig`].
 
+pub mod block_cache;
 pub mod block_event_accumulator;
 pub mod block_processor;
+pub mod control;
+pub mod event_stream;
 pub mod events;
+pub mod progress;
 pub mod reorg;
 pub mod scan_db_handler;
 pub mod types;
@@ -102,5 +115,9 @@ pub use config::ScanRetryConfig;
 pub use config::ScanTimeoutConfig;
 pub use types::ScanError;
 
+pub use block_cache::SqliteBlockCache;
 pub use block_processor::MemoInfo;
+pub use control::ScanControl;
+pub use event_stream::{EventBroadcaster, StreamEvent};
 pub use events::*;
+pub use progress::{ScanProgressSnapshot, ScanProgressStore, ScanState};
@@ -0,0 +1,385 @@
+//! Scan progress, throughput and ETA tracking.
+//!
+//! The coordinator keeps a [`ScanProgressTracker`] per account and attaches its
+//! figures to every [`ScanStatusEvent::Progress`] it emits. Throughput is measured
+//! over a sliding time window so that the estimate follows the current scan speed
+//! rather than the average since start-up.
+//!
+//! [`ScanProgressStore`] is an [`EventSender`] that keeps the most recent progress
+//! snapshot per account in memory, allowing the daemon API to report progress of
+//! the scan running in the same process.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, RwLock},
+    time::{Duration, Instant},
+};
+
+use crate::scan::events::{EventSender, ProcessingEvent, ScanStatusEvent};
+
+/// Default length of the sliding window used to compute throughput.
+pub const DEFAULT_THROUGHPUT_WINDOW: Duration = Duration::from_secs(30);
+
+/// Maximum number of height samples kept in the sliding window.
+const MAX_THROUGHPUT_SAMPLES: usize = 256;
+
+/// Tracks scanned heights over a sliding time window to derive blocks/second and ETA.
+#[derive(Debug, Clone)]
+pub struct ThroughputTracker {
+    window: Duration,
+    samples: VecDeque<(Instant, u64)>,
+}
+
+impl ThroughputTracker {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            samples: VecDeque::new(),
+        }
+    }
+
+    /// Records that `height` was reached now.
+    pub fn record(&mut self, height: u64) {
+        self.record_at(Instant::now(), height);
+    }
+
+    /// Records that `height` was reached at `now`.
+    pub fn record_at(&mut self, now: Instant, height: u64) {
+        // A rollback invalidates previous samples as heights are no longer monotonic.
+        if let Some((_, last_height)) = self.samples.back()
+            && height < *last_height
+        {
+            self.samples.clear();
+        }
+        self.samples.push_back((now, height));
+
+        // Keep a single sample older than the window so the rate always covers the full window.
+        while self.samples.len() > 2
+            && self
+                .samples
+                .get(1)
+                .is_some_and(|(t, _)| now.saturating_duration_since(*t) >= self.window)
+        {
+            self.samples.pop_front();
+        }
+        while self.samples.len() > MAX_THROUGHPUT_SAMPLES {
+            self.samples.pop_front();
+        }
+    }
+
+    /// Clears all samples, e.g. after the scanner was idle waiting for new blocks.
+    pub fn reset(&mut self) {
+        self.samples.clear();
+    }
+
+    /// Returns the number of blocks scanned per second over the window.
+    pub fn blocks_per_second(&self) -> f64 {
+        let (Some((first_t, first_h)), Some((last_t, last_h))) = (self.samples.front(), self.samples.back()) else {
+            return 0.0;
+        };
+        let elapsed = last_t.saturating_duration_since(*first_t).as_secs_f64();
+        if elapsed <= 0.0 {
+            return 0.0;
+        }
+        last_h.saturating_sub(*first_h) as f64 / elapsed
+    }
+
+    /// Estimates the time needed to scan `remaining_blocks` at the current rate.
+    ///
+    /// Returns `None` while no throughput has been measured yet.
+    pub fn eta(&self, remaining_blocks: u64) -> Option<Duration> {
+        if remaining_blocks == 0 {
+            return Some(Duration::ZERO);
+        }
+        let rate = self.blocks_per_second();
+        if rate <= 0.0 {
+            return None;
+        }
+        Some(Duration::from_secs_f64(remaining_blocks as f64 / rate))
+    }
+}
+
+impl Default for ThroughputTracker {
+    fn default() -> Self {
+        Self::new(DEFAULT_THROUGHPUT_WINDOW)
+    }
+}
+
+/// Per-account progress state maintained by the scan coordinator.
+#[derive(Debug, Clone, Default)]
+pub struct ScanProgressTracker {
+    throughput: ThroughputTracker,
+    outputs_found: u64,
+    tip_height: Option<u64>,
+}
+
+impl ScanProgressTracker {
+    pub fn record_height(&mut self, height: u64) {
+        self.throughput.record(height);
+    }
+
+    pub fn add_outputs_found(&mut self, count: u64) {
+        self.outputs_found = self.outputs_found.saturating_add(count);
+    }
+
+    pub fn set_tip_height(&mut self, tip_height: Option<u64>) {
+        if tip_height.is_some() {
+            self.tip_height = tip_height;
+        }
+    }
+
+    pub fn reset_throughput(&mut self) {
+        self.throughput.reset();
+    }
+
+    pub fn outputs_found(&self) -> u64 {
+        self.outputs_found
+    }
+
+    pub fn tip_height(&self) -> Option<u64> {
+        self.tip_height
+    }
+
+    pub fn blocks_per_second(&self) -> f64 {
+        self.throughput.blocks_per_second()
+    }
+
+    /// Estimated time until `current_height` reaches the last known tip height.
+    pub fn eta(&self, current_height: u64) -> Option<Duration> {
+        let tip = self.tip_height?;
+        self.throughput.eta(tip.saturating_sub(current_height))
+    }
+}
+
+/// Lifecycle state of an account scan, as seen by [`ScanProgressStore`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ScanState {
+    Scanning,
+    Waiting,
+    Completed,
+    Paused,
+}
+
+/// Latest known scan progress for an account.
+#[derive(Debug, Clone)]
+pub struct ScanProgressSnapshot {
+    pub state: ScanState,
+    pub current_height: u64,
+    pub tip_height: Option<u64>,
+    pub blocks_per_second: f64,
+    pub outputs_found: u64,
+    pub eta: Option<Duration>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// In-memory store of the latest [`ScanProgressSnapshot`] per account.
+///
+/// Cloning the store is cheap; all clones share the same state.
+#[derive(Debug, Clone, Default)]
+pub struct ScanProgressStore {
+    inner: Arc<RwLock<HashMap<i64, ScanProgressSnapshot>>>,
+}
+
+impl ScanProgressStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the latest snapshot for `account_id`, if a scan has reported progress.
+    pub fn get(&self, account_id: i64) -> Option<ScanProgressSnapshot> {
+        self.inner.read().ok()?.get(&account_id).cloned()
+    }
+
+    /// Applies a scan status event to the stored snapshot of its account.
+    pub fn apply(&self, status: &ScanStatusEvent) {
+        let Ok(mut inner) = self.inner.write() else {
+            return;
+        };
+        let now = chrono::Utc::now();
+        match status {
+            ScanStatusEvent::Started {
+                account_id,
+                from_height,
+            } => {
+                let entry = inner.entry(*account_id).or_insert_with(|| ScanProgressSnapshot {
+                    state: ScanState::Scanning,
+                    current_height: *from_height,
+                    tip_height: None,
+                    blocks_per_second: 0.0,
+                    outputs_found: 0,
+                    eta: None,
+                    updated_at: now,
+                });
+                entry.state = ScanState::Scanning;
+                entry.updated_at = now;
+            },
+            ScanStatusEvent::Progress {
+                account_id,
+                current_height,
+                tip_height,
+                blocks_per_second,
+                outputs_found,
+                eta,
+                ..
+            } => {
+                inner.insert(
+                    *account_id,
+                    ScanProgressSnapshot {
+                        state: ScanState::Scanning,
+                        current_height: *current_height,
+                        tip_height: *tip_height,
+                        blocks_per_second: *blocks_per_second,
+                        outputs_found: *outputs_found,
+                        eta: *eta,
+                        updated_at: now,
+                    },
+                );
+            },
+            ScanStatusEvent::MoreBlocksAvailable { .. } => {},
+            ScanStatusEvent::Completed {
+                account_id,
+                final_height,
+                ..
+            } => {
+                if let Some(entry) = inner.get_mut(account_id) {
+                    entry.state = ScanState::Completed;
+                    entry.current_height = *final_height;
+                    entry.blocks_per_second = 0.0;
+                    entry.eta = Some(Duration::ZERO);
+                    entry.updated_at = now;
+                }
+            },
+            ScanStatusEvent::Waiting { account_id, .. } => {
+                if let Some(entry) = inner.get_mut(account_id) {
+                    entry.state = ScanState::Waiting;
+                    entry.blocks_per_second = 0.0;
+                    entry.updated_at = now;
+                }
+            },
+            ScanStatusEvent::Paused {
+                account_id,
+                last_scanned_height,
+                ..
+            } => {
+                if let Some(entry) = inner.get_mut(account_id) {
+                    entry.state = ScanState::Paused;
+                    entry.current_height = *last_scanned_height;
+                    entry.blocks_per_second = 0.0;
+                    entry.eta = None;
+                    entry.updated_at = now;
+                }
+            },
+        }
+    }
+}
+
+impl EventSender for ScanProgressStore {
+    fn send(&self, event: ProcessingEvent) -> bool {
+        if let ProcessingEvent::ScanStatus(status) = &event {
+            self.apply(status);
+        }
+        true
+    }
+}
+
+/// Formats a single-line, human readable progress summary for CLI output.
+pub fn format_progress_line(
+    current_height: u64,
+    tip_height: Option<u64>,
+    blocks_per_second: f64,
+    outputs_found: u64,
+    eta: Option<Duration>,
+) -> String {
+    let tip = tip_height.map_or_else(|| "?".to_string(), |t| t.to_string());
+    let percent = tip_height.filter(|t| *t > 0).map_or_else(String::new, |t| {
+        format!(" ({:.1}%)", (current_height.min(t) as f64 / t as f64) * 100.0)
+    });
+    let eta = eta.map_or_else(|| "--:--:--".to_string(), format_duration);
+    format!(
+        "Scanned {current_height}/{tip}{percent} | {blocks_per_second:.1} blocks/s | {outputs_found} outputs found | ETA {eta}"
+    )
+}
+
+fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    format!("{:02}:{:02}:{:02}", secs / 3600, (secs % 3600) / 60, secs % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_throughput_is_measured_over_window() {
+        let start = Instant::now();
+        let mut tracker = ThroughputTracker::new(Duration::from_secs(10));
+        assert_eq!(tracker.blocks_per_second(), 0.0);
+        assert_eq!(tracker.eta(100), None);
+
+        tracker.record_at(start, 100);
+        tracker.record_at(start + Duration::from_secs(5), 150);
+        tracker.record_at(start + Duration::from_secs(10), 200);
+        assert!((tracker.blocks_per_second() - 10.0).abs() < f64::EPSILON);
+        assert_eq!(tracker.eta(100), Some(Duration::from_secs(10)));
+        assert_eq!(tracker.eta(0), Some(Duration::ZERO));
+
+        // Old samples drop out of the window, so the rate follows the recent speed.
+        tracker.record_at(start + Duration::from_secs(20), 220);
+        tracker.record_at(start + Duration::from_secs(30), 240);
+        assert!((tracker.blocks_per_second() - 2.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_throughput_resets_on_rollback() {
+        let start = Instant::now();
+        let mut tracker = ThroughputTracker::new(Duration::from_secs(10));
+        tracker.record_at(start, 100);
+        tracker.record_at(start + Duration::from_secs(1), 200);
+        tracker.record_at(start + Duration::from_secs(2), 50);
+        assert_eq!(tracker.blocks_per_second(), 0.0);
+    }
+
+    #[test]
+    fn test_progress_store_tracks_latest_status() {
+        let store = ScanProgressStore::new();
+        assert!(store.get(1).is_none());
+
+        store.send(ProcessingEvent::ScanStatus(ScanStatusEvent::Progress {
+            account_id: 1,
+            current_height: 500,
+            blocks_scanned: 100,
+            tip_height: Some(1000),
+            blocks_per_second: 25.0,
+            outputs_found: 3,
+            eta: Some(Duration::from_secs(20)),
+        }));
+        let snapshot = store.get(1).expect("snapshot should exist");
+        assert_eq!(snapshot.state, ScanState::Scanning);
+        assert_eq!(snapshot.current_height, 500);
+        assert_eq!(snapshot.tip_height, Some(1000));
+        assert_eq!(snapshot.outputs_found, 3);
+
+        store.send(ProcessingEvent::ScanStatus(ScanStatusEvent::Completed {
+            account_id: 1,
+            final_height: 1000,
+            total_blocks_scanned: 600,
+        }));
+        let snapshot = store.get(1).expect("snapshot should exist");
+        assert_eq!(snapshot.state, ScanState::Completed);
+        assert_eq!(snapshot.current_height, 1000);
+        assert_eq!(snapshot.eta, Some(Duration::ZERO));
+        assert!(store.get(2).is_none());
+    }
+
+    #[test]
+    fn test_progress_line_formatting() {
+        let line = format_progress_line(250, Some(1000), 12.5, 2, Some(Duration::from_secs(3725)));
+        assert_eq!(
+            line,
+            "Scanned 250/1000 (25.0%) | 12.5 blocks/s | 2 outputs found | ETA 01:02:05"
+        );
+        let line = format_progress_line(250, None, 0.0, 0, None);
+        assert_eq!(line, "Scanned 250/? | 0.0 blocks/s | 0 outputs found | ETA --:--:--");
+    }
+}
@@ -208,10 +208,8 @@ pub fn rollback_from_height(
         );
     }
 
-    if let Some(config) = webhook_config {
-        for (event_id, event) in generated_events {
-            trigger_webhook_with_balance(tx, account_id, event_id, &event, &config)?;
-        }
+    for (event_id, event) in generated_events {
+        trigger_webhook_with_balance(tx, account_id, event_id, &event, webhook_config.as_ref())?;
     }
 
     Ok(ReorgInformation {
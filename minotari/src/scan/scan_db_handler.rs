@@ -103,7 +103,7 @@ impl<E: EventSender + Clone + Send + 'static> ScanDbHandler<E> {
         let pool = self.pool.clone();
         tokio::task::spawn_blocking(move || {
             let conn = pool.get().map_err(WalletDbError::from)?;
-            prune_scanned_tip_blocks(&conn, account_id, height)
+            prune_scanned_tip_blocks(&conn, account_id, height).map(|_| ())
         })
         .await
         .map_err(|e| {
@@ -1,20 +1,24 @@
+use std::sync::Arc;
+
 use crate::scan::ScanRetryConfig;
 use crate::{ScanError, scan::coordinator::AccountSyncTarget};
-use minotari_scanning::{HttpBlockchainScanner, ScanConfig};
+use minotari_scanning::{BlockCache, HttpBlockchainScanner, ScanConfig};
 use tari_transaction_components::key_manager::KeyManager;
 
 pub struct ScannerStateManager {
     scanner: Option<HttpBlockchainScanner<KeyManager>>,
     active_account_ids: Vec<i64>,
     scan_config: ScanConfig,
+    block_cache: Option<Arc<dyn BlockCache>>,
 }
 
 impl ScannerStateManager {
-    pub fn new() -> Self {
+    pub fn new(block_cache: Option<Arc<dyn BlockCache>>) -> Self {
         Self {
             scanner: None,
             active_account_ids: Vec::new(),
             scan_config: ScanConfig::default(),
+            block_cache,
         }
     }
 
@@ -43,7 +47,7 @@ impl ScannerStateManager {
                 })
                 .collect::<Result<Vec<_>, _>>()?;
 
-            let new_scanner = HttpBlockchainScanner::with_timeout(
+            let mut new_scanner = HttpBlockchainScanner::with_timeout(
                 base_url.to_string(),
                 retry_config.timeout,
                 active_key_managers,
@@ -53,6 +57,9 @@ impl ScannerStateManager {
             )
             .await
             .map_err(|e| ScanError::Intermittent(e.to_string()))?;
+            if let Some(cache) = &self.block_cache {
+                new_scanner = new_scanner.with_block_cache(cache.clone());
+            }
 
             self.scanner = Some(new_scanner);
             self.active_account_ids = new_active_account_ids.to_vec();
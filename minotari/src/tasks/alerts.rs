@@ -0,0 +1,490 @@
+//! Balance and activity alert rules.
+//!
+//! The daemon evaluates the rules configured under `[wallet.alerts]` after each scan cycle
+//! (which includes monitoring of outbound transactions). A triggered alert is recorded as a
+//! wallet event, so it is listed by the events API and delivered to webhooks and event sinks
+//! like any other event.
+//!
+//! Condition rules (balance thresholds, scanner lag, webhook backlog) fire once when their
+//! condition starts to hold and fire again only after it cleared. Large incoming payments
+//! fire once per transaction, stuck transactions once per transaction while it stays in
+//! `Broadcast`. The state is kept in the `alert_states` table, so a restart does not repeat
+//! alerts that already fired. The webhook backlog is shared by all accounts, so its alert is
+//! evaluated once per cycle, its state is kept in `wallet_metadata` and it is recorded on the
+//! first account (by name) the rules apply to.
+
+use std::collections::HashSet;
+
+use log::warn;
+use rusqlite::Connection;
+use tari_transaction_components::tari_amount::MicroMinotari;
+
+use crate::{
+    config::defaults::AlertConfig,
+    db::{self, AccountRow, CompletedTransactionStatus, WalletDbResult},
+    models::{WalletEvent, WalletEventType},
+    transactions::TransactionDirection,
+    webhooks::{WebhookTriggerConfig, utils::trigger_webhook_with_balance},
+};
+
+const LOW_BALANCE: &str = "LowBalanceAlert";
+const HIGH_BALANCE: &str = "HighBalanceAlert";
+const LARGE_INCOMING_PAYMENT: &str = "LargeIncomingPaymentAlert";
+const TRANSACTION_STUCK: &str = "TransactionStuckAlert";
+const SCANNER_LAG: &str = "ScannerLagAlert";
+
+/// Metadata key under which the webhook backlog alert is marked as fired.
+const WEBHOOK_BACKLOG_KEY: &str = "alert.WebhookBacklogAlert";
+
+/// Evaluates the configured alert rules and records triggered alerts as wallet events.
+pub struct AlertEvaluator {
+    config: AlertConfig,
+    webhook_config: Option<WebhookTriggerConfig>,
+}
+
+impl AlertEvaluator {
+    pub fn new(config: AlertConfig, webhook_config: Option<WebhookTriggerConfig>) -> Self {
+        Self { config, webhook_config }
+    }
+
+    /// Returns `true` if at least one rule is configured.
+    pub fn is_enabled(&self) -> bool {
+        let AlertConfig {
+            low_balance_threshold,
+            high_balance_threshold,
+            large_incoming_payment_threshold,
+            stuck_transaction_blocks,
+            scanner_lag_blocks,
+            webhook_backlog_threshold,
+            accounts: _,
+        } = &self.config;
+        [
+            low_balance_threshold,
+            high_balance_threshold,
+            large_incoming_payment_threshold,
+            stuck_transaction_blocks,
+            scanner_lag_blocks,
+            webhook_backlog_threshold,
+        ]
+        .iter()
+        .any(|threshold| threshold.is_some())
+    }
+
+    /// Evaluates all rules for the configured accounts.
+    ///
+    /// `tip_height` is the current chain tip; the stuck transaction and scanner lag rules are
+    /// skipped if it is unknown. `cycle_events` are the events recorded during the scan cycle
+    /// and are checked for large incoming payments.
+    ///
+    /// Should be called inside a transaction, so alert states and events are stored together.
+    pub fn evaluate(
+        &self,
+        conn: &Connection,
+        tip_height: Option<u64>,
+        cycle_events: &[WalletEvent],
+    ) -> WalletDbResult<Vec<WalletEvent>> {
+        let accounts: Vec<AccountRow> = db::get_accounts(conn, None)?
+            .into_iter()
+            .filter(|account| self.applies_to(account))
+            .collect();
+
+        let mut alerts = Vec::new();
+        for account in &accounts {
+            let account_events = cycle_events.iter().filter(|event| event.account_id == account.id);
+            let mut account_alerts = self.balance_alerts(conn, account.id)?;
+            account_alerts.extend(self.large_incoming_payment_alerts(conn, account.id, account_events)?);
+            if let Some(tip_height) = tip_height {
+                account_alerts.extend(self.stuck_transaction_alerts(conn, account.id, tip_height)?);
+                account_alerts.extend(self.scanner_lag_alert(conn, account.id, tip_height)?);
+            }
+
+            for event_type in account_alerts {
+                alerts.push(self.record_alert(conn, account, event_type)?);
+            }
+        }
+
+        // Wallet events belong to an account, so the alert is recorded on the first one
+        if let Some(account) = accounts.first()
+            && let Some(event_type) = self.webhook_backlog_alert(conn)?
+        {
+            alerts.push(self.record_alert(conn, account, event_type)?);
+        }
+        Ok(alerts)
+    }
+
+    fn applies_to(&self, account: &AccountRow) -> bool {
+        self.config
+            .accounts
+            .as_ref()
+            .is_none_or(|names| names.contains(&account.friendly_name))
+    }
+
+    fn balance_alerts(&self, conn: &Connection, account_id: i64) -> WalletDbResult<Vec<WalletEventType>> {
+        if self.config.low_balance_threshold.is_none() && self.config.high_balance_threshold.is_none() {
+            return Ok(Vec::new());
+        }
+
+        let available = db::get_balance(conn, account_id)?.available;
+        let mut alerts = Vec::new();
+        if let Some(threshold) = self.config.low_balance_threshold.map(MicroMinotari::from)
+            && update_condition(conn, account_id, LOW_BALANCE, available < threshold)?
+        {
+            alerts.push(WalletEventType::LowBalanceAlert { available, threshold });
+        }
+        if let Some(threshold) = self.config.high_balance_threshold.map(MicroMinotari::from)
+            && update_condition(conn, account_id, HIGH_BALANCE, available > threshold)?
+        {
+            alerts.push(WalletEventType::HighBalanceAlert { available, threshold });
+        }
+        Ok(alerts)
+    }
+
+    fn large_incoming_payment_alerts<'a>(
+        &self,
+        conn: &Connection,
+        account_id: i64,
+        events: impl Iterator<Item = &'a WalletEvent>,
+    ) -> WalletDbResult<Vec<WalletEventType>> {
+        let Some(threshold) = self.config.large_incoming_payment_threshold.map(MicroMinotari::from) else {
+            return Ok(Vec::new());
+        };
+
+        // Outputs of a re-scan are detected again, but must not repeat the alert
+        let mut alerted: HashSet<String> = db::get_alert_states(conn, account_id, LARGE_INCOMING_PAYMENT)?
+            .into_iter()
+            .map(|state| state.subject)
+            .collect();
+        let mut alerts = Vec::new();
+        for event in events {
+            let WalletEventType::OutputDetected { hash, block_height, .. } = &event.event_type else {
+                continue;
+            };
+            let Some(tx) = db::find_displayed_transaction_by_output_hash(conn, account_id, *block_height, hash)? else {
+                continue;
+            };
+            if tx.direction != TransactionDirection::Incoming || tx.amount < threshold {
+                continue;
+            }
+            let subject = tx.id.to_string();
+            if alerted.insert(subject.clone()) {
+                db::insert_alert_state(conn, account_id, LARGE_INCOMING_PAYMENT, &subject, None, true)?;
+                alerts.push(WalletEventType::LargeIncomingPaymentAlert {
+                    tx_id: tx.id,
+                    amount: tx.amount,
+                    threshold,
+                    block_height: *block_height,
+                });
+            }
+        }
+        Ok(alerts)
+    }
+
+    fn stuck_transaction_alerts(
+        &self,
+        conn: &Connection,
+        account_id: i64,
+        tip_height: u64,
+    ) -> WalletDbResult<Vec<WalletEventType>> {
+        let Some(max_blocks) = self.config.stuck_transaction_blocks else {
+            return Ok(Vec::new());
+        };
+
+        let states = db::get_alert_states(conn, account_id, TRANSACTION_STUCK)?;
+        let broadcast =
+            db::get_completed_transactions_by_status(conn, account_id, CompletedTransactionStatus::Broadcast)?;
+
+        let mut alerts = Vec::new();
+        for tx in &broadcast {
+            let subject = tx.id.to_string();
+            // The broadcast height is not stored, so blocks are counted from the first cycle
+            // that saw the transaction in `Broadcast`.
+            match states.iter().find(|state| state.subject == subject) {
+                None => db::insert_alert_state(conn, account_id, TRANSACTION_STUCK, &subject, Some(tip_height), false)?,
+                Some(state) if !state.triggered => {
+                    let first_seen_height = state.first_seen_height.unwrap_or(tip_height);
+                    let blocks_pending = tip_height.saturating_sub(first_seen_height);
+                    if blocks_pending >= max_blocks {
+                        db::mark_alert_state_triggered(conn, account_id, TRANSACTION_STUCK, &subject)?;
+                        alerts.push(WalletEventType::TransactionStuckAlert {
+                            tx_id: tx.id,
+                            first_seen_height,
+                            blocks_pending,
+                        });
+                    }
+                },
+                Some(_) => {},
+            }
+        }
+
+        // Mined, rejected or cancelled transactions are no longer watched
+        let broadcast_ids: HashSet<String> = broadcast.iter().map(|tx| tx.id.to_string()).collect();
+        for state in states {
+            if !broadcast_ids.contains(&state.subject) {
+                db::delete_alert_state(conn, account_id, TRANSACTION_STUCK, &state.subject)?;
+            }
+        }
+        Ok(alerts)
+    }
+
+    fn scanner_lag_alert(
+        &self,
+        conn: &Connection,
+        account_id: i64,
+        tip_height: u64,
+    ) -> WalletDbResult<Option<WalletEventType>> {
+        let Some(max_lag) = self.config.scanner_lag_blocks else {
+            return Ok(None);
+        };
+        // Accounts that have not scanned a block yet are still in their initial sync
+        let Some(scanned) = db::get_latest_scanned_tip_block_by_account(conn, account_id)? else {
+            return Ok(None);
+        };
+
+        let lag_blocks = tip_height.saturating_sub(scanned.height);
+        if update_condition(conn, account_id, SCANNER_LAG, lag_blocks > max_lag)? {
+            return Ok(Some(WalletEventType::ScannerLagAlert {
+                scanned_height: scanned.height,
+                tip_height,
+                lag_blocks,
+            }));
+        }
+        Ok(None)
+    }
+
+    fn webhook_backlog_alert(&self, conn: &Connection) -> WalletDbResult<Option<WalletEventType>> {
+        let Some(threshold) = self.config.webhook_backlog_threshold else {
+            return Ok(None);
+        };
+
+        let undelivered = db::count_undelivered_webhooks(conn)?;
+        let active = db::get_metadata(conn, WEBHOOK_BACKLOG_KEY)?.is_some();
+        match (undelivered > threshold, active) {
+            (true, false) => {
+                db::set_metadata(conn, WEBHOOK_BACKLOG_KEY, "triggered")?;
+                Ok(Some(WalletEventType::WebhookBacklogAlert { undelivered, threshold }))
+            },
+            (false, true) => {
+                db::delete_metadata(conn, WEBHOOK_BACKLOG_KEY)?;
+                Ok(None)
+            },
+            _ => Ok(None),
+        }
+    }
+
+    fn record_alert(
+        &self,
+        conn: &Connection,
+        account: &AccountRow,
+        event_type: WalletEventType,
+    ) -> WalletDbResult<WalletEvent> {
+        let event = WalletEvent {
+            id: 0,
+            account_id: account.id,
+            description: describe(&event_type),
+            event_type,
+        };
+        warn!(
+            target: "audit",
+            account = &*account.friendly_name,
+            alert = &*event.event_type.to_key_string();
+            "Alert triggered: {}",
+            event.description
+        );
+        let event_id = db::insert_wallet_event(conn, account.id, &event)?;
+        trigger_webhook_with_balance(conn, account.id, event_id, &event, self.webhook_config.as_ref())?;
+        Ok(WalletEvent { id: event_id, ..event })
+    }
+}
+
+/// Stores whether an account-wide condition holds. Returns `true` if the alert should fire,
+/// i.e. the condition holds now but did not at the previous evaluation.
+fn update_condition(conn: &Connection, account_id: i64, rule: &str, holds: bool) -> WalletDbResult<bool> {
+    let active = !db::get_alert_states(conn, account_id, rule)?.is_empty();
+    match (holds, active) {
+        (true, false) => {
+            db::insert_alert_state(conn, account_id, rule, "", None, true)?;
+            Ok(true)
+        },
+        (false, true) => {
+            db::delete_alert_state(conn, account_id, rule, "")?;
+            Ok(false)
+        },
+        _ => Ok(false),
+    }
+}
+
+fn describe(event_type: &WalletEventType) -> String {
+    match event_type {
+        WalletEventType::LowBalanceAlert { available, threshold } => {
+            format!("Available balance {} is below {}", available, threshold)
+        },
+        WalletEventType::HighBalanceAlert { available, threshold } => {
+            format!("Available balance {} is above {}", available, threshold)
+        },
+        WalletEventType::LargeIncomingPaymentAlert { tx_id, amount, .. } => {
+            format!("Received {} in transaction {}", amount, tx_id)
+        },
+        WalletEventType::TransactionStuckAlert {
+            tx_id, blocks_pending, ..
+        } => format!("Transaction {} not mined after {} blocks", tx_id, blocks_pending),
+        WalletEventType::ScannerLagAlert {
+            lag_blocks, tip_height, ..
+        } => format!("Scanner is {} blocks behind the tip at {}", lag_blocks, tip_height),
+        WalletEventType::WebhookBacklogAlert { undelivered, .. } => {
+            format!("{} webhook deliveries are waiting to be sent", undelivered)
+        },
+        other => other.to_key_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::indexing_slicing)]
+    use super::*;
+    use crate::db::{create_account, get_account_by_name, init_db, insert_balance_change, insert_scanned_tip_block};
+    use crate::models::BalanceChange;
+    use chrono::Utc;
+    use tari_common::configuration::Network;
+    use tari_common_types::seeds::cipher_seed::CipherSeed;
+    use tari_transaction_components::key_manager::wallet_types::{SeedWordsWallet, WalletType};
+    use tempfile::tempdir;
+
+    fn create_test_account(conn: &Connection, name: &str) -> AccountRow {
+        let seeds = CipherSeed::random();
+        let wallet_type = WalletType::SeedWords(SeedWordsWallet::construct_new(seeds).unwrap());
+        create_account(conn, name, &wallet_type, "password", Network::Esmeralda).unwrap();
+        get_account_by_name(conn, name).unwrap().unwrap()
+    }
+
+    fn credit(conn: &Connection, account_id: i64, amount: u64, height: u64) {
+        insert_balance_change(
+            conn,
+            &BalanceChange {
+                account_id,
+                caused_by_output_id: None,
+                caused_by_input_id: None,
+                description: "Deposit".into(),
+                balance_credit: MicroMinotari::from(amount),
+                balance_debit: MicroMinotari::from(0),
+                effective_date: Utc::now().naive_utc(),
+                effective_height: height,
+                claimed_recipient_address: None,
+                claimed_sender_address: None,
+                memo_parsed: None,
+                memo_hex: None,
+                claimed_fee: None,
+                claimed_amount: None,
+                is_reversal: false,
+                reversal_of_balance_change_id: None,
+                is_reversed: false,
+            },
+        )
+        .unwrap();
+    }
+
+    fn alert_keys(events: &[WalletEvent]) -> Vec<String> {
+        events.iter().map(|event| event.event_type.to_key_string()).collect()
+    }
+
+    #[test]
+    fn test_low_balance_alert_fires_once_until_cleared() {
+        let temp_dir = tempdir().unwrap();
+        let pool = init_db(temp_dir.path().join("alerts.db"), Network::Esmeralda).unwrap();
+        let conn = pool.get().unwrap();
+        let account = create_test_account(&conn, "hot");
+
+        let evaluator = AlertEvaluator::new(
+            AlertConfig {
+                low_balance_threshold: Some(1_000),
+                ..Default::default()
+            },
+            None,
+        );
+        assert!(evaluator.is_enabled());
+
+        let alerts = evaluator.evaluate(&conn, None, &[]).unwrap();
+        assert_eq!(alert_keys(&alerts), vec!["LowBalanceAlert"]);
+        assert!(alerts.iter().all(|alert| alert.id > 0));
+
+        // Still below the threshold: no repeated alert
+        assert!(evaluator.evaluate(&conn, None, &[]).unwrap().is_empty());
+
+        // Topped up: the alert is re-armed
+        credit(&conn, account.id, 5_000, 10);
+        assert!(evaluator.evaluate(&conn, None, &[]).unwrap().is_empty());
+        assert!(db::get_alert_states(&conn, account.id, LOW_BALANCE).unwrap().is_empty());
+
+        let events = db::get_events_by_account_id(&conn, account.id, 10, 0).unwrap();
+        assert_eq!(events.iter().filter(|e| e.event_type == "LowBalanceAlert").count(), 1);
+    }
+
+    #[test]
+    fn test_scanner_lag_and_account_filter() {
+        let temp_dir = tempdir().unwrap();
+        let pool = init_db(temp_dir.path().join("alerts.db"), Network::Esmeralda).unwrap();
+        let conn = pool.get().unwrap();
+        let hot = create_test_account(&conn, "hot");
+        let cold = create_test_account(&conn, "cold");
+        insert_scanned_tip_block(&conn, hot.id, 100, &[1u8; 32]).unwrap();
+        insert_scanned_tip_block(&conn, cold.id, 100, &[1u8; 32]).unwrap();
+
+        let evaluator = AlertEvaluator::new(
+            AlertConfig {
+                scanner_lag_blocks: Some(50),
+                accounts: Some(vec!["hot".to_string()]),
+                ..Default::default()
+            },
+            None,
+        );
+
+        assert!(evaluator.evaluate(&conn, Some(150), &[]).unwrap().is_empty());
+        // Without a known tip the rule is skipped
+        assert!(evaluator.evaluate(&conn, None, &[]).unwrap().is_empty());
+
+        let alerts = evaluator.evaluate(&conn, Some(151), &[]).unwrap();
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].account_id, hot.id);
+        assert!(matches!(
+            alerts[0].event_type,
+            WalletEventType::ScannerLagAlert {
+                scanned_height: 100,
+                tip_height: 151,
+                lag_blocks: 51,
+            }
+        ));
+    }
+
+    #[test]
+    fn test_webhook_backlog_alert_is_recorded_once_per_cycle() {
+        let temp_dir = tempdir().unwrap();
+        let pool = init_db(temp_dir.path().join("alerts.db"), Network::Esmeralda).unwrap();
+        let conn = pool.get().unwrap();
+        let hot = create_test_account(&conn, "hot");
+        let cold = create_test_account(&conn, "cold");
+        db::enqueue_webhook(&conn, None, "OutputDetected", "{}", "http://localhost/hook", None).unwrap();
+
+        let evaluator = AlertEvaluator::new(
+            AlertConfig {
+                webhook_backlog_threshold: Some(0),
+                ..Default::default()
+            },
+            None,
+        );
+
+        let alerts = evaluator.evaluate(&conn, None, &[]).unwrap();
+        assert_eq!(alert_keys(&alerts), vec!["WebhookBacklogAlert"]);
+        assert_eq!(alerts[0].account_id, cold.id);
+        let hot_events = db::get_events_by_account_id(&conn, hot.id, 10, 0).unwrap();
+        assert!(hot_events.iter().all(|e| e.event_type != "WebhookBacklogAlert"));
+        assert!(db::get_metadata(&conn, WEBHOOK_BACKLOG_KEY).unwrap().is_some());
+
+        // An account created while the backlog persists does not repeat the alert
+        create_test_account(&conn, "new");
+        assert!(evaluator.evaluate(&conn, None, &[]).unwrap().is_empty());
+
+        // Once the backlog is cleared the alert is re-armed
+        conn.execute("DELETE FROM webhook_queue", []).unwrap();
+        assert!(evaluator.evaluate(&conn, None, &[]).unwrap().is_empty());
+        assert!(db::get_metadata(&conn, WEBHOOK_BACKLOG_KEY).unwrap().is_none());
+    }
+}
@@ -65,6 +65,7 @@ pub struct AbridgedTransactionKernel {
 use crate::{
     db::{DbBurnProof, SqlitePool, get_pending_burn_proofs, mark_burn_proof_complete},
     http::WalletHttpClient,
+    tasks::health::TaskGuard,
 };
 
 const LOG_TARGET: &str = "wallet::tasks::burn_proof_worker";
@@ -74,6 +75,7 @@ pub struct BurnProofWorker {
     db_pool: SqlitePool,
     client: WalletHttpClient,
     burn_proofs_dir: PathBuf,
+    task_guard: Option<TaskGuard>,
 }
 
 impl BurnProofWorker {
@@ -82,9 +84,16 @@ impl BurnProofWorker {
             db_pool,
             client,
             burn_proofs_dir,
+            task_guard: None,
         }
     }
 
+    /// Reports the worker's liveness through `task_guard` while it runs.
+    pub fn with_task_guard(mut self, task_guard: TaskGuard) -> Self {
+        self.task_guard = Some(task_guard);
+        self
+    }
+
     pub fn run(self, mut shutdown_rx: broadcast::Receiver<()>) -> JoinHandle<Result<(), anyhow::Error>> {
         tokio::spawn(async move {
             info!(target: LOG_TARGET, "Burn proof worker started.");
@@ -93,6 +102,9 @@ impl BurnProofWorker {
             loop {
                 tokio::select! {
                     _ = ticker.tick() => {
+                        if let Some(guard) = &self.task_guard {
+                            guard.heartbeat();
+                        }
                         if let Err(e) = self.process_pending_proofs().await {
                             error!(target: LOG_TARGET, error:% = e; "Error processing pending burn proofs");
                         }
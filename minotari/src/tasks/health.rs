@@ -0,0 +1,236 @@
+//! Liveness tracking of the daemon's background tasks.
+//!
+//! Every long-running task registers with the [`HealthMonitor`] and holds the returned
+//! [`TaskGuard`] for as long as it runs. The guard marks the task as stopped when it is
+//! dropped, which also covers tasks that exit early with an error or panic. Tasks record a
+//! heartbeat on every iteration; a task whose last heartbeat is older than its stall timeout
+//! is reported as stalled.
+//!
+//! The monitor also keeps the result of the daemon's last base node check, which is
+//! reported by the readiness endpoint together with the database and scan lag checks.
+
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, RwLock},
+    time::{Duration, Instant},
+};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::scan::{EventSender, ProcessingEvent};
+
+/// Name of the daemon's scan loop.
+pub const SCANNER_TASK: &str = "scanner";
+/// Name of the [`TransactionUnlocker`](super::unlocker::TransactionUnlocker) task.
+pub const UNLOCKER_TASK: &str = "transaction_unlocker";
+/// Name of the [`BurnProofWorker`](super::burn_proof_worker::BurnProofWorker) task.
+pub const BURN_PROOF_TASK: &str = "burn_proof_worker";
+/// Name of the [`WebhookWorker`](crate::webhooks::worker::WebhookWorker) task.
+pub const WEBHOOK_TASK: &str = "webhook_worker";
+
+/// State of a background task as reported by the health endpoints.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct TaskHealth {
+    /// Name of the task
+    pub name: String,
+    /// Whether the task is still running
+    pub running: bool,
+    /// Whether the task has not recorded a heartbeat within its stall timeout
+    pub stalled: bool,
+    /// Time of the task's last heartbeat
+    pub last_heartbeat: DateTime<Utc>,
+}
+
+impl TaskHealth {
+    pub fn is_healthy(&self) -> bool {
+        self.running && !self.stalled
+    }
+}
+
+/// Result of the daemon's last base node check.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct BaseNodeHealth {
+    /// Whether the base node answered the last request
+    pub reachable: bool,
+    /// Chain tip height reported by the base node
+    pub tip_height: Option<u64>,
+    /// Time of the last check
+    pub checked_at: DateTime<Utc>,
+}
+
+#[derive(Debug)]
+struct TaskEntry {
+    running: bool,
+    stall_timeout: Duration,
+    last_heartbeat: Instant,
+    last_heartbeat_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Default)]
+struct MonitorState {
+    tasks: BTreeMap<&'static str, TaskEntry>,
+    base_node: Option<BaseNodeHealth>,
+}
+
+/// Shared registry of the daemon's tasks and base node status.
+///
+/// Cloning is cheap and every clone refers to the same state.
+#[derive(Debug, Clone)]
+pub struct HealthMonitor {
+    state: Arc<RwLock<MonitorState>>,
+    max_scan_lag_blocks: u64,
+}
+
+impl HealthMonitor {
+    /// Creates a monitor. Accounts more than `max_scan_lag_blocks` behind the chain tip are
+    /// reported as not ready.
+    pub fn new(max_scan_lag_blocks: u64) -> Self {
+        Self {
+            state: Arc::new(RwLock::new(MonitorState::default())),
+            max_scan_lag_blocks,
+        }
+    }
+
+    pub fn max_scan_lag_blocks(&self) -> u64 {
+        self.max_scan_lag_blocks
+    }
+
+    /// Registers a running task. The task is reported as stopped once the guard is dropped.
+    pub fn register(&self, name: &'static str, stall_timeout: Duration) -> TaskGuard {
+        let entry = TaskEntry {
+            running: true,
+            stall_timeout,
+            last_heartbeat: Instant::now(),
+            last_heartbeat_at: Utc::now(),
+        };
+        if let Ok(mut state) = self.state.write() {
+            state.tasks.insert(name, entry);
+        }
+        TaskGuard {
+            monitor: self.clone(),
+            name,
+        }
+    }
+
+    fn update_task(&self, name: &str, update: impl FnOnce(&mut TaskEntry)) {
+        if let Ok(mut state) = self.state.write()
+            && let Some(entry) = state.tasks.get_mut(name)
+        {
+            update(entry);
+        }
+    }
+
+    fn heartbeat(&self, name: &str) {
+        self.update_task(name, |entry| {
+            entry.last_heartbeat = Instant::now();
+            entry.last_heartbeat_at = Utc::now();
+        });
+    }
+
+    /// Returns the state of every registered task.
+    pub fn tasks(&self) -> Vec<TaskHealth> {
+        let Ok(state) = self.state.read() else {
+            return Vec::new();
+        };
+        state
+            .tasks
+            .iter()
+            .map(|(name, entry)| TaskHealth {
+                name: name.to_string(),
+                running: entry.running,
+                stalled: entry.running && entry.last_heartbeat.elapsed() > entry.stall_timeout,
+                last_heartbeat: entry.last_heartbeat_at,
+            })
+            .collect()
+    }
+
+    /// Records the result of a base node request.
+    pub fn record_base_node(&self, reachable: bool, tip_height: Option<u64>) {
+        if let Ok(mut state) = self.state.write() {
+            state.base_node = Some(BaseNodeHealth {
+                reachable,
+                tip_height,
+                checked_at: Utc::now(),
+            });
+        }
+    }
+
+    /// Returns the result of the last base node check, `None` before the first one.
+    pub fn base_node(&self) -> Option<BaseNodeHealth> {
+        self.state.read().ok().and_then(|state| state.base_node.clone())
+    }
+}
+
+/// Registration of a running task, see [`HealthMonitor::register`].
+#[derive(Debug)]
+pub struct TaskGuard {
+    monitor: HealthMonitor,
+    name: &'static str,
+}
+
+impl TaskGuard {
+    /// Records that the task is making progress.
+    pub fn heartbeat(&self) {
+        self.monitor.heartbeat(self.name);
+    }
+
+    /// Returns an [`EventSender`] recording a heartbeat for every scanner event, so a long
+    /// scan is not reported as stalled.
+    pub fn event_heartbeat(&self) -> HeartbeatEventSender {
+        HeartbeatEventSender {
+            monitor: self.monitor.clone(),
+            name: self.name,
+        }
+    }
+}
+
+impl Drop for TaskGuard {
+    fn drop(&mut self) {
+        self.monitor.update_task(self.name, |entry| entry.running = false);
+    }
+}
+
+/// [`EventSender`] recording a task heartbeat per event, see [`TaskGuard::event_heartbeat`].
+#[derive(Debug, Clone)]
+pub struct HeartbeatEventSender {
+    monitor: HealthMonitor,
+    name: &'static str,
+}
+
+impl EventSender for HeartbeatEventSender {
+    fn send(&self, _event: ProcessingEvent) -> bool {
+        self.monitor.heartbeat(self.name);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::indexing_slicing)]
+    use super::*;
+
+    #[test]
+    fn test_task_guard_tracks_running_and_stalled_tasks() {
+        let monitor = HealthMonitor::new(10);
+        let scanner = monitor.register(SCANNER_TASK, Duration::from_secs(60));
+        let unlocker = monitor.register(UNLOCKER_TASK, Duration::ZERO);
+        std::thread::sleep(Duration::from_millis(5));
+        scanner.heartbeat();
+
+        let tasks = monitor.tasks();
+        assert_eq!(tasks.len(), 2);
+        // Tasks are ordered by name
+        assert_eq!(tasks[0].name, SCANNER_TASK);
+        assert!(tasks[0].is_healthy());
+        assert_eq!(tasks[1].name, UNLOCKER_TASK);
+        assert!(tasks[1].running);
+        assert!(tasks[1].stalled);
+
+        drop(scanner);
+        drop(unlocker);
+        let tasks = monitor.tasks();
+        assert!(tasks.iter().all(|task| !task.running && !task.is_healthy()));
+    }
+}
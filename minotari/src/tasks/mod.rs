@@ -6,11 +6,15 @@
 //! # Available Tasks
 //!
 //! - [`unlocker::TransactionUnlocker`] - Automatically unlocks expired transaction locks
+//! - [`alerts::AlertEvaluator`] - Evaluates balance and activity alert rules after each scan cycle
+//! - [`health::HealthMonitor`] - Tracks whether the daemon's tasks are alive for the health endpoints
 //!
 //! # Usage
 //!
 //! Tasks are typically started by the daemon and run in the background until
 //! a shutdown signal is received.
 
+pub mod alerts;
 pub mod burn_proof_worker;
+pub mod health;
 pub mod unlocker;
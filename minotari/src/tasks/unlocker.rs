@@ -7,15 +7,26 @@ use tokio::{sync::broadcast, task::JoinHandle, time::interval};
 use crate::{
     db::{self, SqlitePool},
     models::PendingTransactionStatus,
+    tasks::health::TaskGuard,
 };
 
 pub struct TransactionUnlocker {
     db_pool: SqlitePool,
+    task_guard: Option<TaskGuard>,
 }
 
 impl TransactionUnlocker {
     pub fn new(db_pool: SqlitePool) -> Self {
-        Self { db_pool }
+        Self {
+            db_pool,
+            task_guard: None,
+        }
+    }
+
+    /// Reports the task's liveness through `task_guard` while it runs.
+    pub fn with_task_guard(mut self, task_guard: TaskGuard) -> Self {
+        self.task_guard = Some(task_guard);
+        self
     }
 
     pub fn unlock_expired_transactions(conn: &mut Connection) -> Result<(), anyhow::Error> {
@@ -42,6 +53,9 @@ impl TransactionUnlocker {
             loop {
                 tokio::select! {
                     _ = interval.tick() => {
+                        if let Some(guard) = &self.task_guard {
+                            guard.heartbeat();
+                        }
                         let mut conn = self.db_pool.get()?;
                         if let Err(e) = Self::unlock_expired_transactions(&mut conn) {
                             error!(error:% = e; "Error unlocking expired transactions");
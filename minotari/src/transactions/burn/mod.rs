@@ -37,7 +37,7 @@ use tari_utilities::{ByteArray, hex::Hex};
 use crate::{
     db::{AccountRow, NewBurnProof, SqlitePool},
     models::PendingTransactionStatus,
-    transactions::fund_locker::FundLocker,
+    transactions::{fund_locker::FundLocker, manager::ensure_no_approval_policy},
 };
 
 /// Result returned from a successful burn transaction build.
@@ -70,7 +70,9 @@ pub struct BurnTxParams {
 /// Builds, signs, and returns a burn transaction along with its partial proof data.
 ///
 /// A `claim_public_key` must be supplied, as this function is designed to
-/// always produce a burn proof.
+/// always produce a burn proof. The ownership proof is signed by the wallet, so externally
+/// signed accounts cannot burn. Burns cannot be put up for approval either, so accounts with
+/// an approval policy cannot burn.
 pub fn create_burn_tx(
     account: &AccountRow,
     db_pool: SqlitePool,
@@ -78,6 +80,13 @@ pub fn create_burn_tx(
     password: &str,
     params: BurnTxParams,
 ) -> Result<BurnTxResult, anyhow::Error> {
+    if account.external_signer {
+        return Err(anyhow!(
+            "Account '{}' is signed externally. Burns are signed by the wallet and are not supported",
+            account.friendly_name
+        ));
+    }
+    ensure_no_approval_policy(&db_pool.get()?, account, "burn")?;
     let consensus_constants = ConsensusConstantsBuilder::new(network).build();
 
     info!(
@@ -286,3 +295,56 @@ pub fn persist_burn_records(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{NewApiKey, create_account, init_db, insert_api_key};
+    use crate::models::ApiKeyScope;
+    use tari_common_types::seeds::cipher_seed::CipherSeed;
+    use tari_transaction_components::key_manager::wallet_types::{SeedWordsWallet, WalletType};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_burn_is_refused_for_account_with_approval_policy() {
+        let temp = tempdir().unwrap();
+        let pool = init_db(temp.path().join("burn_approvals.db"), Network::Esmeralda).unwrap();
+        let conn = pool.get().unwrap();
+        let wallet_type = WalletType::SeedWords(SeedWordsWallet::construct_new(CipherSeed::random()).unwrap());
+        create_account(&conn, "treasury", &wallet_type, "password", Network::Esmeralda).unwrap();
+        let account = crate::db::get_account_by_name(&conn, "treasury").unwrap().unwrap();
+        let cfo = insert_api_key(
+            &conn,
+            &NewApiKey {
+                name: "cfo",
+                key_prefix: "mtk_0000",
+                key_hash: b"cfo",
+                scope: ApiKeyScope::Spend,
+                account_ids: None,
+            },
+        )
+        .unwrap();
+        // The policy's threshold does not matter, burns are never put up for approval
+        crate::db::set_approval_policy(&conn, account.id, 1, MicroMinotari(1_000_000), &[cfo]).unwrap();
+
+        let params = BurnTxParams {
+            account_id: account.id,
+            amount: MicroMinotari(1),
+            claim_public_key: None,
+            sidechain_deployment_key: None,
+            fee_per_gram: MicroMinotari(5),
+            payment_id: None,
+            idempotency_key: Some("burn-1".to_string()),
+            seconds_to_lock: 300,
+            confirmation_window: 3,
+        };
+        let err = create_burn_tx(&account, pool.clone(), Network::Esmeralda, "password", params)
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("approval policy"), "{}", err);
+
+        // Refused before any funds were locked
+        let pending = crate::db::find_pending_transaction_by_idempotency_key(&conn, "burn-1", account.id).unwrap();
+        assert!(pending.is_none());
+    }
+}
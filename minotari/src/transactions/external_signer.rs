@@ -0,0 +1,433 @@
+//! Signing of transactions outside the wallet process.
+//!
+//! Accounts marked as externally signed (see [`AccountRow::external_signer`]) hold only the
+//! view key and public spend key; their spend keys live on a hardware wallet or a remote
+//! signing service. Their transactions are prepared by the wallet as usual and handed to the
+//! signer configured under `[wallet.external_signer]`, either:
+//!
+//! - a **command**, run once per transaction, that reads the request on stdin and writes the
+//!   response to stdout, or
+//! - a local **HTTP endpoint** the request is `POST`ed to, answering with the response.
+//!
+//! # Protocol
+//!
+//! The request is a JSON object:
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "account": "cold-storage",
+//!   "network": "mainnet",
+//!   "unsigned_transaction": { ...PrepareOneSidedTransactionForSigningResult... }
+//! }
+//! ```
+//!
+//! The signer answers with `{"signed_transaction": { ...SignedOneSidedTransactionResult... }}`,
+//! or with `{"error": "reason"}` if it refuses to sign (e.g. the user rejected it on the
+//! device). A command that exits with a non-zero status counts as a refusal. The signed result
+//! must embed the unchanged request, so a signer cannot sign a different transaction than the
+//! one the wallet prepared.
+//!
+//! [`answer_sign_request`] is a reference signer holding the keys in memory. It backs the
+//! `mock_signer` example used in tests, and is not meant for production custody.
+
+use std::{
+    path::{Path, PathBuf},
+    process::Stdio,
+    str::FromStr,
+    time::Duration,
+};
+
+use anyhow::{anyhow, bail};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use tari_common::configuration::Network;
+use tari_transaction_components::{
+    consensus::ConsensusConstantsBuilder,
+    key_manager::{KeyManager, wallet_types::WalletType},
+    offline_signing::{
+        models::{PrepareOneSidedTransactionForSigningResult, SignedOneSidedTransactionResult},
+        sign_locked_transaction,
+    },
+};
+use tokio::io::AsyncWriteExt;
+
+use crate::{config::defaults::ExternalSignerConfig, db::AccountRow};
+
+/// Version of the request format sent to external signers.
+pub const SIGNER_PROTOCOL_VERSION: u32 = 1;
+
+/// Number of characters of a signer's stderr or HTTP error body kept in the error message.
+const MAX_SIGNER_OUTPUT_CHARS: usize = 200;
+
+/// Request sent to an external signer.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignRequest {
+    pub version: u32,
+    /// Name of the account the transaction spends from
+    pub account: String,
+    /// Key string of the network (e.g. `mainnet`)
+    pub network: String,
+    pub unsigned_transaction: PrepareOneSidedTransactionForSigningResult,
+}
+
+/// Response of an external signer. Exactly one of the fields is set.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignResponse {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signed_transaction: Option<SignedOneSidedTransactionResult>,
+    /// Why the signer refused to sign
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// How the signing request reaches the signer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignerTransport {
+    /// Program run per request, with the request on stdin and the response on stdout
+    Command { program: PathBuf, args: Vec<String> },
+    /// Local endpoint the request is posted to
+    Http { url: String },
+}
+
+/// A configured external signer.
+#[derive(Debug, Clone)]
+pub struct ExternalSigner {
+    transport: SignerTransport,
+    timeout: Duration,
+}
+
+impl ExternalSigner {
+    pub fn new(transport: SignerTransport, timeout: Duration) -> Self {
+        Self { transport, timeout }
+    }
+
+    /// Returns the signer set in the configuration, or `None` if no signer is configured.
+    pub fn from_config(config: &ExternalSignerConfig) -> anyhow::Result<Option<Self>> {
+        let transport = match (&config.command, &config.url) {
+            (Some(_), Some(_)) => bail!("Configure either a command or a URL for the external signer, not both"),
+            (Some(program), None) => SignerTransport::Command {
+                program: program.clone(),
+                args: config.args.clone(),
+            },
+            (None, Some(url)) => {
+                reqwest::Url::parse(url).map_err(|e| anyhow!("Invalid external signer URL '{}': {}", url, e))?;
+                SignerTransport::Http { url: url.clone() }
+            },
+            (None, None) => return Ok(None),
+        };
+        Ok(Some(Self::new(transport, Duration::from_secs(config.timeout_secs))))
+    }
+
+    /// Has the signer sign `unsigned` for `account_name`.
+    ///
+    /// Fails if the signer cannot be reached, refuses to sign, or returns a result for another
+    /// transaction.
+    pub async fn sign(
+        &self,
+        account_name: &str,
+        network: Network,
+        unsigned: PrepareOneSidedTransactionForSigningResult,
+    ) -> anyhow::Result<SignedOneSidedTransactionResult> {
+        let expected_request = serde_json::to_value(&unsigned)?;
+        let request = SignRequest {
+            version: SIGNER_PROTOCOL_VERSION,
+            account: account_name.to_string(),
+            network: network.as_key_str().to_string(),
+            unsigned_transaction: unsigned,
+        };
+        let body = serde_json::to_vec(&request)?;
+
+        info!(target: "audit", account = account_name; "Requesting signature from external signer");
+        let response = self.exchange(&body).await?;
+        let signed = decode_response(&response)?;
+
+        if serde_json::to_value(&signed.request)? != expected_request {
+            warn!(target: "audit", account = account_name; "External signer returned a different transaction");
+            bail!("The external signer signed a different transaction than the one requested");
+        }
+        info!(
+            target: "audit",
+            account = account_name,
+            tx_id = signed.signed_transaction.tx_id.to_string().as_str();
+            "Transaction signed by external signer"
+        );
+        Ok(signed)
+    }
+
+    async fn exchange(&self, body: &[u8]) -> anyhow::Result<Vec<u8>> {
+        match &self.transport {
+            SignerTransport::Command { program, args } => self.run_command(program, args, body).await,
+            SignerTransport::Http { url } => self.post(url, body).await,
+        }
+    }
+
+    async fn run_command(&self, program: &Path, args: &[String], body: &[u8]) -> anyhow::Result<Vec<u8>> {
+        let mut child = tokio::process::Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| anyhow!("Failed to run the external signer {}: {}", program.display(), e))?;
+
+        // The request is written while waiting for the response, and both count towards the
+        // timeout, so a signer that does not read its stdin cannot block the wallet
+        let stdin = child.stdin.take();
+        let write_request = async move {
+            if let Some(mut stdin) = stdin {
+                stdin.write_all(body).await?;
+                // Closing stdin signals the end of the request
+                drop(stdin);
+            }
+            Ok::<_, std::io::Error>(())
+        };
+        let exchange = async { tokio::join!(write_request, child.wait_with_output()) };
+
+        match tokio::time::timeout(self.timeout, exchange).await {
+            Ok((_, Ok(output))) if !output.status.success() => Err(anyhow!(
+                "The external signer {} exited with {}: {}",
+                program.display(),
+                output.status,
+                truncate(&String::from_utf8_lossy(&output.stderr))
+            )),
+            Ok((Err(e), Ok(_))) => Err(anyhow!("Failed to pass the request to {}: {}", program.display(), e)),
+            Ok((Ok(()), Ok(output))) => Ok(output.stdout),
+            Ok((_, Err(e))) => Err(anyhow!(
+                "Failed to wait for the external signer {}: {}",
+                program.display(),
+                e
+            )),
+            // The child is killed when dropped
+            Err(_) => Err(anyhow!("The external signer {} timed out", program.display())),
+        }
+    }
+
+    async fn post(&self, url: &str, body: &[u8]) -> anyhow::Result<Vec<u8>> {
+        let client = reqwest::Client::builder().timeout(self.timeout).build()?;
+        let response = client
+            .post(url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body.to_vec())
+            .send()
+            .await
+            .map_err(|e| anyhow!("Failed to reach the external signer at {}: {}", url, e))?;
+
+        let status = response.status();
+        let bytes = response.bytes().await?;
+        if !status.is_success() && serde_json::from_slice::<SignResponse>(&bytes).is_err() {
+            bail!(
+                "The external signer at {} responded with {}: {}",
+                url,
+                status,
+                truncate(&String::from_utf8_lossy(&bytes))
+            );
+        }
+        Ok(bytes.to_vec())
+    }
+}
+
+fn decode_response(response: &[u8]) -> anyhow::Result<SignedOneSidedTransactionResult> {
+    let response: SignResponse =
+        serde_json::from_slice(response).map_err(|e| anyhow!("Invalid response from the external signer: {}", e))?;
+    match response {
+        SignResponse { error: Some(error), .. } => Err(anyhow!("The external signer refused to sign: {}", error)),
+        SignResponse {
+            signed_transaction: Some(signed),
+            ..
+        } => Ok(signed),
+        SignResponse { .. } => Err(anyhow!(
+            "The external signer returned neither a transaction nor an error"
+        )),
+    }
+}
+
+fn truncate(output: &str) -> String {
+    output.trim().chars().take(MAX_SIGNER_OUTPUT_CHARS).collect()
+}
+
+/// Signs `unsigned` for `account` with the external signer if the account is marked as
+/// externally signed, or with the keys the wallet holds for it otherwise.
+pub async fn sign_for_account(
+    account: &AccountRow,
+    password: &str,
+    network: Network,
+    signer: Option<&ExternalSigner>,
+    unsigned: PrepareOneSidedTransactionForSigningResult,
+) -> anyhow::Result<SignedOneSidedTransactionResult> {
+    if account.external_signer {
+        let signer = signer.ok_or_else(|| {
+            anyhow!(
+                "Account '{}' is signed externally, but no external signer is configured in [wallet.external_signer]",
+                account.friendly_name
+            )
+        })?;
+        return signer.sign(&account.friendly_name, network, unsigned).await;
+    }
+
+    let key_manager = account.get_key_manager(password)?;
+    let consensus_constants = ConsensusConstantsBuilder::new(network).build();
+    sign_locked_transaction(&key_manager, consensus_constants, network, unsigned)
+        .map_err(|e| anyhow!("Failed to sign transaction: {}", e))
+}
+
+/// Reference signer: answers a signing request with the keys of `wallet`.
+///
+/// Invalid requests and signing failures are answered with an error response, as a real
+/// signer would.
+pub fn answer_sign_request(wallet: WalletType, request: &[u8]) -> SignResponse {
+    let signed = serde_json::from_slice::<SignRequest>(request)
+        .map_err(|e| anyhow!("Invalid signing request: {}", e))
+        .and_then(|request| {
+            if request.version != SIGNER_PROTOCOL_VERSION {
+                bail!("Unsupported signing request version {}", request.version);
+            }
+            let network = Network::from_str(&request.network)
+                .map_err(|e| anyhow!("Invalid network '{}': {}", request.network, e))?;
+            let key_manager = KeyManager::new(wallet)?;
+            let consensus_constants = ConsensusConstantsBuilder::new(network).build();
+            sign_locked_transaction(&key_manager, consensus_constants, network, request.unsigned_transaction)
+                .map_err(|e| anyhow!("Failed to sign transaction: {}", e))
+        });
+
+    match signed {
+        Ok(signed) => SignResponse {
+            signed_transaction: Some(signed),
+            error: None,
+        },
+        Err(e) => SignResponse {
+            signed_transaction: None,
+            error: Some(e.to_string()),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tari_common_types::seeds::cipher_seed::CipherSeed;
+    use tari_transaction_components::key_manager::wallet_types::SeedWordsWallet;
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+
+    fn shell_signer(script: &str) -> ExternalSigner {
+        ExternalSigner::new(
+            SignerTransport::Command {
+                program: PathBuf::from("/bin/sh"),
+                args: vec!["-c".to_string(), script.to_string()],
+            },
+            Duration::from_secs(5),
+        )
+    }
+
+    #[test]
+    fn test_signer_from_config() {
+        let mut config = ExternalSignerConfig::default();
+        assert!(ExternalSigner::from_config(&config).unwrap().is_none());
+
+        config.command = Some(PathBuf::from("/usr/local/bin/signer"));
+        config.args = vec!["--device".to_string()];
+        let signer = ExternalSigner::from_config(&config).unwrap().unwrap();
+        assert_eq!(
+            signer.transport,
+            SignerTransport::Command {
+                program: PathBuf::from("/usr/local/bin/signer"),
+                args: vec!["--device".to_string()],
+            }
+        );
+
+        config.url = Some("http://127.0.0.1:7070/sign".to_string());
+        assert!(ExternalSigner::from_config(&config).is_err());
+        config.command = None;
+        config.url = Some("not a url".to_string());
+        assert!(ExternalSigner::from_config(&config).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_command_signer_exchange() {
+        let echo = shell_signer("cat");
+        assert_eq!(echo.exchange(b"{\"version\":1}").await.unwrap(), b"{\"version\":1}");
+
+        let failing = shell_signer("cat >/dev/null; echo 'device locked' >&2; exit 3");
+        let error = failing.exchange(b"{}").await.unwrap_err().to_string();
+        assert!(error.contains("device locked"), "{}", error);
+
+        let mut slow = shell_signer("cat >/dev/null; sleep 5");
+        slow.timeout = Duration::from_millis(100);
+        assert!(
+            slow.exchange(b"{}")
+                .await
+                .unwrap_err()
+                .to_string()
+                .contains("timed out")
+        );
+
+        // A request larger than the pipe buffer, sent to a signer that never reads it
+        let mut deaf = shell_signer("sleep 5");
+        deaf.timeout = Duration::from_millis(100);
+        let large_request = vec![b' '; 1024 * 1024];
+        assert!(
+            deaf.exchange(&large_request)
+                .await
+                .unwrap_err()
+                .to_string()
+                .contains("timed out")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_http_signer_exchange() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/sign"))
+            .and(header("Content-Type", "application/json"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"error":"rejected on device"}"#))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/broken"))
+            .respond_with(ResponseTemplate::new(502).set_body_string("bad gateway"))
+            .mount(&mock_server)
+            .await;
+
+        let signer = ExternalSigner::new(
+            SignerTransport::Http {
+                url: format!("{}/sign", mock_server.uri()),
+            },
+            Duration::from_secs(5),
+        );
+        let response = signer.exchange(b"{}").await.unwrap();
+        let error = decode_response(&response).unwrap_err().to_string();
+        assert!(error.contains("rejected on device"), "{}", error);
+
+        let broken = ExternalSigner::new(
+            SignerTransport::Http {
+                url: format!("{}/broken", mock_server.uri()),
+            },
+            Duration::from_secs(5),
+        );
+        assert!(broken.exchange(b"{}").await.unwrap_err().to_string().contains("502"));
+    }
+
+    #[test]
+    fn test_decode_response() {
+        assert!(decode_response(b"not json").is_err());
+        assert!(decode_response(b"{}").is_err());
+        assert!(
+            decode_response(br#"{"error":"user rejected"}"#)
+                .unwrap_err()
+                .to_string()
+                .contains("user rejected")
+        );
+    }
+
+    #[test]
+    fn test_reference_signer_rejects_invalid_requests() {
+        let wallet = WalletType::SeedWords(SeedWordsWallet::construct_new(CipherSeed::random()).unwrap());
+        let response = answer_sign_request(wallet, b"{\"version\":1}");
+        assert!(response.signed_transaction.is_none());
+        assert!(response.error.unwrap().contains("Invalid signing request"));
+    }
+}
@@ -24,6 +24,18 @@
 //! If a transaction with the same idempotency key exists, the existing
 //! transaction data is returned rather than creating a duplicate.
 //!
+//! # Approvals
+//!
+//! If the account has an approval policy covering the payment's amount, the pending
+//! transaction is put up for approval when it is started (see [`crate::db::set_approval_policy`]).
+//! [`finalize_transaction_and_broadcast`](TransactionSender::finalize_transaction_and_broadcast)
+//! refuses to broadcast it until enough approvers have approved it through the REST API. The
+//! approvals lapse together with the UTXO lock, and a single rejection cancels the transaction.
+//! They only apply to the payment they were given for: resuming the transaction with the same
+//! idempotency key but a different recipient, amount or payment id is refused.
+//! Burns and validator node transactions cannot be put up for approval, so they are refused
+//! for accounts with an approval policy.
+//!
 //! # Example
 //!
 //! ```rust,ignore
@@ -59,6 +71,7 @@ use chrono::{Duration, Utc};
 use log::{error, info, warn};
 use r2d2::PooledConnection;
 use r2d2_sqlite::SqliteConnectionManager;
+use rusqlite::Connection;
 use tari_common::configuration::Network;
 use tari_common_types::types::FixedHash;
 use tari_common_types::{tari_address::TariAddressFeatures, transaction::TxId};
@@ -77,10 +90,12 @@ use tari_transaction_components::{
     transaction_components::{MemoField, OutputFeatures, WalletOutput, memo_field::TxType},
 };
 use tari_utilities::ByteArray;
+use zeroize::Zeroizing;
 
-use crate::db::DbWalletOutput;
-use crate::models::OutputStatus;
+use crate::db::{DbApprovalRequest, DbWalletOutput, NewApprovalRequest};
+use crate::models::{OutputStatus, WalletEvent, WalletEventType};
 use crate::transactions::TransactionOutput;
+use crate::webhooks::{WebhookTriggerConfig, utils::trigger_webhook_with_balance};
 use crate::{
     db::{self, AccountRow, SqlitePool},
     http::WalletHttpClient,
@@ -201,13 +216,15 @@ pub struct TransactionSender {
     pub network: Network,
     /// The sender's account.
     pub account: AccountRow,
-    /// Password for key manager access.
-    pub password: String,
+    /// Password for key manager access, wiped from memory when the sender is dropped.
+    pub password: Zeroizing<String>,
     /// The transaction currently being processed.
     pub processed_transactions: ProcessedTransaction,
     /// Fee rate for this transaction.
     pub fee_per_gram: MicroMinotari,
     pub confirmation_window: u64,
+    /// Webhook configuration for approval events.
+    pub webhook_config: Option<WebhookTriggerConfig>,
 }
 
 impl TransactionSender {
@@ -240,14 +257,14 @@ impl TransactionSender {
     /// let sender = TransactionSender::new(
     ///     db_pool,
     ///     "my_wallet".to_string(),
-    ///     "secure_password".to_string(),
+    ///     Zeroizing::new("secure_password".to_string()),
     ///     Network::MainNet,
     /// ).await?;
     /// ```
     pub fn new(
         db_pool: SqlitePool,
         account_name: String,
-        password: String,
+        password: Zeroizing<String>,
         network: Network,
         confirmation_window: u64,
     ) -> Result<Self, anyhow::Error> {
@@ -263,9 +280,15 @@ impl TransactionSender {
             processed_transactions: ProcessedTransaction::default(),
             fee_per_gram: MicroMinotari(5),
             confirmation_window,
+            webhook_config: None,
         })
     }
 
+    /// Sets the webhook configuration used to queue approval events.
+    pub fn set_webhook_config(&mut self, config: Option<WebhookTriggerConfig>) {
+        self.webhook_config = config;
+    }
+
     /// Acquires a database connection from the pool.
     fn get_connection(&self) -> Result<PooledConnection<SqliteConnectionManager>, anyhow::Error> {
         self.db_pool
@@ -429,6 +452,8 @@ impl TransactionSender {
     ///
     /// Returns an error if:
     /// - A completed transaction with the same idempotency key exists
+    /// - The transaction was put up for approval as a payment to a different recipient, of a
+    ///   different amount or with a different payment id
     /// - The sender address does not support one-sided transactions
     /// - Insufficient funds are available
     /// - Transaction building fails
@@ -468,7 +493,14 @@ impl TransactionSender {
         let pending_transaction_id = self.create_or_find_pending_transaction(&mut processed_transaction)?;
         processed_transaction.update_id(pending_transaction_id.clone());
 
+        // Checked before the closure below, as a mismatch must not cancel the approved payment
+        if let Some(request) = db::get_approval_request(&connection, processed_transaction.id())? {
+            ensure_approved_payment(&request, &processed_transaction.recipient)?;
+        }
+
         let result: Result<PrepareOneSidedTransactionForSigningResult, anyhow::Error> = (|| {
+            self.request_approval_if_required(&connection, &processed_transaction)?;
+
             let mut utxo_selection = processed_transaction.selected_utxos.clone();
             if utxo_selection.is_empty() {
                 let db_utxo_selection = db::fetch_outputs_by_lock_request_id(&connection, processed_transaction.id())?;
@@ -518,10 +550,119 @@ impl TransactionSender {
         }
     }
 
+    /// Returns the approval request of the transaction started by
+    /// [`start_new_transaction`](Self::start_new_transaction), or `None` if the account's
+    /// approval policy does not cover it.
+    pub fn approval_request(&self) -> Result<Option<DbApprovalRequest>, anyhow::Error> {
+        let connection = self.get_connection()?;
+        Ok(db::get_approval_request(&connection, self.processed_transactions.id())?)
+    }
+
+    /// Puts the transaction up for approval if the account's approval policy covers its amount.
+    ///
+    /// Does nothing if the transaction already awaits approval, e.g. because it was resumed with
+    /// the same idempotency key.
+    fn request_approval_if_required(
+        &self,
+        connection: &Connection,
+        processed_transaction: &ProcessedTransaction,
+    ) -> Result<(), anyhow::Error> {
+        let Some(policy) = db::get_approval_policy(connection, self.account.id)? else {
+            return Ok(());
+        };
+        let recipient = &processed_transaction.recipient;
+        if !policy.applies_to(recipient.amount) {
+            return Ok(());
+        }
+
+        let tx = connection.unchecked_transaction()?;
+        let created = db::create_approval_request(
+            &tx,
+            &NewApprovalRequest {
+                pending_transaction_id: processed_transaction.id(),
+                account_id: self.account.id,
+                recipient_address: &recipient.address.to_base58(),
+                amount: recipient.amount,
+                payment_id: recipient.payment_id.as_deref(),
+                required_approvals: policy.required_approvals,
+            },
+        )?;
+        if created {
+            info!(
+                target: "audit",
+                id = processed_transaction.id(),
+                required_approvals = policy.required_approvals;
+                "Transaction requires approval"
+            );
+            let event = WalletEvent {
+                id: 0,
+                account_id: self.account.id,
+                event_type: WalletEventType::TransactionApprovalRequested {
+                    tx_id: processed_transaction.id().to_string(),
+                    amount: recipient.amount,
+                    required_approvals: policy.required_approvals,
+                },
+                description: format!(
+                    "Transaction {} needs {} approval(s) before it can be sent",
+                    processed_transaction.id(),
+                    policy.required_approvals
+                ),
+            };
+            let event_id = db::insert_wallet_event(&tx, self.account.id, &event)?;
+            trigger_webhook_with_balance(&tx, self.account.id, event_id, &event, self.webhook_config.as_ref())?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Fails unless the transaction needs no approval or has been approved.
+    ///
+    /// A transaction started before the account's approval policy was set is put up for
+    /// approval now instead of being let through.
+    fn check_approvals(&self, processed_transaction: &ProcessedTransaction) -> Result<(), anyhow::Error> {
+        let connection = self.get_connection()?;
+        self.request_approval_if_required(&connection, processed_transaction)?;
+        let Some(request) = db::get_approval_request(&connection, processed_transaction.id())? else {
+            return Ok(());
+        };
+
+        ensure_approved_payment(&request, &processed_transaction.recipient)?;
+        if let Some(rejection) = request.rejection() {
+            return Err(anyhow!(
+                "Transaction {} was rejected by approver '{}'",
+                request.pending_transaction_id,
+                rejection.approver
+            ));
+        }
+        if !request.is_approved() {
+            return Err(anyhow!(
+                "Transaction {} has {} of {} required approvals",
+                request.pending_transaction_id,
+                request.approvals(),
+                request.required_approvals
+            ));
+        }
+        Ok(())
+    }
+
+    /// Abandons the transaction started by [`start_new_transaction`](Self::start_new_transaction),
+    /// e.g. because the signer refused to sign it, and unlocks its UTXOs.
+    pub fn abandon_transaction(&self) -> Result<(), anyhow::Error> {
+        let connection = self.get_connection()?;
+        info!(
+            target: "audit",
+            id = self.processed_transactions.id();
+            "Abandoning transaction"
+        );
+        self.fail_and_unlock_pending_transaction(&connection, self.processed_transactions.id());
+        Ok(())
+    }
+
     /// Finalizes a signed transaction and broadcasts it to the network.
     ///
     /// This method completes the transaction flow by:
-    /// 1. Verifying the transaction hasn't expired
+    /// 1. Verifying the transaction hasn't expired and, if the account's approval policy
+    ///    covers it, has been approved
     /// 2. Recording the completed transaction in the database
     /// 3. Broadcasting to the network via the wallet HTTP client
     /// 4. Creating a [`DisplayedTransaction`] for immediate UI display
@@ -540,6 +681,7 @@ impl TransactionSender {
     ///
     /// Returns an error if:
     /// - The transaction lock has expired
+    /// - The transaction lacks the approvals required by the account's approval policy
     /// - Transaction serialization fails
     /// - The network rejects the transaction
     /// - Database operations fail
@@ -582,6 +724,11 @@ impl TransactionSender {
                 self.fail_and_unlock_pending_transaction(&connection, processed_transaction.id());
             })?;
 
+        // Approvals lapse with the lock, so they are only checked once the lock is known to hold
+        self.check_approvals(processed_transaction).inspect_err(|e| {
+            warn!(target: "audit", error:% = e; "Transaction is not approved, refusing to broadcast");
+        })?;
+
         // Extract transaction info from the signed result for building DisplayedTransaction
         let tx_info = &signed_transaction_result.request.info;
         let actual_fee = tx_info.fee;
@@ -785,3 +932,164 @@ impl TransactionSender {
         }
     }
 }
+
+/// Fails unless `request` was made for the payment to `recipient`, so that approvals given for
+/// one payment are never used to send another under the same idempotency key.
+fn ensure_approved_payment(request: &DbApprovalRequest, recipient: &Recipient) -> Result<(), anyhow::Error> {
+    if request.is_for_payment(
+        &recipient.address.to_base58(),
+        recipient.amount,
+        recipient.payment_id.as_deref(),
+    ) {
+        return Ok(());
+    }
+
+    warn!(
+        target: "audit",
+        id = &*request.pending_transaction_id;
+        "Refusing to resume transaction as a different payment than the one put up for approval"
+    );
+    Err(anyhow!(
+        "Transaction {} was put up for approval as a payment of {} to {}; it cannot be resumed with a \
+         different recipient, amount or payment id",
+        request.pending_transaction_id,
+        request.amount,
+        request.recipient_address
+    ))
+}
+
+/// Fails if the account has an approval policy.
+///
+/// Burns and validator node transactions are signed and broadcast straight away instead of
+/// going through a pending transaction that can await approvals, so they are refused for
+/// accounts whose outgoing payments need approval.
+pub(crate) fn ensure_no_approval_policy(
+    conn: &Connection,
+    account: &AccountRow,
+    operation: &str,
+) -> Result<(), anyhow::Error> {
+    let Some(policy) = db::get_approval_policy(conn, account.id)? else {
+        return Ok(());
+    };
+
+    warn!(
+        target: "audit",
+        account_id = account.id,
+        operation = operation;
+        "Refusing transaction on account with an approval policy"
+    );
+    Err(anyhow!(
+        "Account '{}' has an approval policy requiring {} approval(s); {} transactions cannot be approved \
+         and are not supported for it",
+        account.friendly_name,
+        policy.required_approvals,
+        operation
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{NewApiKey, create_account, create_pending_transaction, init_db, insert_api_key};
+    use crate::models::{ApiKeyScope, ApprovalDecision};
+    use tari_common_types::{seeds::cipher_seed::CipherSeed, tari_address::TariAddress};
+    use tari_transaction_components::key_manager::wallet_types::{SeedWordsWallet, WalletType};
+    use tempfile::tempdir;
+
+    fn address_of(conn: &Connection, name: &str) -> TariAddress {
+        let wallet_type = WalletType::SeedWords(SeedWordsWallet::construct_new(CipherSeed::random()).unwrap());
+        create_account(conn, name, &wallet_type, "password", Network::Esmeralda).unwrap();
+        db::get_account_by_name(conn, name)
+            .unwrap()
+            .unwrap()
+            .get_address(Network::Esmeralda, "password")
+            .unwrap()
+    }
+
+    #[test]
+    fn test_resuming_approved_transaction_as_different_payment_is_refused() {
+        let temp = tempdir().unwrap();
+        let pool = init_db(temp.path().join("approvals_manager.db"), Network::Esmeralda).unwrap();
+        let conn = pool.get().unwrap();
+        address_of(&conn, "treasury");
+        let payee = address_of(&conn, "payee");
+        let attacker = address_of(&conn, "attacker");
+        let account = db::get_account_by_name(&conn, "treasury").unwrap().unwrap();
+        let cfo = insert_api_key(
+            &conn,
+            &NewApiKey {
+                name: "cfo",
+                key_prefix: "mtk_0000",
+                key_hash: b"cfo",
+                scope: ApiKeyScope::Spend,
+                account_ids: None,
+            },
+        )
+        .unwrap();
+        db::set_approval_policy(&conn, account.id, 1, MicroMinotari(1_000), &[cfo]).unwrap();
+
+        // An approved payment of 5 000 µT to the payee, started with idempotency key "payment-1"
+        let id = create_pending_transaction(
+            &conn,
+            "payment-1",
+            account.id,
+            false,
+            MicroMinotari(10_000),
+            MicroMinotari(10),
+            MicroMinotari(20),
+            Utc::now() + Duration::hours(1),
+        )
+        .unwrap();
+        db::create_approval_request(
+            &conn,
+            &NewApprovalRequest {
+                pending_transaction_id: &id,
+                account_id: account.id,
+                recipient_address: &payee.to_base58(),
+                amount: MicroMinotari(5_000),
+                payment_id: None,
+                required_approvals: 1,
+            },
+        )
+        .unwrap();
+        let request = db::get_approval_request(&conn, &id).unwrap().unwrap();
+        db::record_approval_decision(&conn, &request, cfo, ApprovalDecision::Approved, None).unwrap();
+
+        let mut sender = TransactionSender::new(
+            pool.clone(),
+            "treasury".to_string(),
+            Zeroizing::new("password".to_string()),
+            Network::Esmeralda,
+            3,
+        )
+        .unwrap();
+        let resumed_elsewhere = [
+            Recipient {
+                address: attacker,
+                amount: MicroMinotari(5_000),
+                payment_id: None,
+            },
+            Recipient {
+                address: payee.clone(),
+                amount: MicroMinotari(500_000),
+                payment_id: None,
+            },
+            Recipient {
+                address: payee,
+                amount: MicroMinotari(5_000),
+                payment_id: Some("other invoice".to_string()),
+            },
+        ];
+        for recipient in resumed_elsewhere {
+            let err = sender
+                .start_new_transaction("payment-1".to_string(), recipient, 300)
+                .unwrap_err();
+            assert!(err.to_string().contains("cannot be resumed"), "{}", err);
+        }
+
+        // The approved payment itself stays pending with its approval
+        let pending = db::find_pending_transaction_by_idempotency_key(&conn, "payment-1", account.id).unwrap();
+        assert_eq!(pending.unwrap().id.to_string(), id);
+        assert!(db::get_approval_request(&conn, &id).unwrap().unwrap().is_approved());
+    }
+}
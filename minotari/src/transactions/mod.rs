@@ -33,7 +33,7 @@
 //! 1. **Selection**: [`InputSelector`] selects appropriate UTXOs and calculates fees
 //! 2. **Locking**: [`FundLocker`] locks selected UTXOs with an expiration time
 //! 3. **Building**: [`OneSidedTransaction`] or [`TransactionSender`] constructs the transaction
-//! 4. **Signing**: External signing is performed on the prepared transaction
+//! 4. **Signing**: The prepared transaction is signed by the wallet or an [`external_signer`]
 //! 5. **Broadcasting**: The signed transaction is submitted to the network
 //! 6. **Monitoring**: [`TransactionMonitor`] tracks confirmation status
 //!
@@ -61,6 +61,7 @@
 //! # Modules
 //!
 //! - [`displayed_transaction_processor`]: Processes raw blockchain data into displayable transactions
+//! - [`external_signer`]: Signs transactions of accounts whose keys are held outside the wallet
 //! - [`fee_estimator`]: Estimates fees
 //! - [`fund_locker`]: Manages UTXO locking for transaction construction
 //! - [`input_selector`]: Implements UTXO selection algorithms with fee estimation
@@ -71,6 +72,7 @@
 
 pub mod burn;
 pub mod displayed_transaction_processor;
+pub mod external_signer;
 pub mod fee_estimator;
 pub mod fund_locker;
 pub mod input_selector;
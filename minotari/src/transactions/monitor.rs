@@ -314,9 +314,7 @@ impl TransactionMonitor {
 
     fn persist_event(&self, conn: &Connection, account_id: i64, event: &WalletEvent) -> Result<()> {
         let event_id = db::insert_wallet_event(conn, account_id, event)?;
-        if let Some(config) = &self.webhook_config {
-            trigger_webhook_with_balance(conn, account_id, event_id, event, config)?;
-        }
+        trigger_webhook_with_balance(conn, account_id, event_id, event, self.webhook_config.as_ref())?;
         Ok(())
     }
 
@@ -40,6 +40,8 @@
 //! ).await?;
 //! ```
 
+use std::str::FromStr;
+
 use crate::db::SqlitePool;
 use crate::{api::types::LockFundsResult, db::AccountRow};
 use anyhow::anyhow;
@@ -54,6 +56,7 @@ use tari_transaction_components::{
     tari_amount::MicroMinotari,
     transaction_components::{MemoField, OutputFeatures, memo_field::TxType},
 };
+use zeroize::Zeroizing;
 
 /// Represents a recipient of a one-sided transaction.
 ///
@@ -88,6 +91,29 @@ pub struct Recipient {
     pub payment_id: Option<String>,
 }
 
+impl FromStr for Recipient {
+    type Err = anyhow::Error;
+
+    /// Parses a recipient in `address::amount` or `address::amount::payment_id` format.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.split("::").collect();
+        let (address, amount, payment_id) = match parts.as_slice() {
+            [address, amount] => (address, amount, None),
+            [address, amount, payment_id] => (address, amount, Some(payment_id.to_string())),
+            _ => {
+                return Err(anyhow!(
+                    "Invalid recipient format. Expected 'address::amount' or 'address::amount::payment_id'"
+                ));
+            },
+        };
+        Ok(Recipient {
+            address: TariAddress::from_str(address)?,
+            amount: MicroMinotari::from_str(amount)?,
+            payment_id,
+        })
+    }
+}
+
 /// Builder for creating unsigned one-sided transactions.
 ///
 /// `OneSidedTransaction` prepares transactions that can be sent without recipient
@@ -108,7 +134,7 @@ pub struct Recipient {
 /// let builder = OneSidedTransaction::new(
 ///     db_pool,
 ///     Network::MainNet,
-///     "secure_password".to_string(),
+///     Zeroizing::new("secure_password".to_string()),
 /// );
 ///
 /// let unsigned = builder.create_unsigned_transaction(
@@ -124,7 +150,7 @@ pub struct OneSidedTransaction {
     /// The network (MainNet, TestNet, etc.) for consensus rules.
     pub network: Network,
     /// Password for decrypting the account's key manager.
-    pub password: String,
+    pub password: Zeroizing<String>,
 }
 
 impl OneSidedTransaction {
@@ -141,7 +167,7 @@ impl OneSidedTransaction {
     /// ```rust,ignore
     /// let builder = OneSidedTransaction::new(db_pool, Network::MainNet, password);
     /// ```
-    pub fn new(db_pool: SqlitePool, network: Network, password: String) -> Self {
+    pub fn new(db_pool: SqlitePool, network: Network, password: Zeroizing<String>) -> Self {
         Self {
             db_pool,
             network,
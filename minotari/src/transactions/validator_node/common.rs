@@ -20,14 +20,15 @@ use tari_transaction_components::{
 
 use crate::{
     db::{AccountRow, SqlitePool},
-    transactions::fund_locker::FundLocker,
+    transactions::{fund_locker::FundLocker, manager::ensure_no_approval_policy},
 };
 
 /// Locks the VN registration deposit and prepares a pay-to-self transaction for signing.
 ///
 /// Used by all three VN operations (registration, exit, eviction) which share the same
 /// transaction shape: one output sent back to the sender, carrying operation-specific
-/// `output_features`.
+/// `output_features`. Fails for accounts with an approval policy, since these transactions
+/// are broadcast without awaiting approvals.
 #[allow(clippy::too_many_arguments)]
 pub(crate) fn build_vn_pay_to_self_tx(
     account: &AccountRow,
@@ -42,6 +43,7 @@ pub(crate) fn build_vn_pay_to_self_tx(
     confirmation_window: u64,
     tx_description: &str,
 ) -> Result<PrepareOneSidedTransactionForSigningResult, anyhow::Error> {
+    ensure_no_approval_policy(&db_pool.get()?, account, tx_description)?;
     let consensus_constants = ConsensusConstantsBuilder::new(network).build();
     let deposit_amount = consensus_constants.validator_node_registration_min_deposit_amount();
 
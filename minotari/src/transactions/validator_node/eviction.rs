@@ -7,7 +7,7 @@
 //!
 //! Unlike registration and exit, no wallet-side signature validation is performed —
 //! the proof is self-validating via embedded quorum certificates.
-//! Only [`WalletType::SeedWords`] wallets are supported.
+//! Only [`WalletType::SeedWords`] wallets and externally signed accounts are supported.
 //!
 //! # Flow
 //!
@@ -50,7 +50,7 @@ pub struct ValidatorNodeEvictionParams {
 /// # Errors
 ///
 /// Returns an error if:
-/// - The account is not a [`WalletType::SeedWords`] wallet
+/// - The account is neither a [`WalletType::SeedWords`] wallet nor externally signed
 /// - There are insufficient funds for the consensus-required deposit
 /// - Transaction construction fails
 #[allow(clippy::too_many_arguments)]
@@ -65,9 +65,9 @@ pub fn create_validator_node_eviction_tx(
     confirmation_window: u64,
 ) -> Result<PrepareOneSidedTransactionForSigningResult, anyhow::Error> {
     let wallet_type = account.decrypt_wallet_type(password)?;
-    if !matches!(wallet_type, WalletType::SeedWords(_)) {
+    if !matches!(wallet_type, WalletType::SeedWords(_)) && !account.external_signer {
         return Err(anyhow!(
-            "Validator node eviction requires a SeedWords wallet, not a view-only wallet"
+            "Validator node eviction requires a SeedWords wallet or an external signer, not a view-only wallet"
         ));
     }
 
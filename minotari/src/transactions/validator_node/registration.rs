@@ -9,7 +9,8 @@
 //!
 //! The caller pre-computes the [`ValidatorNodeSignature`] on the validator node
 //! side, then passes the public key and signature to the wallet for transaction
-//! construction. Only [`WalletType::SeedWords`] wallets are supported.
+//! construction. Only [`WalletType::SeedWords`] wallets and externally signed
+//! accounts are supported.
 //!
 //! # Flow
 //!
@@ -63,7 +64,7 @@ pub struct ValidatorNodeRegistrationParams {
 /// # Errors
 ///
 /// Returns an error if:
-/// - The account is not a [`WalletType::SeedWords`] wallet
+/// - The account is neither a [`WalletType::SeedWords`] wallet nor externally signed
 /// - The validator node signature is invalid
 /// - There are insufficient funds for the consensus-required deposit
 /// - Transaction construction fails
@@ -79,9 +80,9 @@ pub fn create_validator_node_registration_tx(
     confirmation_window: u64,
 ) -> Result<PrepareOneSidedTransactionForSigningResult, anyhow::Error> {
     let wallet_type = account.decrypt_wallet_type(password)?;
-    if !matches!(wallet_type, WalletType::SeedWords(_)) {
+    if !matches!(wallet_type, WalletType::SeedWords(_)) && !account.external_signer {
         return Err(anyhow!(
-            "Validator node registration requires a SeedWords wallet, not a view-only wallet"
+            "Validator node registration requires a SeedWords wallet or an external signer, not a view-only wallet"
         ));
     }
 
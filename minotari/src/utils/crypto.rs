@@ -5,6 +5,7 @@ use chacha20poly1305::{
     aead::{Aead, Generate, KeyInit},
 };
 use phc::Salt;
+use rand::{RngCore, rngs::OsRng};
 use tari_common_types::types::{CompressedPublicKey, PrivateKey};
 use tari_utilities::byte_array::ByteArray;
 
@@ -63,6 +64,111 @@ pub fn decrypt_data<S: AsRef<[u8]>>(data: &FullEncryptedData<S>, password: &str)
     Ok(plaintext)
 }
 
+/// Size of the plaintext chunks encrypted by [`StreamEncryptor`], except for the last one.
+pub const STREAM_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Length of the random nonce prefix of an encrypted stream. The remaining five bytes of each
+/// chunk's nonce hold the chunk counter and a flag marking the last chunk.
+pub const STREAM_NONCE_PREFIX_LENGTH: usize = 19;
+
+/// Length of the authentication tag added to every chunk.
+pub const STREAM_TAG_LENGTH: usize = 16;
+
+/// Encrypts data of any size in chunks with XChaCha20-Poly1305, using the STREAM construction.
+///
+/// Each chunk is encrypted with a nonce made of a random prefix, the chunk's position and
+/// whether it is the last chunk, so reordered, dropped or truncated chunks fail to decrypt in
+/// [`StreamDecryptor`]. Every chunk but the last must hold exactly [`STREAM_CHUNK_SIZE`] bytes.
+pub struct StreamEncryptor {
+    cipher: XChaCha20Poly1305,
+    salt: Vec<u8>,
+    nonce_prefix: [u8; STREAM_NONCE_PREFIX_LENGTH],
+    counter: u32,
+}
+
+impl StreamEncryptor {
+    /// Derives the key from `password` with a new salt and picks a random nonce prefix.
+    pub fn new(password: &str) -> Result<Self, anyhow::Error> {
+        let salt = Salt::generate();
+        let key = derive_key(password, salt.as_ref())?;
+        let mut nonce_prefix = [0u8; STREAM_NONCE_PREFIX_LENGTH];
+        OsRng.fill_bytes(&mut nonce_prefix);
+        Ok(Self {
+            cipher: XChaCha20Poly1305::new(&key),
+            salt: salt.as_ref().to_vec(),
+            nonce_prefix,
+            counter: 0,
+        })
+    }
+
+    /// Salt the key was derived with, needed to decrypt the stream.
+    pub fn salt(&self) -> &[u8] {
+        &self.salt
+    }
+
+    /// Nonce prefix of the stream, needed to decrypt it.
+    pub fn nonce_prefix(&self) -> &[u8; STREAM_NONCE_PREFIX_LENGTH] {
+        &self.nonce_prefix
+    }
+
+    /// Encrypts the next chunk. `last` must be set for the final chunk, which may be empty.
+    pub fn encrypt_chunk(&mut self, chunk: &[u8], last: bool) -> Result<Vec<u8>, anyhow::Error> {
+        let nonce = stream_nonce(&self.nonce_prefix, self.counter, last);
+        self.counter = self
+            .counter
+            .checked_add(1)
+            .ok_or_else(|| anyhow!("Too much data for one encrypted stream"))?;
+        self.cipher
+            .encrypt(&nonce, chunk)
+            .map_err(|e| anyhow!("Encryption failed: {}", e))
+    }
+}
+
+/// Decrypts a stream written by [`StreamEncryptor`], chunk by chunk.
+pub struct StreamDecryptor {
+    cipher: XChaCha20Poly1305,
+    nonce_prefix: [u8; STREAM_NONCE_PREFIX_LENGTH],
+    counter: u32,
+}
+
+impl StreamDecryptor {
+    /// Derives the key from `password` and the stream's salt.
+    pub fn new(password: &str, salt: &[u8], nonce_prefix: &[u8]) -> Result<Self, anyhow::Error> {
+        let nonce_prefix = nonce_prefix
+            .try_into()
+            .map_err(|_| anyhow!("Nonce prefix must be {} bytes", STREAM_NONCE_PREFIX_LENGTH))?;
+        let key = derive_key(password, salt)?;
+        Ok(Self {
+            cipher: XChaCha20Poly1305::new(&key),
+            nonce_prefix,
+            counter: 0,
+        })
+    }
+
+    /// Decrypts the next chunk. `last` must be set for the final chunk.
+    pub fn decrypt_chunk(&mut self, chunk: &[u8], last: bool) -> Result<Vec<u8>, anyhow::Error> {
+        let nonce = stream_nonce(&self.nonce_prefix, self.counter, last);
+        self.counter = self
+            .counter
+            .checked_add(1)
+            .ok_or_else(|| anyhow!("Too much data for one encrypted stream"))?;
+        self.cipher
+            .decrypt(&nonce, chunk)
+            .map_err(|e| anyhow!("Decryption failed: {}", e))
+    }
+}
+
+/// Builds the nonce of a stream chunk: the prefix, the big-endian counter and the last-chunk flag.
+fn stream_nonce(prefix: &[u8; STREAM_NONCE_PREFIX_LENGTH], counter: u32, last: bool) -> XNonce {
+    let mut nonce = [0u8; 24];
+    let (nonce_prefix, rest) = nonce.split_at_mut(STREAM_NONCE_PREFIX_LENGTH);
+    let (nonce_counter, flag) = rest.split_at_mut(4);
+    nonce_prefix.copy_from_slice(prefix);
+    nonce_counter.copy_from_slice(&counter.to_be_bytes());
+    flag.copy_from_slice(&[u8::from(last)]);
+    XNonce::from(nonce)
+}
+
 /// Decodes a hex string into a [`CompressedPublicKey`].
 pub fn parse_public_key_hex(s: &str) -> Result<CompressedPublicKey, anyhow::Error> {
     let bytes = hex::decode(s).map_err(|e| anyhow!("Invalid public key hex: {}", e))?;
@@ -1,5 +1,7 @@
 use std::path::Path;
 
+use tari_common::configuration::Network;
+
 use crate::db::{self, init_db};
 use anyhow::Context;
 
@@ -10,9 +12,10 @@ use anyhow::Context;
 /// # Parameters
 ///
 /// * `database_file` - Path to the SQLite database file
+/// * `network` - The network the database belongs to
 /// * `account_name` - The friendly name of the account to delete
-pub fn delete_wallet(database_file: &Path, account_name: &str) -> Result<(), anyhow::Error> {
-    let pool = init_db(database_file.to_path_buf()).context("Failed to initialize database")?;
+pub fn delete_wallet(database_file: &Path, network: Network, account_name: &str) -> Result<(), anyhow::Error> {
+    let pool = init_db(database_file.to_path_buf(), network).context("Failed to initialize database")?;
     let mut conn = pool.get().context("Failed to get DB connection from pool")?;
 
     let tx = conn.transaction()?;
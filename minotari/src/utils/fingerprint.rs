@@ -1,3 +1,4 @@
+use tari_common_types::tari_address::TariAddress;
 use tari_crypto::{hash_domain, hashing::DomainSeparatedHasher};
 use tari_transaction_components::key_manager::wallet_types::{KeyDigest, WalletType};
 use tari_utilities::ByteArray;
@@ -16,10 +17,31 @@ pub fn calculate_fingerprint(wallet_type: &WalletType) -> Vec<u8> {
     // even if the root keys are the same.
     let account_index = wallet_type.get_ledger_details().map(|l| l.account).unwrap_or(0);
 
+    hash_keys(
+        public_spend.as_bytes(),
+        public_view.as_bytes(),
+        &account_index.to_le_bytes(),
+    )
+}
+
+/// Whether `address` is an address of the wallet with `fingerprint`, i.e. carries its public spend
+/// and view keys. Ledger wallets are only recognised for their first account.
+pub fn is_address_of(address: &TariAddress, fingerprint: &[u8]) -> bool {
+    let Some(public_view) = address.public_view_key() else {
+        return false;
+    };
+    hash_keys(
+        address.public_spend_key().as_bytes(),
+        public_view.as_bytes(),
+        &0u64.to_le_bytes(),
+    ) == fingerprint
+}
+
+fn hash_keys(public_spend: &[u8], public_view: &[u8], account_index: &[u8]) -> Vec<u8> {
     let hash = DomainSeparatedHasher::<KeyDigest, KeyManagerDomain>::new_with_label(WALLET_FINGERPRINT_LABEL)
-        .chain(public_spend.as_bytes())
-        .chain(public_view.as_bytes())
-        .chain(account_index.to_le_bytes())
+        .chain(public_spend)
+        .chain(public_view)
+        .chain(account_index)
         .finalize();
 
     hash.as_ref().to_vec()
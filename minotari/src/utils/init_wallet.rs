@@ -8,6 +8,7 @@ use std::path::Path;
 
 use crate::{db, init_db};
 use anyhow::Context;
+use tari_common::configuration::Network;
 use tari_common_types::{
     seeds::cipher_seed::CipherSeed,
     types::{CompressedPublicKey, PrivateKey},
@@ -30,6 +31,7 @@ use tari_utilities::hex::Hex;
 /// * `spend_public_key` - Hex-encoded spend public key
 /// * `password` - Password for encrypting the keys (will be padded to 32 bytes)
 /// * `database_file` - Path to the SQLite database file
+/// * `network` - Network the wallet belongs to
 /// * `birthday` - Block height to start scanning from (0 to scan entire chain)
 /// * `friendly_name` - Optional account name (defaults to "default")
 ///
@@ -53,6 +55,7 @@ use tari_utilities::hex::Hex;
 /// ```ignore
 /// use minotari::utils::init_wallet::init_with_view_key;
 /// use std::path::Path;
+/// use tari_common::configuration::Network;
 ///
 /// # fn example() -> Result<(), anyhow::Error> {
 /// init_with_view_key(
@@ -60,6 +63,7 @@ use tari_utilities::hex::Hex;
 ///     "b1c2d3e4f5a0...",  // spend_public_key (hex)
 ///     "my_secure_password",
 ///     Path::new("wallet.db"),
+///     Network::MainNet,
 ///     0,                   // birthday height
 ///     Some("my_wallet"),   // account name
 /// )?;
@@ -73,6 +77,7 @@ pub fn init_with_view_key(
     spend_public_key: &str,
     password: &str,
     database_file: &Path,
+    network: Network,
     birthday: u16,
     friendly_name: Option<&str>,
 ) -> Result<(), anyhow::Error> {
@@ -83,7 +88,7 @@ pub fn init_with_view_key(
     let view_wallet = ViewWallet::new(spend_key, view_key, Some(birthday));
     let wallet_enum = WalletType::ViewWallet(view_wallet);
 
-    save_wallet_to_db(wallet_enum, password, database_file, friendly_name)
+    save_wallet_to_db(wallet_enum, password, database_file, network, friendly_name)
 }
 
 // Initializes a new wallet account using a Seed (CipherSeed).
@@ -95,32 +100,35 @@ pub fn init_with_view_key(
 /// * `cipher_seed` - The seed containing entropy and birthday.
 /// * `password` - Password for encrypting the wallet.
 /// * `database_file` - Path to the SQLite database file.
+/// * `network` - Network the wallet belongs to.
 /// * `friendly_name` - Optional account name (defaults to "default").
 pub fn init_with_seed_words(
     cipher_seed: CipherSeed,
     password: &str,
     database_file: &Path,
+    network: Network,
     friendly_name: Option<&str>,
 ) -> Result<(), anyhow::Error> {
     let seed_wallet =
         SeedWordsWallet::construct_new(cipher_seed).map_err(|e| anyhow::anyhow!("Invalid seed: {}", e))?;
     let wallet_enum = WalletType::SeedWords(seed_wallet);
 
-    save_wallet_to_db(wallet_enum, password, database_file, friendly_name)
+    save_wallet_to_db(wallet_enum, password, database_file, network, friendly_name)
 }
 
 fn save_wallet_to_db(
     wallet: WalletType,
     password: &str,
     database_file: &Path,
+    network: Network,
     friendly_name: Option<&str>,
 ) -> Result<(), anyhow::Error> {
-    let pool = init_db(database_file.to_path_buf()).context("Failed to initialize database")?;
+    let pool = init_db(database_file.to_path_buf(), network).context("Failed to initialize database")?;
     let conn = pool.get().context("Failed to get DB connection from pool")?;
 
     let name = friendly_name.unwrap_or("default");
 
-    db::create_account(&conn, name, &wallet, password)?;
+    db::create_account(&conn, name, &wallet, password, network)?;
 
     Ok(())
 }
@@ -2,5 +2,7 @@ pub mod crypto;
 pub mod delete_wallet;
 pub mod fingerprint;
 pub mod init_wallet;
+pub mod password;
 pub mod rename_wallet;
+pub mod seed_words;
 pub mod timestamp;
@@ -0,0 +1,233 @@
+//! Reading the wallet password without exposing it on the command line.
+//!
+//! A password passed with `--password` ends up in the shell history and is visible to other
+//! users in process listings. The wallet password can instead be read from:
+//!
+//! - an environment variable (`--password-env`, or [`PASSWORD_ENV_VAR`] by default)
+//! - an inherited file descriptor (`--password-fd`), e.g. a pipe set up by a secret manager
+//! - a file that only its owner can read (`--password-file`)
+//! - an interactive prompt, used when no other source is given and stdin is a terminal
+//!
+//! Passwords read from a file or file descriptor have a single trailing newline removed.
+//! The password is returned as [`Zeroizing`] so that it is wiped from memory once dropped.
+
+use std::{
+    fs::{self, File},
+    io::{IsTerminal, Read},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, anyhow, bail};
+use log::warn;
+use zeroize::Zeroizing;
+
+/// Environment variable read when no password source is given on the command line.
+pub const PASSWORD_ENV_VAR: &str = "MINOTARI_PASSWORD";
+
+/// Where to read the wallet password from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PasswordSource {
+    /// Password given directly on the command line
+    Argument(String),
+    /// Name of an environment variable holding the password
+    EnvVar(String),
+    /// Inherited file descriptor to read the password from
+    FileDescriptor(u32),
+    /// File holding the password, which must not be accessible by group or others
+    File(PathBuf),
+    /// Interactive prompt
+    Prompt,
+    /// [`PASSWORD_ENV_VAR`] if set, otherwise an interactive prompt
+    Default,
+}
+
+/// Reads the wallet password from the given source.
+///
+/// An interactive prompt shows `prompt`. If `confirm` is set, it asks for the password twice,
+/// which should be used when a new wallet is encrypted with it.
+///
+/// # Errors
+///
+/// Fails if the source cannot be read, the password is empty, a password file is accessible
+/// by group or others, or no source is given and stdin is not a terminal.
+pub fn read_password(source: &PasswordSource, prompt: &str, confirm: bool) -> anyhow::Result<Zeroizing<String>> {
+    let password = match source {
+        PasswordSource::Argument(password) => {
+            warn!("A password given on the command line is visible in the shell history and process list");
+            Zeroizing::new(password.clone())
+        },
+        PasswordSource::EnvVar(name) => {
+            read_env_var(name).ok_or_else(|| anyhow!("Environment variable {} is not set or not valid UTF-8", name))?
+        },
+        PasswordSource::FileDescriptor(fd) => read_file_descriptor(*fd)?,
+        PasswordSource::File(path) => read_password_file(path)?,
+        PasswordSource::Prompt => prompt_password(prompt, confirm)?,
+        PasswordSource::Default => match read_env_var(PASSWORD_ENV_VAR) {
+            Some(password) => password,
+            None => prompt_password(prompt, confirm)?,
+        },
+    };
+
+    if password.is_empty() {
+        bail!("The wallet password must not be empty");
+    }
+    Ok(password)
+}
+
+fn read_env_var(name: &str) -> Option<Zeroizing<String>> {
+    std::env::var(name).ok().map(Zeroizing::new)
+}
+
+#[cfg(unix)]
+fn read_file_descriptor(fd: u32) -> anyhow::Result<Zeroizing<String>> {
+    let contents = Zeroizing::new(
+        fs::read_to_string(format!("/dev/fd/{}", fd))
+            .with_context(|| format!("Failed to read the password from file descriptor {}", fd))?,
+    );
+    Ok(strip_trailing_newline(&contents))
+}
+
+#[cfg(not(unix))]
+fn read_file_descriptor(_fd: u32) -> anyhow::Result<Zeroizing<String>> {
+    bail!("Reading the password from a file descriptor is only supported on Unix")
+}
+
+/// Reads a password file through a single handle, so the file that is checked is the file that is
+/// read even if the path is swapped in between.
+fn read_password_file(path: &Path) -> anyhow::Result<Zeroizing<String>> {
+    let mut file =
+        open_password_file(path).with_context(|| format!("Failed to open password file {}", path.display()))?;
+    check_file_permissions(path, &file)?;
+    let mut contents = Zeroizing::new(String::new());
+    file.read_to_string(&mut contents)
+        .with_context(|| format!("Failed to read password file {}", path.display()))?;
+    Ok(strip_trailing_newline(&contents))
+}
+
+/// Opens the password file without following a symlink in its final component.
+#[cfg(unix)]
+fn open_password_file(path: &Path) -> std::io::Result<File> {
+    use std::os::unix::fs::OpenOptionsExt;
+
+    fs::OpenOptions::new()
+        .read(true)
+        .custom_flags(libc::O_NOFOLLOW)
+        .open(path)
+}
+
+#[cfg(not(unix))]
+fn open_password_file(path: &Path) -> std::io::Result<File> {
+    File::open(path)
+}
+
+#[cfg(unix)]
+fn check_file_permissions(path: &Path, file: &File) -> anyhow::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let metadata = file
+        .metadata()
+        .with_context(|| format!("Failed to read password file {}", path.display()))?;
+    if !metadata.is_file() {
+        bail!("Password file {} is not a regular file", path.display());
+    }
+    let mode = metadata.permissions().mode();
+    if mode & 0o077 != 0 {
+        bail!(
+            "Password file {} is accessible by group or others (mode {:o}), restrict it with `chmod 600`",
+            path.display(),
+            mode & 0o777
+        );
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn check_file_permissions(path: &Path, file: &File) -> anyhow::Result<()> {
+    let metadata = file
+        .metadata()
+        .with_context(|| format!("Failed to read password file {}", path.display()))?;
+    if !metadata.is_file() {
+        bail!("Password file {} is not a regular file", path.display());
+    }
+    Ok(())
+}
+
+fn prompt_password(prompt: &str, confirm: bool) -> anyhow::Result<Zeroizing<String>> {
+    if !std::io::stdin().is_terminal() {
+        bail!(
+            "{} not given and stdin is not a terminal to prompt for it. Read it from a file, a file descriptor \
+             or an environment variable instead",
+            prompt
+        );
+    }
+
+    let mut input = dialoguer::Password::new().with_prompt(prompt);
+    if confirm {
+        input = input.with_confirmation(
+            format!("Repeat {}", prompt.to_lowercase()),
+            "The passwords do not match",
+        );
+    }
+    let password = input
+        .interact()
+        .context("Failed to read the password from the terminal")?;
+    Ok(Zeroizing::new(password))
+}
+
+fn strip_trailing_newline(contents: &str) -> Zeroizing<String> {
+    let password = contents
+        .strip_suffix('\n')
+        .map(|s| s.strip_suffix('\r').unwrap_or(s))
+        .unwrap_or(contents);
+    Zeroizing::new(password.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_trailing_newline() {
+        assert_eq!(*strip_trailing_newline("secret\n"), "secret");
+        assert_eq!(*strip_trailing_newline("secret\r\n"), "secret");
+        assert_eq!(*strip_trailing_newline("secret\n\n"), "secret\n");
+        assert_eq!(*strip_trailing_newline(" secret "), " secret ");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_password_file_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("password");
+        fs::write(&path, "secret\n").unwrap();
+
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+        assert!(read_password(&PasswordSource::File(path.clone()), "Wallet password", false).is_err());
+
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o600)).unwrap();
+        let password = read_password(&PasswordSource::File(path.clone()), "Wallet password", false).unwrap();
+        assert_eq!(*password, "secret");
+
+        fs::write(&path, "\n").unwrap();
+        assert!(read_password(&PasswordSource::File(path), "Wallet password", false).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_password_file_symlink_is_refused() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("password");
+        fs::write(&target, "secret\n").unwrap();
+        fs::set_permissions(&target, fs::Permissions::from_mode(0o600)).unwrap();
+        let link = dir.path().join("password-link");
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+
+        assert!(read_password(&PasswordSource::File(link), "Wallet password", false).is_err());
+        let password = read_password(&PasswordSource::File(target), "Wallet password", false).unwrap();
+        assert_eq!(*password, "secret");
+    }
+}
@@ -0,0 +1,117 @@
+//! Seed words in every mnemonic language, optionally protected by a passphrase.
+//!
+//! A cipher seed is written as seed words after enciphering it with a passphrase, or with the
+//! default passphrase if none is given. The passphrase does not change the keys of the wallet:
+//! it only protects the seed words, so they can only be restored with it. The language is
+//! detected from the words when restoring, unless it is given explicitly, which is only needed
+//! for the few seeds whose words exist in more than one word list.
+
+use std::str::FromStr;
+
+use anyhow::anyhow;
+use clap::ValueEnum;
+use tari_common_types::seeds::{
+    cipher_seed::CipherSeed,
+    mnemonic::{Mnemonic, MnemonicLanguage},
+    seed_words::SeedWords,
+};
+use tari_utilities::SafePassword;
+
+/// Word list seed words are written in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum SeedLanguage {
+    ChineseSimplified,
+    #[default]
+    English,
+    French,
+    Italian,
+    Japanese,
+    Korean,
+    Spanish,
+}
+
+impl From<SeedLanguage> for MnemonicLanguage {
+    fn from(language: SeedLanguage) -> Self {
+        match language {
+            SeedLanguage::ChineseSimplified => MnemonicLanguage::ChineseSimplified,
+            SeedLanguage::English => MnemonicLanguage::English,
+            SeedLanguage::French => MnemonicLanguage::French,
+            SeedLanguage::Italian => MnemonicLanguage::Italian,
+            SeedLanguage::Japanese => MnemonicLanguage::Japanese,
+            SeedLanguage::Korean => MnemonicLanguage::Korean,
+            SeedLanguage::Spanish => MnemonicLanguage::Spanish,
+        }
+    }
+}
+
+/// Writes `cipher_seed` as seed words in `language`, enciphered with `passphrase` if given.
+pub fn to_seed_words(
+    cipher_seed: &CipherSeed,
+    language: SeedLanguage,
+    passphrase: Option<&str>,
+) -> anyhow::Result<SeedWords> {
+    cipher_seed
+        .to_mnemonic(language.into(), passphrase.map(safe_passphrase))
+        .map_err(|e| anyhow!("Failed to generate seed words: {}", e))
+}
+
+/// Restores a cipher seed from space separated seed words.
+///
+/// The language is detected from the words unless `language` is given. Fails if the words
+/// are not valid seed words or were enciphered with another passphrase.
+pub fn from_seed_words(
+    words: &str,
+    language: Option<SeedLanguage>,
+    passphrase: Option<&str>,
+) -> anyhow::Result<CipherSeed> {
+    let mnemonic = SeedWords::from_str(words).map_err(|e| anyhow!("Invalid seed words format: {}", e))?;
+    let passphrase = passphrase.map(safe_passphrase);
+    match language {
+        Some(language) => CipherSeed::from_mnemonic_with_language(&mnemonic, language.into(), passphrase),
+        None => CipherSeed::from_mnemonic(&mnemonic, passphrase),
+    }
+    .map_err(|e| anyhow!("Invalid seed words or passphrase: {}", e))
+}
+
+fn safe_passphrase(passphrase: &str) -> SafePassword {
+    SafePassword::from(passphrase.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn english_words(cipher_seed: &CipherSeed) -> String {
+        to_seed_words(cipher_seed, SeedLanguage::English, None)
+            .unwrap()
+            .join(" ")
+            .reveal()
+            .clone()
+    }
+
+    #[test]
+    fn test_seed_words_round_trip() {
+        let cipher_seed = CipherSeed::random();
+        let expected = english_words(&cipher_seed);
+        for language in SeedLanguage::value_variants() {
+            for passphrase in [None, Some("correct horse battery staple")] {
+                let words = to_seed_words(&cipher_seed, *language, passphrase).unwrap().join(" ");
+                let restored = from_seed_words(words.reveal(), None, passphrase).unwrap();
+                assert_eq!(english_words(&restored), expected);
+                assert_eq!(restored.birthday(), cipher_seed.birthday());
+                let restored = from_seed_words(words.reveal(), Some(*language), passphrase).unwrap();
+                assert_eq!(english_words(&restored), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_wrong_passphrase_is_rejected() {
+        let cipher_seed = CipherSeed::random();
+        let words = to_seed_words(&cipher_seed, SeedLanguage::Spanish, Some("secret"))
+            .unwrap()
+            .join(" ");
+        assert!(from_seed_words(words.reveal(), None, None).is_err());
+        assert!(from_seed_words(words.reveal(), None, Some("other")).is_err());
+    }
+}
@@ -0,0 +1,269 @@
+//! Reconciliation of account balances against the outputs they were derived from.
+//!
+//! A balance is recorded three times: as credits and debits in `balance_changes`, as the
+//! outputs and inputs the scanner detected, and as the displayed transactions the API serves.
+//! [`verify_accounts`] recomputes the balance from the outputs and inputs and compares it to
+//! the other two, and checks the links between the tables that a reorg rewrites:
+//!
+//! - every output marked spent has exactly one active input, and no other output has one
+//! - every locked output belongs to a pending transaction that is still pending or completed
+//! - every balance change marked reversed has its reversal
+//!
+//! Displayed transactions are derived data. [`repair_displayed_transactions`] regenerates them
+//! from the active balance changes and marks the ones no balance change backs as reorganized.
+
+use std::{collections::HashSet, path::Path};
+
+use anyhow::anyhow;
+use log::info;
+use tari_common::configuration::Network;
+
+use crate::{
+    db::{self, SpentOutputMismatch, StaleOutputLock},
+    transactions::{DisplayedTransactionProcessor, TransactionDisplayStatus},
+};
+
+/// Results of verifying one account.
+#[derive(Debug, Clone)]
+pub struct AccountVerification {
+    pub account_id: i64,
+    pub account_name: String,
+    /// Credits minus debits in `balance_changes`
+    pub ledger_balance: i64,
+    /// Value of the active outputs no active input spends
+    pub unspent_output_value: i64,
+    /// Incoming minus outgoing amounts of the mined displayed transactions
+    pub displayed_balance: i64,
+    pub spent_output_mismatches: Vec<SpentOutputMismatch>,
+    pub stale_output_locks: Vec<StaleOutputLock>,
+    /// Balance changes marked reversed that have no reversal
+    pub unreversed_balance_changes: Vec<i64>,
+}
+
+impl AccountVerification {
+    /// Whether the ledger agrees with the outputs and inputs.
+    pub fn ledger_matches_outputs(&self) -> bool {
+        self.ledger_balance == self.unspent_output_value
+    }
+
+    /// Whether the displayed transactions add up to the ledger.
+    pub fn displayed_matches_ledger(&self) -> bool {
+        self.displayed_balance == self.ledger_balance
+    }
+
+    /// Whether every check passed.
+    pub fn is_consistent(&self) -> bool {
+        self.ledger_matches_outputs()
+            && self.displayed_matches_ledger()
+            && self.spent_output_mismatches.is_empty()
+            && self.stale_output_locks.is_empty()
+            && self.unreversed_balance_changes.is_empty()
+    }
+}
+
+/// Changes made by [`repair_displayed_transactions`].
+#[derive(Debug, Default, Clone, Copy)]
+pub struct DisplayedTransactionRepair {
+    /// Existing displayed transactions that were brought up to date
+    pub updated: usize,
+    /// Displayed transactions that were missing
+    pub inserted: usize,
+    /// Displayed transactions no balance change backs, now marked reorganized
+    pub reorganized: usize,
+}
+
+/// Verifies the named account, or every account in the database.
+pub fn verify_accounts(
+    database_file: &Path,
+    network: Network,
+    account_name: Option<&str>,
+) -> anyhow::Result<Vec<AccountVerification>> {
+    let pool = db::init_db(database_file.to_path_buf(), network)?;
+    let conn = pool.get()?;
+
+    let accounts = db::get_accounts(&conn, account_name)?;
+    if let Some(name) = account_name
+        && accounts.is_empty()
+    {
+        return Err(anyhow!("Account '{}' not found", name));
+    }
+
+    let mut results = Vec::with_capacity(accounts.len());
+    for account in accounts {
+        let balance = db::get_balance(&conn, account.id)?;
+        let credits = balance.total_credits.unwrap_or_default().as_u64();
+        let debits = balance.total_debits.unwrap_or_default().as_u64();
+        #[allow(clippy::cast_possible_wrap)]
+        let ledger_balance = credits as i64 - debits as i64;
+
+        let verification = AccountVerification {
+            account_id: account.id,
+            ledger_balance,
+            unspent_output_value: db::get_unspent_output_value(&conn, account.id)?,
+            displayed_balance: db::get_displayed_net_amount(&conn, account.id)?,
+            spent_output_mismatches: db::find_spent_output_mismatches(&conn, account.id)?,
+            stale_output_locks: db::find_stale_output_locks(&conn, account.id)?,
+            unreversed_balance_changes: db::find_reversed_balance_changes_without_reversal(&conn, account.id)?,
+            account_name: account.friendly_name,
+        };
+        info!(
+            target: "audit",
+            account = verification.account_name.as_str(),
+            consistent = verification.is_consistent();
+            "Verified account"
+        );
+        results.push(verification);
+    }
+    Ok(results)
+}
+
+/// Regenerates the displayed transactions of an account from its active balance changes.
+///
+/// The password decrypts the view key the transaction ids are derived from. Transactions are
+/// confirmed once they are `required_confirmations` blocks below the scanned tip.
+pub fn repair_displayed_transactions(
+    database_file: &Path,
+    network: Network,
+    account_name: &str,
+    password: &str,
+    required_confirmations: u64,
+) -> anyhow::Result<DisplayedTransactionRepair> {
+    let pool = db::init_db(database_file.to_path_buf(), network)?;
+    let mut conn = pool.get()?;
+
+    let account =
+        db::get_account_by_name(&conn, account_name)?.ok_or_else(|| anyhow!("Account '{}' not found", account_name))?;
+    let view_key = account.decrypt_wallet_type(password)?.get_view_key().clone();
+    let tip_height = db::get_latest_scanned_tip_block_by_account(&conn, account.id)?.map_or(0, |tip| tip.height);
+
+    let tx = conn.transaction()?;
+    let processor = DisplayedTransactionProcessor::new(tip_height, required_confirmations, view_key);
+    let (updated, new) = processor.process_all_stored_with_conn(account.id, &tx)?;
+    for displayed in &updated {
+        db::update_displayed_transaction_mined(&tx, displayed)?;
+    }
+    for displayed in &new {
+        db::insert_displayed_transaction(&tx, displayed)?;
+    }
+
+    let backed: HashSet<_> = updated.iter().chain(&new).map(|displayed| displayed.id).collect();
+    let mut reorganized = 0;
+    for status in [
+        TransactionDisplayStatus::Unconfirmed,
+        TransactionDisplayStatus::Confirmed,
+    ] {
+        for mut stale in db::get_displayed_transactions_by_status(&tx, account.id, status)? {
+            if backed.contains(&stale.id) {
+                continue;
+            }
+            stale.status = TransactionDisplayStatus::Reorganized;
+            db::update_displayed_transaction_status(
+                &tx,
+                &stale.id.to_string(),
+                TransactionDisplayStatus::Reorganized,
+                &stale,
+            )?;
+            reorganized += 1;
+        }
+    }
+    tx.commit()?;
+
+    let repair = DisplayedTransactionRepair {
+        updated: updated.len(),
+        inserted: new.len(),
+        reorganized,
+    };
+    info!(
+        target: "audit",
+        account = account_name,
+        updated = repair.updated,
+        inserted = repair.inserted,
+        reorganized = repair.reorganized;
+        "Regenerated displayed transactions"
+    );
+    Ok(repair)
+}
+
+#[cfg(test)]
+mod tests {
+    use rusqlite::{Connection, named_params};
+    use tari_common_types::seeds::cipher_seed::CipherSeed;
+    use tari_transaction_components::key_manager::wallet_types::{SeedWordsWallet, WalletType};
+
+    use super::*;
+
+    fn insert_output(conn: &Connection, account_id: i64, tx_id: i64, value: i64, status: &str) -> i64 {
+        conn.execute(
+            r#"
+            INSERT INTO outputs (account_id, tx_id, output_hash, mined_in_block_hash, mined_in_block_height, value,
+                wallet_output_json, mined_timestamp, status)
+            VALUES (:account_id, :tx_id, :hash, :hash, 1, :value, '{}', CURRENT_TIMESTAMP, :status)
+            "#,
+            named_params! {
+                ":account_id": account_id,
+                ":tx_id": tx_id,
+                ":hash": tx_id.to_le_bytes().to_vec(),
+                ":value": value,
+                ":status": status,
+            },
+        )
+        .unwrap();
+        let output_id = conn.last_insert_rowid();
+        conn.execute(
+            r#"
+            INSERT INTO balance_changes (account_id, caused_by_output_id, description, balance_debit,
+                balance_credit, effective_date, effective_height)
+            VALUES (:account_id, :output_id, 'Output found', 0, :value, CURRENT_TIMESTAMP, 1)
+            "#,
+            named_params! { ":account_id": account_id, ":output_id": output_id, ":value": value },
+        )
+        .unwrap();
+        output_id
+    }
+
+    #[test]
+    fn test_verify_accounts() {
+        let dir = tempfile::tempdir().unwrap();
+        let database_file = dir.path().join("wallet.db");
+        let pool = db::init_db(database_file.clone(), Network::Esmeralda).unwrap();
+        let conn = pool.get().unwrap();
+        let wallet = WalletType::SeedWords(SeedWordsWallet::construct_new(CipherSeed::random()).unwrap());
+        db::create_account(&conn, "default", &wallet, "password", Network::Esmeralda).unwrap();
+        let account = db::get_account_by_name(&conn, "default").unwrap().unwrap();
+
+        insert_output(&conn, account.id, 1, 100, "UNSPENT");
+        let verification = verify_accounts(&database_file, Network::Esmeralda, Some("default")).unwrap();
+        let verification = verification.first().unwrap();
+        assert_eq!(verification.ledger_balance, 100);
+        assert!(verification.ledger_matches_outputs());
+        assert!(!verification.displayed_matches_ledger());
+        assert!(verification.spent_output_mismatches.is_empty());
+
+        let spent = insert_output(&conn, account.id, 2, 50, "SPENT");
+        let locked = insert_output(&conn, account.id, 3, 25, "LOCKED");
+        conn.execute(
+            "UPDATE outputs SET locked_by_request_id = 'gone' WHERE id = :id",
+            named_params! { ":id": locked },
+        )
+        .unwrap();
+        conn.execute(
+            "UPDATE balance_changes SET is_reversed = TRUE WHERE caused_by_output_id = :id",
+            named_params! { ":id": spent },
+        )
+        .unwrap();
+
+        let verification = verify_accounts(&database_file, Network::Esmeralda, None).unwrap();
+        let verification = verification.first().unwrap();
+        assert!(!verification.is_consistent());
+        assert_eq!(verification.ledger_balance, 175);
+        assert_eq!(verification.unspent_output_value, 175);
+        let mismatch = verification.spent_output_mismatches.first().unwrap();
+        assert_eq!((mismatch.output_id, mismatch.active_inputs), (spent, 0));
+        let lock = verification.stale_output_locks.first().unwrap();
+        assert_eq!(lock.output_id, locked);
+        assert_eq!(lock.pending_status, None);
+        assert_eq!(verification.unreversed_balance_changes.len(), 1);
+
+        assert!(verify_accounts(&database_file, Network::Esmeralda, Some("missing")).is_err());
+    }
+}
@@ -1,5 +1,6 @@
 pub mod models;
 pub mod sender;
+pub mod sinks;
 pub mod utils;
 pub mod worker;
 
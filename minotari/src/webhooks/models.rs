@@ -2,11 +2,16 @@ use chrono::NaiveDateTime;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::str::FromStr;
+use tari_common::configuration::Network;
 
+/// Settings used when events are queued for webhook delivery.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WebhookTriggerConfig {
-    pub url: String,
+    /// Webhook from the config file, `None` if events are only delivered to subscriptions
+    pub url: Option<String>,
     pub send_only_event_types: Option<Vec<String>>,
+    /// Network reported in webhook payloads
+    pub network: Network,
 }
 
 /// Represents the status of a webhook delivery attempt.
@@ -54,10 +59,15 @@ pub struct WebhookQueueItem {
     pub event_id: Option<i64>,
     pub event_type: String,
     pub payload: String,
+    /// Current URL of the subscription, or the URL the item was queued for if it has none
     pub target_url: String,
     pub status: WebhookStatus,
     pub attempt_count: i32,
     pub next_retry_at: NaiveDateTime,
     pub created_at: NaiveDateTime,
     pub last_error: Option<String>,
+    /// Subscription the item was queued for, `None` for the webhook from the config file
+    pub subscription_id: Option<i64>,
+    /// Current signing secret of the subscription
+    pub secret: Option<String>,
 }
@@ -0,0 +1,267 @@
+//! Delivery targets for queued wallet events.
+//!
+//! Every queue item (and every webhook subscription) has a target URL whose scheme selects
+//! the sink the payload is delivered to:
+//!
+//! | Scheme | Example | Delivery |
+//! |--------|---------|----------|
+//! | `http`, `https` | `https://hooks.example.com/minotari` | Signed `POST` request, see [`WebhookSender`] |
+//! | `file` | `file:///var/log/minotari/events.jsonl` | One JSON line appended per event |
+//! | `unix` | `unix:///run/shipper/events.sock` | One JSON line written per connection |
+//! | `exec` | `exec:///usr/local/bin/on-wallet-event` | Program run per event with the payload on stdin |
+//!
+//! All sinks share the queueing, filtering and retry logic of the
+//! [`WebhookWorker`](super::worker::WebhookWorker): a sink reports a [`DeliveryResult`] and
+//! failed deliveries are retried with the same backoff. Local paths are taken literally
+//! (no percent-decoding) and must be absolute. Only HTTP deliveries are signed, since local
+//! sinks are configured by whoever operates the machine.
+
+use std::{
+    path::{Path, PathBuf},
+    process::Stdio,
+    time::Duration,
+};
+
+use tokio::io::AsyncWriteExt;
+
+use crate::webhooks::sender::{DeliveryResult, WebhookSender};
+
+/// Maximum time a Unix socket write or an `exec` program may take before it counts as failed.
+const LOCAL_SINK_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Number of characters of an `exec` program's stderr kept in the error message.
+const MAX_STDERR_CHARS: usize = 200;
+
+/// Where a queued event is delivered to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EventSink {
+    /// Signed POST to an HTTP(S) endpoint
+    Http(String),
+    /// Append-only JSONL file
+    File(PathBuf),
+    /// Unix domain socket, one connection per event
+    UnixSocket(PathBuf),
+    /// Program executed per event, receiving the payload on stdin
+    Command(PathBuf),
+}
+
+impl EventSink {
+    /// Parses a target URL into a sink.
+    pub fn parse(target: &str) -> Result<Self, String> {
+        let (scheme, rest) = target
+            .split_once(':')
+            .ok_or_else(|| format!("Invalid event sink '{}': missing scheme", target))?;
+
+        match scheme.to_ascii_lowercase().as_str() {
+            "http" | "https" => {
+                reqwest::Url::parse(target).map_err(|e| format!("Invalid webhook URL '{}': {}", target, e))?;
+                Ok(Self::Http(target.to_string()))
+            },
+            "file" => local_path(target, rest).map(Self::File),
+            "unix" => local_path(target, rest).map(Self::UnixSocket),
+            "exec" => local_path(target, rest).map(Self::Command),
+            other => Err(format!(
+                "Unsupported event sink scheme '{}', use http, https, file, unix or exec",
+                other
+            )),
+        }
+    }
+
+    /// Whether the sink writes to the local machine rather than sending over the network.
+    pub fn is_local(&self) -> bool {
+        !matches!(self, Self::Http(_))
+    }
+
+    /// Delivers a payload to the sink.
+    ///
+    /// HTTP deliveries are signed with `secret` and fail permanently without one.
+    pub async fn deliver(&self, http: &WebhookSender, secret: Option<&str>, payload: &str) -> DeliveryResult {
+        match self {
+            Self::Http(url) => match secret {
+                Some(secret) => http.send(url, secret, payload).await,
+                None => DeliveryResult::PermanentFailure("No webhook signing secret configured".to_string()),
+            },
+            Self::File(path) => append_line(path, payload).await,
+            Self::UnixSocket(path) => write_to_socket(path, payload).await,
+            Self::Command(program) => run_command(program, payload, LOCAL_SINK_TIMEOUT).await,
+        }
+    }
+}
+
+/// Extracts the absolute path of `scheme:/path` or `scheme:///path`.
+fn local_path(target: &str, rest: &str) -> Result<PathBuf, String> {
+    let path = rest.strip_prefix("//").unwrap_or(rest);
+    if path.starts_with('/') {
+        Ok(PathBuf::from(path))
+    } else {
+        Err(format!("Invalid event sink '{}': expected an absolute path", target))
+    }
+}
+
+async fn append_line(path: &Path, payload: &str) -> DeliveryResult {
+    let result = async {
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .await?;
+        // A single write keeps lines intact when several processes append to the same file
+        file.write_all(format!("{}\n", payload).as_bytes()).await?;
+        file.flush().await
+    }
+    .await;
+
+    match result {
+        Ok(()) => DeliveryResult::Success,
+        Err(e) => DeliveryResult::RetryableFailure(format!("Failed to append to {}: {}", path.display(), e)),
+    }
+}
+
+#[cfg(unix)]
+async fn write_to_socket(path: &Path, payload: &str) -> DeliveryResult {
+    let result = tokio::time::timeout(LOCAL_SINK_TIMEOUT, async {
+        let mut stream = tokio::net::UnixStream::connect(path).await?;
+        stream.write_all(format!("{}\n", payload).as_bytes()).await?;
+        stream.shutdown().await
+    })
+    .await;
+
+    match result {
+        Ok(Ok(())) => DeliveryResult::Success,
+        Ok(Err(e)) => DeliveryResult::RetryableFailure(format!("Failed to write to socket {}: {}", path.display(), e)),
+        Err(_) => DeliveryResult::RetryableFailure(format!("Timed out writing to socket {}", path.display())),
+    }
+}
+
+#[cfg(not(unix))]
+async fn write_to_socket(path: &Path, _payload: &str) -> DeliveryResult {
+    DeliveryResult::PermanentFailure(format!(
+        "Cannot write to {}: Unix sockets are not supported on this platform",
+        path.display()
+    ))
+}
+
+async fn run_command(program: &Path, payload: &str, timeout: Duration) -> DeliveryResult {
+    let mut child = match tokio::process::Command::new(program)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(e) => return DeliveryResult::RetryableFailure(format!("Failed to run {}: {}", program.display(), e)),
+    };
+
+    // The payload is written while waiting for the program to exit, and both count towards the
+    // timeout, so a program that does not read its stdin cannot block the delivery worker
+    let stdin = child.stdin.take();
+    let write_payload = async move {
+        if let Some(mut stdin) = stdin {
+            stdin.write_all(payload.as_bytes()).await?;
+            // Closing stdin signals the end of the payload
+            drop(stdin);
+        }
+        Ok::<_, std::io::Error>(())
+    };
+    let delivery = async { tokio::join!(write_payload, child.wait_with_output()) };
+
+    match tokio::time::timeout(timeout, delivery).await {
+        Ok((_, Ok(output))) if !output.status.success() => {
+            let stderr = String::from_utf8_lossy(&output.stderr)
+                .trim()
+                .chars()
+                .take(MAX_STDERR_CHARS)
+                .collect::<String>();
+            DeliveryResult::RetryableFailure(format!(
+                "{} exited with {}: {}",
+                program.display(),
+                output.status,
+                stderr
+            ))
+        },
+        Ok((Err(e), Ok(_))) => {
+            DeliveryResult::RetryableFailure(format!("Failed to pass the payload to {}: {}", program.display(), e))
+        },
+        Ok((Ok(()), Ok(_))) => DeliveryResult::Success,
+        Ok((_, Err(e))) => DeliveryResult::RetryableFailure(format!("Failed to wait for {}: {}", program.display(), e)),
+        // The child is killed when dropped
+        Err(_) => DeliveryResult::RetryableFailure(format!("{} timed out", program.display())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_parse_event_sinks() {
+        assert_eq!(
+            EventSink::parse("https://hooks.example.com/minotari"),
+            Ok(EventSink::Http("https://hooks.example.com/minotari".to_string()))
+        );
+        assert_eq!(
+            EventSink::parse("file:///var/log/minotari/events.jsonl"),
+            Ok(EventSink::File(PathBuf::from("/var/log/minotari/events.jsonl")))
+        );
+        assert_eq!(
+            EventSink::parse("unix:/run/shipper.sock"),
+            Ok(EventSink::UnixSocket(PathBuf::from("/run/shipper.sock")))
+        );
+        assert_eq!(
+            EventSink::parse("exec:///usr/local/bin/on-event"),
+            Ok(EventSink::Command(PathBuf::from("/usr/local/bin/on-event")))
+        );
+
+        assert!(EventSink::parse("file://relative/events.jsonl").is_err());
+        assert!(EventSink::parse("ftp://example.com/events").is_err());
+        assert!(EventSink::parse("not a url").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_file_sink_appends_json_lines() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("events.jsonl");
+        let sink = EventSink::File(path.clone());
+        let http = WebhookSender::new();
+
+        assert!(matches!(
+            sink.deliver(&http, None, r#"{"event_id":1}"#).await,
+            DeliveryResult::Success
+        ));
+        assert!(matches!(
+            sink.deliver(&http, None, r#"{"event_id":2}"#).await,
+            DeliveryResult::Success
+        ));
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(contents, "{\"event_id\":1}\n{\"event_id\":2}\n");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_command_sink_reports_exit_status() {
+        let http = WebhookSender::new();
+
+        let cat = EventSink::Command(PathBuf::from("/bin/cat"));
+        assert!(matches!(cat.deliver(&http, None, "{}").await, DeliveryResult::Success));
+
+        let failing = EventSink::Command(PathBuf::from("/bin/false"));
+        assert!(matches!(
+            failing.deliver(&http, None, "{}").await,
+            DeliveryResult::RetryableFailure(_)
+        ));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_command_sink_times_out_when_stdin_is_not_read() {
+        // A payload larger than the pipe buffer, sent to a program that never reads it or exits
+        let payload = " ".repeat(1024 * 1024);
+        match run_command(Path::new("/usr/bin/yes"), &payload, Duration::from_millis(100)).await {
+            DeliveryResult::RetryableFailure(e) => assert!(e.contains("timed out"), "{}", e),
+            other => panic!("Expected a timeout, got {:?}", other),
+        }
+    }
+}
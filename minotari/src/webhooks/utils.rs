@@ -1,12 +1,64 @@
-use crate::db::{self, WalletDbResult};
-use crate::models::{WalletEvent, WebhookBalanceSnapshot, WebhookPayload};
+use crate::db::{self, DbWebhookSubscription, WalletDbError, WalletDbResult};
+use crate::models::{
+    WEBHOOK_PAYLOAD_SCHEMA_VERSION, WalletEvent, WalletEventType, WebhookBalanceSnapshot, WebhookPayload,
+    WebhookTransaction,
+};
+use crate::transactions::{DisplayedTransaction, TransactionDirection};
 use crate::webhooks::WebhookTriggerConfig;
+use crate::webhooks::sinks::EventSink;
 use chrono::Utc;
 use log::warn;
+use rand::{RngCore, rngs::OsRng};
 use rusqlite::Connection;
+use tari_common_types::payment_reference::generate_payment_reference;
+
+/// Prefix of generated webhook signing secrets.
+const WEBHOOK_SECRET_PREFIX: &str = "whsec_";
+
+/// Generates a random secret for signing the payloads of a webhook subscription.
+pub fn generate_webhook_secret() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    format!("{WEBHOOK_SECRET_PREFIX}{}", hex::encode(bytes))
+}
+
+/// Checks that a webhook URL is an absolute `http` or `https` URL, or a local event sink.
+///
+/// Local sinks (`file:`, `unix:` and `exec:`) write to or run programs on the wallet's machine,
+/// so they are only accepted when `allow_local_sinks` is set, i.e. when configured from the
+/// command line rather than over the API.
+pub fn validate_webhook_url(url: &str, allow_local_sinks: bool) -> Result<(), String> {
+    let sink = EventSink::parse(url)?;
+    if sink.is_local() && !allow_local_sinks {
+        return Err(format!(
+            "Event sink '{}' writes to the local machine and can only be configured from the command line",
+            url
+        ));
+    }
+    Ok(())
+}
+
+/// Checks that every entry of an event type filter is a known wallet event type.
+pub fn validate_event_types(event_types: &[String]) -> Result<(), String> {
+    match event_types
+        .iter()
+        .find(|event_type| !WalletEventType::KEY_STRINGS.contains(&event_type.as_str()))
+    {
+        Some(unknown) => Err(format!(
+            "Unknown event type '{}', expected one of: {}",
+            unknown,
+            WalletEventType::KEY_STRINGS.join(", ")
+        )),
+        None => Ok(()),
+    }
+}
 
 /// Enriches an event with the current account balance and enqueues it for webhook delivery.
 ///
+/// One queue item is created for the webhook from the config file (if `config` is set and
+/// its event filter matches) and one for every enabled subscription matching the event's
+/// type and account.
+///
 /// This function should be called inside the same transaction that created the event
 /// to ensure data consistency.
 pub fn trigger_webhook_with_balance(
@@ -14,17 +66,121 @@ pub fn trigger_webhook_with_balance(
     account_id: i64,
     event_id: i64,
     event: &WalletEvent,
-    config: &WebhookTriggerConfig,
+    config: Option<&WebhookTriggerConfig>,
 ) -> WalletDbResult<()> {
-    let event_key = event.event_type.to_key_string();
+    let targets = matching_targets(conn, account_id, &event.event_type, config)?;
+    enqueue_for_targets(conn, account_id, event_id, &event.event_type, config, targets)?;
+    Ok(())
+}
+
+/// Re-sends a stored wallet event to webhook receivers.
+///
+/// With a `subscription`, the event is queued for that subscription only, regardless of its
+/// event type filter, but never to a subscription scoped to other accounts. Otherwise it is
+/// queued for every receiver that would have received it when it was recorded. The balance in
+/// the payload is the account's current balance, not the balance at the time of the event.
+///
+/// Returns the number of queued deliveries, or `None` if the event does not exist.
+///
+/// # Errors
+///
+/// Fails with [`WalletDbError::InvalidInput`] if `subscription` may not receive events of the
+/// event's account.
+pub fn resend_event(
+    conn: &Connection,
+    event_id: i64,
+    config: Option<&WebhookTriggerConfig>,
+    subscription: Option<&DbWebhookSubscription>,
+) -> WalletDbResult<Option<usize>> {
+    let Some(event) = db::get_wallet_event_by_id(conn, event_id)? else {
+        return Ok(None);
+    };
+    let data_json = event
+        .data_json
+        .ok_or_else(|| WalletDbError::Unexpected(format!("Event {} has no stored event data", event_id)))?;
+    let event_type: WalletEventType = serde_json::from_str(&data_json)?;
+
+    let targets = match subscription {
+        Some(subscription) if !subscription.allows_account(event.account_id) => {
+            return Err(WalletDbError::InvalidInput(format!(
+                "Webhook subscription '{}' does not receive events of account {}",
+                subscription.name, event.account_id
+            )));
+        },
+        Some(subscription) => vec![(Some(subscription.id), subscription.url.clone())],
+        None => matching_targets(conn, event.account_id, &event_type, config)?,
+    };
+    let count = enqueue_for_targets(conn, event.account_id, event_id, &event_type, config, targets)?;
+    Ok(Some(count))
+}
 
-    if let Some(allowed_events) = &config.send_only_event_types
-        && !allowed_events.is_empty()
-        && !allowed_events.contains(&event_key)
+/// Collects the `(subscription id, url)` pairs an event of the given type should be sent to.
+fn matching_targets(
+    conn: &Connection,
+    account_id: i64,
+    event_type: &WalletEventType,
+    config: Option<&WebhookTriggerConfig>,
+) -> WalletDbResult<Vec<(Option<i64>, String)>> {
+    let event_key = event_type.to_key_string();
+
+    let mut targets = Vec::new();
+    if let Some(config) = config
+        && let Some(url) = &config.url
+        && config
+            .send_only_event_types
+            .as_ref()
+            .is_none_or(|allowed_events| allowed_events.is_empty() || allowed_events.contains(&event_key))
     {
-        return Ok(());
+        targets.push((None, url.clone()));
+    }
+    for subscription in db::get_matching_webhook_subscriptions(conn, account_id, &event_key)? {
+        targets.push((Some(subscription.id), subscription.url));
     }
+    Ok(targets)
+}
+
+fn enqueue_for_targets(
+    conn: &Connection,
+    account_id: i64,
+    event_id: i64,
+    event_type: &WalletEventType,
+    config: Option<&WebhookTriggerConfig>,
+    targets: Vec<(Option<i64>, String)>,
+) -> WalletDbResult<usize> {
+    if targets.is_empty() {
+        return Ok(0);
+    }
+
+    let payload = build_payload(conn, account_id, event_id, event_type, config)?;
+    let payload_json = serde_json::to_string(&payload).map_err(|e| {
+        warn!("Failed to serialize webhook payload: {}", e);
+        WalletDbError::Unexpected(format!("Serialization error: {}", e))
+    })?;
+
+    for (subscription_id, url) in &targets {
+        db::enqueue_webhook(
+            conn,
+            Some(event_id),
+            &payload.event_type,
+            &payload_json,
+            url,
+            *subscription_id,
+        )?;
+    }
+
+    Ok(targets.len())
+}
 
+/// Builds the payload of an event, including the current balance and the related transaction.
+fn build_payload(
+    conn: &Connection,
+    account_id: i64,
+    event_id: i64,
+    event_type: &WalletEventType,
+    config: Option<&WebhookTriggerConfig>,
+) -> WalletDbResult<WebhookPayload> {
+    let account = db::get_account_by_id(conn, account_id)?
+        .ok_or_else(|| WalletDbError::Unexpected(format!("Account {} not found", account_id)))?;
     let balance = db::get_balance(conn, account_id)?;
 
     let snapshot = WebhookBalanceSnapshot {
@@ -33,22 +189,80 @@ pub fn trigger_webhook_with_balance(
         pending_outgoing: balance.locked.0,
     };
 
-    let payload = WebhookPayload {
+    Ok(WebhookPayload {
+        schema_version: WEBHOOK_PAYLOAD_SCHEMA_VERSION,
         event_id,
-        event_type: event.event_type.to_key_string(),
+        event_type: event_type.to_key_string(),
         created_at: Utc::now().to_rfc3339(),
+        account_id,
+        account_name: account.friendly_name,
+        network: config.map(|config| config.network.as_key_str().to_string()),
         balance: Some(snapshot),
-        data: event.event_type.clone(),
-    };
+        transaction: related_transaction(conn, account_id, event_type)?.map(summarize_transaction),
+        data: event_type.clone(),
+    })
+}
 
-    let payload_json = serde_json::to_string(&payload).map_err(|e| {
-        warn!("Failed to serialize webhook payload: {}", e);
-        crate::db::WalletDbError::Unexpected(format!("Serialization error: {}", e))
-    })?;
+/// Looks up the displayed transaction an output or transaction event refers to.
+fn related_transaction(
+    conn: &Connection,
+    account_id: i64,
+    event_type: &WalletEventType,
+) -> WalletDbResult<Option<DisplayedTransaction>> {
+    match event_type {
+        WalletEventType::OutputDetected { hash, block_height, .. }
+        | WalletEventType::OutputConfirmed { hash, block_height, .. }
+        | WalletEventType::OutputRolledBack {
+            hash,
+            original_block_height: block_height,
+            ..
+        } => db::find_displayed_transaction_by_output_hash(conn, account_id, *block_height, hash),
+        WalletEventType::TransactionBroadcast { tx_id, .. }
+        | WalletEventType::TransactionUnconfirmed { tx_id, .. }
+        | WalletEventType::TransactionConfirmed { tx_id, .. }
+        | WalletEventType::TransactionRejected { tx_id, .. }
+        | WalletEventType::TransactionReorged { tx_id, .. }
+        | WalletEventType::LargeIncomingPaymentAlert { tx_id, .. }
+        | WalletEventType::TransactionStuckAlert { tx_id, .. } => {
+            db::get_displayed_transaction_by_id(conn, &tx_id.to_string())
+        },
+        WalletEventType::BlockRolledBack { .. }
+        | WalletEventType::PendingTransactionCancelled { .. }
+        | WalletEventType::LowBalanceAlert { .. }
+        | WalletEventType::HighBalanceAlert { .. }
+        | WalletEventType::ScannerLagAlert { .. }
+        | WalletEventType::WebhookBacklogAlert { .. }
+        | WalletEventType::TransactionApprovalRequested { .. }
+        | WalletEventType::TransactionApproved { .. }
+        | WalletEventType::TransactionApprovalRejected { .. } => Ok(None),
+    }
+}
 
-    db::enqueue_webhook(conn, Some(event_id), &payload.event_type, &payload_json, &config.url)?;
+fn summarize_transaction(tx: DisplayedTransaction) -> WebhookTransaction {
+    let payrefs = match tx.direction {
+        TransactionDirection::Outgoing => tx.details.sent_payrefs.iter().map(hex::encode).collect(),
+        TransactionDirection::Incoming => tx
+            .details
+            .outputs
+            .iter()
+            .filter(|output| !output.is_change)
+            .map(|output| hex::encode(generate_payment_reference(&output.mined_in_block_hash, &output.hash)))
+            .collect(),
+    };
 
-    Ok(())
+    WebhookTransaction {
+        id: tx.id,
+        direction: tx.direction,
+        source: tx.source,
+        status: tx.status,
+        amount: tx.amount.as_u64(),
+        fee: tx.fee.map(|fee| fee.amount.as_u64()),
+        counterparty: tx.counterparty.map(|address| address.to_base58()),
+        memo: tx.message,
+        payrefs,
+        block_height: tx.blockchain.block_height,
+        confirmations: tx.blockchain.confirmations,
+    }
 }
 
 #[cfg(test)]
@@ -56,19 +270,24 @@ mod tests {
     #![allow(clippy::indexing_slicing)]
     use super::*;
     use crate::db::{create_account, get_account_by_name, init_db, insert_balance_change, insert_wallet_event};
-    use crate::models::{BalanceChange, WalletEvent, WalletEventType};
+    use crate::models::{BalanceChange, OutputStatus, WalletEvent, WalletEventType};
+    use crate::transactions::{
+        DisplayedTransactionBuilder, TransactionDisplayStatus, TransactionOutput, TransactionSource,
+    };
     use r2d2::PooledConnection;
     use r2d2_sqlite::SqliteConnectionManager;
+    use tari_common::configuration::Network;
     use tari_common_types::seeds::cipher_seed::CipherSeed;
     use tari_common_types::types::FixedHash;
     use tari_transaction_components::key_manager::wallet_types::{SeedWordsWallet, WalletType};
     use tari_transaction_components::tari_amount::MicroMinotari;
+    use tari_transaction_components::transaction_components::OutputType;
     use tempfile::tempdir;
 
     fn create_test_account(conn: &PooledConnection<SqliteConnectionManager>, name: &str) -> db::AccountRow {
         let seeds = CipherSeed::random();
         let wallet_type = WalletType::SeedWords(SeedWordsWallet::construct_new(seeds).unwrap());
-        create_account(conn, name, &wallet_type, "password").unwrap();
+        create_account(conn, name, &wallet_type, "password", Network::Esmeralda).unwrap();
         get_account_by_name(conn, name).unwrap().unwrap()
     }
 
@@ -76,7 +295,7 @@ mod tests {
     fn test_trigger_webhook_captures_correct_balance() {
         // Setup DB and Account
         let temp_dir = tempdir().unwrap();
-        let pool = init_db(temp_dir.path().join("test_trigger.db")).unwrap();
+        let pool = init_db(temp_dir.path().join("test_trigger.db"), Network::Esmeralda).unwrap();
         let conn = pool.get().unwrap();
 
         let account = create_test_account(&conn, "boss_account");
@@ -150,11 +369,12 @@ mod tests {
         // Execution: Trigger the webhook
         let webhook_url = "https://hooks.slack.com/services/T000/B000/XXXX";
         let config = WebhookTriggerConfig {
-            url: webhook_url.to_string(),
+            url: Some(webhook_url.to_string()),
             send_only_event_types: None,
+            network: Network::Esmeralda,
         };
 
-        trigger_webhook_with_balance(&conn, account.id, event_id, &event, &config).unwrap();
+        trigger_webhook_with_balance(&conn, account.id, event_id, &event, Some(&config)).unwrap();
 
         // Assertions: Check the queue
         let mut stmt = conn.prepare("SELECT payload FROM webhook_queue").unwrap();
@@ -174,12 +394,82 @@ mod tests {
 
         // Verify the data payload contains the tx_id
         assert_eq!(parsed["data"]["TransactionConfirmed"]["tx_id"], 12345);
+
+        assert_eq!(parsed["schema_version"], WEBHOOK_PAYLOAD_SCHEMA_VERSION);
+        assert_eq!(parsed["account_name"], "boss_account");
+        assert_eq!(parsed["network"], "esmeralda");
+        assert!(parsed["transaction"].is_null(), "no displayed transaction with this id");
+    }
+
+    #[test]
+    fn test_payload_includes_related_transaction() {
+        let temp_dir = tempdir().unwrap();
+        let pool = init_db(temp_dir.path().join("test_payload_transaction.db"), Network::Esmeralda).unwrap();
+        let conn = pool.get().unwrap();
+
+        let account = create_test_account(&conn, "boss_account");
+        let output_hash = FixedHash::from([7u8; 32]);
+        let block_hash = FixedHash::from([1u8; 32]);
+        let displayed = DisplayedTransactionBuilder::new()
+            .account_id(account.id)
+            .source(TransactionSource::OneSided)
+            .status(TransactionDisplayStatus::Unconfirmed)
+            .credits_and_debits(MicroMinotari::from(5_000), MicroMinotari::from(0))
+            .message(Some("Invoice 1001".to_string()))
+            .blockchain_info(150, block_hash, Utc::now().naive_utc(), 1)
+            .outputs(vec![TransactionOutput {
+                hash: output_hash,
+                amount: MicroMinotari::from(5_000),
+                status: OutputStatus::Unspent,
+                mined_in_block_height: 150,
+                mined_in_block_hash: block_hash,
+                output_type: OutputType::Standard,
+                is_change: false,
+            }])
+            .build(99_u64.into())
+            .unwrap();
+        db::insert_displayed_transaction(&conn, &displayed).unwrap();
+
+        let event = WalletEvent {
+            id: 0,
+            account_id: account.id,
+            event_type: WalletEventType::OutputDetected {
+                hash: output_hash,
+                block_height: 150,
+                block_hash: block_hash.to_vec(),
+                memo_parsed: Some("Invoice 1001".to_string()),
+                memo_hex: None,
+            },
+            description: "Detected output".into(),
+        };
+        let event_id = insert_wallet_event(&conn, account.id, &event).unwrap();
+        let config = WebhookTriggerConfig {
+            url: Some("https://config.example.com".to_string()),
+            send_only_event_types: None,
+            network: Network::Esmeralda,
+        };
+        trigger_webhook_with_balance(&conn, account.id, event_id, &event, Some(&config)).unwrap();
+
+        let payload_json: String = conn
+            .query_row("SELECT payload FROM webhook_queue", [], |r| r.get(0))
+            .unwrap();
+        let payload: WebhookPayload = serde_json::from_str(&payload_json).unwrap();
+        let transaction = payload.transaction.expect("transaction should be included");
+        assert_eq!(transaction.id, 99_u64.into());
+        assert_eq!(transaction.direction, TransactionDirection::Incoming);
+        assert_eq!(transaction.amount, 5_000);
+        assert_eq!(transaction.memo.as_deref(), Some("Invoice 1001"));
+        assert_eq!(
+            transaction.payrefs,
+            vec![hex::encode(generate_payment_reference(&block_hash, &output_hash))]
+        );
+        assert_eq!(transaction.confirmations, 1);
     }
 
     #[test]
     fn test_trigger_webhook_filtering() {
         let temp_dir = tempdir().unwrap();
-        let pool = init_db(temp_dir.path().join("test_filtering.db")).unwrap();
+        let pool = init_db(temp_dir.path().join("test_filtering.db"), Network::Esmeralda).unwrap();
         let conn = pool.get().unwrap();
 
         let account = create_test_account(&conn, "boss_account");
@@ -187,8 +477,9 @@ mod tests {
 
         // Define a config that ONLY allows 'TransactionConfirmed'
         let config = WebhookTriggerConfig {
-            url: "http://example.com".to_string(),
+            url: Some("http://example.com".to_string()),
             send_only_event_types: Some(vec!["TransactionConfirmed".to_string()]),
+            network: Network::Esmeralda,
         };
 
         // 1. Create an event that should be BLOCKED (OutputDetected)
@@ -207,7 +498,7 @@ mod tests {
         let blocked_id = insert_wallet_event(&conn, account_id, &blocked_event).unwrap();
 
         // Attempt trigger
-        trigger_webhook_with_balance(&conn, account_id, blocked_id, &blocked_event, &config).unwrap();
+        trigger_webhook_with_balance(&conn, account_id, blocked_id, &blocked_event, Some(&config)).unwrap();
 
         // Verify queue is EMPTY
         let count: i64 = conn
@@ -229,7 +520,7 @@ mod tests {
         let allowed_id = insert_wallet_event(&conn, account_id, &allowed_event).unwrap();
 
         // Attempt trigger
-        trigger_webhook_with_balance(&conn, account_id, allowed_id, &allowed_event, &config).unwrap();
+        trigger_webhook_with_balance(&conn, account_id, allowed_id, &allowed_event, Some(&config)).unwrap();
 
         // Verify queue has ONE item
         let count: i64 = conn
@@ -243,4 +534,101 @@ mod tests {
             .unwrap();
         assert_eq!(event_type, "TransactionConfirmed");
     }
+
+    #[test]
+    fn test_trigger_webhook_fans_out_to_subscriptions() {
+        let temp_dir = tempdir().unwrap();
+        let pool = init_db(temp_dir.path().join("test_subscriptions.db"), Network::Esmeralda).unwrap();
+        let conn = pool.get().unwrap();
+
+        let account = create_test_account(&conn, "boss_account");
+        let other = create_test_account(&conn, "other_account");
+
+        let other_ids = [other.id];
+        for (name, account_ids) in [("accounting", None), ("support", Some(&other_ids[..]))] {
+            db::insert_webhook_subscription(
+                &conn,
+                &db::NewWebhookSubscription {
+                    name,
+                    url: &format!("https://{}.example.com", name),
+                    secret: &generate_webhook_secret(),
+                    event_types: None,
+                    account_ids,
+                },
+            )
+            .unwrap();
+        }
+
+        let event = WalletEvent {
+            id: 0,
+            account_id: account.id,
+            event_type: WalletEventType::TransactionConfirmed {
+                tx_id: 1_u64.into(),
+                mined_height: 12,
+                confirmation_height: 15,
+            },
+            description: "Confirmed transaction".into(),
+        };
+        let event_id = insert_wallet_event(&conn, account.id, &event).unwrap();
+        let config = WebhookTriggerConfig {
+            url: Some("https://config.example.com".to_string()),
+            send_only_event_types: None,
+            network: Network::Esmeralda,
+        };
+
+        trigger_webhook_with_balance(&conn, account.id, event_id, &event, Some(&config)).unwrap();
+
+        let mut stmt = conn
+            .prepare("SELECT target_url, subscription_id IS NOT NULL FROM webhook_queue ORDER BY id")
+            .unwrap();
+        let targets: Vec<(String, bool)> = stmt
+            .query_map([], |r| Ok((r.get(0)?, r.get(1)?)))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(
+            targets,
+            vec![
+                ("https://config.example.com".to_string(), false),
+                ("https://accounting.example.com".to_string(), true),
+            ],
+            "the support subscription is scoped to another account"
+        );
+
+        // Re-sending reaches the same receivers, or only the requested subscription
+        assert_eq!(resend_event(&conn, event_id, Some(&config), None).unwrap(), Some(2));
+        let accounting = db::get_webhook_subscription_by_name(&conn, "accounting")
+            .unwrap()
+            .unwrap();
+        assert_eq!(resend_event(&conn, event_id, None, Some(&accounting)).unwrap(), Some(1));
+        assert_eq!(resend_event(&conn, 9999, Some(&config), None).unwrap(), None);
+
+        // A subscription scoped to another account never receives the event
+        let support = db::get_webhook_subscription_by_name(&conn, "support").unwrap().unwrap();
+        assert!(matches!(
+            resend_event(&conn, event_id, None, Some(&support)),
+            Err(WalletDbError::InvalidInput(_))
+        ));
+
+        let queued: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM webhook_queue WHERE event_id = ?1",
+                [event_id],
+                |r| r.get(0),
+            )
+            .unwrap();
+        assert_eq!(queued, 5);
+    }
+
+    #[test]
+    fn test_validate_subscription_settings() {
+        assert!(validate_webhook_url("https://hooks.example.com/minotari", false).is_ok());
+        assert!(validate_webhook_url("ftp://hooks.example.com", true).is_err());
+        assert!(validate_webhook_url("not a url", true).is_err());
+        assert!(validate_webhook_url("exec:///usr/local/bin/on-event", true).is_ok());
+        assert!(validate_webhook_url("exec:///usr/local/bin/on-event", false).is_err());
+
+        assert!(validate_event_types(&["OutputDetected".to_string(), "TransactionConfirmed".to_string()]).is_ok());
+        assert!(validate_event_types(&["OutputFound".to_string()]).is_err());
+    }
 }
@@ -6,14 +6,22 @@ use std::time::Duration;
 use tokio::sync::broadcast;
 
 use crate::db::{self, SqlitePool};
+use crate::metrics::WalletMetrics;
+use crate::tasks::health::TaskGuard;
 use crate::webhooks::models::{WebhookQueueItem, WebhookStatus};
 use crate::webhooks::sender::{DeliveryResult, WebhookSender};
+use crate::webhooks::sinks::EventSink;
 
 const POLL_INTERVAL: u64 = 30; // 30 seconds
 const BATCH_SIZE: i64 = 10;
 const MAX_ATTEMPTS: i32 = 10;
 const MAX_AGE: u64 = 24 * 60 * 60; // 24 hrs
 
+/// Settings of the webhook configured in the config file.
+///
+/// Subscriptions created at runtime carry their own secret, so the worker runs even
+/// when this webhook is disabled. The secret is only needed when the webhook URL is an
+/// HTTP(S) endpoint, see [`EventSink`].
 #[derive(Clone)]
 pub struct WebhookWorkerConfig {
     pub enabled: bool,
@@ -25,6 +33,8 @@ pub struct WebhookWorker {
     db_pool: SqlitePool,
     config: WebhookWorkerConfig,
     sender: WebhookSender,
+    metrics: Option<WalletMetrics>,
+    task_guard: Option<TaskGuard>,
 }
 
 impl WebhookWorker {
@@ -33,13 +43,26 @@ impl WebhookWorker {
             db_pool,
             config,
             sender: WebhookSender::new(),
+            metrics: None,
+            task_guard: None,
         }
     }
 
+    /// Records the outcome of every delivery attempt in `metrics`.
+    pub fn with_metrics(mut self, metrics: WalletMetrics) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Reports the worker's liveness through `task_guard` while it runs.
+    pub fn with_task_guard(mut self, task_guard: TaskGuard) -> Self {
+        self.task_guard = Some(task_guard);
+        self
+    }
+
     pub async fn run(self: Arc<Self>, mut shutdown_rx: broadcast::Receiver<()>) {
-        if !self.config.enabled || self.config.secret.is_none() {
-            info!("Webhook worker disabled or missing secret. Exiting task.");
-            return;
+        if !self.config.enabled {
+            info!("Config file webhook disabled or missing secret. Only webhook subscriptions will be delivered.");
         }
 
         info!("Webhook worker started.");
@@ -49,6 +72,9 @@ impl WebhookWorker {
         loop {
             tokio::select! {
                 _ = interval.tick() => {
+                    if let Some(guard) = &self.task_guard {
+                        guard.heartbeat();
+                    }
                     if let Err(e) = self.process_batch().await {
                         error!("Error processing webhook batch: {}", e);
                     }
@@ -75,17 +101,26 @@ impl WebhookWorker {
 
         debug!("Processing {} due webhooks", items.len());
 
-        let secret = self.config.secret.as_ref().unwrap();
-
         for item in items {
-            self.process_item(item, secret).await;
+            self.process_item(item).await;
         }
 
         Ok(())
     }
 
-    async fn process_item(&self, item: WebhookQueueItem, secret: &str) {
-        let result = self.sender.send(&item.target_url, secret, &item.payload).await;
+    async fn process_item(&self, item: WebhookQueueItem) {
+        // Subscription items are signed with the subscription's current secret
+        let secret = match item.subscription_id {
+            Some(_) => item.secret.as_deref(),
+            None => self.config.secret.as_deref(),
+        };
+        let result = match EventSink::parse(&item.target_url) {
+            Ok(_) if item.subscription_id.is_none() && !self.config.enabled => {
+                DeliveryResult::PermanentFailure("Config file webhook is disabled".to_string())
+            },
+            Ok(sink) => sink.deliver(&self.sender, secret, &item.payload).await,
+            Err(e) => DeliveryResult::PermanentFailure(e),
+        };
 
         let (new_status, next_retry, error_msg) = match result {
             DeliveryResult::Success => {
@@ -109,6 +144,10 @@ impl WebhookWorker {
             },
         };
 
+        if let Some(metrics) = &self.metrics {
+            metrics.record_webhook_delivery(&new_status.to_string());
+        }
+
         let pool = self.db_pool.clone();
         let update_result = tokio::task::spawn_blocking(move || {
             let conn = pool.get()?;
@@ -163,6 +202,9 @@ mod tests {
     use super::*;
     use crate::db::{enqueue_webhook, init_db};
     use chrono::Utc;
+    use hmac::{Hmac, Mac};
+    use sha2::Sha256;
+    use tari_common::configuration::Network;
     use tempfile::tempdir;
     use wiremock::matchers::{header, method, path};
     use wiremock::{Mock, MockServer, ResponseTemplate};
@@ -181,14 +223,14 @@ mod tests {
             .await;
 
         let temp_dir = tempdir().unwrap();
-        let pool = init_db(temp_dir.path().join("worker_test.db")).unwrap();
+        let pool = init_db(temp_dir.path().join("worker_test.db"), Network::Esmeralda).unwrap();
         let conn = pool.get().unwrap();
 
         // Enqueue a "Due" Webhook
         let target_url = format!("{}/receive", mock_server.uri());
         let payload = r#"{"event": "worker_test"}"#;
 
-        let webhook_id = enqueue_webhook(&conn, None, "TestEvent", payload, &target_url).unwrap();
+        let webhook_id = enqueue_webhook(&conn, None, "TestEvent", payload, &target_url, None).unwrap();
 
         let config = WebhookWorkerConfig {
             enabled: true,
@@ -221,10 +263,10 @@ mod tests {
             .await;
 
         let temp_dir = tempdir().unwrap();
-        let pool = init_db(temp_dir.path().join("retry_test.db")).unwrap();
+        let pool = init_db(temp_dir.path().join("retry_test.db"), Network::Esmeralda).unwrap();
         let conn = pool.get().unwrap();
 
-        let webhook_id = enqueue_webhook(&conn, None, "TestEvent", "{}", &mock_server.uri()).unwrap();
+        let webhook_id = enqueue_webhook(&conn, None, "TestEvent", "{}", &mock_server.uri(), None).unwrap();
 
         let worker = WebhookWorker::new(
             pool.clone(),
@@ -257,6 +299,156 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_worker_signs_subscription_items_with_subscription_secret() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&mock_server)
+            .await;
+
+        let temp_dir = tempdir().unwrap();
+        let pool = init_db(temp_dir.path().join("subscription_test.db"), Network::Esmeralda).unwrap();
+        let conn = pool.get().unwrap();
+
+        let subscription_id = db::insert_webhook_subscription(
+            &conn,
+            &db::NewWebhookSubscription {
+                name: "support",
+                url: &mock_server.uri(),
+                secret: "initial-secret",
+                event_types: None,
+                account_ids: None,
+            },
+        )
+        .unwrap();
+        let payload = r#"{"event": "subscription_test"}"#;
+        enqueue_webhook(
+            &conn,
+            None,
+            "TestEvent",
+            payload,
+            &mock_server.uri(),
+            Some(subscription_id),
+        )
+        .unwrap();
+        db::rotate_webhook_subscription_secret(&conn, "support", "rotated-secret").unwrap();
+
+        // The config file webhook is disabled, subscriptions are still delivered
+        let worker = WebhookWorker::new(
+            pool.clone(),
+            WebhookWorkerConfig {
+                enabled: false,
+                secret: None,
+                send_only_event_types: None,
+            },
+        );
+        worker.process_batch().await.unwrap();
+
+        let requests = mock_server.received_requests().await.unwrap();
+        assert_eq!(requests.len(), 1);
+        let signature = requests
+            .first()
+            .and_then(|r| r.headers.get("X-Minotari-Signature"))
+            .and_then(|h| h.to_str().ok())
+            .unwrap()
+            .to_string();
+        let (timestamp, signature_hex) = signature.split_once(",v1=").unwrap();
+        let timestamp = timestamp.strip_prefix("t=").unwrap();
+
+        let mut mac = Hmac::<Sha256>::new_from_slice(b"rotated-secret").unwrap();
+        mac.update(format!("{}.{}", timestamp, payload).as_bytes());
+        assert_eq!(signature_hex, hex::encode(mac.finalize().into_bytes()));
+    }
+
+    #[tokio::test]
+    async fn test_worker_delivers_subscription_items_to_current_url() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/moved"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&mock_server)
+            .await;
+
+        let temp_dir = tempdir().unwrap();
+        let pool = init_db(temp_dir.path().join("moved_url_test.db"), Network::Esmeralda).unwrap();
+        let conn = pool.get().unwrap();
+
+        let old_url = format!("{}/old", mock_server.uri());
+        let subscription_id = db::insert_webhook_subscription(
+            &conn,
+            &db::NewWebhookSubscription {
+                name: "support",
+                url: &old_url,
+                secret: "secret",
+                event_types: None,
+                account_ids: None,
+            },
+        )
+        .unwrap();
+        let webhook_id = enqueue_webhook(&conn, None, "TestEvent", "{}", &old_url, Some(subscription_id)).unwrap();
+        let new_url = format!("{}/moved", mock_server.uri());
+        db::update_webhook_subscription(
+            &conn,
+            "support",
+            &db::WebhookSubscriptionUpdate {
+                url: Some(&new_url),
+                event_types: None,
+                account_ids: None,
+                enabled: None,
+            },
+        )
+        .unwrap();
+
+        let worker = WebhookWorker::new(
+            pool.clone(),
+            WebhookWorkerConfig {
+                enabled: false,
+                secret: None,
+                send_only_event_types: None,
+            },
+        );
+        worker.process_batch().await.unwrap();
+
+        let requests = mock_server.received_requests().await.unwrap();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests.first().map(|r| r.url.path()), Some("/moved"));
+        let item = db::get_webhook_delivery(&conn, webhook_id).unwrap().unwrap();
+        assert_eq!(item.status, WebhookStatus::Success);
+        assert_eq!(item.target_url, new_url);
+    }
+
+    #[tokio::test]
+    async fn test_worker_delivers_to_file_sink() {
+        let temp_dir = tempdir().unwrap();
+        let pool = init_db(temp_dir.path().join("file_sink_test.db"), Network::Esmeralda).unwrap();
+        let conn = pool.get().unwrap();
+
+        let events_path = temp_dir.path().join("events.jsonl");
+        let target_url = format!("file://{}", events_path.display());
+        let payload = r#"{"event": "file_sink_test"}"#;
+        let webhook_id = enqueue_webhook(&conn, None, "TestEvent", payload, &target_url, None).unwrap();
+
+        // Local sinks are not signed, so no secret is needed
+        let worker = WebhookWorker::new(
+            pool.clone(),
+            WebhookWorkerConfig {
+                enabled: true,
+                secret: None,
+                send_only_event_types: None,
+            },
+        );
+        worker.process_batch().await.unwrap();
+
+        let status: String = conn
+            .query_row("SELECT status FROM webhook_queue WHERE id = ?", [webhook_id], |r| {
+                r.get(0)
+            })
+            .unwrap();
+        assert_eq!(status, "success");
+        assert_eq!(std::fs::read_to_string(&events_path).unwrap(), format!("{}\n", payload));
+    }
+
     #[test]
     fn test_backoff_calculation_limits() {
         let (next_time, stop) = calculate_backoff(1);